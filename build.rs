@@ -1,7 +1,11 @@
 const COMMANDS: &[&str] = &[
+    "bridge_token",
+    "capture_trigger",
     "click_element",
+    "console_entry",
     "control_window",
     "eval_js",
+    "eval_result",
     "get_element_text",
     "get_html",
     "get_title",
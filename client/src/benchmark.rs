@@ -0,0 +1,164 @@
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::process::Command;
+
+use crate::client::McpClient;
+use crate::error::{Error, Result};
+
+/// How often to retry connecting and recording while a launch is in progress
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How the benchmark reaches each launched instance
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    /// IPC socket; `None` uses the plugin's default path
+    Ipc(Option<PathBuf>),
+    Tcp { host: String, port: u16 },
+}
+
+/// Cold start benchmark: launches the app `runs` times, has the plugin record time to first
+/// window, first paint and app ready for each launch, and returns the plugin's aggregate report.
+///
+/// ```no_run
+/// # async fn run() -> tauri_plugin_mcp_client::Result<()> {
+/// use tauri_plugin_mcp_client::StartupBenchmark;
+///
+/// let report = StartupBenchmark::new("target/release/my-app").runs(10).run().await?;
+/// println!("{}", report["app_ready_ms"]["median"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StartupBenchmark {
+    program: PathBuf,
+    args: Vec<String>,
+    runs: usize,
+    endpoint: Endpoint,
+    window_label: Option<String>,
+    launch_timeout: Duration,
+    pause: Duration,
+    keep_previous: bool,
+}
+
+impl StartupBenchmark {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            runs: 5,
+            endpoint: Endpoint::Ipc(None),
+            window_label: None,
+            launch_timeout: Duration::from_secs(60),
+            pause: Duration::from_millis(500),
+            keep_previous: false,
+        }
+    }
+
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Number of launches (default 5)
+    pub fn runs(mut self, runs: usize) -> Self {
+        self.runs = runs.max(1);
+        self
+    }
+
+    pub fn endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// The main window whose readiness ends startup (default "main")
+    pub fn window_label(mut self, label: impl Into<String>) -> Self {
+        self.window_label = Some(label.into());
+        self
+    }
+
+    /// How long one launch may take to become ready (default 60 seconds)
+    pub fn launch_timeout(mut self, timeout: Duration) -> Self {
+        self.launch_timeout = timeout;
+        self
+    }
+
+    /// Wait between closing one instance and starting the next (default 500ms)
+    pub fn pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Add to the runs already recorded instead of starting over
+    pub fn keep_previous(mut self, keep: bool) -> Self {
+        self.keep_previous = keep;
+        self
+    }
+
+    /// Launch the app once per run and return the plugin's `benchmark_startup` report
+    pub async fn run(&self) -> Result<Value> {
+        let mut report = Value::Null;
+        for run in 0..self.runs {
+            if run > 0 {
+                tokio::time::sleep(self.pause).await;
+            }
+            let spawned_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            let mut child = Command::new(&self.program)
+                .args(&self.args)
+                .kill_on_drop(true)
+                .spawn()?;
+            let outcome = self.measure(run, spawned_ms).await;
+            let _ = child.kill().await;
+            if let Some(value) = outcome? {
+                report = value;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Record one launch; the last launch also fetches the report
+    async fn measure(&self, run: usize, spawned_ms: u64) -> Result<Option<Value>> {
+        let deadline = Instant::now() + self.launch_timeout;
+        let client = loop {
+            match self.connect().await {
+                Ok(client) => break client,
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        };
+
+        if run == 0 && !self.keep_previous {
+            client.call("benchmark_startup", &json!({ "action": "reset" })).await?;
+        }
+        let record = json!({
+            "action": "record",
+            "window_label": self.window_label,
+            "spawned_ms": spawned_ms,
+        });
+        loop {
+            match client.call("benchmark_startup", &record).await {
+                Ok(_) => break,
+                Err(Error::Command(e)) if Instant::now() >= deadline => {
+                    return Err(Error::Command(format!("Run {} never became ready: {}", run + 1, e)));
+                }
+                Err(Error::Command(_)) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if run + 1 == self.runs {
+            return Ok(Some(client.call("benchmark_startup", &json!({ "action": "report" })).await?));
+        }
+        Ok(None)
+    }
+
+    async fn connect(&self) -> Result<McpClient> {
+        match &self.endpoint {
+            Endpoint::Ipc(path) => McpClient::connect_ipc(path.clone()).await,
+            Endpoint::Tcp { host, port } => McpClient::connect_tcp(host, *port).await,
+        }
+    }
+}
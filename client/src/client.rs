@@ -0,0 +1,237 @@
+use interprocess::local_socket::tokio::prelude::*;
+use interprocess::local_socket::{GenericFilePath, GenericNamespaced};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, oneshot};
+
+use crate::error::{Error, Result};
+
+/// Protocol version this client speaks
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Largest frame accepted from the server, matching the server's own limit
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Default time to wait for a response
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Buffered notifications per receiver before older ones are dropped
+const NOTIFICATION_CAPACITY: usize = 256;
+
+trait Transport: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Transport for T {}
+
+type BoxedTransport = Box<dyn Transport>;
+type PendingReply = oneshot::Sender<Result<Value>>;
+
+/// Pushed by the server to connections that subscribed to its topic
+#[derive(Debug, Clone, Deserialize)]
+pub struct Notification {
+    pub topic: String,
+    pub data: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestFrame<'a, P: Serialize> {
+    command: &'a str,
+    payload: &'a P,
+    protocol_version: u32,
+}
+
+#[derive(Deserialize)]
+struct IncomingFrame {
+    #[serde(default)]
+    success: bool,
+    #[serde(default)]
+    data: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    notification: Option<Notification>,
+}
+
+/// Async client for a running tauri-plugin-mcp socket server.
+///
+/// Requests are answered in order, so one client can be shared between tasks.
+/// Frames are length-prefixed; see the plugin README for the wire format.
+pub struct McpClient {
+    writer: tokio::sync::Mutex<WriteHalf<BoxedTransport>>,
+    pending: Arc<Mutex<VecDeque<PendingReply>>>,
+    notifications: broadcast::Sender<Notification>,
+    timeout: Duration,
+}
+
+impl McpClient {
+    /// Connect over IPC. `None` uses the plugin's default socket path in the temp directory.
+    pub async fn connect_ipc(path: Option<PathBuf>) -> Result<Self> {
+        let path = path
+            .unwrap_or_else(|| std::env::temp_dir().join(default_socket_name()))
+            .to_string_lossy()
+            .to_string();
+        // Same naming as the server: a named pipe on Windows, a socket file elsewhere
+        let name = if cfg!(target_os = "windows") {
+            path.to_ns_name::<GenericNamespaced>()?
+        } else {
+            path.to_fs_name::<GenericFilePath>()?
+        };
+        let stream = LocalSocketStream::connect(name).await?;
+        Ok(Self::from_transport(Box::new(stream)))
+    }
+
+    /// Connect over plain TCP
+    pub async fn connect_tcp(host: &str, port: u16) -> Result<Self> {
+        let stream = TcpStream::connect((host, port)).await?;
+        stream.set_nodelay(true)?;
+        Ok(Self::from_transport(Box::new(stream)))
+    }
+
+    /// Change how long requests wait for a response (default 30 seconds)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn from_transport(transport: BoxedTransport) -> Self {
+        let (reader, writer) = tokio::io::split(transport);
+        let pending = Arc::new(Mutex::new(VecDeque::new()));
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+
+        tokio::spawn(read_loop(reader, pending.clone(), notifications.clone()));
+
+        Self {
+            writer: tokio::sync::Mutex::new(writer),
+            pending,
+            notifications,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Receive notifications for topics registered with [`Self::subscribe`]
+    pub fn notifications(&self) -> broadcast::Receiver<Notification> {
+        self.notifications.subscribe()
+    }
+
+    /// Send a command and return the `data` of a successful response
+    pub async fn call<P: Serialize>(&self, command: &str, payload: &P) -> Result<Value> {
+        let body = serde_json::to_vec(&RequestFrame {
+            command,
+            payload,
+            protocol_version: PROTOCOL_VERSION,
+        })?;
+        let header = u32::try_from(body.len())
+            .map_err(|_| Error::Protocol(format!("Request of {} bytes is too large", body.len())))?
+            .to_be_bytes();
+
+        let (tx, rx) = oneshot::channel();
+        {
+            // Queue the reply slot under the writer lock so replies match requests in order
+            let mut writer = self.writer.lock().await;
+            self.pending.lock().unwrap().push_back(tx);
+            writer.write_all(&header).await?;
+            writer.write_all(&body).await?;
+            writer.flush().await?;
+        }
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(Error::Closed),
+            Err(_) => Err(Error::Timeout(self.timeout.as_millis())),
+        }
+    }
+
+    /// Send a command and deserialize the `data` of a successful response
+    pub async fn request<P: Serialize, T: DeserializeOwned>(
+        &self,
+        command: &str,
+        payload: &P,
+    ) -> Result<T> {
+        let data = self.call(command, payload).await?;
+        Ok(serde_json::from_value(data)?)
+    }
+}
+
+/// Route incoming frames to waiting requests or notification receivers until the stream ends
+async fn read_loop(
+    mut reader: ReadHalf<BoxedTransport>,
+    pending: Arc<Mutex<VecDeque<PendingReply>>>,
+    notifications: broadcast::Sender<Notification>,
+) {
+    let mut buf = Vec::new();
+    loop {
+        let frame = match read_frame(&mut reader, &mut buf).await {
+            Ok(()) => serde_json::from_slice::<IncomingFrame>(&buf),
+            Err(_) => break,
+        };
+
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                // An unreadable response still consumes the oldest pending request
+                if let Some(reply) = pending.lock().unwrap().pop_front() {
+                    let _ = reply.send(Err(Error::Protocol(format!("Invalid response: {}", e))));
+                }
+                continue;
+            }
+        };
+
+        if let Some(notification) = frame.notification {
+            // No receivers is fine; notifications are best effort
+            let _ = notifications.send(notification);
+            continue;
+        }
+
+        let result = if frame.success {
+            Ok(frame.data.unwrap_or(Value::Null))
+        } else {
+            Err(Error::Command(
+                frame
+                    .error
+                    .unwrap_or_else(|| "Command failed without specific error".to_string()),
+            ))
+        };
+        if let Some(reply) = pending.lock().unwrap().pop_front() {
+            let _ = reply.send(result);
+        }
+    }
+
+    // Dropping the reply senders wakes every waiting request with `Error::Closed`
+    pending.lock().unwrap().clear();
+}
+
+async fn read_frame(reader: &mut ReadHalf<BoxedTransport>, buf: &mut Vec<u8>) -> Result<()> {
+    let mut header = [0u8; 4];
+    reader.read_exact(&mut header).await?;
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(Error::Protocol(format!(
+            "Frame of {} bytes exceeds the {} byte limit",
+            len, MAX_FRAME_LEN
+        )));
+    }
+
+    buf.clear();
+    buf.resize(len, 0);
+    reader.read_exact(buf).await?;
+    Ok(())
+}
+
+/// Mirrors the plugin's machine-specific default socket name
+fn default_socket_name() -> String {
+    let machine_id: String = if let Ok(id) = std::fs::read_to_string("/etc/machine-id") {
+        id.trim().chars().take(8).collect()
+    } else if let Ok(id) = std::fs::read_to_string("/proc/sys/kernel/random/boot_id") {
+        id.trim().chars().take(8).collect()
+    } else {
+        // The plugin falls back to its own process id, which a client cannot know
+        format!("{:08x}", std::process::id())
+    };
+    format!("tauri-mcp-{}.sock", machine_id)
+}
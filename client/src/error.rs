@@ -0,0 +1,26 @@
+use thiserror::Error as ThisError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(ThisError, Debug)]
+pub enum Error {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The server ran the command and reported a failure
+    #[error("Command failed: {0}")]
+    Command(String),
+
+    /// The server sent something this client does not understand
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    #[error("Timed out after {0}ms waiting for a response")]
+    Timeout(u128),
+
+    #[error("Connection closed")]
+    Closed,
+}
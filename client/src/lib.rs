@@ -0,0 +1,27 @@
+//! Typed Rust client for the tauri-plugin-mcp socket server.
+//!
+//! ```no_run
+//! # async fn run() -> tauri_plugin_mcp_client::Result<()> {
+//! use tauri_plugin_mcp_client::{requests::ScreenshotRequest, McpClient};
+//!
+//! let client = McpClient::connect_ipc(None).await?;
+//! let shot = client
+//!     .take_screenshot(&ScreenshotRequest {
+//!         window_label: "main".into(),
+//!         ..Default::default()
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod benchmark;
+mod client;
+mod error;
+pub mod requests;
+mod tools;
+
+pub use benchmark::{Endpoint, StartupBenchmark};
+pub use client::{McpClient, Notification, PROTOCOL_VERSION};
+pub use error::{Error, Result};
+pub use tools::{PingResponse, ScreenshotResponse};
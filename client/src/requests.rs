@@ -0,0 +1,1073 @@
+//! Request payloads for every tool, mirroring what the plugin's handlers accept.
+//!
+//! Optional fields left as `None` are omitted so the server applies its own defaults.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Identifies an element by CSS selector or by a handle returned from `query_elements`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ElementTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+}
+
+impl ElementTarget {
+    pub fn selector(selector: impl Into<String>) -> Self {
+        Self {
+            selector: Some(selector.into()),
+            handle: None,
+        }
+    }
+
+    pub fn handle(handle: impl Into<String>) -> Self {
+        Self {
+            selector: None,
+            handle: Some(handle.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PingRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TopicsRequest {
+    pub topics: Vec<String>,
+}
+
+/// Output format for screenshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScreenshotRequest {
+    pub window_label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ImageFormat>,
+    /// Encoder quality (1-100) for lossy formats
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<u32>,
+    /// Bypass the server's observation cache
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fresh: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GetDomRequest {
+    pub window_label: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fresh: bool,
+}
+
+/// Payload for `manage_local_storage`. Field names are camelCase on the wire.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalStorageRequest {
+    /// `get`, `set`, `remove`, `clear` or `keys`
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExecuteJsRequest {
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DirectEvalRequest {
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    /// Idempotency key: resubmitting it returns the first response instead of running again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isolate: Option<bool>,
+    /// `Isolated` runs in a hidden frame with its own globals instead of the page's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realm: Option<Realm>,
+    /// `Typescript` transpiles the code first; needs the plugin's `ts` feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// Also return the result as MCP content blocks in `content`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_blocks: Option<bool>,
+    /// `Module` runs the code as an ES module and returns its default export
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub script_type: Option<ScriptType>,
+    /// Map stack frames in the app's scripts through their source maps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_maps: Option<bool>,
+    #[serde(flatten)]
+    pub frame: FrameTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptType {
+    Classic,
+    Module,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Realm {
+    Page,
+    Isolated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Javascript,
+    Typescript,
+}
+
+/// Same-origin child frame to run in, by selector or document URL; see `list_frames`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FrameTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalAsyncRequest {
+    /// Body of an async function; `return` the value to get back
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+    /// Idempotency key: resubmitting it returns the first response instead of running again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isolate: Option<bool>,
+    /// `Isolated` runs in a hidden frame with its own globals instead of the page's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realm: Option<Realm>,
+    /// `Typescript` transpiles the code first; needs the plugin's `ts` feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    /// Also return the result as MCP content blocks in `content`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_blocks: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_maps: Option<bool>,
+    #[serde(flatten)]
+    pub frame: FrameTarget,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalExpressionRequest {
+    /// A single expression; statements are rejected
+    pub expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<u32>,
+    #[serde(flatten)]
+    pub frame: FrameTarget,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalAllWindowsRequest {
+    pub code: String,
+    /// Window labels to run in, with `*` and `?` wildcards; every window when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WindowManagerRequest {
+    /// e.g. `minimize`, `maximize`, `close`, `set_position`, `set_size`
+    pub operation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+/// Payload for `set_window_style`. Unset fields are left as they are
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WindowStyleRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub always_on_top: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decorations: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resizable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_taskbar: Option<bool>,
+    /// 0.0 to 1.0; macOS and Windows only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InjectScriptRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub script: String,
+    /// Name to track the script under; defaults to a hash of its source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Run the script even if this exact source is already installed
+    pub force: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalHistoryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Only evals recorded after this id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Payload for `replay_eval`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplayEvalRequest {
+    /// History ids to run again, in order
+    pub ids: Vec<u64>,
+    /// Run them in this window instead of the original ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub stop_on_failure: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HeapSnapshotRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Devtools port, if the plugin wasn't configured with one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collect_garbage: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartCpuProfileRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Devtools port, if the plugin wasn't configured with one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling_interval_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ToolLatencyRequest {
+    /// Only this tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WaitForSplashCloseRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Payload for `set_fullscreen`. Leave `fullscreen` unset to toggle
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FullscreenRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fullscreen: Option<bool>,
+}
+
+/// Payload for `set_kiosk_mode`. Leave `enabled` unset to toggle
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KioskModeRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Payload for `simulate_text_input`. Field names are camelCase on the wire.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextInputRequest {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_delay_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MouseMovementRequest {
+    pub x: i32,
+    pub y: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click: Option<bool>,
+    /// `left`, `right` or `middle`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ElementPositionRequest {
+    pub window_label: String,
+    /// `id`, `class`, `tag`, `text` or `handle`
+    pub selector_type: String,
+    pub selector_value: String,
+    pub should_click: bool,
+    pub raw_coordinates: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SendTextToElementRequest {
+    pub window_label: String,
+    pub selector_type: String,
+    pub selector_value: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WindowRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SetupConsoleCaptureRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Record call-site stack traces for `console.error` and `console.warn`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_stacks: Option<bool>,
+    /// Set to false to keep consecutive identical messages as separate entries instead of one
+    /// entry with a `repeat_count`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapse_repeats: Option<bool>,
+    /// Drop messages below this level (`debug`, `log`, `info`, `warn` or `error`) in the page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_level: Option<String>,
+    /// Replace the page's sampling rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<Vec<SamplingRule>>,
+    /// Most console entries the window keeps before evicting the oldest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+    /// Approximate bytes of console output the window keeps before evicting the oldest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,    /// Also relay console output from Web Workers; replaces the page's worker constructors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_workers: Option<bool>,
+    /// Count timers, `Math.random` and clock reads for `detect_nondeterminism` from now on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_nondeterminism: Option<bool>,
+    /// Record `addEventListener` calls from now on, for `get_event_listeners`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_listeners: Option<bool>,
+}
+
+/// Keep one in `keep_one_in` console messages containing `pattern`
+#[derive(Debug, Clone, Serialize)]
+pub struct SamplingRule {
+    pub pattern: String,
+    pub keep_one_in: u32,
+}
+
+/// A point in time for `from` and `to` filters
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TimeBound {
+    /// Milliseconds since the Unix epoch
+    Millis(u64),
+    /// ISO 8601 time, e.g. `2024-05-01T12:30:00.250Z`
+    Iso8601(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsoleBufferRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// `next_cursor` from an earlier response, to get only newer entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_cursor: Option<u64>,
+    /// ISO 8601 time; only entries logged after it are returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_timestamp: Option<String>,
+    /// Only what was logged at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<TimeBound>,
+    /// Only what was logged at or before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<TimeBound>,
+    /// Only entries at these levels; JavaScript errors count as `error`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub levels: Option<Vec<String>>,
+    /// Only entries and errors whose message matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_regex: Option<String>,
+    /// Only entries and errors caused by the tool call whose response carried this `correlationId`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Leave out entries and errors caused by other clients' tool calls
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub client_view: bool,
+    /// Keep the plugin's own signaling lines, which are left out by default
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_internal: bool,
+    /// Matches to skip, oldest first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    /// Most entries and errors to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsoleDeltaRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Highest sequence number already seen (0 for everything)
+    pub since_seq: u64,
+    /// Only what was logged at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<TimeBound>,
+    /// Only what was logged at or before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<TimeBound>,
+    /// Leave out entries caused by other clients' tool calls
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub client_view: bool,
+    /// Keep the plugin's own signaling lines, which are left out by default
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_internal: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExecuteWithConsoleRequest {
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Extra time after completion for asynchronous console output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settle_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GetJsResultRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineCategory {
+    Console,
+    Error,
+    Network,
+    Navigation,
+    Window,
+    Annotation,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TimelineRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<TimelineCategory>>,
+    /// Only what was logged at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<TimeBound>,
+    /// Only what was logged at or before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<TimeBound>,
+    /// Inclusive lower bound, milliseconds since the Unix epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BufferSource {
+    Console,
+    Errors,
+    Network,
+    Annotations,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchBuffersRequest {
+    /// Regular expression to look for
+    pub pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub case_insensitive: bool,
+    /// Buffers to search (default: all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<BufferSource>>,
+    /// Only what was logged at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<TimeBound>,
+    /// Only what was logged at or before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<TimeBound>,
+    /// Neighbouring items returned on each side of a match (default 2)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<usize>,
+    /// Most matches to return (default 50)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClearConsoleRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Clear only JavaScript errors, keeping console entries
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub errors_only: bool,
+    /// Clear only what the page with this session id logged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Clear only what was last logged before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<TimeBound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JsErrorsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Only errors thrown at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<TimeBound>,
+    /// Only errors thrown at or before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<TimeBound>,
+    /// Only errors whose message matches this regular expression
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_regex: Option<String>,
+    /// Most errors to return, keeping the newest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AnnotationRequest {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WaitForIdleRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// How long the page must stay quiet (default 500ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quiet_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SetAnimationsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finish_running: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameAction {
+    Pause,
+    Step,
+    Resume,
+    Status,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameControlRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub action: FrameAction,
+    /// Frames to advance for `step` (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frames: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameTimingAction {
+    Start,
+    /// Stop and save the timings as a `frame-timing` artifact
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameTimingRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub action: FrameTimingAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_frames: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JankReportRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Only consider the last `window_ms` milliseconds (default 30000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EventListenersRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    pub include_ancestors: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DispatchEventRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    pub event_type: String,
+    /// e.g. `CustomEvent` or `PointerEvent` (default `Event`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_class: Option<String>,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub init: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ForcePseudoStateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// e.g. `["hover"]`
+    pub states: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalOnElementRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// Function source called with the element first, e.g. `"(el) => el.value"`
+    pub function: String,
+    pub args: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryElementsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub selector: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fresh: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExtractTableRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fresh: bool,
+}
+
+/// How to read one field from a list item
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum FieldSpec {
+    /// Text of the first descendant matching this selector
+    Selector(String),
+    Detailed {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        selector: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        attribute: Option<String>,
+        all: bool,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExtractListRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub item_selector: String,
+    pub fields: BTreeMap<String, FieldSpec>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fresh: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiffTextRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    pub name: String,
+    /// Region to read; defaults to the whole body
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PasteImage {
+    /// Base64-encoded image bytes
+    pub data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PasteRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<PasteImage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_clipboard: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpenContextMenuRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpenMenuItemsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub menu_selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevealMethod {
+    Hover,
+    Focus,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CaptureTransientUiRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Element that reveals the tooltip or popover
+    #[serde(flatten)]
+    pub trigger: ElementTarget,
+    pub popover_selector: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<RevealMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KeyboardAuditRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Dispatch Enter/Space to each element (default true); app handlers run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub try_activation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_elements: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NondeterminismRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_timer_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Area to ignore when diffing, in screenshot pixels
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MaskRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOutput {
+    Image,
+    Regions,
+    Score,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiffScreenshotsRequest {
+    /// Screenshot data URLs, e.g. from [`crate::McpClient::take_screenshot`]. `before` may be
+    /// left out when comparing against a stored baseline.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    pub after: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_baseline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub masks: Option<Vec<MaskRect>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask_selectors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u8>,
+    /// Defaults to score and regions; request only `Score` to skip everything else
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Vec<DiffOutput>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Dhash,
+    Phash,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScreenshotHashRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// Hash one element instead of the whole window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<HashAlgorithm>,
+    /// Hash from an earlier call; the response then includes the Hamming distance to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare_to: Option<String>,
+}
+
+/// Needs the plugin's `ocr` feature
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OcrScreenshotRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    /// Area to read, in screenshot pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<MaskRect>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CancelEvalRequest {
+    pub execution_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FetchResultChunkRequest {
+    pub execution_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalInSessionRequest {
+    /// Returned by [`crate::McpClient::eval_session_create`]
+    pub session_id: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EvalSessionRequest {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfirmRequest {
+    /// `operation_id` from the response of the held call
+    pub operation_id: String,
+    /// Drop the operation instead of running it
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cancel: bool,
+}
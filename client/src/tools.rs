@@ -0,0 +1,335 @@
+//! One method per tool. Responses whose shape is fixed are deserialized; the rest are returned
+//! as `serde_json::Value` because they mirror page content.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::client::McpClient;
+use crate::error::Result;
+use crate::requests::*;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PingResponse {
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScreenshotResponse {
+    /// Image as a data URL
+    pub data: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl McpClient {
+    /// Authenticate and identify this client; required first when the server has a token
+    pub async fn handshake(&self, request: &HandshakeRequest) -> Result<Value> {
+        self.call("handshake", request).await
+    }
+
+    pub async fn ping(&self, value: Option<&str>) -> Result<PingResponse> {
+        let request = PingRequest {
+            value: value.map(str::to_string),
+        };
+        self.request("ping", &request).await
+    }
+
+    pub async fn server_status(&self) -> Result<Value> {
+        self.call("server_status", &json!({})).await
+    }
+
+    /// Start receiving notifications for `topics`; read them from [`McpClient::notifications`]
+    pub async fn subscribe(&self, topics: &[&str]) -> Result<Value> {
+        let request = TopicsRequest {
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+        };
+        self.call("subscribe", &request).await
+    }
+
+    pub async fn unsubscribe(&self, topics: &[&str]) -> Result<Value> {
+        let request = TopicsRequest {
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+        };
+        self.call("unsubscribe", &request).await
+    }
+
+    pub async fn take_screenshot(&self, request: &ScreenshotRequest) -> Result<ScreenshotResponse> {
+        self.request("take_screenshot", request).await
+    }
+
+    pub async fn get_dom(&self, request: &GetDomRequest) -> Result<Value> {
+        self.call("get_dom", request).await
+    }
+
+    pub async fn manage_local_storage(&self, request: &LocalStorageRequest) -> Result<Value> {
+        self.call("manage_local_storage", request).await
+    }
+
+    pub async fn execute_js(&self, request: &ExecuteJsRequest) -> Result<Value> {
+        self.call("execute_js", request).await
+    }
+
+    pub async fn direct_eval(&self, request: &DirectEvalRequest) -> Result<Value> {
+        self.call("direct_eval", request).await
+    }
+
+    pub async fn eval_async(&self, request: &EvalAsyncRequest) -> Result<Value> {
+        self.call("eval_async", request).await
+    }
+
+    pub async fn eval_expression(&self, request: &EvalExpressionRequest) -> Result<Value> {
+        self.call("eval_expression", request).await
+    }
+
+    pub async fn eval_all_windows(&self, request: &EvalAllWindowsRequest) -> Result<Value> {
+        self.call("eval_all_windows", request).await
+    }
+
+    pub async fn cancel_eval(&self, request: &CancelEvalRequest) -> Result<Value> {
+        self.call("cancel_eval", request).await
+    }
+
+    pub async fn fetch_result_chunk(&self, request: &FetchResultChunkRequest) -> Result<Value> {
+        self.call("fetch_result_chunk", request).await
+    }
+
+    pub async fn eval_session_create(&self, request: &WindowRequest) -> Result<Value> {
+        self.call("eval_session_create", request).await
+    }
+
+    pub async fn eval_in_session(&self, request: &EvalInSessionRequest) -> Result<Value> {
+        self.call("eval_in_session", request).await
+    }
+
+    pub async fn eval_session_destroy(&self, request: &EvalSessionRequest) -> Result<Value> {
+        self.call("eval_session_destroy", request).await
+    }
+
+    pub async fn list_frames(&self, request: &WindowRequest) -> Result<Value> {
+        self.call("list_frames", request).await
+    }
+
+    pub async fn diff_screenshots(&self, request: &DiffScreenshotsRequest) -> Result<Value> {
+        self.call("diff_screenshots", request).await
+    }
+
+    pub async fn screenshot_hash(&self, request: &ScreenshotHashRequest) -> Result<Value> {
+        self.call("screenshot_hash", request).await
+    }
+
+    pub async fn ocr_screenshot(&self, request: &OcrScreenshotRequest) -> Result<Value> {
+        self.call("ocr_screenshot", request).await
+    }
+
+    /// The app's windows frontmost first, with whether each is covered or off screen
+    pub async fn get_window_z_order(&self) -> Result<Value> {
+        self.call("get_window_z_order", &json!({})).await
+    }
+
+    pub async fn manage_window(&self, request: &WindowManagerRequest) -> Result<Value> {
+        self.call("manage_window", request).await
+    }
+
+    pub async fn set_window_style(&self, request: &WindowStyleRequest) -> Result<Value> {
+        self.call("set_window_style", request).await
+    }
+
+    pub async fn get_performance_metrics(&self, request: &WindowRequest) -> Result<Value> {
+        self.call("get_performance_metrics", request).await
+    }
+
+    /// Leave `window_label` unset to measure every window
+    pub async fn get_webview_memory(&self, request: &WindowRequest) -> Result<Value> {
+        self.call("get_webview_memory", request).await
+    }
+
+    pub async fn capture_heap_snapshot(&self, request: &HeapSnapshotRequest) -> Result<Value> {
+        self.call("capture_heap_snapshot", request).await
+    }
+
+    pub async fn start_cpu_profile(&self, request: &StartCpuProfileRequest) -> Result<Value> {
+        self.call("start_cpu_profile", request).await
+    }
+
+    /// Saves the profile started by `start_cpu_profile` as an artifact
+    pub async fn stop_cpu_profile(&self, request: &WindowRequest) -> Result<Value> {
+        self.call("stop_cpu_profile", request).await
+    }
+
+    /// Run (or with `cancel`, drop) an operation the plugin held for confirmation
+    pub async fn confirm(&self, request: &ConfirmRequest) -> Result<Value> {
+        self.call("confirm", request).await
+    }
+
+    pub async fn get_splash_window(&self) -> Result<Value> {
+        self.call("get_splash_window", &json!({})).await
+    }
+
+    pub async fn wait_for_splash_close(&self, request: &WaitForSplashCloseRequest) -> Result<Value> {
+        self.call("wait_for_splash_close", request).await
+    }
+
+    pub async fn set_fullscreen(&self, request: &FullscreenRequest) -> Result<Value> {
+        self.call("set_fullscreen", request).await
+    }
+
+    pub async fn set_kiosk_mode(&self, request: &KioskModeRequest) -> Result<Value> {
+        self.call("set_kiosk_mode", request).await
+    }
+
+    pub async fn inject_script(&self, request: &InjectScriptRequest) -> Result<Value> {
+        self.call("inject_script", request).await
+    }
+
+    pub async fn get_eval_history(&self, request: &EvalHistoryRequest) -> Result<Value> {
+        self.call("get_eval_history", request).await
+    }
+
+    pub async fn replay_eval(&self, request: &ReplayEvalRequest) -> Result<Value> {
+        self.call("replay_eval", request).await
+    }
+
+    pub async fn get_tool_latency(&self, request: &ToolLatencyRequest) -> Result<Value> {
+        self.call("get_tool_latency", request).await
+    }
+
+    pub async fn simulate_text_input(&self, request: &TextInputRequest) -> Result<Value> {
+        self.call("simulate_text_input", request).await
+    }
+
+    pub async fn simulate_mouse_movement(&self, request: &MouseMovementRequest) -> Result<Value> {
+        self.call("simulate_mouse_movement", request).await
+    }
+
+    pub async fn get_element_position(&self, request: &ElementPositionRequest) -> Result<Value> {
+        self.call("get_element_position", request).await
+    }
+
+    pub async fn send_text_to_element(&self, request: &SendTextToElementRequest) -> Result<Value> {
+        self.call("send_text_to_element", request).await
+    }
+
+    pub async fn setup_console_capture(&self, request: &SetupConsoleCaptureRequest) -> Result<Value> {
+        self.call("setup_console_capture", request).await
+    }
+
+    pub async fn get_console_buffer(&self, request: &ConsoleBufferRequest) -> Result<Value> {
+        self.call("get_console_buffer", request).await
+    }
+
+    pub async fn get_console_delta(&self, request: &ConsoleDeltaRequest) -> Result<Value> {
+        self.call("get_console_delta", request).await
+    }
+
+    pub async fn execute_with_console(&self, request: &ExecuteWithConsoleRequest) -> Result<Value> {
+        self.call("execute_with_console", request).await
+    }
+
+    pub async fn get_js_result(&self, request: &GetJsResultRequest) -> Result<Value> {
+        self.call("get_js_result", request).await
+    }
+
+    pub async fn get_timeline(&self, request: &TimelineRequest) -> Result<Value> {
+        self.call("get_timeline", request).await
+    }
+
+    pub async fn search_buffers(&self, request: &SearchBuffersRequest) -> Result<Value> {
+        self.call("search_buffers", request).await
+    }
+
+    pub async fn get_js_errors(&self, request: &JsErrorsRequest) -> Result<Value> {
+        self.call("get_js_errors", request).await
+    }
+
+    pub async fn clear_js_errors(&self, request: &WindowRequest) -> Result<Value> {
+        self.call("clear_js_errors", request).await
+    }
+
+    pub async fn clear_console_buffer(&self, request: &ClearConsoleRequest) -> Result<Value> {
+        self.call("clear_console_buffer", request).await
+    }
+
+    pub async fn add_annotation(&self, request: &AnnotationRequest) -> Result<Value> {
+        self.call("add_annotation", request).await
+    }
+
+    pub async fn list_artifacts(&self) -> Result<Value> {
+        self.call("list_artifacts", &json!({})).await
+    }
+
+    pub async fn wait_for_idle(&self, request: &WaitForIdleRequest) -> Result<Value> {
+        self.call("wait_for_idle", request).await
+    }
+
+    pub async fn set_animations(&self, request: &SetAnimationsRequest) -> Result<Value> {
+        self.call("set_animations", request).await
+    }
+
+    pub async fn control_animation_frames(&self, request: &FrameControlRequest) -> Result<Value> {
+        self.call("control_animation_frames", request).await
+    }
+
+    pub async fn record_frame_timing(&self, request: &FrameTimingRequest) -> Result<Value> {
+        self.call("record_frame_timing", request).await
+    }
+
+    pub async fn get_jank_report(&self, request: &JankReportRequest) -> Result<Value> {
+        self.call("get_jank_report", request).await
+    }
+
+    pub async fn get_event_listeners(&self, request: &EventListenersRequest) -> Result<Value> {
+        self.call("get_event_listeners", request).await
+    }
+
+    pub async fn dispatch_event(&self, request: &DispatchEventRequest) -> Result<Value> {
+        self.call("dispatch_event", request).await
+    }
+
+    pub async fn force_pseudo_state(&self, request: &ForcePseudoStateRequest) -> Result<Value> {
+        self.call("force_pseudo_state", request).await
+    }
+
+    pub async fn eval_on_element(&self, request: &EvalOnElementRequest) -> Result<Value> {
+        self.call("eval_on_element", request).await
+    }
+
+    pub async fn query_elements(&self, request: &QueryElementsRequest) -> Result<Value> {
+        self.call("query_elements", request).await
+    }
+
+    pub async fn extract_table(&self, request: &ExtractTableRequest) -> Result<Value> {
+        self.call("extract_table", request).await
+    }
+
+    pub async fn extract_list(&self, request: &ExtractListRequest) -> Result<Value> {
+        self.call("extract_list", request).await
+    }
+
+    pub async fn diff_text(&self, request: &DiffTextRequest) -> Result<Value> {
+        self.call("diff_text", request).await
+    }
+
+    pub async fn paste(&self, request: &PasteRequest) -> Result<Value> {
+        self.call("paste", request).await
+    }
+
+    pub async fn open_context_menu(&self, request: &OpenContextMenuRequest) -> Result<Value> {
+        self.call("open_context_menu", request).await
+    }
+
+    pub async fn get_open_menu_items(&self, request: &OpenMenuItemsRequest) -> Result<Value> {
+        self.call("get_open_menu_items", request).await
+    }
+
+    pub async fn capture_transient_ui(&self, request: &CaptureTransientUiRequest) -> Result<Value> {
+        self.call("capture_transient_ui", request).await
+    }
+
+    pub async fn audit_keyboard_navigation(&self, request: &KeyboardAuditRequest) -> Result<Value> {
+        self.call("audit_keyboard_navigation", request).await
+    }
+
+    pub async fn detect_nondeterminism(&self, request: &NondeterminismRequest) -> Result<Value> {
+        self.call("detect_nondeterminism", request).await
+    }
+}
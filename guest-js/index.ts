@@ -88,6 +88,13 @@ async function handleGetElementPositionRequest(event: any) {
                     debugInfo.push(`Found ${elemsByTag.length} elements with tag="${selectorValue}", using the first one`);
                 }
                 break;
+            case 'handle':
+                // Handle ids come from the query_elements tool
+                element = resolveElementHandle(selectorValue);
+                if (!element) {
+                    debugInfo.push(`Handle "${selectorValue}" is stale or unknown; query the element again`);
+                }
+                break;
             case 'text':
                 // Find element by text content
                 element = findElementByText(selectorValue);
@@ -203,6 +210,19 @@ async function handleGetElementPositionRequest(event: any) {
     }
 }
 
+// Resolve an element handle registered by the plugin's query tools
+function resolveElementHandle(id: string): Element | null {
+    const handles = (window as any).__mcpHandles;
+    if (!handles) {
+        return null;
+    }
+    try {
+        return handles.resolve(id);
+    } catch (e) {
+        return null;
+    }
+}
+
 // Helper function to find an element by its text content
 function findElementByText(text: string): Element | null {
     // Get all elements in the document
@@ -545,6 +565,13 @@ async function handleSendTextToElementRequest(event: any) {
                     debugInfo.push(`Found ${elemsByTag.length} elements with tag="${selectorValue}", using the first one`);
                 }
                 break;
+            case 'handle':
+                // Handle ids come from the query_elements tool
+                element = resolveElementHandle(selectorValue);
+                if (!element) {
+                    debugInfo.push(`Handle "${selectorValue}" is stale or unknown; query the element again`);
+                }
+                break;
             case 'text':
                 // Find element by text content
                 element = findElementByText(selectorValue);
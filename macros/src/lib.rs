@@ -0,0 +1,151 @@
+//! Attribute macro for declaring tauri-plugin-mcp tools. Use it through the re-export,
+//! `tauri_plugin_mcp::mcp_tool`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    FnArg, GenericArgument, Ident, ItemFn, LitStr, PathArguments, ReturnType, Type,
+    parse_macro_input,
+};
+
+/// Declare a typed tool handler.
+///
+/// ```ignore
+/// #[mcp_tool(name = "click", description = "Click an element", capability = Input)]
+/// pub async fn click<R: Runtime>(app: &AppHandle<R>, request: ClickRequest) -> tauri_plugin_mcp::Result<ClickResponse> {
+///     ...
+/// }
+/// ```
+///
+/// The function is kept as written. Alongside it the macro generates a unit struct named after
+/// the tool (`ClickTool`) implementing `McpTool`, which parses the payload into the request type,
+/// wraps the result in a socket response, publishes the request's JSON schema and registers the
+/// handler. The request type must implement `Deserialize` and `JsonSchema`, the response type
+/// `Serialize`, and the function must be generic over the Tauri runtime.
+///
+/// `capability` is one of `Read`, `Input`, `Eval`, `Window` or `Storage`; tools without one
+/// require `Eval` from restricted clients. `name` defaults to the function name.
+#[proc_macro_attribute]
+pub fn mcp_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut name: Option<LitStr> = None;
+    let mut description: Option<LitStr> = None;
+    let mut capability: Option<Ident> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("description") {
+            description = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("capability") {
+            capability = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `name`, `description` or `capability`"))
+        }
+    });
+    parse_macro_input!(attr with parser);
+    let function = parse_macro_input!(item as ItemFn);
+
+    expand(function, name, description, capability)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(
+    function: ItemFn,
+    name: Option<LitStr>,
+    description: Option<LitStr>,
+    capability: Option<Ident>,
+) -> syn::Result<TokenStream2> {
+    let sig = &function.sig;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new(sig.fn_token.span(), "tool handlers must be `async`"));
+    }
+
+    let request = match sig.inputs.iter().nth(1) {
+        Some(FnArg::Typed(arg)) if sig.inputs.len() == 2 => &arg.ty,
+        _ => {
+            return Err(syn::Error::new(
+                sig.inputs.span(),
+                "tool handlers take `(app: &AppHandle<R>, request: Request)`",
+            ));
+        }
+    };
+    let response = result_ok_type(&sig.output)?;
+
+    let fn_ident = &sig.ident;
+    let name = name.unwrap_or_else(|| LitStr::new(&fn_ident.to_string(), fn_ident.span()));
+    let description = description.unwrap_or_else(|| LitStr::new("", name.span()));
+    let capability = match capability {
+        Some(capability) => quote!(::core::option::Option::Some(::tauri_plugin_mcp::Capability::#capability)),
+        None => quote!(::core::option::Option::None),
+    };
+
+    let vis = &function.vis;
+    let struct_ident = format_ident!("{}Tool", camel_case(&name.value()), span = name.span());
+    let doc = format!("Registration for the `{}` tool", name.value());
+
+    Ok(quote! {
+        #function
+
+        #[doc = #doc]
+        #vis struct #struct_ident;
+
+        impl ::tauri_plugin_mcp::McpTool for #struct_ident {
+            fn info() -> ::tauri_plugin_mcp::ToolInfo {
+                ::tauri_plugin_mcp::ToolInfo {
+                    name: #name,
+                    description: #description,
+                    capability: #capability,
+                    input_schema: ::tauri_plugin_mcp::__private::schema_of::<#request>(),
+                }
+            }
+
+            fn register<R: ::tauri_plugin_mcp::__private::tauri::Runtime>(
+                registry: &::tauri_plugin_mcp::ToolRegistry<R>,
+            ) {
+                registry.register_typed::<#request, #response>(#name, |app, request| {
+                    ::std::boxed::Box::pin(#fn_ident(app, request))
+                });
+            }
+        }
+    })
+}
+
+/// `T` from a return type of the form `Result<T>` or `Result<T, E>`
+fn result_ok_type(output: &ReturnType) -> syn::Result<&Type> {
+    let error = || syn::Error::new(output.span(), "tool handlers must return `Result<Response>`");
+    let ReturnType::Type(_, ty) = output else {
+        return Err(error());
+    };
+    let Type::Path(path) = ty.as_ref() else {
+        return Err(error());
+    };
+    let segment = path.path.segments.last().ok_or_else(error)?;
+    if segment.ident != "Result" {
+        return Err(error());
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Err(error());
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) => Ok(ty),
+        _ => Err(error()),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    name.split(|c: char| c == '_' || c == '-' || c == '.')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
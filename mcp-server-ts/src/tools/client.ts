@@ -5,6 +5,9 @@ import * as fs from 'fs';
 // Constants
 const SOCKET_FILENAME = 'tauri-mcp.sock';
 const DEFAULT_SOCKET_PATH = `/private/tmp/${SOCKET_FILENAME}`;
+// Frames are a 4-byte big-endian length followed by the JSON document
+const FRAME_HEADER_BYTES = 4;
+const MAX_FRAME_BYTES = 64 * 1024 * 1024;
 
 // Connection configuration types
 export interface IpcConfig {
@@ -26,7 +29,8 @@ export class TauriSocketClient {
   private client: net.Socket | null = null;
   private isConnected = false;
   private responseCallbacks: Map<string, { resolve: (value: any) => void, reject: (reason: any) => void }> = new Map();
-  private buffer = '';
+  private chunks: Buffer[] = [];
+  private bufferedBytes = 0;
   private reconnectAttempts = 0;
 
   constructor(config?: ConnectionConfig) {
@@ -86,6 +90,8 @@ export class TauriSocketClient {
       this.client!.on('close', () => {
         this.isConnected = false;
         console.error('Socket connection closed');
+        this.chunks = [];
+        this.bufferedBytes = 0;
         
         // Try to reconnect if not too many attempts
         if (this.reconnectAttempts < 3) {
@@ -102,21 +108,18 @@ export class TauriSocketClient {
   }
   
   private handleData(data: Buffer) {
-    // Accumulate data in the buffer
-    this.buffer += data.toString();
+    // Keep received chunks as-is and only join them once a whole frame has arrived
+    this.chunks.push(data);
+    this.bufferedBytes += data.length;
     
-    console.error(`Received ${data.length} bytes, buffer size: ${this.buffer.length}`);
+    console.error(`Received ${data.length} bytes, buffer size: ${this.bufferedBytes}`);
     
-    // Try to find complete JSON responses that end with newline
-    let newlineIndex;
-    while ((newlineIndex = this.buffer.indexOf('\n')) !== -1) {
-      const jsonStr = this.buffer.substring(0, newlineIndex);
-      this.buffer = this.buffer.substring(newlineIndex + 1);
-      
-      console.error(`Processing JSON response of ${jsonStr.length} bytes`);
+    let frame;
+    while ((frame = this.takeFrame()) !== null) {
+      console.error(`Processing JSON response of ${frame.length} bytes`);
       
       try {
-        const response = JSON.parse(jsonStr);
+        const response = JSON.parse(frame.toString('utf8'));
         
         // Process all matching callbacks that might be waiting for this response
         // Rather than just taking the first one, match based on timestamps (oldest first)
@@ -147,31 +150,54 @@ export class TauriSocketClient {
       } catch (err) {
         console.error('Error parsing response:', err);
         
-        // Log first and last 100 characters of the JSON string for debugging
-        if (jsonStr.length > 200) {
-          console.error(`JSON starts with: ${jsonStr.substring(0, 100)}...`);
-          console.error(`JSON ends with: ...${jsonStr.substring(jsonStr.length - 100)}`);
+        // Log first and last 100 bytes of the frame for debugging
+        if (frame.length > 200) {
+          console.error(`JSON starts with: ${frame.subarray(0, 100).toString('utf8')}...`);
+          console.error(`JSON ends with: ...${frame.subarray(frame.length - 100).toString('utf8')}`);
         } else {
-          console.error(`Full JSON: ${jsonStr}`);
-        }
-        
-        // If parsing failed, this could be a partial message
-        // so we'll just add it back to the buffer and wait for more data
-        // But if it's too large (>10MB), something is wrong, so clear it
-        if (this.buffer.length > 10_000_000) {
-          console.error('Buffer overflow, clearing buffer');
-          this.buffer = '';
-          
-          // Reject any pending callbacks
-          for (const [id, callback] of this.responseCallbacks.entries()) {
-            callback.reject(new Error('Buffer overflow'));
-            this.responseCallbacks.delete(id);
-          }
+          console.error(`Full JSON: ${frame.toString('utf8')}`);
         }
       }
     }
   }
 
+  // Remove and return the next complete frame, or null if it has not fully arrived
+  private takeFrame(): Buffer | null {
+    if (this.bufferedBytes < FRAME_HEADER_BYTES) {
+      return null;
+    }
+    
+    if (this.chunks[0].length < FRAME_HEADER_BYTES) {
+      this.chunks = [Buffer.concat(this.chunks)];
+    }
+    const length = this.chunks[0].readUInt32BE(0);
+    
+    if (length > MAX_FRAME_BYTES) {
+      console.error(`Frame of ${length} bytes exceeds limit, dropping connection`);
+      this.chunks = [];
+      this.bufferedBytes = 0;
+      
+      // Reject any pending callbacks
+      for (const [id, callback] of this.responseCallbacks.entries()) {
+        callback.reject(new Error('Frame too large'));
+        this.responseCallbacks.delete(id);
+      }
+      this.client?.destroy();
+      return null;
+    }
+    
+    if (this.bufferedBytes < FRAME_HEADER_BYTES + length) {
+      return null;
+    }
+    
+    const joined = this.chunks.length === 1 ? this.chunks[0] : Buffer.concat(this.chunks, this.bufferedBytes);
+    const frame = joined.subarray(FRAME_HEADER_BYTES, FRAME_HEADER_BYTES + length);
+    const rest = joined.subarray(FRAME_HEADER_BYTES + length);
+    this.chunks = rest.length > 0 ? [rest] : [];
+    this.bufferedBytes = rest.length;
+    return frame;
+  }
+
   async sendCommand(command: string, payload: Record<string, any> | string = {}): Promise<any> {
     if (!this.isConnected) {
       try {
@@ -198,10 +224,12 @@ export class TauriSocketClient {
         finalPayload = payload;
       }
       
-      const request = JSON.stringify({
+      const body = Buffer.from(JSON.stringify({
         command,
         payload: finalPayload
-      }) + '\n';
+      }), 'utf8');
+      const header = Buffer.allocUnsafe(FRAME_HEADER_BYTES);
+      header.writeUInt32BE(body.length, 0);
 
       // Generate a unique ID for this request including timestamp for ordering
       const requestId = Date.now().toString() + Math.random().toString(36).substring(2);
@@ -211,13 +239,16 @@ export class TauriSocketClient {
       console.error(`Sending request: ${command} with payload: ${JSON.stringify(finalPayload)}`);
       
       // Send the request
-      this.client!.write(request, (err) => {
+      this.client!.cork();
+      this.client!.write(header);
+      this.client!.write(body, (err) => {
         if (err) {
           console.error(`Error writing to socket: ${err.message}`);
           this.responseCallbacks.delete(requestId);
           reject(new Error(`Failed to send request: ${err.message}`));
         }
       });
+      this.client!.uncork();
       
       // Set a timeout to prevent hanging if response never comes
       setTimeout(() => {
@@ -0,0 +1,42 @@
+import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
+import { z } from "zod";
+import { socketClient } from "./client.js";
+import { createContentResponse, createErrorResponse, logCommandParams } from "./response-helpers.js";
+
+export function registerDirectEvalTool(server: McpServer) {
+  server.tool(
+    "direct_eval",
+    "Runs JavaScript in an application window's webview and returns the value it returns. The code is the body of a function: use `return` to get a value back, and set `async` to `await` inside it. Strings come back as text, other values as JSON, and returned canvases, images, ImageBitmaps, ImageData and image Blobs as images. Caution: the code can change the page's state.",
+    {
+      code: z.string().describe("Required. JavaScript statements to run; `return` the value to get back."),
+      window_label: z.string().default("main").describe("Label of the window to run the code in. Defaults to 'main'."),
+      async: z.boolean().optional().describe("Run the code as an async function and return the value its promise resolves to."),
+      timeout_ms: z.number().int().positive().optional().describe("Give up on the code after this many milliseconds."),
+      realm: z.enum(["page", "isolated"]).optional().describe("Globals the code runs against. 'page' (default) is the page's own window; 'isolated' is a hidden frame with its own globals, so helpers the code defines don't land on the page's window."),
+    },
+    {
+      title: "Evaluate JavaScript in an Application Window",
+      readOnlyHint: false,
+      destructiveHint: true,
+      idempotentHint: false,
+      openWorldHint: false,
+    },
+    async ({ code, window_label, async: awaited, timeout_ms, realm }) => {
+      try {
+        if (!code || code.trim() === '') {
+          return createErrorResponse("The code parameter is required and cannot be empty");
+        }
+
+        const params = { code, window_label: window_label || 'main', timeout_ms, realm, content_blocks: true };
+        const command = awaited ? 'eval_async' : 'direct_eval';
+        logCommandParams(command, params);
+
+        const result = await socketClient.sendCommand(command, params);
+        return createContentResponse(result?.content, !result?.success);
+      } catch (error) {
+        console.error('Eval error:', error);
+        return createErrorResponse(`Failed to evaluate JavaScript: ${(error as Error).message}`);
+      }
+    },
+  );
+}
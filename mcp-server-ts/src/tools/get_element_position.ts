@@ -15,7 +15,7 @@ export function registerGetElementPositionTool(server: McpServer) {
     "get_element_position",
     "Finds an HTML element on the page by ID, class, tag name, or text content, and returns its raw position coordinates for use with mouse_movement. Can optionally click the element.",
     {
-      selector_type: z.enum(["id", "class", "tag", "text"]).describe("The type of selector to use: 'id', 'class', 'tag', or 'text'."),
+      selector_type: z.enum(["id", "class", "tag", "text", "handle"]).describe("The type of selector to use: 'id', 'class', 'tag', 'text', or 'handle' (an id returned by query_elements)."),
       selector_value: z.string().describe("The value to search for based on the selector type."),
       window_label: z.string().default("main").describe("The identifier of the application window to search in. Defaults to 'main' if not specified."),
       should_click: z.boolean().default(false).describe("Whether to click the element once found. Default is false."),
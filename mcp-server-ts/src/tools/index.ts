@@ -1,6 +1,7 @@
 import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
 import { registerTakeScreenshotTool } from "./take_screenshot.js";
 import { registerExecuteJsTool } from "./execute_js.js";
+import { registerDirectEvalTool } from "./direct_eval.js";
 import { registerGetDomTool } from "./get_dom.js";
 import { registerManageWindowTool } from "./manage_window.js";
 import { registerManageLocalStorageTool } from "./manage_local_storage.js";
@@ -17,6 +18,7 @@ export { socketClient } from "./client.js";
 export function registerAllTools(server: McpServer) {
   registerTakeScreenshotTool(server);
   registerExecuteJsTool(server);
+  registerDirectEvalTool(server);
   registerGetDomTool(server);
   registerManageWindowTool(server);
   registerManageLocalStorageTool(server);
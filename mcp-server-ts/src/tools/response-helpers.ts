@@ -46,6 +46,21 @@ export function createImageResponse(base64Data: string, mimeType: string = 'imag
   };
 }
 
+/**
+ * Creates a response from content blocks the plugin already built, e.g. for evals run with
+ * `content_blocks: true`
+ *
+ * @param content Text, image and resource blocks from the plugin
+ * @param isError Whether the command failed
+ * @returns Properly formatted response object
+ */
+export function createContentResponse(content: unknown, isError: boolean = false) {
+  if (!Array.isArray(content) || content.length === 0) {
+    return createErrorResponse("The plugin returned no content blocks");
+  }
+  return { isError, content };
+}
+
 /**
  * Helper to safely extract base64 data from various response formats
  * 
@@ -79,6 +94,27 @@ export function extractBase64Data(result: unknown): string | null {
   return null;
 }
 
+/**
+ * Read the MIME type from a data URL in a command result
+ * 
+ * @param result Result from command execution
+ * @param fallback MIME type used when the result carries no data URL
+ * @returns The MIME type of the image
+ */
+export function extractImageMimeType(result: unknown, fallback = 'image/jpeg'): string {
+  const obj = result as Record<string, any> | null;
+  const candidates = [result, obj?.data, obj?.data?.data];
+  for (const candidate of candidates) {
+    if (typeof candidate === 'string') {
+      const match = /^data:(image\/[a-z+.-]+);base64,/.exec(candidate);
+      if (match) {
+        return match[1];
+      }
+    }
+  }
+  return fallback;
+}
+
 /**
  * Format result from command as text, handling different types
  * 
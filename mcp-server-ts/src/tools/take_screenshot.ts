@@ -1,7 +1,7 @@
 import { McpServer } from "@modelcontextprotocol/sdk/server/mcp.js";
 import { z } from "zod";
 import { socketClient } from "./client.js";
-import { createErrorResponse, createImageResponse, extractBase64Data, logCommandParams } from "./response-helpers.js";
+import { createErrorResponse, createImageResponse, extractBase64Data, extractImageMimeType, logCommandParams } from "./response-helpers.js";
 
 export function registerTakeScreenshotTool(server: McpServer) {
   server.tool(
@@ -9,6 +9,9 @@ export function registerTakeScreenshotTool(server: McpServer) {
     "Captures a still image (screenshot) of a designated application window and returns it, typically as a JPEG image. This tool is read-only and does not modify any application or system state. Useful for visual inspection or documentation.",
     {
       window_label: z.string().default("main").describe("The identifier for the window to capture. This could be the window's visible title text or a unique internal label if available. Ensure this label accurately targets the desired window. Defaults to 'main' if not specified."),
+      format: z.enum(["jpeg", "png", "webp"]).optional().describe("Image format. PNG and WebP are lossless. Defaults to the plugin's configured format (JPEG unless changed)."),
+      quality: z.number().int().min(1).max(100).optional().describe("Encoder quality (1-100) for JPEG."),
+      max_width: z.number().int().positive().optional().describe("Downscale the image to at most this width in pixels."),
     },
     {
       title: "Capture Screenshot of a Specific Application Window",
@@ -17,7 +20,7 @@ export function registerTakeScreenshotTool(server: McpServer) {
       idempotentHint: true,
       openWorldHint: false,
     },
-    async ({ window_label }) => {
+    async ({ window_label, format, quality, max_width }) => {
       try {
         // The window_label now has a default value in the schema, so this check is redundant
         // But we'll keep it for extra safety
@@ -25,7 +28,7 @@ export function registerTakeScreenshotTool(server: McpServer) {
           window_label = "main";
         }
         
-        const params = { window_label };
+        const params = { window_label, format, quality, max_width };
         logCommandParams('take_screenshot', params);
         
         const result = await socketClient.sendCommand('take_screenshot', params);
@@ -40,7 +43,7 @@ export function registerTakeScreenshotTool(server: McpServer) {
           return createErrorResponse(`Failed to extract image data from response: ${JSON.stringify(result).substring(0, 100)}...`);
         }
         
-        return createImageResponse(base64Data, 'image/jpeg');
+        return createImageResponse(base64Data, extractImageMimeType(result));
       } catch (error) {
         console.error('Screenshot error:', error);
         return createErrorResponse(`Failed to take screenshot: ${(error as Error).message}`);
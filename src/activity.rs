@@ -0,0 +1,249 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, Webview};
+use tauri::webview::PageLoadEvent;
+use log::warn;
+
+use crate::tools::page_helpers::minify;
+use crate::tools::timeline::now_ms;
+use crate::tools::webview_backend;
+
+/// What one socket connection has been doing, as shown in the details panel
+#[derive(Debug, Clone, Serialize)]
+struct ClientActivity {
+    /// Name from the handshake, if the client gave one
+    name: Option<String>,
+    connected_ms: u64,
+    commands: u64,
+    /// Command being handled right now
+    current: Option<String>,
+    last_command: Option<String>,
+    last_ms: Option<u64>,
+}
+
+#[derive(Default)]
+struct Activity {
+    next_id: u64,
+    clients: BTreeMap<u64, ClientActivity>,
+}
+
+/// Connected socket clients and what they are running, managed in plugin state when
+/// `PluginConfig::activity_indicator` is on. Every change is pushed to the indicator in each window.
+#[derive(Default)]
+pub struct ActivityTracker {
+    inner: Mutex<Activity>,
+}
+
+impl ActivityTracker {
+    fn update<R: Runtime>(&self, app: &AppHandle<R>, change: impl FnOnce(&mut Activity)) {
+        let script = {
+            let mut activity = self.inner.lock().unwrap();
+            change(&mut activity);
+            indicator_script(&activity)
+        };
+        let Ok(backend) = webview_backend::backend(app) else {
+            return;
+        };
+        for label in backend.window_labels() {
+            if let Err(e) = backend.eval(&label, &script) {
+                warn!("[TAURI_MCP] Failed to update the activity indicator in {}: {}", label, e);
+            }
+        }
+    }
+}
+
+/// A socket connection shown by the activity indicator; it disappears from it when dropped
+pub struct ActiveConnection<R: Runtime> {
+    app: AppHandle<R>,
+    id: u64,
+}
+
+impl<R: Runtime> ActiveConnection<R> {
+    /// Register a new connection, or `None` when the indicator is off
+    pub fn open(app: &AppHandle<R>) -> Option<Self> {
+        let tracker = app.try_state::<ActivityTracker>()?;
+        let mut id = 0;
+        tracker.update(app, |activity| {
+            activity.next_id += 1;
+            id = activity.next_id;
+            activity.clients.insert(
+                id,
+                ClientActivity {
+                    name: None,
+                    connected_ms: now_ms(),
+                    commands: 0,
+                    current: None,
+                    last_command: None,
+                    last_ms: None,
+                },
+            );
+        });
+        Some(Self { app: app.clone(), id })
+    }
+
+    /// Show a command as running
+    pub fn begin(&self, command: &str, client_name: Option<&str>) {
+        let tracker = self.app.state::<ActivityTracker>();
+        tracker.update(&self.app, |activity| {
+            if let Some(client) = activity.clients.get_mut(&self.id) {
+                client.name = client_name.map(str::to_string).or(client.name.take());
+                client.commands += 1;
+                client.current = Some(command.to_string());
+            }
+        });
+    }
+
+    /// Show the running command as finished
+    pub fn end(&self) {
+        let tracker = self.app.state::<ActivityTracker>();
+        tracker.update(&self.app, |activity| {
+            if let Some(client) = activity.clients.get_mut(&self.id) {
+                client.last_command = client.current.take();
+                client.last_ms = Some(now_ms());
+            }
+        });
+    }
+}
+
+impl<R: Runtime> Drop for ActiveConnection<R> {
+    fn drop(&mut self) {
+        let tracker = self.app.state::<ActivityTracker>();
+        tracker.update(&self.app, |activity| {
+            activity.clients.remove(&self.id);
+        });
+    }
+}
+
+/// Plugin `on_page_load` hook: a new page gets the indicator while clients are connected
+pub fn page_loaded<R: Runtime>(webview: &Webview<R>, event: PageLoadEvent) {
+    if !matches!(event, PageLoadEvent::Finished) {
+        return;
+    }
+    let Some(tracker) = webview.try_state::<ActivityTracker>() else {
+        return;
+    };
+    let script = {
+        let activity = tracker.inner.lock().unwrap();
+        if activity.clients.is_empty() {
+            return;
+        }
+        indicator_script(&activity)
+    };
+    let shown = webview_backend::backend(webview.app_handle())
+        .and_then(|backend| backend.eval(webview.label(), &script));
+    if let Err(e) = shown {
+        warn!("[TAURI_MCP] Failed to show the activity indicator in {}: {}", webview.label(), e);
+    }
+}
+
+fn indicator_script(activity: &Activity) -> String {
+    let clients: Vec<&ClientActivity> = activity.clients.values().collect();
+    format!(
+        "{}({});",
+        minify(INDICATOR_SCRIPT),
+        serde_json::to_string(&serde_json::json!({ "clients": clients }))
+            .unwrap_or_else(|_| "{\"clients\":[]}".to_string())
+    )
+}
+
+/// Badge in the corner of the page while MCP clients are connected, pulsing while one runs a
+/// command; clicking it lists the clients. It lives in a closed shadow root so DOM tools don't
+/// see it, and styles go through a constructed stylesheet so a strict CSP doesn't strip them.
+const INDICATOR_SCRIPT: &str = r#"
+    (function(state) {
+        if (!window.__mcpShowActivity) {
+            const css = `
+                :host { all: initial; }
+                .badge { pointer-events: auto; cursor: pointer; display: flex; align-items: center; gap: 6px;
+                    margin-left: auto; padding: 4px 10px; border: none; border-radius: 999px;
+                    background: rgba(24, 24, 27, 0.88); color: #fafafa; font: 600 12px system-ui, sans-serif;
+                    box-shadow: 0 2px 8px rgba(0, 0, 0, 0.3); }
+                .dot { width: 8px; height: 8px; border-radius: 50%; background: #22c55e; }
+                .busy .dot { background: #f59e0b; animation: pulse 0.8s ease-in-out infinite alternate; }
+                @keyframes pulse { from { opacity: 1; } to { opacity: 0.3; } }
+                .panel { pointer-events: auto; width: 280px; max-height: 320px; overflow: auto; margin-bottom: 8px;
+                    padding: 10px 12px; border-radius: 8px; background: rgba(24, 24, 27, 0.95); color: #e4e4e7;
+                    font: 12px/1.5 system-ui, sans-serif; box-shadow: 0 4px 16px rgba(0, 0, 0, 0.4); }
+                .panel p { margin: 0 0 8px; }
+                .panel ul { margin: 0; padding: 0; list-style: none; }
+                .panel li { padding: 6px 0; border-top: 1px solid rgba(255, 255, 255, 0.1); }
+                .panel code { font: 11px ui-monospace, monospace; color: #fde68a; }
+                .muted { color: #a1a1aa; }
+            `;
+            let host = null;
+            let root = null;
+            let current = null;
+            let open = false;
+
+            function escapeHtml(text) {
+                return String(text).replace(/[&<>"]/g, function(ch) {
+                    return { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;' }[ch];
+                });
+            }
+            function ago(ms) {
+                const seconds = Math.max(0, Math.round((Date.now() - ms) / 1000));
+                if (seconds < 60) return seconds + 's ago';
+                if (seconds < 3600) return Math.floor(seconds / 60) + 'm ago';
+                return Math.floor(seconds / 3600) + 'h ago';
+            }
+            function describe(client) {
+                let text = '<b>' + escapeHtml(client.name || 'Unnamed client') + '</b><br>' +
+                    '<span class="muted">connected ' + ago(client.connected_ms) + ', ' +
+                    client.commands + (client.commands === 1 ? ' command' : ' commands') + '</span>';
+                if (client.current) {
+                    text += '<br>running <code>' + escapeHtml(client.current) + '</code>';
+                } else if (client.last_command) {
+                    text += '<br>last ran <code>' + escapeHtml(client.last_command) + '</code> ' + ago(client.last_ms);
+                }
+                return text;
+            }
+            function mount() {
+                host = document.createElement('div');
+                host.setAttribute('data-mcp-activity', '');
+                host.style.cssText = 'position:fixed;right:12px;bottom:12px;z-index:2147483647;' +
+                    'display:flex;flex-direction:column;align-items:flex-end;pointer-events:none;';
+                root = host.attachShadow({ mode: 'closed' });
+                if (root.adoptedStyleSheets !== undefined && typeof CSSStyleSheet === 'function') {
+                    const sheet = new CSSStyleSheet();
+                    sheet.replaceSync(css);
+                    root.adoptedStyleSheets = [sheet];
+                } else {
+                    const style = document.createElement('style');
+                    style.textContent = css;
+                    root.appendChild(style);
+                }
+                root.appendChild(document.createElement('div'));
+                (document.documentElement || document.body).appendChild(host);
+            }
+            function render() {
+                if (!current || !current.clients.length) {
+                    if (host) host.remove();
+                    host = null;
+                    open = false;
+                    return;
+                }
+                if (!host || !host.isConnected) mount();
+                const busy = current.clients.some(function(client) { return client.current; });
+                const count = current.clients.length;
+                const content = root.lastChild;
+                content.innerHTML =
+                    (open ? '<div class="panel"><p>This app is being controlled remotely by ' +
+                        (count === 1 ? 'an MCP client' : count + ' MCP clients') + '.</p><ul>' +
+                        current.clients.map(function(client) { return '<li>' + describe(client) + '</li>'; }).join('') +
+                        '</ul></div>' : '') +
+                    '<button class="badge' + (busy ? ' busy' : '') + '" title="MCP clients are connected to this app">' +
+                    '<span class="dot"></span>MCP' + (count > 1 ? ' ×' + count : '') + '</button>';
+                content.querySelector('.badge').addEventListener('click', function() {
+                    open = !open;
+                    render();
+                });
+            }
+            window.__mcpShowActivity = function(next) {
+                current = next;
+                render();
+            };
+        }
+        window.__mcpShowActivity(state);
+    })
+"#;
@@ -0,0 +1,159 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::error::Error;
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::confirmation::{action_of, matches_rules};
+
+/// Longest stretch of code shown in an approval dialog
+const MAX_CODE_PREVIEW: usize = 400;
+
+const ALLOW: &str = "Allow";
+const ALLOW_SESSION: &str = "Allow for this session";
+const DENY: &str = "Deny";
+
+/// Tools that ask the person at the app before they run, managed in plugin state. Rules are
+/// written as for `require_confirmation`: a tool name, or `tool:action`.
+pub struct ApprovalPolicy {
+    rules: Vec<String>,
+}
+
+/// Calls a connection was allowed to make for the rest of its session, as `tool` or `tool:action`
+#[derive(Default)]
+pub struct SessionApprovals {
+    allowed: HashSet<String>,
+}
+
+enum Decision {
+    Allow,
+    AllowSession,
+    Deny,
+}
+
+impl ApprovalPolicy {
+    pub fn new(rules: Vec<String>) -> Self {
+        Self { rules }
+    }
+}
+
+/// What "Allow for this session" covers: the same tool, with the same action if it has one
+fn session_key(command: &str, payload: &Value) -> String {
+    match action_of(payload) {
+        Some(action) => format!("{}:{}", command, action),
+        None => command.to_string(),
+    }
+}
+
+/// The dialog text for a call
+fn describe(client: &str, command: &str, payload: &Value) -> String {
+    let window = payload
+        .get("window_label")
+        .and_then(|v| v.as_str())
+        .unwrap_or("main");
+    let code = payload
+        .get("code")
+        .or_else(|| payload.get("script"))
+        .and_then(|v| v.as_str());
+    match (command, code) {
+        (commands::SIMULATE_MOUSE_MOVEMENT | commands::SIMULATE_TEXT_INPUT, _) => format!(
+            "{} wants to control the mouse and keyboard ({}).",
+            client, command
+        ),
+        (_, Some(code)) => {
+            let mut preview: String = code.chars().take(MAX_CODE_PREVIEW).collect();
+            if preview.len() < code.len() {
+                preview.push('…');
+            }
+            format!(
+                "{} wants to execute JavaScript in window '{}' ({}):\n\n{}",
+                client, window, command, preview
+            )
+        }
+        _ => match action_of(payload) {
+            Some(action) => format!(
+                "{} wants to run {} ({}) in window '{}'.",
+                client, command, action, window
+            ),
+            None => format!("{} wants to run {} in window '{}'.", client, command, window),
+        },
+    }
+}
+
+/// Show the approval dialog on the main thread and wait for the answer. Closing the dialog denies.
+async fn ask<R: Runtime>(app: &AppHandle<R>, description: String) -> crate::Result<Decision> {
+    let title = format!("{}: allow MCP request?", app.package_info().name);
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    app.run_on_main_thread(move || {
+        let result = rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title(title)
+            .set_description(description)
+            .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+                ALLOW.to_string(),
+                ALLOW_SESSION.to_string(),
+                DENY.to_string(),
+            ))
+            .show();
+        let _ = sender.send(result);
+    })
+    .map_err(|e| Error::Anyhow(format!("Failed to show the approval dialog: {}", e)))?;
+    let result = receiver
+        .await
+        .map_err(|_| Error::Anyhow("The approval dialog closed without an answer".to_string()))?;
+
+    Ok(match result {
+        rfd::MessageDialogResult::Custom(label) if label == ALLOW => Decision::Allow,
+        rfd::MessageDialogResult::Custom(label) if label == ALLOW_SESSION => Decision::AllowSession,
+        _ => Decision::Deny,
+    })
+}
+
+/// Ask before a call that needs approval. Returns the response to send instead when the call
+/// must not run; dry runs go through without asking, since they change nothing.
+pub async fn check<R: Runtime>(
+    app: &AppHandle<R>,
+    session: &mut SessionApprovals,
+    client_name: Option<&str>,
+    command: &str,
+    payload: &Value,
+) -> crate::Result<Option<SocketResponse>> {
+    let Some(policy) = app.try_state::<ApprovalPolicy>() else {
+        return Ok(None);
+    };
+    if !matches_rules(&policy.rules, command, payload)
+        || (crate::tools::mutates_page(app, command, payload)
+            && crate::tools::dry_run::requested(payload))
+    {
+        return Ok(None);
+    }
+    let key = session_key(command, payload);
+    if session.allowed.contains(&key) {
+        return Ok(None);
+    }
+
+    let client = match client_name {
+        Some(name) => format!("MCP client '{}'", name),
+        None => "An MCP client".to_string(),
+    };
+    info!("[TAURI_MCP] Asking the user to approve {}", key);
+    match ask(app, describe(&client, command, payload)).await? {
+        Decision::Allow => Ok(None),
+        Decision::AllowSession => {
+            info!("[TAURI_MCP] {} allowed for the rest of the session", key);
+            session.allowed.insert(key);
+            Ok(None)
+        }
+        Decision::Deny => {
+            info!("[TAURI_MCP] The user denied {}", key);
+            Ok(Some(SocketResponse {
+                success: false,
+                data: Some(serde_json::json!({ "approval_denied": true, "tool": command })),
+                error: Some(format!("The user denied {}", key)),
+                ..Default::default()
+            }))
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::error::Error;
+
+/// Metadata about a set of files captured together (e.g. screenshot + DOM + timeline)
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactInfo {
+    pub id: String,
+    pub kind: String,
+    pub reason: String,
+    pub window_label: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub created_ms: u64,
+    pub files: Vec<PathBuf>,
+}
+
+/// On-disk store for captured evidence, one sub-directory per artifact
+pub struct ArtifactStore {
+    dir: PathBuf,
+    next_id: AtomicU64,
+    index: Mutex<Vec<ArtifactInfo>>,
+}
+
+impl ArtifactStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            next_id: AtomicU64::new(1),
+            index: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Allocate a unique artifact id, prefixed with the artifact kind
+    pub fn allocate_id(&self, kind: &str, created_ms: u64) -> String {
+        format!(
+            "{}-{}-{}",
+            kind,
+            created_ms,
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// Write one file belonging to an artifact and return its path
+    pub fn write_file(
+        &self,
+        artifact_id: &str,
+        file_name: &str,
+        contents: &[u8],
+    ) -> crate::Result<PathBuf> {
+        let artifact_dir = self.dir.join(artifact_id);
+        std::fs::create_dir_all(&artifact_dir).map_err(|e| {
+            Error::Io(format!(
+                "Failed to create artifact directory {}: {}",
+                artifact_dir.display(),
+                e
+            ))
+        })?;
+
+        let path = artifact_dir.join(file_name);
+        std::fs::write(&path, contents)
+            .map_err(|e| Error::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+        Ok(path)
+    }
+
+    pub fn register(&self, info: ArtifactInfo) {
+        self.index.lock().unwrap().push(info);
+    }
+
+    pub fn list(&self) -> Vec<ArtifactInfo> {
+        self.index.lock().unwrap().clone()
+    }
+}
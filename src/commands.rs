@@ -1 +1,40 @@
+use log::warn;
+use serde_json::Value;
+use tauri::{Manager, Runtime, State, Webview, command};
 
+use crate::tools::console_capture::{self, ForwardedEntry};
+use crate::tools::triggers::{self, TriggerEvent};
+use crate::tools::webview_backend::{BridgeTokens, PendingCalls};
+
+/// Hands the event bridge in the calling window its token. The label comes from the webview that
+/// made the call, so a page can't ask for another window's token.
+#[command]
+pub(crate) fn bridge_token<R: Runtime>(webview: Webview<R>, tokens: State<'_, BridgeTokens>) -> String {
+    tokens.token(webview.label())
+}
+
+/// Delivers the reply to a script the plugin ran. Only the window the script was sent to can
+/// complete the call.
+#[command]
+pub(crate) fn eval_result<R: Runtime>(
+    webview: Webview<R>,
+    pending: State<'_, PendingCalls>,
+    id: String,
+    reply: Value,
+) {
+    if !pending.resolve(&id, webview.label(), reply) {
+        warn!("[TAURI_MCP] Ignoring a result from '{}' for a call it was not sent", webview.label());
+    }
+}
+
+/// Stores a console entry or error from the capture script under the calling window
+#[command]
+pub(crate) fn console_entry<R: Runtime>(webview: Webview<R>, forwarded: ForwardedEntry) {
+    console_capture::record_entry(webview.app_handle(), webview.label(), forwarded);
+}
+
+/// Reports something the capture script saw that may fire a capture trigger for the calling window
+#[command]
+pub(crate) fn capture_trigger<R: Runtime>(webview: Webview<R>, trigger: TriggerEvent) {
+    triggers::fire(webview.app_handle(), webview.label(), trigger);
+}
@@ -5,8 +5,9 @@ use crate::shared::{
     ScreenshotResult as SharedScreenshotResult, TextInputParams, TextInputResult,
     WindowManagerParams, WindowManagerResult,
 };
+use crate::redaction::Redactor;
 use crate::socket_server::SocketServer;
-use crate::tools::mouse_movement;
+use crate::tools::{mouse_movement, sensitive_regions};
 use crate::{PluginConfig, Result};
 use enigo::{Enigo, Keyboard, Settings};
 use serde::de::DeserializeOwned;
@@ -49,7 +50,11 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
     config: &PluginConfig,
 ) -> crate::Result<TauriMcp<R>> {
     let socket_server = if config.start_socket_server {
-        let mut server = SocketServer::new(app.clone(), config.socket_type.clone());
+        let mut server = SocketServer::new(
+            app.clone(),
+            config.socket_type.clone(),
+            config.security.clone(),
+        );
         server.start()?;
         Some(Arc::new(Mutex::new(server)))
     } else {
@@ -60,6 +65,9 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
         app: app.clone(),
         socket_server,
         application_name: config.application_name.clone(),
+        redactor: config.redactor.clone(),
+        image_format: config.image_format,
+        image_quality: config.image_quality,
     })
 }
 
@@ -68,15 +76,40 @@ pub struct TauriMcp<R: Runtime> {
     app: AppHandle<R>,
     socket_server: Option<Arc<Mutex<SocketServer<R>>>>,
     application_name: String,
+    redactor: Redactor,
+    image_format: crate::ImageFormat,
+    image_quality: Option<u8>,
 }
 
 impl<R: Runtime> TauriMcp<R> {
+    /// Redaction hooks configured for this plugin instance
+    pub(crate) fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    /// Make a tool declared with [`crate::mcp_tool`] available to socket clients
+    pub fn register_tool<T: crate::McpTool>(&self) {
+        self.app.state::<crate::ToolRegistry<R>>().add::<T>();
+    }
+
     pub fn ping(&self, payload: PingRequest) -> crate::Result<PingResponse> {
         Ok(PingResponse {
             value: payload.value,
         })
     }
 
+    // Report socket server transport and network policy
+    pub fn server_status(&self) -> crate::Result<ServerStatusResponse> {
+        let server = self
+            .socket_server
+            .as_ref()
+            .ok_or_else(|| Error::Anyhow("Socket server is not running".to_string()))?;
+        let server = server
+            .lock()
+            .map_err(|_| Error::Anyhow("Socket server state is poisoned".to_string()))?;
+        Ok(server.status())
+    }
+
     // Take screenshot - this feature depends on Tauri's window capabilities
     pub async fn take_screenshot_async(
         &self,
@@ -92,8 +125,9 @@ impl<R: Runtime> TauriMcp<R> {
         // Create shared parameters struct from the request
         let params = ScreenshotParams {
             window_label: Some(window_label),
-            quality: None,
-            max_width: None,
+            format: Some(payload.format.unwrap_or(self.image_format)),
+            quality: payload.quality.or(self.image_quality).map(i32::from),
+            max_width: payload.max_width.map(|w| w as i32),
             max_size_mb: None,
             application_name: Some(self.application_name.clone()),
         };
@@ -103,10 +137,59 @@ impl<R: Runtime> TauriMcp<R> {
             window: window.clone(),
         };
 
-        info!("[TAURI_MCP] Taking screenshot with default parameters");
+        // Sensitive regions are masked in the page around the capture. If that fails they may
+        // be showing, so no screenshot is taken.
+        let regions = sensitive_regions::regions(&self.app);
+        if !regions.is_empty() {
+            sensitive_regions::mask(&self.app, &payload.window_label, &regions)
+                .await
+                .map_err(|e| {
+                    Error::WindowOperationFailed(format!(
+                        "Could not mask sensitive regions, so no screenshot was taken: {}",
+                        e
+                    ))
+                })?;
+        }
+
+        info!("[TAURI_MCP] Taking screenshot as {:?}", params.format.unwrap_or_default());
 
         // Use platform-specific implementation to capture the window
-        crate::platform::current::take_screenshot(params, window_context).await
+        let result = crate::platform::current::take_screenshot(params, window_context).await;
+        if !regions.is_empty() {
+            sensitive_regions::unmask(&self.app, &payload.window_label).await;
+        }
+        result
+    }
+
+    /// Run `code` as the body of an async function in a window and return the value it
+    /// returns, through the same bridge the eval tools use. Exceptions and rejected promises
+    /// become errors. Gives up after 30 seconds; see [`Self::eval_with_timeout`].
+    pub async fn eval(&self, window_label: &str, code: &str) -> Result<serde_json::Value> {
+        self.eval_with_timeout(window_label, code, Duration::from_secs(30)).await
+    }
+
+    pub async fn eval_with_timeout(
+        &self,
+        window_label: &str,
+        code: &str,
+        timeout: Duration,
+    ) -> Result<serde_json::Value> {
+        crate::tools::js_bridge::eval_with_result(&self.app, window_label, code, timeout).await
+    }
+
+    /// [`Self::eval`], deserializing the returned value into `T`
+    pub async fn eval_as<T: DeserializeOwned>(&self, window_label: &str, code: &str) -> Result<T> {
+        let value = self.eval(window_label, code).await?;
+        serde_json::from_value(value)
+            .map_err(|e| Error::Anyhow(format!("Unexpected eval result: {}", e)))
+    }
+
+    /// Mark the app as ready, e.g. once its initial data has loaded. `benchmark_startup` then
+    /// measures time to ready up to the first call instead of the main window's page load.
+    pub fn mark_app_ready(&self) {
+        if let Some(tracker) = self.app.try_state::<crate::tools::startup::StartupTracker>() {
+            tracker.mark_app_ready();
+        }
     }
 
     // Add async method to perform window operations
@@ -294,8 +377,14 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
     ) -> std::result::Result<SharedScreenshotResult, String> {
         // Create a ScreenshotRequest from our interface params
         let window_label = params.window_label.unwrap_or_else(|| "main".to_string());
+        let format = params.format.unwrap_or(self.image_format);
 
-        let request = ScreenshotRequest { window_label };
+        let request = ScreenshotRequest {
+            window_label,
+            format: Some(format),
+            quality: params.quality.map(|q| q.clamp(1, 100) as u8),
+            max_width: params.max_width.map(|w| w.max(1) as u32),
+        };
         match futures::executor::block_on(self.take_screenshot_async(request)) {
             Ok(response) => {
                 // Convert to the shared result type
@@ -303,7 +392,7 @@ impl<R: Runtime> McpInterface for TauriMcp<R> {
                     success: response.success,
                     error: response.error,
                     data: response.data,
-                    mime_type: Some("image/jpeg".to_string()),
+                    mime_type: Some(format.mime_type().to_string()),
                 })
             }
             Err(err) => {
@@ -0,0 +1,200 @@
+//! Minimal Chrome DevTools Protocol client for webviews that expose a remote debugging port
+//! (WebView2 started with `--remote-debugging-port`).
+
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use log::info;
+
+use crate::error::Error;
+use crate::tools::js_bridge::eval_with_result;
+
+/// Remote debugging port configured with `PluginConfig::devtools_port`, managed in plugin state
+pub struct DevtoolsPort(pub Option<u16>);
+
+/// Error for tools that need a devtools endpoint when none is configured or requested
+pub const NO_ENDPOINT: &str =
+    "No devtools endpoint: configure PluginConfig::devtools_port (WebView2 only) or pass `port`";
+
+/// The port a request asked for, or else the configured one
+pub fn resolve_port<R: Runtime>(app: &AppHandle<R>, requested: Option<u16>) -> Option<u16> {
+    requested.or_else(|| app.try_state::<DevtoolsPort>().and_then(|port| port.0))
+}
+
+/// Ask WebView2 to open a remote debugging port. Must run before the first webview is created,
+/// which plugin initialization does.
+#[cfg(target_os = "windows")]
+pub fn enable_remote_debugging(port: u16) {
+    const VARIABLE: &str = "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS";
+    let flag = format!("--remote-debugging-port={}", port);
+    let arguments = match std::env::var(VARIABLE) {
+        Ok(existing) if existing.contains("--remote-debugging-port") => return,
+        Ok(existing) if !existing.trim().is_empty() => format!("{} {}", existing, flag),
+        _ => flag,
+    };
+    info!("[TAURI_MCP] Enabling the WebView2 remote debugging port {}", port);
+    // SAFETY: plugins are built before the app starts its webview and runtime threads
+    unsafe { std::env::set_var(VARIABLE, arguments) };
+}
+
+/// Only WebView2 speaks the DevTools Protocol; WebKit's inspector uses its own
+#[cfg(not(target_os = "windows"))]
+pub fn enable_remote_debugging(port: u16) {
+    info!(
+        "[TAURI_MCP] Not opening devtools port {}: the platform webview has no DevTools Protocol endpoint",
+        port
+    );
+}
+
+/// A debuggable page listed by the endpoint's `/json/list`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevtoolsTarget {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub web_socket_debugger_url: Option<String>,
+}
+
+/// Debuggable targets on `127.0.0.1:port`
+pub async fn list_targets(port: u16) -> crate::Result<Vec<DevtoolsTarget>> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await.map_err(|e| {
+        Error::Io(format!("No devtools endpoint on port {}: {}", port, e))
+    })?;
+    let request = format!("GET /json/list HTTP/1.0\r\nHost: 127.0.0.1:{}\r\n\r\n", port);
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).await?;
+
+    let response = String::from_utf8_lossy(&response);
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body)
+        .ok_or_else(|| Error::Anyhow("Malformed devtools target list".to_string()))?;
+    serde_json::from_str(body)
+        .map_err(|e| Error::Anyhow(format!("Failed to parse devtools target list: {}", e)))
+}
+
+/// An open DevTools Protocol connection to one page
+pub struct CdpSession {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+}
+
+impl CdpSession {
+    pub async fn connect(target: &DevtoolsTarget) -> crate::Result<Self> {
+        let url = target.web_socket_debugger_url.as_deref().ok_or_else(|| {
+            Error::Anyhow(format!("Page '{}' is already being debugged by another client", target.url))
+        })?;
+        let (socket, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|e| Error::Io(format!("Failed to connect to {}: {}", url, e)))?;
+        Ok(Self { socket, next_id: 1 })
+    }
+
+    /// Send a command and wait for its result, handing every event received meanwhile to
+    /// `on_event` as `(method, params)`
+    pub async fn call(
+        &mut self,
+        method: &str,
+        params: Value,
+        mut on_event: impl FnMut(&str, &Value),
+    ) -> crate::Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let command = serde_json::json!({ "id": id, "method": method, "params": params });
+        self.socket
+            .send(Message::Text(command.to_string()))
+            .await
+            .map_err(|e| Error::Io(format!("Failed to send {}: {}", method, e)))?;
+
+        while let Some(message) = self.socket.next().await {
+            let text = match message {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Close(_)) => break,
+                Ok(_) => continue,
+                Err(e) => return Err(Error::Io(format!("Devtools connection failed: {}", e))),
+            };
+            let Ok(message) = serde_json::from_str::<Value>(&text) else {
+                continue;
+            };
+            if message.get("id").and_then(|v| v.as_u64()) == Some(id) {
+                if let Some(error) = message.get("error") {
+                    return Err(Error::Anyhow(format!(
+                        "{} failed: {}",
+                        method,
+                        error.get("message").and_then(|v| v.as_str()).unwrap_or("unknown error")
+                    )));
+                }
+                return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+            }
+            if let Some(event) = message.get("method").and_then(|v| v.as_str()) {
+                on_event(event, message.get("params").unwrap_or(&Value::Null));
+            }
+        }
+        Err(Error::Io(format!("Devtools connection closed during {}", method)))
+    }
+}
+
+/// Connect to the page shown in `window`. Pages are matched by URL; when several windows show
+/// the same URL, a marker set through the plugin's own eval tells them apart.
+pub async fn connect_window<R: Runtime>(
+    window: &WebviewWindow<R>,
+    port: u16,
+) -> crate::Result<(CdpSession, DevtoolsTarget)> {
+    let url = window.url()?.to_string();
+    let mut candidates: Vec<DevtoolsTarget> = list_targets(port)
+        .await?
+        .into_iter()
+        .filter(|target| target.kind == "page" && target.url == url)
+        .collect();
+
+    match candidates.len() {
+        0 => Err(Error::Anyhow(format!(
+            "The devtools endpoint on port {} has no page for window '{}' ({})",
+            port,
+            window.label(),
+            url
+        ))),
+        1 => {
+            let target = candidates.remove(0);
+            Ok((CdpSession::connect(&target).await?, target))
+        }
+        _ => {
+            let marker = format!("{}-{}", window.label(), crate::tools::timeline::now_ms());
+            let set_marker = format!(
+                "window.__mcpDevtoolsMarker = {}; return true;",
+                serde_json::to_string(&marker).unwrap_or_default()
+            );
+            let timeout = std::time::Duration::from_secs(5);
+            eval_with_result(window.app_handle(), window.label(), &set_marker, timeout).await?;
+            for target in candidates {
+                let Ok(mut session) = CdpSession::connect(&target).await else {
+                    continue;
+                };
+                let probe = session
+                    .call(
+                        "Runtime.evaluate",
+                        serde_json::json!({ "expression": "window.__mcpDevtoolsMarker", "returnByValue": true }),
+                        |_, _| {},
+                    )
+                    .await?;
+                if probe.pointer("/result/value").and_then(|v| v.as_str()) == Some(marker.as_str()) {
+                    return Ok((session, target));
+                }
+            }
+            Err(Error::Anyhow(format!(
+                "Could not tell which devtools page belongs to window '{}'",
+                window.label()
+            )))
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use image::{DynamicImage, ImageOutputFormat};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use log::{info, warn};
+
+use crate::error::Error;
+
+/// Encoder threads started when the pool is used before the plugin configured it
+const DEFAULT_ENCODER_THREADS: usize = 2;
+
+static POOL: OnceLock<EncoderPool> = OnceLock::new();
+
+/// Output format for screenshots and recordings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    #[default]
+    Jpeg,
+    Png,
+    /// Lossless; `quality` is ignored
+    Webp,
+}
+
+impl ImageFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+
+    /// Whether lowering the quality shrinks the output
+    pub fn is_lossy(self) -> bool {
+        matches!(self, ImageFormat::Jpeg)
+    }
+
+    fn output_format(self, quality: u8) -> ImageOutputFormat {
+        match self {
+            ImageFormat::Jpeg => ImageOutputFormat::Jpeg(quality),
+            ImageFormat::Png => ImageOutputFormat::Png,
+            ImageFormat::Webp => ImageOutputFormat::WebP,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EncodedImage {
+    /// `data:<mime>;base64,...`
+    pub data_url: String,
+    /// Size of the encoded image before base64
+    pub byte_len: usize,
+}
+
+type EncodeReply = Box<dyn FnOnce(std::result::Result<EncodedImage, String>) + Send>;
+
+struct EncodeJob {
+    image: Arc<DynamicImage>,
+    format: ImageFormat,
+    quality: u8,
+    reply: EncodeReply,
+}
+
+/// Dedicated threads for image encoding, so captures never occupy the async runtime or the
+/// shared blocking pool. Each worker keeps its output buffer between jobs.
+struct EncoderPool {
+    sender: Mutex<mpsc::Sender<EncodeJob>>,
+}
+
+impl EncoderPool {
+    fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<EncodeJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            if let Err(e) = thread::Builder::new()
+                .name(format!("tauri-mcp-encoder-{}", index))
+                .spawn(move || encoder_worker(receiver))
+            {
+                warn!("[TAURI_MCP] Failed to start image encoder thread: {}", e);
+            }
+        }
+        info!("[TAURI_MCP] Started {} image encoder thread(s)", threads.max(1));
+
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    fn submit(&self, job: EncodeJob) -> crate::Result<()> {
+        self.sender
+            .lock()
+            .map_err(|_| Error::Anyhow("Image encoder pool is poisoned".to_string()))?
+            .send(job)
+            .map_err(|_| Error::Anyhow("Image encoder pool has shut down".to_string()))
+    }
+}
+
+fn encoder_worker(receiver: Arc<Mutex<mpsc::Receiver<EncodeJob>>>) {
+    let mut buffer: Vec<u8> = Vec::new();
+    loop {
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(job) = job else {
+            return;
+        };
+
+        buffer.clear();
+        let result = job
+            .image
+            .write_to(
+                &mut Cursor::new(&mut buffer),
+                job.format.output_format(job.quality),
+            )
+            .map(|_| EncodedImage {
+                data_url: format!(
+                    "data:{};base64,{}",
+                    job.format.mime_type(),
+                    base64::encode(&buffer)
+                ),
+                byte_len: buffer.len(),
+            })
+            .map_err(|e| e.to_string());
+        (job.reply)(result);
+    }
+}
+
+/// Size the encoder pool. Only the first call (or first use) takes effect.
+pub fn init_pool(threads: usize) {
+    if POOL.set(EncoderPool::new(threads)).is_err() {
+        warn!("[TAURI_MCP] Image encoder pool already initialized");
+    }
+}
+
+fn pool() -> &'static EncoderPool {
+    POOL.get_or_init(|| EncoderPool::new(DEFAULT_ENCODER_THREADS))
+}
+
+/// Encode on the pool and block the calling thread until done. For use from blocking contexts.
+pub fn encode_blocking(
+    image: Arc<DynamicImage>,
+    format: ImageFormat,
+    quality: u8,
+) -> crate::Result<EncodedImage> {
+    let (tx, rx) = mpsc::channel();
+    pool().submit(EncodeJob {
+        image,
+        format,
+        quality,
+        reply: Box::new(move |result| {
+            let _ = tx.send(result);
+        }),
+    })?;
+
+    rx.recv()
+        .map_err(|_| Error::Anyhow("Image encoder dropped the job".to_string()))?
+        .map_err(|e| Error::WindowOperationFailed(format!("Failed to encode image: {}", e)))
+}
@@ -0,0 +1,231 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use serde::Serialize;
+use std::io::{self, BufRead, IoSlice, Write};
+
+/// Largest frame accepted from a client
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// How messages are delimited on a connection. Detected from the first byte the client sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// One JSON document per line (the original protocol)
+    Line,
+    /// A 4-byte big-endian length followed by the JSON document
+    LengthPrefixed,
+}
+
+impl Framing {
+    /// Peek at the next byte without consuming it. JSON never starts with a byte below `\t`,
+    /// while a length prefix within [`MAX_FRAME_LEN`] always does. Returns `None` at end of stream.
+    pub fn detect<R: BufRead>(reader: &mut R) -> io::Result<Option<Framing>> {
+        let available = loop {
+            match reader.fill_buf() {
+                Ok(available) => break available,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+
+        Ok(available.first().map(|&byte| {
+            if byte < b'\t' {
+                Framing::LengthPrefixed
+            } else {
+                Framing::Line
+            }
+        }))
+    }
+}
+
+/// Reads frames into a reused buffer. Each frame is split off as an immutable `Bytes`,
+/// so the allocation is reclaimed once the previous frame has been dropped.
+#[derive(Default)]
+pub struct FrameReader {
+    buf: BytesMut,
+}
+
+impl FrameReader {
+    /// Read the next frame, without its delimiter. Returns `None` at end of stream.
+    pub fn read_frame<R: BufRead>(
+        &mut self,
+        reader: &mut R,
+        framing: Framing,
+    ) -> io::Result<Option<Bytes>> {
+        self.buf.clear();
+        match framing {
+            Framing::Line => self.read_line(reader),
+            Framing::LengthPrefixed => self.read_prefixed(reader),
+        }
+    }
+
+    fn read_line<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<Bytes>> {
+        loop {
+            let available = match reader.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            if available.is_empty() {
+                // A trailing line without a newline still counts as a frame
+                return Ok((!self.buf.is_empty()).then(|| self.buf.split().freeze()));
+            }
+
+            match available.iter().position(|&byte| byte == b'\n') {
+                Some(end) => {
+                    self.buf.extend_from_slice(&available[..end]);
+                    reader.consume(end + 1);
+                    return Ok(Some(self.buf.split().freeze()));
+                }
+                None => {
+                    let len = available.len();
+                    self.buf.extend_from_slice(available);
+                    reader.consume(len);
+                }
+            }
+
+            if self.buf.len() > MAX_FRAME_LEN {
+                return Err(frame_too_large(self.buf.len()));
+            }
+        }
+    }
+
+    fn read_prefixed<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<Bytes>> {
+        let mut header = [0u8; 4];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let len = u32::from_be_bytes(header) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(frame_too_large(len));
+        }
+
+        self.buf.reserve(len);
+        while self.buf.len() < len {
+            let available = match reader.fill_buf() {
+                Ok(available) => available,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Connection closed in the middle of a frame",
+                ));
+            }
+
+            let take = available.len().min(len - self.buf.len());
+            self.buf.extend_from_slice(&available[..take]);
+            reader.consume(take);
+        }
+
+        Ok(Some(self.buf.split().freeze()))
+    }
+}
+
+fn frame_too_large(len: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Frame of {} bytes exceeds the {} byte limit", len, MAX_FRAME_LEN),
+    )
+}
+
+/// Serialize a value straight into a `Bytes` buffer
+pub fn encode_json<T: Serialize>(value: &T) -> serde_json::Result<Bytes> {
+    let mut writer = BytesMut::new().writer();
+    serde_json::to_writer(&mut writer, value)?;
+    Ok(writer.into_inner().freeze())
+}
+
+/// Write one frame and flush. The delimiter and payload go out in a single vectored write
+/// where the stream supports it, so the payload is never copied to append a delimiter.
+pub fn write_frame<W: Write>(writer: &mut W, framing: Framing, payload: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(payload.len()).map_err(|_| frame_too_large(payload.len()))?;
+    let header = len.to_be_bytes();
+    let parts: [&[u8]; 3] = match framing {
+        Framing::Line => [&[], payload, b"\n"],
+        Framing::LengthPrefixed => [&header, payload, &[]],
+    };
+
+    write_all_vectored(writer, parts)?;
+    writer.flush()
+}
+
+fn write_all_vectored<W: Write>(writer: &mut W, mut parts: [&[u8]; 3]) -> io::Result<()> {
+    while parts.iter().any(|part| !part.is_empty()) {
+        let slices = parts.map(IoSlice::new);
+        let mut written = match writer.write_vectored(&slices) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => written,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        for part in parts.iter_mut() {
+            let advance = written.min(part.len());
+            *part = &part[advance..];
+            written -= advance;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn frames(input: &[u8]) -> (Framing, Vec<Bytes>) {
+        let mut reader = Cursor::new(input);
+        let framing = Framing::detect(&mut reader).unwrap().unwrap();
+        let mut frame_reader = FrameReader::default();
+        let mut frames = Vec::new();
+        while let Some(frame) = frame_reader.read_frame(&mut reader, framing).unwrap() {
+            frames.push(frame);
+        }
+        (framing, frames)
+    }
+
+    #[test]
+    fn detects_nothing_at_end_of_stream() {
+        assert_eq!(Framing::detect(&mut Cursor::new(b"")).unwrap(), None);
+    }
+
+    #[test]
+    fn reads_lines_including_an_unterminated_last_one() {
+        let (framing, frames) = frames(b"{\"a\":1}\n{\"b\":2}");
+        assert_eq!(framing, Framing::Line);
+        assert_eq!(frames, [&b"{\"a\":1}"[..], &b"{\"b\":2}"[..]]);
+    }
+
+    #[test]
+    fn round_trips_length_prefixed_frames() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, Framing::LengthPrefixed, b"{\"a\":1}").unwrap();
+        write_frame(&mut buffer, Framing::LengthPrefixed, b"{}").unwrap();
+        let (framing, frames) = frames(&buffer);
+        assert_eq!(framing, Framing::LengthPrefixed);
+        assert_eq!(frames, [&b"{\"a\":1}"[..], &b"{}"[..]]);
+    }
+
+    #[test]
+    fn rejects_frames_over_the_limit() {
+        let header = ((MAX_FRAME_LEN + 1) as u32).to_be_bytes();
+        let error = FrameReader::default()
+            .read_frame(&mut Cursor::new(&header[..]), Framing::LengthPrefixed)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn reports_a_frame_cut_short() {
+        let mut input = 10u32.to_be_bytes().to_vec();
+        input.extend_from_slice(b"{}");
+        let error = FrameReader::default()
+            .read_frame(&mut Cursor::new(input), Framing::LengthPrefixed)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,30 +1,65 @@
 use tauri::{
-    Manager, Runtime,
+    Manager, RunEvent, Runtime,
     plugin::{Builder, TauriPlugin},
 };
 use log::info;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 pub use models::*;
 
+// Lets `#[mcp_tool]` output refer to `::tauri_plugin_mcp` inside this crate too
+extern crate self as tauri_plugin_mcp;
+
 #[cfg(desktop)]
 mod desktop;
 #[cfg(mobile)]
 mod mobile;
 
+mod activity;
+#[cfg(feature = "approval")]
+mod approval;
+mod artifacts;
 mod commands;
+mod devtools;
+mod encoding;
 mod error;
+mod framing;
 mod models;
+mod notifications;
+mod redaction;
+mod security;
 pub mod shared;
 mod socket_server;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 mod tools;
 // Platform-specific module
 mod platform;
 
+pub use encoding::ImageFormat;
+pub use tools::console_capture::{ConsoleLevel, SamplingRule};
 pub use error::{Error, Result};
+pub use redaction::RedactionHook;
+pub use tools::page_helpers::InjectionMode;
+pub use tools::sensitive_regions::RegionMask;
+pub use tools::triggers::CaptureTrigger;
+pub use tools::webview_backend::{BackendFuture, TauriBackend, WebviewBackend};
+pub use tools::registry::{McpTool, ToolFuture, ToolHandler, ToolInfo, ToolRegistry};
+pub use socket_server::SocketResponse;
+pub use tauri_plugin_mcp_macros::mcp_tool;
+pub use security::{Capability, ClientGrant, SecurityStatus, TlsConfig, TransportSecurity};
 pub use shared::{
     McpInterface, ScreenshotParams, ScreenshotResult, WindowManagerParams, WindowManagerResult,
 };
 
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::tools::registry::schema_of;
+    pub use tauri;
+}
+
 #[cfg(desktop)]
 use desktop::TauriMcp;
 #[cfg(mobile)]
@@ -51,7 +86,7 @@ pub enum SocketType {
     },
     /// Use TCP socket
     Tcp {
-        /// Host to bind to (e.g., "127.0.0.1" or "0.0.0.0")
+        /// Host to bind to (e.g., "127.0.0.1"). Non-loopback hosts require [`PluginConfig::allow_remote`].
         host: String,
         /// Port to bind to
         port: u16,
@@ -65,7 +100,6 @@ impl Default for SocketType {
 }
 
 /// Plugin configuration options.
-#[derive(Default)]
 pub struct PluginConfig {
     /// Application name (used for default socket naming)
     pub application_name: String,
@@ -73,6 +107,78 @@ pub struct PluginConfig {
     pub socket_type: SocketType,
     /// Whether to start the socket server automatically. Default is true.
     pub start_socket_server: bool,
+    /// Hooks applied to responses carrying page content (eval results, console entries and errors,
+    /// the network log, DOM and text) and to every notification before they reach clients
+    pub redactor: redaction::Redactor,
+    /// Network access policy for the socket server
+    pub security: TransportSecurity,
+    /// Conditions that automatically capture a screenshot, DOM snapshot and recent timeline
+    pub capture_triggers: Vec<CaptureTrigger>,
+    /// Directory for captured artifacts. Defaults to a per-application folder in the temp dir.
+    pub artifacts_dir: Option<std::path::PathBuf>,
+    /// How long observation tool responses are reused. `None` disables the cache.
+    pub observation_cache_ttl: Option<std::time::Duration>,
+    /// Default screenshot format when a request does not name one
+    pub image_format: ImageFormat,
+    /// Default encoder quality (1-100) for lossy formats
+    pub image_quality: Option<u8>,
+    /// Number of dedicated image encoder threads. Defaults to 2.
+    pub encoder_threads: Option<usize>,
+    /// How page helpers and the capture script are injected into webviews
+    pub injection_mode: InjectionMode,
+    /// Console messages below this level are dropped in the page unless a capture request overrides it
+    pub console_min_level: Option<ConsoleLevel>,
+    /// Sampling rules for chatty console messages, applied in the page
+    pub console_sampling: Vec<SamplingRule>,
+    /// Console entries each window keeps, in the page and in the plugin's store
+    pub console_max_entries: usize,
+    /// Approximate bytes of console output each window keeps
+    pub console_max_bytes: usize,
+    /// Install console capture in every page as an initialization script, and set it up again
+    /// after each navigation or reload
+    pub console_capture_on_load: bool,
+    /// Evaluates scripts in webviews. `None` uses Tauri's webview API.
+    pub webview_backend: Option<Arc<dyn WebviewBackend>>,
+    /// Helper scripts installed into every page before its own scripts run
+    pub init_scripts: Vec<String>,
+    /// Longest eval result, in characters of JSON text, returned in one response
+    pub max_eval_result_size: usize,
+    /// Label of the splash window. `None` treats the first window labelled like "splash" as one.
+    pub splash_window: Option<String>,
+    /// Register the event bridge initialization script so evals that a page's CSP silently drops
+    /// are retried through it. Default is true, for [`init`] as well.
+    pub eval_fallback: bool,
+    /// Number of `direct_eval` and `execute_with_console` calls kept for `get_eval_history`
+    /// (default 200); 0 records nothing
+    pub eval_history_size: usize,
+    /// Tool call durations above which a `latency` notification is pushed, by tool name (`"*"`
+    /// for every tool without its own)
+    pub latency_budgets: HashMap<String, std::time::Duration>,
+    /// Remote debugging port opened in WebView2 for DevTools Protocol tools such as heap snapshots
+    pub devtools_port: Option<u16>,
+    /// Add `requestId`, `durationMs`, `windowLabel` and `tool` to every socket response. Default
+    /// is true.
+    pub response_metadata: bool,
+    /// Tools, or `tool:action` pairs, that only run after a `confirm` call
+    pub confirmation_required: Vec<String>,
+    /// How long a held operation waits for its `confirm` call
+    pub confirmation_timeout: std::time::Duration,
+    /// Tools, or `tool:action` pairs, the user approves in a dialog before they run
+    #[cfg(feature = "approval")]
+    pub approval_required: Vec<String>,
+    /// Show a badge in every window while MCP clients are connected
+    pub activity_indicator: bool,
+    /// CSS selectors of elements screenshots mask and DOM snapshots redact
+    pub sensitive_regions: Vec<String>,
+    /// How screenshots mask sensitive regions
+    pub sensitive_region_mask: RegionMask,
+}
+
+impl Default for PluginConfig {
+    /// The defaults of [`PluginConfig::new`], without an application name
+    fn default() -> Self {
+        Self::new(String::new())
+    }
 }
 
 impl PluginConfig {
@@ -82,6 +188,36 @@ impl PluginConfig {
             application_name,
             socket_type: SocketType::default(),
             start_socket_server: true,
+            redactor: redaction::Redactor::default(),
+            security: TransportSecurity::default(),
+            capture_triggers: Vec::new(),
+            artifacts_dir: None,
+            observation_cache_ttl: None,
+            image_format: ImageFormat::default(),
+            image_quality: None,
+            encoder_threads: None,
+            injection_mode: InjectionMode::default(),
+            console_min_level: None,
+            console_sampling: Vec::new(),
+            console_max_entries: tools::console_capture::DEFAULT_MAX_ENTRIES,
+            console_max_bytes: tools::console_capture::DEFAULT_MAX_BYTES,
+            console_capture_on_load: false,
+            webview_backend: None,
+            init_scripts: Vec::new(),
+            max_eval_result_size: tools::direct_eval::DEFAULT_MAX_RESULT_SIZE,
+            splash_window: None,
+            eval_fallback: true,
+            eval_history_size: tools::eval_history::DEFAULT_EVAL_HISTORY_SIZE,
+            latency_budgets: HashMap::new(),
+            devtools_port: None,
+            response_metadata: true,
+            confirmation_required: Vec::new(),
+            confirmation_timeout: tools::confirmation::DEFAULT_CONFIRMATION_TIMEOUT,
+            #[cfg(feature = "approval")]
+            approval_required: Vec::new(),
+            activity_indicator: false,
+            sensitive_regions: Vec::new(),
+            sensitive_region_mask: RegionMask::default(),
         }
     }
 
@@ -97,20 +233,297 @@ impl PluginConfig {
         self
     }
 
+    /// Allow TCP connections from the given CIDR ranges (e.g. `"10.0.0.0/8"`) and permit binding
+    /// to non-loopback hosts. Remote access also requires [`Self::auth_token`] and [`Self::tls`].
+    pub fn allow_remote<I, S>(mut self, cidr_list: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.security.allowed_remote = cidr_list.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Require clients to send a `handshake` command with this token before any other command.
+    pub fn auth_token(mut self, token: impl Into<String>) -> Self {
+        self.security.auth_token = Some(token.into());
+        self
+    }
+
+    /// Register a client identity. Once any client is registered, connections must present a
+    /// matching `clientName`/`clientKey` in their handshake and are limited to the granted capabilities.
+    pub fn grant_client<I>(
+        mut self,
+        name: impl Into<String>,
+        key: impl Into<String>,
+        capabilities: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = Capability>,
+    {
+        self.security.clients.push(ClientGrant {
+            name: name.into(),
+            key: key.into(),
+            capabilities: capabilities.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Serve TCP connections over TLS using the given PEM certificate chain and private key.
+    pub fn tls(mut self, cert_path: std::path::PathBuf, key_path: std::path::PathBuf) -> Self {
+        self.security.tls = Some(TlsConfig {
+            cert_path,
+            key_path,
+        });
+        self
+    }
+
+    /// Automatically capture artifacts when the given condition occurs in any webview.
+    /// Captures are announced on the `artifacts` notification topic.
+    pub fn capture_on(mut self, trigger: CaptureTrigger) -> Self {
+        self.capture_triggers.push(trigger);
+        self
+    }
+
+    /// Set the directory captured artifacts are written to.
+    pub fn artifacts_dir(mut self, path: std::path::PathBuf) -> Self {
+        self.artifacts_dir = Some(path);
+        self
+    }
+
+    /// Reuse responses of expensive read-only tools (DOM snapshots, screenshots, extraction) for `ttl`.
+    /// Clients bypass the cache with `"fresh": true`; commands that change the page clear it.
+    pub fn cache_observations(mut self, ttl: std::time::Duration) -> Self {
+        self.observation_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Set the default screenshot format and quality. Requests may still override both.
+    pub fn image_encoding(mut self, format: ImageFormat, quality: u8) -> Self {
+        self.image_format = format;
+        self.image_quality = Some(quality.clamp(1, 100));
+        self
+    }
+
+    /// Set how many threads encode screenshots. Encoding never runs on the async runtime.
+    pub fn encoder_threads(mut self, threads: usize) -> Self {
+        self.encoder_threads = Some(threads.max(1));
+        self
+    }
+
+    /// Choose how page helpers and the capture script reach webviews. [`InjectionMode::InitScript`]
+    /// registers them as initialization scripts, which works under a strict Content-Security-Policy.
+    pub fn injection_mode(mut self, mode: InjectionMode) -> Self {
+        self.injection_mode = mode;
+        self
+    }
+
+    /// Drop console messages below `level` in the page rather than buffering them, e.g.
+    /// [`ConsoleLevel::Info`] to skip `debug` and `log` output. `setup_console_capture` may override it.
+    pub fn console_min_level(mut self, level: ConsoleLevel) -> Self {
+        self.console_min_level = Some(level);
+        self
+    }
+
+    /// Keep only one in `keep_one_in` console messages containing `pattern` (e.g. 1 in 100
+    /// "render tick" messages). Dropped messages are counted in `get_console_delta` responses.
+    pub fn sample_console(mut self, pattern: impl Into<String>, keep_one_in: u32) -> Self {
+        self.console_sampling.push(SamplingRule {
+            pattern: pattern.into(),
+            keep_one_in: keep_one_in.max(1),
+        });
+        self
+    }
+
+    /// Keep at most `max_entries` console entries per window (default 1000), evicting the oldest.
+    /// `setup_console_capture` may override it for a window.
+    pub fn console_max_entries(mut self, max_entries: usize) -> Self {
+        self.console_max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Keep about `max_bytes` of console messages and stacks per window (default 1 MiB),
+    /// evicting the oldest entries. `setup_console_capture` may override it for a window.
+    pub fn console_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.console_max_bytes = max_bytes;
+        self
+    }
+
+    /// Capture console output in every window from the start instead of from the first
+    /// `setup_console_capture` call. The capture script is registered as an initialization script,
+    /// so messages logged while a page loads are kept too.
+    pub fn capture_console_on_load(mut self, enabled: bool) -> Self {
+        self.console_capture_on_load = enabled;
+        self
+    }
+
+    /// Route window lookups and script evaluation through a custom backend instead of Tauri's
+    /// webview API, e.g. a scripted fake in tests or a devtools protocol connection.
+    pub fn webview_backend(mut self, backend: impl WebviewBackend + 'static) -> Self {
+        self.webview_backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Install helper scripts (e.g. lodash or the app's own test utilities) into every page
+    /// before its own scripts run, so evals can rely on them. Each script runs inside a function
+    /// with `this` set to `window` and is tracked like an `inject_script` call, so injecting the
+    /// same source later is a no-op.
+    pub fn init_scripts(mut self, scripts: Vec<String>) -> Self {
+        self.init_scripts.extend(scripts);
+        self
+    }
+
+    /// Cap the JSON text an eval tool returns in one response (default 1M characters). Larger
+    /// results come back truncated with `truncated: true` and are paged with `fetch_result_chunk`.
+    pub fn max_eval_result_size(mut self, size: usize) -> Self {
+        self.max_eval_result_size = size.max(1);
+        self
+    }
+
+    /// Name the window shown while the app loads, for `wait_for_splash_close` and the startup
+    /// metrics in `get_performance_metrics`. Without it, a window whose label contains "splash"
+    /// is used.
+    pub fn splash_window(mut self, label: impl Into<String>) -> Self {
+        self.splash_window = Some(label.into());
+        self
+    }
+
+    /// Enable or disable the eval fallback (on by default). When on, every page gets a small
+    /// initialization script holding the page helpers and a listener that runs code sent as a
+    /// Tauri event, used when webview eval goes unanswered, as under a CSP without `unsafe-eval`.
+    /// Eval responses report which path ran the code in `strategy`.
+    pub fn eval_fallback(mut self, enabled: bool) -> Self {
+        self.eval_fallback = enabled;
+        self
+    }
+
+    /// Keep the last `size` evals (default 200) for `get_eval_history` and `replay_eval`.
+    /// `0` turns recording off.
+    pub fn eval_history_size(mut self, size: usize) -> Self {
+        self.eval_history_size = size;
+        self
+    }
+
+    /// Warn when `tool` takes longer than `budget`, e.g. `latency_budget("direct_eval",
+    /// Duration::from_millis(500))`. Slow calls are logged and pushed to subscribers of the
+    /// `latency` topic; `"*"` sets the budget for every tool without its own.
+    pub fn latency_budget(mut self, tool: impl Into<String>, budget: std::time::Duration) -> Self {
+        self.latency_budgets.insert(tool.into(), budget);
+        self
+    }
+
+    /// Open WebView2's remote debugging port on `127.0.0.1:port` so DevTools Protocol tools
+    /// (`capture_heap_snapshot`) can reach the pages. Only WebView2 (Windows) has such an
+    /// endpoint. Anything local can connect to the port, so keep it out of release builds.
+    pub fn devtools_port(mut self, port: u16) -> Self {
+        self.devtools_port = Some(port);
+        self
+    }
+
+    /// Stamp socket responses with the request's id, how long it took, the window it targeted
+    /// and the tool that ran (default on). Protocol v1 clients never get these fields.
+    pub fn response_metadata(mut self, enabled: bool) -> Self {
+        self.response_metadata = enabled;
+        self
+    }
+
+    /// Hold calls to `tool` until a client sends `confirm` with the operation id the held call
+    /// returned. `"manage_local_storage:clear"` holds only calls whose `action` (or `operation`)
+    /// is `clear`. Protects real user data when an agent runs with little supervision.
+    pub fn require_confirmation(mut self, tool: impl Into<String>) -> Self {
+        self.confirmation_required.push(tool.into());
+        self
+    }
+
+    /// How long a held operation can wait for `confirm` before it is dropped (default 60s)
+    pub fn confirmation_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.confirmation_timeout = timeout;
+        self
+    }
+
+    /// Ask the user in a native dialog before `tool` runs, e.g. `execute_js`, or only one of its
+    /// actions with `tool:action`. The dialog can allow the call once or for the rest of the
+    /// client's connection.
+    #[cfg(feature = "approval")]
+    pub fn require_approval(mut self, tool: impl Into<String>) -> Self {
+        self.approval_required.push(tool.into());
+        self
+    }
+
+    /// Show a badge in the corner of every window while MCP clients are connected, pulsing while
+    /// one runs a command. Clicking it lists the clients and what they last ran, so users can
+    /// tell when the app is being driven remotely.
+    pub fn activity_indicator(mut self, enabled: bool) -> Self {
+        self.activity_indicator = enabled;
+        self
+    }
+
+    /// Declare elements matching `selector` sensitive, e.g. `.patient-record` or
+    /// `input[type=password]`. Screenshots mask them and DOM and text snapshots carry
+    /// `[redacted]` in place of their content.
+    pub fn sensitive_region(mut self, selector: impl Into<String>) -> Self {
+        self.sensitive_regions.push(selector.into());
+        self
+    }
+
+    /// How screenshots mask sensitive regions: blurred (the default) or blacked out
+    pub fn sensitive_region_mask(mut self, mask: RegionMask) -> Self {
+        self.sensitive_region_mask = mask;
+        self
+    }
+
+    fn capture_defaults(&self) -> tools::console_capture::CaptureDefaults {
+        tools::console_capture::CaptureDefaults {
+            min_level: self.console_min_level,
+            sampling: self.console_sampling.clone(),
+            limits: tools::console_capture::ConsoleLimits {
+                max_entries: Some(self.console_max_entries),
+                max_bytes: Some(self.console_max_bytes),
+            },
+            on_load: self.console_capture_on_load,
+        }
+    }
+
     /// Set whether to start the socket server automatically.
     pub fn start_socket_server(mut self, start: bool) -> Self {
         self.start_socket_server = start;
         self
     }
+
+    /// Register a redaction hook. Hooks run in registration order over every string in every
+    /// response, custom tools' included, and in every notification, before they leave the
+    /// process. Screenshot responses (`take_screenshot`, `diff_screenshots`, `screenshot_hash`)
+    /// are left alone, since they carry encoded images rather than text.
+    pub fn redact<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.redactor.add_hook(Arc::new(hook));
+        self
+    }
+
+    /// Replace every match of `pattern` with `replacement` (e.g. to mask bearer tokens).
+    pub fn redact_pattern(self, pattern: Regex, replacement: impl Into<String>) -> Self {
+        let replacement = replacement.into();
+        self.redact(move |input| {
+            pattern
+                .replace_all(input, replacement.as_str())
+                .into_owned()
+        })
+    }
 }
 
-/// Initializes the plugin.
+/// Initializes the plugin with [`PluginConfig::default`], the defaults of [`PluginConfig::new`].
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     init_with_config(PluginConfig::default())
 }
 
 /// Initializes the plugin with the given configuration.
 pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
+    let launched_ms = tools::timeline::now_ms();
+    if let Some(port) = config.devtools_port {
+        devtools::enable_remote_debugging(port);
+    }
     // Log socket configuration
     match &config.socket_type {
         SocketType::Ipc { path } => {
@@ -141,19 +554,137 @@ pub fn init_with_config<R: Runtime>(config: PluginConfig) -> TauriPlugin<R> {
         info!("[TAURI_MCP] Socket server auto-start is disabled");
     }
 
-    Builder::new("tauri-mcp")
+    // A plugin holds a single initialization script, so everything is joined into one
+    let mut init_scripts = Vec::new();
+    if config.injection_mode == InjectionMode::InitScript || config.console_capture_on_load {
+        info!("[TAURI_MCP] Page helpers and capture will load as initialization scripts");
+        let kinds = tools::triggers::TriggerState::new(config.capture_triggers.clone()).kinds();
+        init_scripts.push(tools::page_helpers::init_script(&kinds, &config.capture_defaults()));
+    }
+    if !config.init_scripts.is_empty() {
+        info!("[TAURI_MCP] {} user script(s) will load as initialization scripts", config.init_scripts.len());
+        init_scripts.push(tools::user_scripts::init_script(&config.init_scripts));
+    }
+    if config.eval_fallback {
+        init_scripts.push(tools::webview_backend::event_bridge_script());
+    }
+
+    let mut builder = Builder::new("mcp");
+    if !init_scripts.is_empty() {
+        builder = builder.js_init_script(init_scripts.join("\n"));
+    }
+
+    builder
         .invoke_handler(tauri::generate_handler![
-        // Server Commands
+            // Page-facing commands used by the plugin's own scripts
+            commands::bridge_token,
+            commands::capture_trigger,
+            commands::console_entry,
+            commands::eval_result
         ])
         .setup(move |app, api| {
             info!("[TAURI_MCP] Setting up plugin");
             #[cfg(mobile)]
             panic!("Mobile is not supported");
+            if let Some(threads) = config.encoder_threads {
+                encoding::init_pool(threads);
+            }
             #[cfg(desktop)]
             let tauri_mcp = desktop::init(app, api, &config)?;
             app.manage(tauri_mcp);
+            app.manage(tools::timeline::TimelineStore::default());
+            app.manage(tools::text_diff::TextSnapshotStore::default());
+            app.manage(tools::visual_diff::ScreenshotBaselineStore::default());
+            app.manage(tools::window_manager::KioskStore::default());
+            app.manage(tools::startup::StartupTracker::new(launched_ms, config.splash_window.clone()));
+            app.manage(tools::startup_benchmark::StartupBenchmarkState::default());
+            app.manage(tools::eval_history::EvalHistory::new(config.eval_history_size));
+            app.manage(tools::latency::LatencyTracker::new(config.latency_budgets.clone()));
+            app.manage(tools::responsiveness::LagMonitor::default());
+            tools::responsiveness::start(app.app_handle());
+            app.manage(devtools::DevtoolsPort(config.devtools_port));
+            app.manage(tools::cpu_profile::CpuProfiles::default());
+            app.manage(tools::capture_state::CaptureRegistry::default());
+            app.manage(tools::cache::ResponseCache::new(config.observation_cache_ttl));
+            app.manage(notifications::NotificationHub::new(config.redactor.clone()));
+            app.manage(config.injection_mode);
+            app.manage(config.capture_defaults());
+            app.manage(tools::direct_eval::ResultSizeLimit(config.max_eval_result_size));
+            app.manage(tools::direct_eval::IdempotentResults::default());
+            app.manage(tools::console_capture::ConsoleStore::new(
+                config.console_max_entries,
+                config.console_max_bytes,
+            ));
+            app.manage(socket_server::ResponseMetadata(config.response_metadata));
+            app.manage(tools::confirmation::ConfirmationGate::new(
+                config.confirmation_required.clone(),
+                config.confirmation_timeout,
+            ));
+            #[cfg(feature = "approval")]
+            if !config.approval_required.is_empty() {
+                app.manage(approval::ApprovalPolicy::new(config.approval_required.clone()));
+            }
+            if config.activity_indicator {
+                app.manage(activity::ActivityTracker::default());
+            }
+            app.manage(tools::sensitive_regions::SensitiveRegions {
+                selectors: config.sensitive_regions.clone(),
+                mask: config.sensitive_region_mask,
+            });
+            let backend = config
+                .webview_backend
+                .clone()
+                .unwrap_or_else(|| {
+                    Arc::new(
+                        TauriBackend::new(app.app_handle().clone())
+                            .with_event_bridge(config.eval_fallback),
+                    )
+                });
+            app.manage(tools::webview_backend::SharedBackend(backend));
+            app.manage(tools::webview_backend::BridgeTokens::default());
+            app.manage(tools::webview_backend::PendingCalls::default());
+            app.manage(tools::builtin_tools::<R>());
+
+            let artifacts_dir = config.artifacts_dir.clone().unwrap_or_else(|| {
+                std::env::temp_dir()
+                    .join("tauri-mcp-artifacts")
+                    .join(&config.application_name)
+            });
+            info!("[TAURI_MCP] Artifacts will be written to {}", artifacts_dir.display());
+            app.manage(artifacts::ArtifactStore::new(artifacts_dir));
+
+            app.manage(tools::triggers::TriggerState::new(config.capture_triggers.clone()));
             info!("[TAURI_MCP] Plugin setup complete");
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            tools::timeline::record_page_load(webview, payload.url().as_str(), payload.event());
+            tools::triggers::arm_page(webview, payload.event());
+            tools::capture_state::page_loading(webview, webview.label(), payload.event());
+            tools::console_capture::page_loaded(webview, payload.event());
+            tools::startup::page_loaded(webview, webview.label(), payload.event());
+            activity::page_loaded(webview, payload.event());
+        })
+        .on_webview_ready(|webview| {
+            tools::console_capture::watch_browser_messages(&webview);
+        })
+        .on_window_ready(|window| {
+            tools::capture_state::forget(&window, window.label());
+            tools::startup::window_created(&window, window.label());
+            tools::timeline::record_event(
+                &window,
+                tools::timeline::TimelineCategory::Window,
+                Some(window.label().to_string()),
+                "Window created".to_string(),
+                serde_json::Value::Null,
+            );
+        })
+        .on_event(|app, event| {
+            if let RunEvent::WindowEvent { label, event, .. } = event {
+                tools::timeline::record_window_event(app, label, event);
+                tools::capture_state::window_event(app, label, event);
+                tools::startup::window_event(app, label, event);
+            }
+        })
         .build()
 }
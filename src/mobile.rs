@@ -25,7 +25,11 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
     // For now, we'll initialize it the same way as desktop, but in a real implementation
     // you might want to use a different approach or disable it on mobile
     let socket_server = if config.start_socket_server {
-        let mut server = SocketServer::new(app.clone(), config.socket_type.clone());
+        let mut server = SocketServer::new(
+            app.clone(),
+            config.socket_type.clone(),
+            config.security.clone(),
+        );
         server.start()?;
         Some(Arc::new(Mutex::new(server)))
     } else {
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::security::SecurityStatus;
+use crate::tools::responsiveness::ResponsivenessStatus;
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PingRequest {
@@ -112,18 +115,28 @@ pub struct TypeTextRequest {
 }
 
 // Screenshot request - updated to use shared interface
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct ScreenshotRequest {
     pub window_label: String,
+    /// Output format; falls back to the plugin's configured default
+    #[serde(default)]
+    pub format: Option<crate::ImageFormat>,
+    /// Encoder quality (1-100) for lossy formats
+    #[serde(default)]
+    pub quality: Option<u8>,
+    /// Maximum image width in pixels
+    #[serde(default)]
+    pub max_width: Option<u32>,
 }
 
 impl From<ScreenshotRequest> for crate::shared::ScreenshotParams {
     fn from(req: ScreenshotRequest) -> Self {
         Self {
             window_label: Some(req.window_label),
-            quality: None,
-            max_width: None,
+            format: req.format,
+            quality: req.quality.map(i32::from),
+            max_width: req.max_width.map(|w| w as i32),
             max_size_mb: None,
             application_name: None,
         }
@@ -243,3 +256,16 @@ pub struct MouseMovementResponse {
     pub duration_ms: u64,
     pub position: Option<(i32, i32)>,
 }
+
+// Server status response model
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatusResponse {
+    pub running: bool,
+    pub protocol_version: u32,
+    pub transport: String,
+    pub address: String,
+    pub security: SecurityStatus,
+    /// Lag of the plugin's async runtime and of the main thread
+    pub responsiveness: Option<ResponsivenessStatus>,
+}
@@ -0,0 +1,48 @@
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+use crate::redaction::Redactor;
+
+/// How many notifications a slow subscriber may fall behind before it starts losing them
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A message pushed to subscribed socket clients outside the request/response cycle
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub topic: String,
+    pub data: Value,
+}
+
+/// Fan-out point for notifications; socket connections subscribe to the topics they care about
+pub struct NotificationHub {
+    sender: broadcast::Sender<Notification>,
+    /// Applied to every notification, as to responses carrying page content
+    redactor: Redactor,
+}
+
+impl Default for NotificationHub {
+    fn default() -> Self {
+        Self::new(Redactor::default())
+    }
+}
+
+impl NotificationHub {
+    pub fn new(redactor: Redactor) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender, redactor }
+    }
+
+    /// Publish to all current subscribers, redacted. Notifications without subscribers are dropped.
+    pub fn publish(&self, topic: &str, mut data: Value) {
+        self.redactor.redact_value(&mut data);
+        let _ = self.sender.send(Notification {
+            topic: topic.to_string(),
+            data,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Notification> {
+        self.sender.subscribe()
+    }
+}
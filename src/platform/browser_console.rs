@@ -0,0 +1,103 @@
+// Messages the webview logs to its own console rather than through `console.*`: mixed-content
+// warnings, failed loads, interventions and the like. Only WebView2 exposes them, through the
+// DevTools Protocol `Log` domain; WKWebView and WebKitGTK have no public API for their console,
+// so pages there only report what the capture script can observe itself.
+
+use serde::Deserialize;
+use tauri::{Runtime, Webview};
+
+/// One console message from the webview
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserMessage {
+    /// The webview's category, e.g. `security`, `network` or `violation`
+    pub source: String,
+    /// `verbose`, `info`, `warning` or `error`
+    pub level: String,
+    pub text: String,
+    pub url: Option<String>,
+    pub line_number: Option<u32>,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: Option<f64>,
+}
+
+// Subscribe to a webview's console messages. Returns false where the platform offers no way to.
+#[cfg(target_os = "windows")]
+pub fn watch<R: Runtime>(
+    webview: &Webview<R>,
+    on_message: impl Fn(BrowserMessage) + Send + 'static,
+) -> bool {
+    use log::warn;
+    use webview2_com::{
+        CallDevToolsProtocolMethodCompletedHandler, DevToolsProtocolEventReceivedEventHandler,
+        take_pwstr,
+    };
+    use windows_core::{HSTRING, PCWSTR, PWSTR};
+
+    #[derive(Deserialize)]
+    struct EntryAdded {
+        entry: BrowserMessage,
+    }
+
+    webview
+        .with_webview(move |webview| unsafe {
+            let core = match webview.controller().CoreWebView2() {
+                Ok(core) => core,
+                Err(e) => {
+                    warn!("[TAURI_MCP] No WebView2 core to watch console messages on: {}", e);
+                    return;
+                }
+            };
+            let event = HSTRING::from("Log.entryAdded");
+            let receiver = match core.GetDevToolsProtocolEventReceiver(PCWSTR(event.as_ptr())) {
+                Ok(receiver) => receiver,
+                Err(e) => {
+                    warn!("[TAURI_MCP] Failed to watch WebView2 console messages: {}", e);
+                    return;
+                }
+            };
+            let handler = DevToolsProtocolEventReceivedEventHandler::create(Box::new(
+                move |_sender, args| {
+                    if let Some(args) = args {
+                        let mut json = PWSTR::null();
+                        args.ParameterObjectAsJson(&mut json)?;
+                        match serde_json::from_str::<EntryAdded>(&take_pwstr(json)) {
+                            Ok(added) => on_message(added.entry),
+                            Err(e) => warn!("[TAURI_MCP] Ignoring malformed Log.entryAdded event: {}", e),
+                        }
+                    }
+                    Ok(())
+                },
+            ));
+            let mut token = 0;
+            if let Err(e) = receiver.add_DevToolsProtocolEventReceived(&handler, &mut token) {
+                warn!("[TAURI_MCP] Failed to watch WebView2 console messages: {}", e);
+                return;
+            }
+
+            let method = HSTRING::from("Log.enable");
+            let params = HSTRING::from("{}");
+            let enabled = CallDevToolsProtocolMethodCompletedHandler::create(Box::new(|result, _| {
+                if let Err(e) = result {
+                    warn!("[TAURI_MCP] Failed to enable the WebView2 Log domain: {}", e);
+                }
+                Ok(())
+            }));
+            if let Err(e) = core.CallDevToolsProtocolMethod(
+                PCWSTR(method.as_ptr()),
+                PCWSTR(params.as_ptr()),
+                &enabled,
+            ) {
+                warn!("[TAURI_MCP] Failed to enable the WebView2 Log domain: {}", e);
+            }
+        })
+        .is_ok()
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn watch<R: Runtime>(
+    _webview: &Webview<R>,
+    _on_message: impl Fn(BrowserMessage) + Send + 'static,
+) -> bool {
+    false
+}
@@ -6,7 +6,9 @@ use tauri::Runtime;
 
 // Import shared functionality
 use crate::desktop::{ScreenshotContext, create_success_response};
-use crate::platform::shared::{get_window_title, handle_screenshot_task};
+use crate::platform::shared::{
+    StackedWindow, WebviewProcess, WebviewProcessKind, get_window_title, handle_screenshot_task,
+};
 use crate::shared::ScreenshotParams;
 use crate::tools::take_screenshot::process_image;
 
@@ -116,4 +118,82 @@ fn find_window(xcap_windows: &[xcap::Window], window_title: &str, application_na
     None
 }
 
+// On-screen windows of every application, frontmost first. The window server reports bounds in
+// points, so they are scaled by `scale_factor`.
+pub fn window_stack(scale_factor: f64) -> Option<Vec<StackedWindow>> {
+    let windows = xcap::Window::all().ok()?;
+    Some(
+        windows
+            .iter()
+            .filter(|window| !window.is_minimized())
+            .map(|window| StackedWindow {
+                title: window.title().to_string(),
+                app_name: Some(window.app_name().to_string()),
+                bounds: Some((
+                    window.x() as f64 * scale_factor,
+                    window.y() as f64 * scale_factor,
+                    window.width() as f64 * scale_factor,
+                    window.height() as f64 * scale_factor,
+                )),
+            })
+            .collect(),
+    )
+}
+
+// Set the alpha of the whole native window, 0.0 (transparent) to 1.0 (opaque)
+pub fn set_window_opacity<R: Runtime>(window: &tauri::WebviewWindow<R>, opacity: f64) -> Result<()> {
+    let ns_window = window
+        .ns_window()
+        .map_err(|e| Error::WindowOperationFailed(format!("Failed to get native window: {}", e)))?
+        as usize;
+    // AppKit must only be touched from the main thread
+    window
+        .run_on_main_thread(move || unsafe {
+            let ns_window = ns_window as cocoa::base::id;
+            let _: () = objc::msg_send![ns_window, setAlphaValue: opacity];
+        })
+        .map_err(|e| Error::WindowOperationFailed(format!("Failed to set opacity: {}", e)))
+}
+
+// The web content process of the window's WKWebView, through WebKit's `_webProcessIdentifier`.
+// Blocks until the main thread has answered.
+pub fn webview_process<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Option<WebviewProcess> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    window
+        .with_webview(move |webview| unsafe {
+            let web_view = webview.inner() as cocoa::base::id;
+            let responds: objc::runtime::BOOL =
+                objc::msg_send![web_view, respondsToSelector: objc::sel!(_webProcessIdentifier)];
+            let pid: i32 = if responds == objc::runtime::YES {
+                objc::msg_send![web_view, _webProcessIdentifier]
+            } else {
+                0
+            };
+            let _ = tx.send(pid);
+        })
+        .ok()?;
+    let pid = rx
+        .recv_timeout(std::time::Duration::from_secs(2))
+        .ok()
+        .filter(|pid| *pid > 0)? as u32;
+    Some(WebviewProcess {
+        pid,
+        kind: WebviewProcessKind::WebContent,
+        resident_bytes: resident_bytes(pid),
+    })
+}
+
+// Resident set size of a process in bytes, as `ps` reports it (in KiB)
+fn resident_bytes(pid: u32) -> Option<u64> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|kib| kib * 1024)
+}
+
 // Add any other macOS-specific functionality here
@@ -3,6 +3,9 @@
 // Add shared module for common functionality
 pub mod shared;
 
+// Console messages logged by the webview itself, where the platform exposes them
+pub mod browser_console;
+
 // Define platform-specific modules
 #[cfg(target_os = "macos")]
 pub mod macos;
@@ -30,3 +30,32 @@ pub fn get_window_title<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Result<
         ))),
     }
 }
+
+/// A top-level window as the OS stacks it
+#[derive(Debug, Clone)]
+pub struct StackedWindow {
+    pub title: String,
+    /// Owning application, where the platform reports it
+    pub app_name: Option<String>,
+    /// `(x, y, width, height)` in physical pixels, where the platform reports it
+    pub bounds: Option<(f64, f64, f64, f64)>,
+}
+
+/// Which process a webview's memory figure belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebviewProcessKind {
+    /// The process rendering this webview's pages (WebKit's web content process)
+    WebContent,
+    /// The browser process WebView2 shares between every webview of the app
+    Browser,
+}
+
+/// The OS process behind a webview, where the platform exposes it
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WebviewProcess {
+    pub pid: u32,
+    pub kind: WebviewProcessKind,
+    /// Resident set (working set on Windows) in bytes
+    pub resident_bytes: Option<u64>,
+}
@@ -4,7 +4,7 @@ use tauri::Runtime;
 
 // Import shared functionality
 use crate::desktop::ScreenshotContext;
-use crate::platform::shared::handle_screenshot_task;
+use crate::platform::shared::{StackedWindow, WebviewProcess, handle_screenshot_task};
 use crate::shared::ScreenshotParams;
 
 // Unix-specific implementation for taking screenshots (fallback for non-macOS Unix systems)
@@ -69,4 +69,22 @@ pub async fn take_screenshot<R: Runtime>(
   }).await
 }
 
+// There is no portable way to read the stacking order here (X11 and the Wayland compositors
+// all differ), so callers fall back to focus
+pub fn window_stack(_scale_factor: f64) -> Option<Vec<StackedWindow>> {
+  None
+}
+
+// Window opacity is up to the compositor here and Tauri offers no way to request it
+pub fn set_window_opacity<R: Runtime>(_window: &tauri::WebviewWindow<R>, _opacity: f64) -> Result<()> {
+  Err(Error::WindowOperationFailed(
+    "Window opacity is not supported on this platform".to_string(),
+  ))
+}
+
+// WebKitGTK doesn't say which web process renders a given webview
+pub fn webview_process<R: Runtime>(_window: &tauri::WebviewWindow<R>) -> Option<WebviewProcess> {
+  None
+}
+
 // Add any other Unix-specific functionality here
@@ -7,7 +7,9 @@ use win_screenshot::prelude::*;
 
 // Import shared functionality
 use crate::desktop::{ScreenshotContext, create_success_response};
-use crate::platform::shared::{get_window_title, handle_screenshot_task};
+use crate::platform::shared::{
+  StackedWindow, WebviewProcess, WebviewProcessKind, get_window_title, handle_screenshot_task,
+};
 use crate::shared::ScreenshotParams;
 use crate::tools::take_screenshot::process_image;
 
@@ -89,3 +91,88 @@ pub async fn take_screenshot<R: Runtime>(
     }
   }).await
 }
+
+// Titled top-level windows, topmost first (the order EnumWindows reports them in). Bounds aren't
+// available through win-screenshot.
+pub fn window_stack(_scale_factor: f64) -> Option<Vec<StackedWindow>> {
+  let windows = window_list().ok()?;
+  Some(
+    windows
+      .into_iter()
+      .map(|window_info| StackedWindow {
+        title: window_info.window_name,
+        app_name: None,
+        bounds: None,
+      })
+      .collect(),
+  )
+}
+
+// Set the alpha of the whole native window, 0.0 (transparent) to 1.0 (opaque), by making it a
+// layered window
+pub fn set_window_opacity<R: Runtime>(window: &tauri::WebviewWindow<R>, opacity: f64) -> Result<()> {
+  use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GWL_EXSTYLE, GetWindowLongPtrW, LWA_ALPHA, SetLayeredWindowAttributes, SetWindowLongPtrW,
+    WS_EX_LAYERED,
+  };
+
+  let hwnd = window
+    .hwnd()
+    .map_err(|e| Error::WindowOperationFailed(format!("Failed to get native window: {}", e)))?
+    .0 as windows_sys::Win32::Foundation::HWND;
+  let alpha = (opacity * 255.0).round() as u8;
+  let applied = unsafe {
+    let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+    SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+    SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA)
+  };
+  if applied == 0 {
+    return Err(Error::WindowOperationFailed("SetLayeredWindowAttributes failed".to_string()));
+  }
+  Ok(())
+}
+
+// WebView2 hosts every webview of the app under one browser process, so that process is what gets
+// reported; its renderer processes can't be told apart per window without WebView2's process info
+// interfaces. Blocks until the main thread has answered.
+pub fn webview_process<R: Runtime>(window: &tauri::WebviewWindow<R>) -> Option<WebviewProcess> {
+  let (tx, rx) = std::sync::mpsc::channel();
+  window
+    .with_webview(move |webview| unsafe {
+      let mut pid = 0u32;
+      let found = webview
+        .controller()
+        .CoreWebView2()
+        .and_then(|core| core.BrowserProcessId(&mut pid));
+      let _ = tx.send(if found.is_ok() { pid } else { 0 });
+    })
+    .ok()?;
+  let pid = rx
+    .recv_timeout(std::time::Duration::from_secs(2))
+    .ok()
+    .filter(|pid| *pid > 0)?;
+  Some(WebviewProcess {
+    pid,
+    kind: WebviewProcessKind::Browser,
+    resident_bytes: working_set(pid),
+  })
+}
+
+// Working set of a process in bytes
+fn working_set(pid: u32) -> Option<u64> {
+  use windows_sys::Win32::Foundation::CloseHandle;
+  use windows_sys::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+  use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+  unsafe {
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+    if process.is_null() {
+      return None;
+    }
+    let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+    counters.cb = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+    let read = GetProcessMemoryInfo(process, &mut counters, counters.cb);
+    CloseHandle(process);
+    (read != 0).then(|| counters.WorkingSetSize as u64)
+  }
+}
@@ -0,0 +1,42 @@
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A redaction hook receives text that is about to leave the process and returns the masked version
+pub type RedactionHook = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Ordered list of redaction hooks registered through [`crate::PluginConfig`]
+#[derive(Clone, Default)]
+pub struct Redactor {
+    hooks: Vec<RedactionHook>,
+}
+
+impl Redactor {
+    pub fn add_hook(&mut self, hook: RedactionHook) {
+        self.hooks.push(hook);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run every hook over the input, in registration order
+    pub fn redact_str(&self, input: &str) -> String {
+        self.hooks
+            .iter()
+            .fold(input.to_string(), |acc, hook| hook(&acc))
+    }
+
+    /// Redact every string contained in a JSON value, recursing into arrays and objects
+    pub fn redact_value(&self, value: &mut Value) {
+        if self.is_empty() {
+            return;
+        }
+
+        match value {
+            Value::String(s) => *s = self.redact_str(s),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(map) => map.values_mut().for_each(|item| self.redact_value(item)),
+            _ => {}
+        }
+    }
+}
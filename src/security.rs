@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::error::Error;
+
+/// An IPv4 or IPv6 network in CIDR notation (e.g. `10.0.0.0/8`). A bare address means a single host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        match (self.network, addr.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                prefix_bits(u32::from(net) as u128, 32, self.prefix_len)
+                    == prefix_bits(u32::from(addr) as u128, 32, self.prefix_len)
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                prefix_bits(u128::from(net), 128, self.prefix_len)
+                    == prefix_bits(u128::from(addr), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_bits(bits: u128, width: u32, prefix_len: u8) -> u128 {
+    if prefix_len == 0 {
+        0
+    } else {
+        bits >> (width - prefix_len as u32)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::PluginInit(format!("Invalid CIDR range '{}'", s));
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+
+        let network: IpAddr = addr.trim().parse().map_err(|_| invalid())?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(p) => p
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max_len)
+                .ok_or_else(invalid)?,
+            None => max_len,
+        };
+
+        Ok(Self {
+            network: network.to_canonical(),
+            prefix_len,
+        })
+    }
+}
+
+impl std::fmt::Display for Cidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+/// Certificate and private key (PEM files) used to serve TCP connections over TLS
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    pub(crate) fn load(&self) -> crate::Result<Arc<rustls::ServerConfig>> {
+        let cert_file = File::open(&self.cert_path).map_err(|e| {
+            Error::PluginInit(format!(
+                "Failed to open TLS certificate {}: {}",
+                self.cert_path.display(),
+                e
+            ))
+        })?;
+        let certs = rustls_pemfile::certs(&mut BufReader::new(cert_file))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::PluginInit(format!("Failed to parse TLS certificate: {}", e)))?;
+
+        let key_file = File::open(&self.key_path).map_err(|e| {
+            Error::PluginInit(format!(
+                "Failed to open TLS private key {}: {}",
+                self.key_path.display(),
+                e
+            ))
+        })?;
+        let key = rustls_pemfile::private_key(&mut BufReader::new(key_file))
+            .map_err(|e| Error::PluginInit(format!("Failed to parse TLS private key: {}", e)))?
+            .ok_or_else(|| Error::PluginInit("No private key found in TLS key file".to_string()))?;
+
+        let config = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| Error::PluginInit(format!("Invalid TLS configuration: {}", e)))?;
+
+        Ok(Arc::new(config))
+    }
+}
+
+/// Groups of socket commands that can be granted to a client
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Observe the app: screenshots, DOM, console output, server status
+    Read,
+    /// Simulate keyboard and mouse input
+    Input,
+    /// Run arbitrary JavaScript in a webview
+    Eval,
+    /// Move, resize, show, hide and close windows
+    Window,
+    /// Read and modify localStorage
+    Storage,
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Capability::Read => "read",
+            Capability::Input => "input",
+            Capability::Eval => "eval",
+            Capability::Window => "window",
+            Capability::Storage => "storage",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A named client, the key it must present during the handshake, and what it may do
+#[derive(Clone, Debug)]
+pub struct ClientGrant {
+    pub name: String,
+    pub key: String,
+    pub capabilities: HashSet<Capability>,
+}
+
+/// Who may connect to the socket server and what they must present.
+///
+/// By default TCP sockets only bind to loopback addresses. Listening more widely requires
+/// `allowed_remote` ranges plus both an auth token and TLS.
+#[derive(Clone, Debug, Default)]
+pub struct TransportSecurity {
+    /// CIDR ranges allowed to connect from outside the local machine
+    pub allowed_remote: Vec<String>,
+    /// Shared secret clients must present in a `handshake` before issuing commands
+    pub auth_token: Option<String>,
+    /// TLS settings for TCP connections
+    pub tls: Option<TlsConfig>,
+    /// Known clients and their capabilities. When empty, every connection gets all capabilities.
+    pub clients: Vec<ClientGrant>,
+}
+
+impl TransportSecurity {
+    pub(crate) fn parse_allowed_remote(&self) -> crate::Result<Vec<Cidr>> {
+        self.allowed_remote.iter().map(|c| c.parse()).collect()
+    }
+
+    /// Refuse non-loopback TCP binds unless remote access is fully configured
+    pub(crate) fn validate_tcp_bind(&self, host: &str) -> crate::Result<()> {
+        if is_loopback_host(host) {
+            return Ok(());
+        }
+
+        if self.allowed_remote.is_empty() {
+            return Err(Error::PluginInit(format!(
+                "Refusing to bind TCP socket to '{}': only loopback addresses are allowed. Call allow_remote() with the permitted CIDR ranges to listen more widely.",
+                host
+            )));
+        }
+        if self.auth_token.is_none() {
+            return Err(Error::PluginInit(
+                "Remote access requires an auth token (see PluginConfig::auth_token)".to_string(),
+            ));
+        }
+        if self.tls.is_none() {
+            return Err(Error::PluginInit(
+                "Remote access requires TLS (see PluginConfig::tls)".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn status(&self) -> SecurityStatus {
+        SecurityStatus {
+            loopback_only: self.allowed_remote.is_empty(),
+            allowed_remote: self.allowed_remote.clone(),
+            auth_required: self.auth_token.is_some(),
+            tls: self.tls.is_some(),
+            clients: self.clients.iter().map(|grant| grant.name.clone()).collect(),
+        }
+    }
+
+    /// Check a presented token against the configured one
+    pub(crate) fn verify_token(&self, presented: &str) -> bool {
+        match &self.auth_token {
+            Some(expected) => constant_time_eq(expected, presented),
+            None => true,
+        }
+    }
+
+    /// Whether connections must identify themselves before issuing commands
+    pub(crate) fn requires_handshake(&self) -> bool {
+        self.auth_token.is_some() || !self.clients.is_empty()
+    }
+
+    /// Resolve a client's name and key to its grant
+    pub(crate) fn find_client(&self, name: &str, key: &str) -> Option<&ClientGrant> {
+        self.clients
+            .iter()
+            .find(|grant| grant.name == name && constant_time_eq(&grant.key, key))
+    }
+}
+
+/// Compare two secrets without exiting early on the first mismatching byte
+fn constant_time_eq(expected: &str, presented: &str) -> bool {
+    expected.len() == presented.len()
+        && expected
+            .bytes()
+            .zip(presented.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Loopback peers are always allowed; anything else must fall inside an allowed range
+pub(crate) fn is_peer_allowed(allowed: &[Cidr], addr: &IpAddr) -> bool {
+    addr.to_canonical().is_loopback() || allowed.iter().any(|cidr| cidr.contains(addr))
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+/// Effective network policy, as reported by `server_status`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityStatus {
+    pub loopback_only: bool,
+    pub allowed_remote: Vec<String>,
+    pub auth_required: bool,
+    pub tls: bool,
+    pub clients: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(addr: &str) -> IpAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_ranges_and_single_addresses() {
+        assert_eq!("10.0.0.0/8".parse::<Cidr>().unwrap().to_string(), "10.0.0.0/8");
+        assert_eq!("192.168.1.7".parse::<Cidr>().unwrap().to_string(), "192.168.1.7/32");
+        assert_eq!("::1".parse::<Cidr>().unwrap().to_string(), "::1/128");
+        assert_eq!(" fd00::/8 ".parse::<Cidr>().unwrap().to_string(), "fd00::/8");
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        for range in ["", "10.0.0.0/33", "::/129", "10.0.0/8", "10.0.0.0/x", "host/8"] {
+            assert!(range.parse::<Cidr>().is_err(), "{range} parsed");
+        }
+    }
+
+    #[test]
+    fn matches_addresses_within_the_prefix() {
+        let cidr: Cidr = "10.1.0.0/16".parse().unwrap();
+        assert!(cidr.contains(&ip("10.1.255.3")));
+        assert!(!cidr.contains(&ip("10.2.0.1")));
+        assert!(!cidr.contains(&ip("::1")));
+
+        let any: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(&ip("203.0.113.9")));
+    }
+
+    #[test]
+    fn treats_ipv4_mapped_addresses_as_ipv4() {
+        let cidr: Cidr = "127.0.0.0/8".parse().unwrap();
+        assert!(cidr.contains(&ip("::ffff:127.0.0.1")));
+    }
+}
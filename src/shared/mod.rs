@@ -8,7 +8,11 @@ pub struct ScreenshotParams {
     /// The label of the window to capture
     pub window_label: Option<String>,
 
-    /// JPEG quality (1-100)
+    /// Output format. Defaults to JPEG.
+    #[serde(default)]
+    pub format: Option<crate::ImageFormat>,
+
+    /// Encoder quality (1-100), used by lossy formats
     pub quality: Option<i32>,
 
     /// Maximum image width in pixels
@@ -136,4 +140,58 @@ pub mod commands {
     pub const SIMULATE_MOUSE_MOVEMENT: &str = "simulate_mouse_movement";
     pub const GET_ELEMENT_POSITION: &str = "get_element_position";
     pub const SEND_TEXT_TO_ELEMENT: &str = "send_text_to_element";
+    pub const HANDSHAKE: &str = "handshake";
+    pub const SERVER_STATUS: &str = "server_status";
+    pub const GET_CONSOLE_DELTA: &str = "get_console_delta";
+    pub const GET_TIMELINE: &str = "get_timeline";
+    pub const LIST_ARTIFACTS: &str = "list_artifacts";
+    pub const SUBSCRIBE: &str = "subscribe";
+    pub const UNSUBSCRIBE: &str = "unsubscribe";
+    pub const ADD_ANNOTATION: &str = "add_annotation";
+    pub const WAIT_FOR_IDLE: &str = "wait_for_idle";
+    pub const SET_ANIMATIONS: &str = "set_animations";
+    pub const CONTROL_ANIMATION_FRAMES: &str = "control_animation_frames";
+    pub const GET_JANK_REPORT: &str = "get_jank_report";
+    pub const GET_EVENT_LISTENERS: &str = "get_event_listeners";
+    pub const DISPATCH_EVENT: &str = "dispatch_event";
+    pub const FORCE_PSEUDO_STATE: &str = "force_pseudo_state";
+    pub const EVAL_ON_ELEMENT: &str = "eval_on_element";
+    pub const QUERY_ELEMENTS: &str = "query_elements";
+    pub const EXTRACT_TABLE: &str = "extract_table";
+    pub const EXTRACT_LIST: &str = "extract_list";
+    pub const DIFF_TEXT: &str = "diff_text";
+    pub const PASTE: &str = "paste";
+    pub const OPEN_CONTEXT_MENU: &str = "open_context_menu";
+    pub const GET_OPEN_MENU_ITEMS: &str = "get_open_menu_items";
+    pub const CAPTURE_TRANSIENT_UI: &str = "capture_transient_ui";
+    pub const AUDIT_KEYBOARD_NAVIGATION: &str = "audit_keyboard_navigation";
+    pub const DETECT_NONDETERMINISM: &str = "detect_nondeterminism";
+    pub const LIST_FRAMES: &str = "list_frames";
+    pub const DIFF_SCREENSHOTS: &str = "diff_screenshots";
+    pub const SCREENSHOT_HASH: &str = "screenshot_hash";
+    pub const OCR_SCREENSHOT: &str = "ocr_screenshot";
+    pub const RECORD_FRAME_TIMING: &str = "record_frame_timing";
+    pub const GET_WINDOW_Z_ORDER: &str = "get_window_z_order";
+    pub const SET_WINDOW_STYLE: &str = "set_window_style";
+    pub const INJECT_SCRIPT: &str = "inject_script";
+    pub const SET_FULLSCREEN: &str = "set_fullscreen";
+    pub const SET_KIOSK_MODE: &str = "set_kiosk_mode";
+    pub const GET_PERFORMANCE_METRICS: &str = "get_performance_metrics";
+    pub const GET_SPLASH_WINDOW: &str = "get_splash_window";
+    pub const WAIT_FOR_SPLASH_CLOSE: &str = "wait_for_splash_close";
+    pub const BENCHMARK_STARTUP: &str = "benchmark_startup";
+    pub const GET_EVAL_HISTORY: &str = "get_eval_history";
+    pub const REPLAY_EVAL: &str = "replay_eval";
+    pub const GET_TOOL_LATENCY: &str = "get_tool_latency";
+    pub const GET_WEBVIEW_MEMORY: &str = "get_webview_memory";
+    pub const CAPTURE_HEAP_SNAPSHOT: &str = "capture_heap_snapshot";
+    pub const START_CPU_PROFILE: &str = "start_cpu_profile";
+    pub const STOP_CPU_PROFILE: &str = "stop_cpu_profile";
+    pub const CONFIRM: &str = "confirm";
+    pub const CONSOLE_SUBSCRIBE: &str = "console_subscribe";
+    pub const CONSOLE_UNSUBSCRIBE: &str = "console_unsubscribe";
+    pub const SEARCH_BUFFERS: &str = "search_buffers";
+    pub const GET_JS_ERRORS: &str = "get_js_errors";
+    pub const CLEAR_JS_ERRORS: &str = "clear_js_errors";
+    pub const CLEAR_CONSOLE_BUFFER: &str = "clear_console_buffer";
 }
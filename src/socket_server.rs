@@ -1,23 +1,44 @@
-use interprocess::TryClone;
 use interprocess::local_socket::{
     GenericFilePath, GenericNamespaced, Listener as IpcListener, ListenerOptions, Name, Stream as IpcStream, ToFsName,
     ToNsName, prelude::*,
 };
 use serde_json::Value;
-use std::io::{BufRead, BufReader, Read, Write};
+use bytes::Bytes;
+use std::io::{BufRead, BufReader, IoSlice, Read, Write};
 use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use tauri::{AppHandle, Runtime};
-use log::{info, error};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use log::{debug, error, info};
 
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
 use crate::error::Error;
+use crate::framing::{self, FrameReader, Framing};
+use crate::models::ServerStatusResponse;
+use crate::notifications::{Notification, NotificationHub};
+use crate::security::{Capability, Cidr, TransportSecurity, is_peer_allowed};
+use std::collections::HashSet;
+use crate::activity::ActiveConnection;
+use crate::shared::commands;
 use crate::tools;
+use crate::tools::js_bridge;
+use crate::tools::console_capture::{CONSOLE_TOPIC, ConsoleStore};
 use crate::SocketType;
+use tokio::sync::broadcast;
+
+/// Current wire protocol version, reported in every response envelope
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest protocol version that the server still translates responses for
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// How often a subscribed connection checks for notifications while waiting for input
+const NOTIFICATION_POLL_INTERVAL: Duration = Duration::from_millis(20);
 
 /// Get a machine-specific socket filename to avoid conflicts between host and containers
 pub fn get_machine_specific_socket_name() -> String {
@@ -48,12 +69,23 @@ impl<S: Write + Read> LoggingStream<S> {
     fn new(inner: S) -> Self {
         Self { inner }
     }
+
+    fn get_ref(&self) -> &S {
+        &self.inner
+    }
 }
 
 impl<S: Write + Read> Write for LoggingStream<S> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        info!("[TAURI_MCP] Writing: {}", String::from_utf8_lossy(buf));
-        self.inner.write(buf)
+        let n = self.inner.write(buf)?;
+        debug!("[TAURI_MCP] Wrote {} bytes", n);
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        let n = self.inner.write_vectored(bufs)?;
+        debug!("[TAURI_MCP] Wrote {} bytes", n);
+        Ok(n)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
@@ -64,10 +96,7 @@ impl<S: Write + Read> Write for LoggingStream<S> {
 impl<S: Write + Read> Read for LoggingStream<S> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let n = self.inner.read(buf)?;
-        info!(
-            "[TAURI_MCP] Read: {}",
-            String::from_utf8_lossy(&buf[..n])
-        );
+        debug!("[TAURI_MCP] Read {} bytes", n);
         Ok(n)
     }
 }
@@ -77,20 +106,146 @@ impl<S: Write + Read> Read for LoggingStream<S> {
 struct SocketRequest {
     command: String,
     payload: Value,
+    /// Protocol version the client speaks. Once sent, it applies to the rest of the connection.
+    #[serde(default)]
+    protocol_version: Option<u32>,
+    /// Client-chosen id echoed back in the response, for matching responses to requests
+    #[serde(default)]
+    request_id: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SocketResponse {
     pub success: bool,
     pub data: Option<Value>,
     pub error: Option<String>,
+    /// The request's `requestId`, or the `request_id` in its payload. Set by the dispatcher.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Time the plugin spent on the request. Set by the dispatcher.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<f64>,
+    /// The `window_label` the request targeted, if any. Set by the dispatcher.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_label: Option<String>,
+    /// The command that produced the response. Set by the dispatcher.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// Id that console entries, errors and network requests caused by this command's scripts
+    /// were tagged with in the page; absent when it ran none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+/// Whether responses carry request id, duration, window and tool metadata; see
+/// `PluginConfig::response_metadata`. Managed in plugin state.
+pub struct ResponseMetadata(pub bool);
+
+/// Provenance of a request, stamped onto its response once it has been handled
+struct RequestMetadata {
+    request_id: Option<String>,
+    window_label: Option<String>,
+    tool: String,
+    started: Instant,
+}
+
+impl RequestMetadata {
+    fn of(request: &SocketRequest) -> Self {
+        let payload_str = |key: &str| {
+            request
+                .payload
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        };
+        Self {
+            request_id: request.request_id.clone().or_else(|| payload_str("request_id")),
+            window_label: payload_str("window_label"),
+            tool: request.command.clone(),
+            started: Instant::now(),
+        }
+    }
+
+    fn stamp(self, response: &mut SocketResponse) {
+        response.request_id = self.request_id;
+        response.duration_ms = Some(self.started.elapsed().as_secs_f64() * 1000.0);
+        response.window_label = self.window_label;
+        response.tool = Some(self.tool);
+    }
+}
+
+/// The v1 response shape, which predates response metadata
+#[derive(Serialize)]
+struct LegacyResponse<'a> {
+    success: bool,
+    data: &'a Option<Value>,
+    error: &'a Option<String>,
+}
+
+/// Versioned envelope wrapping every response sent over the socket
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseEnvelope<'a> {
+    protocol_version: u32,
+    #[serde(flatten)]
+    response: &'a SocketResponse,
+}
+
+/// Check that a client-requested protocol version is one we can still speak
+fn negotiate_protocol_version(requested: u32) -> Result<u32, Error> {
+    if (MIN_SUPPORTED_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&requested) {
+        Ok(requested)
+    } else {
+        Err(Error::Anyhow(format!(
+            "Unsupported protocol version {} (supported: {}-{})",
+            requested, MIN_SUPPORTED_PROTOCOL_VERSION, PROTOCOL_VERSION
+        )))
+    }
+}
+
+/// Serialize a response for the given protocol version, applying compat shims for older clients
+fn encode_response(response: &SocketResponse, version: u32) -> Result<Bytes, Error> {
+    let encoded = match version {
+        // v1: bare `{ success, data, error }` object without version metadata
+        1 => framing::encode_json(&LegacyResponse {
+            success: response.success,
+            data: &response.data,
+            error: &response.error,
+        }),
+        _ => framing::encode_json(&ResponseEnvelope {
+            protocol_version: PROTOCOL_VERSION,
+            response,
+        }),
+    };
+
+    encoded.map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationEnvelope<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol_version: Option<u32>,
+    notification: &'a Notification,
+}
+
+/// Serialize a pushed notification frame. Unlike responses, frames carry a `notification` key.
+fn encode_notification(notification: &Notification, version: u32) -> Result<Bytes, Error> {
+    let envelope = NotificationEnvelope {
+        protocol_version: if version == 1 { None } else { Some(PROTOCOL_VERSION) },
+        notification,
+    };
+
+    framing::encode_json(&envelope)
+        .map_err(|e| Error::Anyhow(format!("Failed to serialize notification: {}", e)))
 }
 
 /// Unified stream type that can handle both IPC and TCP
 enum UnifiedStream {
     Ipc(IpcStream),
     Tcp(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ServerConnection, TcpStream>>),
 }
 
 impl Read for UnifiedStream {
@@ -98,6 +253,7 @@ impl Read for UnifiedStream {
         match self {
             UnifiedStream::Ipc(stream) => stream.read(buf),
             UnifiedStream::Tcp(stream) => stream.read(buf),
+            UnifiedStream::Tls(stream) => stream.read(buf),
         }
     }
 }
@@ -107,6 +263,15 @@ impl Write for UnifiedStream {
         match self {
             UnifiedStream::Ipc(stream) => stream.write(buf),
             UnifiedStream::Tcp(stream) => stream.write(buf),
+            UnifiedStream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        match self {
+            UnifiedStream::Ipc(stream) => stream.write_vectored(bufs),
+            UnifiedStream::Tcp(stream) => stream.write_vectored(bufs),
+            UnifiedStream::Tls(stream) => stream.write_vectored(bufs),
         }
     }
 
@@ -114,15 +279,17 @@ impl Write for UnifiedStream {
         match self {
             UnifiedStream::Ipc(stream) => stream.flush(),
             UnifiedStream::Tcp(stream) => stream.flush(),
+            UnifiedStream::Tls(stream) => stream.flush(),
         }
     }
 }
 
 impl UnifiedStream {
-    fn try_clone(&self) -> std::io::Result<Self> {
+    fn set_nonblocking(&self, nonblocking: bool) -> std::io::Result<()> {
         match self {
-            UnifiedStream::Ipc(stream) => Ok(UnifiedStream::Ipc(stream.try_clone()?)),
-            UnifiedStream::Tcp(stream) => Ok(UnifiedStream::Tcp(stream.try_clone()?)),
+            UnifiedStream::Ipc(stream) => stream.set_nonblocking(nonblocking),
+            UnifiedStream::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            UnifiedStream::Tls(stream) => stream.sock.set_nonblocking(nonblocking),
         }
     }
 }
@@ -136,12 +303,13 @@ enum UnifiedListener {
 pub struct SocketServer<R: Runtime> {
     listener: Option<Arc<Mutex<UnifiedListener>>>,
     socket_type: SocketType,
+    security: Arc<TransportSecurity>,
     app: AppHandle<R>,
     running: Arc<Mutex<bool>>,
 }
 
 impl<R: Runtime> SocketServer<R> {
-    pub fn new(app: AppHandle<R>, socket_type: SocketType) -> Self {
+    pub fn new(app: AppHandle<R>, socket_type: SocketType, security: TransportSecurity) -> Self {
         match &socket_type {
             SocketType::Ipc { path } => {
                 let socket_path = if let Some(path) = path {
@@ -169,6 +337,7 @@ impl<R: Runtime> SocketServer<R> {
         SocketServer {
             listener: None,
             socket_type,
+            security: Arc::new(security),
             app,
             running: Arc::new(Mutex::new(false)),
         }
@@ -196,6 +365,9 @@ impl<R: Runtime> SocketServer<R> {
                 UnifiedListener::Ipc(ipc_listener)
             }
             SocketType::Tcp { host, port } => {
+                // Only loopback unless remote access has been explicitly configured
+                self.security.validate_tcp_bind(host)?;
+
                 // Create TCP listener
                 let addr = format!("{}:{}", host, port);
                 let tcp_listener = TcpListener::bind(&addr)
@@ -207,6 +379,12 @@ impl<R: Runtime> SocketServer<R> {
             }
         };
 
+        let allowed_remote: Arc<Vec<Cidr>> = Arc::new(self.security.parse_allowed_remote()?);
+        let tls_config = match (&self.socket_type, &self.security.tls) {
+            (SocketType::Tcp { .. }, Some(tls)) => Some(tls.load()?),
+            _ => None,
+        };
+
         let listener = Arc::new(Mutex::new(listener));
         self.listener = Some(listener.clone());
 
@@ -216,6 +394,7 @@ impl<R: Runtime> SocketServer<R> {
         let app = self.app.clone();
         let running = self.running.clone();
         let socket_type = self.socket_type.clone();
+        let security = self.security.clone();
 
         // Spawn a thread to handle socket connections
         info!("[TAURI_MCP] Spawning listener thread");
@@ -271,6 +450,7 @@ impl<R: Runtime> SocketServer<R> {
                                 Ok(stream) => {
                                     info!("[TAURI_MCP] Accepted new IPC connection");
                                     let app_clone = app.clone();
+                                    let security = security.clone();
                                     let unified_stream = UnifiedStream::Ipc(stream);
 
                                     // Spawn a new thread with its own panic handler for client handling
@@ -299,7 +479,7 @@ impl<R: Runtime> SocketServer<R> {
                                         }));
 
                                         // Handle the client with error trapping
-                                        if let Err(e) = handle_client(unified_stream, app_clone) {
+                                        if let Err(e) = handle_client(unified_stream, app_clone, security) {
                                             if e.to_string()
                                                 .contains("No process is on the other end of the pipe")
                                             {
@@ -336,6 +516,11 @@ impl<R: Runtime> SocketServer<R> {
                             match tcp_listener.accept() {
                                 Ok((mut stream, addr)) => {
                                     info!("[TAURI_MCP] Accepted new TCP connection from: {}", addr);
+
+                                    if !is_peer_allowed(&allowed_remote, &addr.ip()) {
+                                        error!("[TAURI_MCP] Rejected TCP connection from {}: not in allowed remote ranges", addr);
+                                        continue;
+                                    }
                                     
                                     // Set the stream back to blocking mode for normal I/O operations
                                     if let Err(e) = stream.set_nonblocking(false) {
@@ -344,12 +529,22 @@ impl<R: Runtime> SocketServer<R> {
                                     }
                                     
                                     let app_clone = app.clone();
-                                    let unified_stream = UnifiedStream::Tcp(stream);
+                                    let security = security.clone();
+                                    let unified_stream = match &tls_config {
+                                        Some(config) => match rustls::ServerConnection::new(config.clone()) {
+                                            Ok(conn) => UnifiedStream::Tls(Box::new(rustls::StreamOwned::new(conn, stream))),
+                                            Err(e) => {
+                                                error!("[TAURI_MCP] Failed to start TLS session: {}", e);
+                                                continue;
+                                            }
+                                        },
+                                        None => UnifiedStream::Tcp(stream),
+                                    };
 
                                     // Spawn a new thread for client handling
                                     thread::spawn(move || {
                                         // Handle the client with error trapping
-                                        if let Err(e) = handle_client(unified_stream, app_clone) {
+                                        if let Err(e) = handle_client(unified_stream, app_clone, security) {
                                             error!("[TAURI_MCP] Error handling TCP client: {}", e);
                                         }
                                     });
@@ -402,6 +597,28 @@ impl<R: Runtime> SocketServer<R> {
         Ok(())
     }
 
+    /// Report the transport and the effective network policy
+    pub fn status(&self) -> ServerStatusResponse {
+        let (transport, address) = match &self.socket_type {
+            SocketType::Ipc { path } => {
+                let path = path.clone().unwrap_or_else(|| {
+                    std::env::temp_dir().join(get_machine_specific_socket_name())
+                });
+                ("ipc", path.to_string_lossy().to_string())
+            }
+            SocketType::Tcp { host, port } => ("tcp", format!("{}:{}", host, port)),
+        };
+
+        ServerStatusResponse {
+            running: *self.running.lock().unwrap(),
+            protocol_version: PROTOCOL_VERSION,
+            transport: transport.to_string(),
+            address,
+            security: self.security.status(),
+            responsiveness: None,
+        }
+    }
+
     #[cfg(desktop)]
     fn get_socket_name(&self, path: &Option<std::path::PathBuf>) -> Result<Name, Error> {
         let socket_path = if let Some(p) = path {
@@ -426,45 +643,363 @@ impl<R: Runtime> SocketServer<R> {
     }
 }
 
-fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate::Result<()> {
+/// Counter behind each connection's `client_id`
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Per-connection state negotiated by the client
+struct ConnectionState {
+    /// Identifies this connection in what its tool calls cause in the page
+    client_id: String,
+    protocol_version: u32,
+    /// Message delimiting the client chose with its first frame
+    framing: Framing,
+    authenticated: bool,
+    /// Name presented in the handshake, if the client identified itself
+    client_name: Option<String>,
+    /// Capabilities granted to this connection; `None` means unrestricted
+    capabilities: Option<HashSet<Capability>>,
+    /// Notification topics this connection receives, if it subscribed to any
+    subscription: Option<Subscription>,
+    /// Calls the user allowed for the rest of this connection
+    #[cfg(feature = "approval")]
+    approvals: crate::approval::SessionApprovals,
+}
+
+struct Subscription {
+    topics: HashSet<String>,
+    receiver: broadcast::Receiver<Notification>,
+}
+
+impl Subscription {
+    /// Collect encoded frames for every pending notification on a subscribed topic
+    fn drain(&mut self, version: u32) -> Result<Vec<Bytes>, Error> {
+        let mut frames = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(notification) => {
+                    if self.topics.contains(&notification.topic) {
+                        frames.push(encode_notification(&notification, version)?);
+                    }
+                }
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    let notice = Notification {
+                        topic: "lagged".to_string(),
+                        data: serde_json::json!({ "skipped": skipped }),
+                    };
+                    frames.push(encode_notification(&notice, version)?);
+                }
+                Err(_) => break,
+            }
+        }
+        Ok(frames)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionRequest {
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+/// Push pending notifications until the client sends more input. Returns `false` on end of stream.
+async fn pump_notifications(
+    reader: &mut BufReader<LoggingStream<UnifiedStream>>,
+    state: &mut ConnectionState,
+) -> crate::Result<bool> {
+    let version = state.protocol_version;
+    loop {
+        let frames = match state.subscription.as_mut() {
+            Some(subscription) => subscription.drain(version)?,
+            None => return Ok(true),
+        };
+
+        let writer = reader.get_mut();
+        for frame in frames {
+            framing::write_frame(writer, state.framing, &frame)
+                .map_err(|e| Error::Io(format!("Error writing notification: {}", e)))?;
+        }
+
+        reader
+            .get_ref()
+            .get_ref()
+            .set_nonblocking(true)
+            .map_err(|e| Error::Io(format!("Failed to poll socket: {}", e)))?;
+        let polled = reader.fill_buf().map(|buf| buf.len());
+        reader
+            .get_ref()
+            .get_ref()
+            .set_nonblocking(false)
+            .map_err(|e| Error::Io(format!("Failed to poll socket: {}", e)))?;
+
+        match polled {
+            Ok(0) => return Ok(false),
+            Ok(_) => return Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                tokio::time::sleep(NOTIFICATION_POLL_INTERVAL).await;
+            }
+            Err(e) => return Err(Error::Io(format!("Error reading from socket: {}", e))),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HandshakeRequest {
+    auth_token: Option<String>,
+    client_name: Option<String>,
+    client_key: Option<String>,
+}
+
+/// Apply connection-level concerns (versioning, authentication) before routing to the tools
+async fn dispatch_request<R: Runtime>(
+    app: &AppHandle<R>,
+    security: &TransportSecurity,
+    state: &mut ConnectionState,
+    request: SocketRequest,
+) -> crate::Result<SocketResponse> {
+    if let Some(requested) = request.protocol_version {
+        state.protocol_version = negotiate_protocol_version(requested)?;
+    }
+
+    if request.command == commands::HANDSHAKE {
+        let handshake: HandshakeRequest = serde_json::from_value(request.payload)
+            .map_err(|e| Error::Anyhow(format!("Invalid payload for handshake: {}", e)))?;
+        let presented = handshake.auth_token.unwrap_or_default();
+        if !security.verify_token(&presented) {
+            return Err(Error::Anyhow("Authentication failed: invalid auth token".to_string()));
+        }
+
+        if !security.clients.is_empty() {
+            let name = handshake.client_name.unwrap_or_default();
+            let key = handshake.client_key.unwrap_or_default();
+            let grant = security.find_client(&name, &key).ok_or_else(|| {
+                Error::Anyhow("Authentication failed: unknown client name or key".to_string())
+            })?;
+            state.client_name = Some(grant.name.clone());
+            state.capabilities = Some(grant.capabilities.clone());
+        } else {
+            state.client_name = handshake.client_name;
+        }
+
+        state.authenticated = true;
+        let mut capabilities: Vec<String> = match &state.capabilities {
+            Some(caps) => caps.iter().map(|c| c.to_string()).collect(),
+            None => vec!["*".to_string()],
+        };
+        capabilities.sort();
+
+        return Ok(SocketResponse {
+            success: true,
+            data: Some(serde_json::json!({
+                "authenticated": true,
+                "protocolVersion": state.protocol_version,
+                "client": state.client_name,
+                "clientId": state.client_id,
+                "capabilities": capabilities,
+            })),
+            error: None,
+            ..Default::default()
+        });
+    }
+
+    if !state.authenticated {
+        return Err(Error::Anyhow(
+            "Authentication required: send a handshake command first".to_string(),
+        ));
+    }
+
+    if let Some(granted) = &state.capabilities {
+        if let Some(required) = tools::capability_for(app, &request.command, &request.payload) {
+            if !granted.contains(&required) {
+                return Err(Error::Anyhow(format!(
+                    "Client '{}' is not granted the '{}' capability required by '{}'",
+                    state.client_name.as_deref().unwrap_or("unknown"),
+                    required,
+                    request.command
+                )));
+            }
+        }
+    }
+
+    #[cfg(feature = "approval")]
+    if let Some(denied) = crate::approval::check(
+        app,
+        &mut state.approvals,
+        state.client_name.as_deref(),
+        &request.command,
+        &request.payload,
+    )
+    .await?
+    {
+        return Ok(denied);
+    }
+
+    match request.command.as_str() {
+        commands::SUBSCRIBE => subscribe(app, state, request.payload),
+        commands::UNSUBSCRIBE => unsubscribe(state, request.payload),
+        commands::CONSOLE_SUBSCRIBE => console_subscribe(app, state, request.payload).await,
+        commands::CONSOLE_UNSUBSCRIBE => {
+            unsubscribe(state, serde_json::json!({ "topics": [CONSOLE_TOPIC] }))
+        }
+        _ => tools::handle_command(app, &request.command, request.payload).await,
+    }
+}
+
+/// Start receiving notifications for the given topics on this connection
+fn subscribe<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &mut ConnectionState,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: SubscriptionRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid payload for subscribe: {}", e)))?;
+    if request.topics.is_empty() {
+        return Err(Error::Anyhow("subscribe requires at least one topic".to_string()));
+    }
+
+    if state.subscription.is_none() {
+        let hub = app
+            .try_state::<NotificationHub>()
+            .ok_or_else(|| Error::Anyhow("Notifications are not available".to_string()))?;
+        state.subscription = Some(Subscription {
+            topics: HashSet::new(),
+            receiver: hub.subscribe(),
+        });
+    }
+    if let Some(subscription) = state.subscription.as_mut() {
+        subscription.topics.extend(request.topics);
+    }
+
+    Ok(subscription_response(state))
+}
+
+/// Stop receiving the given topics, or all topics when none are listed
+fn unsubscribe(state: &mut ConnectionState, payload: Value) -> crate::Result<SocketResponse> {
+    let request: SubscriptionRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid payload for unsubscribe: {}", e)))?;
+
+    if let Some(subscription) = state.subscription.as_mut() {
+        if request.topics.is_empty() {
+            subscription.topics.clear();
+        } else {
+            for topic in &request.topics {
+                subscription.topics.remove(topic);
+            }
+        }
+        if subscription.topics.is_empty() {
+            state.subscription = None;
+        }
+    }
+
+    Ok(subscription_response(state))
+}
+
+/// Stream the window's console entries and JavaScript errors to this connection as `console`
+/// notifications, installing console capture in the window first
+async fn console_subscribe<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &mut ConnectionState,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let setup = tools::handle_setup_console_capture(app, payload).await?;
+    if !setup.success {
+        return Ok(setup);
+    }
+
+    let mut response = subscribe(app, state, serde_json::json!({ "topics": [CONSOLE_TOPIC] }))?;
+    // Entries after this cursor are streamed; earlier ones are in get_console_buffer
+    let cursor = app.state::<ConsoleStore>().cursor();
+    if let Some(Value::Object(data)) = response.data.as_mut() {
+        data.insert("cursor".to_string(), cursor.into());
+    }
+    Ok(response)
+}
+
+fn subscription_response(state: &ConnectionState) -> SocketResponse {
+    let mut topics: Vec<&String> = state
+        .subscription
+        .as_ref()
+        .map(|s| s.topics.iter().collect())
+        .unwrap_or_default();
+    topics.sort();
+
+    SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({ "topics": topics })),
+        error: None,
+        ..Default::default()
+    }
+}
+
+fn handle_client<R: Runtime>(
+    stream: UnifiedStream,
+    app: AppHandle<R>,
+    security: Arc<TransportSecurity>,
+) -> crate::Result<()> {
     info!("[TAURI_MCP] Handling new client connection");
     // Use tokio runtime to handle async functions
     let rt = tokio::runtime::Runtime::new()
         .map_err(|e| Error::Anyhow(format!("Failed to create runtime: {}", e)))?;
 
     rt.block_on(async {
-        // Create a buffered reader and separate writer for the socket
-        let stream_clone = match stream.try_clone() {
-            Ok(clone) => clone,
-            Err(e) => {
-                // This might be a disconnection error on Windows
-                if e.to_string()
-                    .contains("No process is on the other end of the pipe")
-                {
-                    info!("[TAURI_MCP] Client already disconnected (pipe error)");
-                    return Ok(());
-                }
-                return Err(Error::Io(format!("Failed to clone stream: {}", e)));
+        // Wrap the stream with our logging wrapper. Responses are written through the reader's
+        // inner stream so TLS sessions, which cannot be cloned, work the same as plain sockets.
+        let mut reader = BufReader::new(LoggingStream::new(stream));
+
+        // The first byte decides whether this client sends lines or length-prefixed frames
+        let framing = match Framing::detect(&mut reader) {
+            Ok(Some(framing)) => framing,
+            Ok(None) => {
+                info!("[TAURI_MCP] Client disconnected before sending a request");
+                return Ok(());
             }
+            Err(e) => return Err(Error::Io(format!("Error reading from socket: {}", e))),
         };
+        info!("[TAURI_MCP] Client uses {:?} framing", framing);
 
-        // Wrap the streams with our logging wrapper
-        let logging_reader = LoggingStream::new(stream_clone);
-        let mut reader = BufReader::new(logging_reader);
-        let mut writer = LoggingStream::new(stream);
+        // Clients that never announce a version get the current envelope
+        let mut state = ConnectionState {
+            client_id: format!("client-{}", NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed)),
+            protocol_version: PROTOCOL_VERSION,
+            framing,
+            authenticated: !security.requires_handshake(),
+            client_name: None,
+            capabilities: None,
+            subscription: None,
+            #[cfg(feature = "approval")]
+            approvals: Default::default(),
+        };
+        let mut frames = FrameReader::default();
+        // Shown by the activity indicator until this function returns
+        let activity = ActiveConnection::open(&app);
 
         // Keep handling requests until the client disconnects
         loop {
-            let mut line = String::new();
-            match reader.read_line(&mut line) {
-                Ok(0) => {
+            // Subscribed clients are polled so notifications can be pushed between requests
+            if state.subscription.is_some() && reader.buffer().is_empty() {
+                match pump_notifications(&mut reader, &mut state).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        info!("[TAURI_MCP] Client disconnected cleanly");
+                        return Ok(());
+                    }
+                    Err(e) => {
+                        info!("[TAURI_MCP] Subscribed client dropped: {}", e);
+                        return Ok(());
+                    }
+                }
+            }
+
+            let frame = match frames.read_frame(&mut reader, state.framing) {
+                Ok(Some(frame)) => {
+                    info!("[TAURI_MCP] Received frame: {} bytes", frame.len());
+                    frame
+                }
+                Ok(None) => {
                     // End of stream, client disconnected
                     info!("[TAURI_MCP] Client disconnected cleanly");
                     return Ok(());
                 }
-                Ok(_) => {
-                    info!("[TAURI_MCP] Received command: {}", line.trim());
-                }
                 Err(e) => {
                     // Check if this is a pipe disconnection error
                     if e.to_string()
@@ -479,7 +1014,7 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
             };
 
         // Parse and process the request
-        let request: SocketRequest = match serde_json::from_str(&line) {
+        let request: SocketRequest = match serde_json::from_slice(&frame) {
             Ok(req) => req,
             Err(e) => {
                 let error_msg = format!("Invalid request format: {}", e);
@@ -490,38 +1025,36 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
                     success: false,
                     data: None,
                     error: Some(error_msg),
+                    ..Default::default()
                 };
 
-                let error_json = match serde_json::to_string(&error_response) {
-                    Ok(json) => json + "\n",
-                    Err(_) => {
-                        return Err(Error::Anyhow(
-                            "Failed to serialize error response".to_string(),
-                        ));
-                    }
-                };
-
-                match writer.write_all(error_json.as_bytes()) {
-                    Ok(_) => {
-                        if let Err(e) = writer.flush() {
-                            return Err(Error::Io(format!("Error flushing error response: {}", e)));
-                        }
-                    }
-                    Err(e) => {
-                        return Err(Error::Io(format!("Error writing error response: {}", e)));
-                    }
+                let error_frame = encode_response(&error_response, state.protocol_version)?;
+                if let Err(e) = framing::write_frame(reader.get_mut(), state.framing, &error_frame) {
+                    return Err(Error::Io(format!("Error writing error response: {}", e)));
                 }
 
-                // Clear the line and continue to the next iteration
-                line.clear();
+                // Continue to the next frame
                 continue;
             }
         };
 
         info!("[TAURI_MCP] Processing command: {}", request.command);
+        let metadata = app
+            .try_state::<ResponseMetadata>()
+            .is_none_or(|enabled| enabled.0)
+            .then(|| RequestMetadata::of(&request));
 
+        if let Some(activity) = &activity {
+            activity.begin(&request.command, state.client_name.as_deref());
+        }
         // Use the centralized command handler from tools module
-        let response = match tools::handle_command(&app, &request.command, request.payload).await {
+        let client_id = state.client_id.clone();
+        let result =
+            js_bridge::as_client(client_id, dispatch_request(&app, &security, &mut state, request)).await;
+        if let Some(activity) = &activity {
+            activity.end();
+        }
+        let mut response = match result {
             Ok(resp) => resp,
             Err(e) => {
                 // Convert the error into a response structure
@@ -530,41 +1063,25 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
                     success: false,
                     data: None,
                     error: Some(e.to_string()),
+                    ..Default::default()
                 }
             }
         };
+        if let Some(metadata) = metadata {
+            metadata.stamp(&mut response);
+        }
 
         // When writing the response, handle pipe errors gracefully
-        let response_json = serde_json::to_string(&response)
-            .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?
-            + "\n";
+        let response_frame = encode_response(&response, state.protocol_version)?;
         info!(
             "[TAURI_MCP] Sending response: length = {} bytes",
-            response_json.len()
+            response_frame.len()
         );
 
-        // Write the response directly without chunking
-        match writer.write_all(response_json.as_bytes()) {
+        // Delimiter and payload go out in one vectored write
+        match framing::write_frame(reader.get_mut(), state.framing, &response_frame) {
             Ok(_) => {
-                match writer.flush() {
-                    Ok(_) => {
-                        info!("[TAURI_MCP] Response sent successfully");
-                        // Continue to the next iteration of the loop
-                    }
-                    Err(e) => {
-                        if e.to_string()
-                            .contains("No process is on the other end of the pipe")
-                            || e.kind() == std::io::ErrorKind::BrokenPipe
-                        {
-                            info!(
-                                "[TAURI_MCP] Client disconnected during flush (pipe error)"
-                            );
-                            return Ok(()); // Return success for expected client disconnect
-                        } else {
-                            return Err(Error::Io(format!("Error flushing response: {}", e)));
-                        }
-                    }
-                }
+                info!("[TAURI_MCP] Response sent successfully");
             }
             Err(e) => {
                 if e.to_string()
@@ -578,9 +1095,6 @@ fn handle_client<R: Runtime>(stream: UnifiedStream, app: AppHandle<R>) -> crate:
                 }
             }
         }
-        
-        // Clear the line for the next command
-        line.clear();
         } // End of loop
     })
 }
@@ -0,0 +1,165 @@
+//! In-process harness for exercising tool handlers without launching a real Tauri app.
+//!
+//! Enabled with the `test-utils` feature. Apps are built on Tauri's mock runtime, and page
+//! scripts are answered by a [`ScriptedBackend`] instead of a webview.
+//!
+//! ```ignore
+//! use serde_json::json;
+//! use tauri_plugin_mcp::{PluginConfig, test_utils};
+//!
+//! let app = test_utils::mock_app(PluginConfig::new("Test".to_string()));
+//! app.backend.on_eval("main", |_body| Ok(json!({ "rows": [] })));
+//!
+//! let response = tauri::async_runtime::block_on(
+//!     app.dispatch("extract_table", json!({ "selector": "table" })),
+//! )
+//! .unwrap();
+//! assert!(response.success);
+//! ```
+
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime, WebviewWindowBuilder};
+
+pub use crate::socket_server::SocketResponse;
+pub use tauri::test::MockRuntime;
+
+use crate::PluginConfig;
+use crate::error::Error;
+use crate::tools::webview_backend::{BackendFuture, WebviewBackend};
+
+/// Answers one page script: the body of an async function, as a tool would have evaluated it
+type ScriptHandler = Arc<dyn Fn(&str) -> std::result::Result<Value, String> + Send + Sync>;
+
+/// Fake [`WebviewBackend`] whose windows answer scripts from registered handlers
+#[derive(Default)]
+pub struct ScriptedBackend {
+    windows: Mutex<HashSet<String>>,
+    handlers: Mutex<HashMap<String, ScriptHandler>>,
+    evaluated: Mutex<Vec<(String, String)>>,
+}
+
+impl ScriptedBackend {
+    /// Make a window available to tools
+    pub fn add_window(&self, window_label: &str) {
+        self.windows.lock().unwrap().insert(window_label.to_string());
+    }
+
+    /// Make a window unavailable, as if it had been closed
+    pub fn remove_window(&self, window_label: &str) {
+        self.windows.lock().unwrap().remove(window_label);
+    }
+
+    /// Answer scripts evaluated in `window_label` with `handler`. `Ok` becomes the script's return
+    /// value, `Err` a thrown exception. Windows without a handler return `null` for every script.
+    pub fn on_eval<F>(&self, window_label: &str, handler: F)
+    where
+        F: Fn(&str) -> std::result::Result<Value, String> + Send + Sync + 'static,
+    {
+        self.handlers
+            .lock()
+            .unwrap()
+            .insert(window_label.to_string(), Arc::new(handler));
+    }
+
+    /// Every `(window_label, script)` evaluated so far, oldest first
+    pub fn evaluated(&self) -> Vec<(String, String)> {
+        self.evaluated.lock().unwrap().clone()
+    }
+
+    fn record(&self, window_label: &str, script: &str) -> crate::Result<()> {
+        if !self.has_window(window_label) {
+            return Err(Error::WindowNotFound(window_label.to_string()));
+        }
+        self.evaluated
+            .lock()
+            .unwrap()
+            .push((window_label.to_string(), script.to_string()));
+        Ok(())
+    }
+}
+
+impl WebviewBackend for ScriptedBackend {
+    fn has_window(&self, window_label: &str) -> bool {
+        self.windows.lock().unwrap().contains(window_label)
+    }
+
+    fn window_labels(&self) -> Vec<String> {
+        self.windows.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn eval(&self, window_label: &str, script: &str) -> crate::Result<()> {
+        self.record(window_label, script)
+    }
+
+    fn call<'a>(
+        &'a self,
+        window_label: &'a str,
+        body: &'a str,
+        _timeout: Duration,
+    ) -> BackendFuture<'a, Value> {
+        Box::pin(async move {
+            self.record(window_label, body)?;
+            let handler = self.handlers.lock().unwrap().get(window_label).cloned();
+            Ok(match handler.map(|handler| handler(body)) {
+                None => json!({ "ok": true, "value": null }),
+                Some(Ok(value)) => json!({ "ok": true, "value": value }),
+                Some(Err(error)) => json!({ "ok": false, "error": error }),
+            })
+        })
+    }
+}
+
+/// A plugin instance on the mock runtime together with its scripted backend
+pub struct MockApp {
+    pub app: tauri::App<MockRuntime>,
+    pub backend: Arc<ScriptedBackend>,
+}
+
+impl MockApp {
+    pub fn handle(&self) -> &AppHandle<MockRuntime> {
+        self.app.handle()
+    }
+
+    /// Open another window, both in the mock runtime and in the backend
+    pub fn add_window(&self, label: &str) {
+        WebviewWindowBuilder::new(self.app.handle(), label, Default::default())
+            .build()
+            .expect("failed to create mock window");
+        self.backend.add_window(label);
+    }
+
+    /// Shorthand for [`dispatch_tool`] on this app
+    pub async fn dispatch(&self, name: &str, payload: Value) -> crate::Result<SocketResponse> {
+        dispatch_tool(self.app.handle(), name, payload).await
+    }
+}
+
+/// Build an app on the mock runtime with the plugin installed and a `main` window.
+/// The socket server is never started and any backend in `config` is replaced by a [`ScriptedBackend`].
+pub fn mock_app(config: PluginConfig) -> MockApp {
+    let backend = Arc::new(ScriptedBackend::default());
+    let mut config = config.start_socket_server(false);
+    config.webview_backend = Some(backend.clone());
+
+    let app = tauri::test::mock_builder()
+        .plugin(crate::init_with_config(config))
+        .build(tauri::test::mock_context(tauri::test::noop_assets()))
+        .expect("failed to build mock app");
+
+    let mock = MockApp { app, backend };
+    mock.add_window("main");
+    mock
+}
+
+/// Run a tool exactly as the socket server would after authentication, including
+/// redaction and the observation cache.
+pub async fn dispatch_tool<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    crate::tools::handle_command(app, name, payload).await
+}
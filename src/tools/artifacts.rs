@@ -0,0 +1,22 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::artifacts::ArtifactStore;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+
+pub fn handle_list_artifacts<R: Runtime>(
+    app: &AppHandle<R>,
+    _payload: Value,
+) -> Result<SocketResponse, Error> {
+    let store = app.state::<ArtifactStore>();
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({
+            "directory": store.dir(),
+            "artifacts": store.list(),
+        })),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,287 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::{handle_setup_console_capture, ConsoleQuery, ConsoleStore};
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::time_range::{TimeBound, TimeRange};
+use crate::tools::timeline::{TimelineCategory, TimelineStore};
+
+/// Matches returned unless the request sets `limit`
+const DEFAULT_LIMIT: usize = 50;
+/// Items shown on each side of a match unless the request sets `context`
+const DEFAULT_CONTEXT: usize = 2;
+const MAX_CONTEXT: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BufferSource {
+    Console,
+    Errors,
+    Network,
+    Annotations,
+}
+
+impl BufferSource {
+    const ALL: [BufferSource; 4] = [Self::Console, Self::Errors, Self::Network, Self::Annotations];
+
+    /// Fields of an item the pattern is run against, in the order they are tried
+    fn fields(self) -> &'static [&'static str] {
+        match self {
+            Self::Console => &["message", "stack"],
+            Self::Errors => &["message", "stack", "filename"],
+            Self::Network => &["url", "method", "status", "error"],
+            Self::Annotations => &["summary", "details"],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchBuffersRequest {
+    pub window_label: Option<String>,
+    /// Regular expression to look for
+    pub pattern: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Buffers to search (default: all)
+    pub sources: Option<Vec<BufferSource>>,
+    /// Only items logged at or after this time, in milliseconds since the Unix epoch or ISO 8601
+    pub from: Option<TimeBound>,
+    /// Only items logged at or before this time
+    pub to: Option<TimeBound>,
+    /// Neighbouring items to return on each side of a match (default 2, at most 20)
+    pub context: Option<usize>,
+    /// Most matches to return (default 50)
+    pub limit: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BufferItem {
+    pub source: BufferSource,
+    pub item: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BufferMatch {
+    #[serde(flatten)]
+    pub hit: BufferItem,
+    /// Field of the item the pattern matched in
+    pub field: &'static str,
+    /// Text the pattern matched
+    pub matched: String,
+    /// Items just before the match in the same buffer, oldest first
+    pub before: Vec<BufferItem>,
+    /// Items just after the match in the same buffer
+    pub after: Vec<BufferItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchBuffersResponse {
+    pub window_label: String,
+    pub matches: Vec<BufferMatch>,
+    /// Matches found, including any past `limit`
+    pub total_matches: usize,
+    pub truncated: bool,
+    /// Items searched across all buffers
+    pub searched_count: usize,
+}
+
+/// A field as text: strings as they are, anything else as JSON
+fn field_text(item: &Value, field: &str) -> Option<String> {
+    match item.get(field)? {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Whether an item was logged in the range: annotations by `timestamp_ms`, everything else
+/// by the ISO 8601 `timestamp` the capture script gave it
+fn in_range(range: &TimeRange, item: &BufferItem) -> bool {
+    if range.is_unbounded() {
+        return true;
+    }
+    match item.item.get("timestamp_ms").and_then(|v| v.as_u64()) {
+        Some(ms) => range.contains_ms(ms),
+        None => {
+            let logged = item
+                .item
+                .get("last_timestamp")
+                .or_else(|| item.item.get("timestamp"))
+                .and_then(|v| v.as_str());
+            logged.is_some_and(|timestamp| range.contains_timestamp(timestamp))
+        }
+    }
+}
+
+/// The first match in an item, trying its fields in order
+fn find(regex: &Regex, item: &BufferItem) -> Option<(&'static str, String)> {
+    item.source.fields().iter().find_map(|&field| {
+        let text = field_text(&item.item, field)?;
+        regex.find(&text).map(|m| (field, m.as_str().to_string()))
+    })
+}
+
+/// Console entries and JavaScript errors as one buffer in the order they were stored, so an
+/// error's context is what was logged around it
+fn console_buffer<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    wants: impl Fn(BufferSource) -> bool,
+) -> Vec<BufferItem> {
+    let snapshot = app
+        .state::<ConsoleStore>()
+        .snapshot(window_label, &ConsoleQuery::default());
+    let mut items: Vec<(Option<u64>, BufferItem)> = Vec::new();
+    if wants(BufferSource::Console) {
+        items.extend(snapshot.entries.into_iter().map(|entry| {
+            (entry.cursor, BufferItem {
+                source: BufferSource::Console,
+                item: serde_json::to_value(entry).unwrap_or_default(),
+            })
+        }));
+    }
+    if wants(BufferSource::Errors) {
+        items.extend(snapshot.errors.into_iter().map(|error| {
+            (error.cursor, BufferItem {
+                source: BufferSource::Errors,
+                item: serde_json::to_value(error).unwrap_or_default(),
+            })
+        }));
+    }
+    items.sort_by_key(|(cursor, _)| *cursor);
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Annotations attached to the window or to every window
+fn annotation_buffer<R: Runtime>(app: &AppHandle<R>, window_label: &str) -> Vec<BufferItem> {
+    app.state::<TimelineStore>()
+        .snapshot()
+        .into_iter()
+        .filter(|e| e.category == TimelineCategory::Annotation)
+        .filter(|e| e.window_label.as_deref().is_none_or(|l| l == window_label))
+        .map(|e| BufferItem {
+            source: BufferSource::Annotations,
+            item: serde_json::to_value(e).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Run a regular expression over a window's console entries, JavaScript errors, network log and
+/// annotations in the plugin, returning each match with the items around it
+pub async fn handle_search_buffers<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: SearchBuffersRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Searching buffers of window {} for /{}/", window_label, request.pattern);
+
+    let regex = match RegexBuilder::new(&request.pattern)
+        .case_insensitive(request.case_insensitive)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(e) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Invalid pattern: {}", e)),
+                ..Default::default()
+            })
+        }
+    };
+    let range = match TimeRange::new(request.from.as_ref(), request.to.as_ref()) {
+        Ok(range) => range,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                ..Default::default()
+            })
+        }
+    };
+    let sources = request.sources.unwrap_or_else(|| BufferSource::ALL.to_vec());
+    let wants = |source: BufferSource| sources.contains(&source);
+
+    let mut buffers = Vec::new();
+    if wants(BufferSource::Console) || wants(BufferSource::Errors) {
+        buffers.push(console_buffer(app, &window_label, wants));
+    }
+    // The network log only lives in the page
+    if wants(BufferSource::Network) {
+        let setup_result = handle_setup_console_capture(app, serde_json::json!({
+            "window_label": window_label
+        })).await?;
+        if !setup_result.success {
+            return Ok(setup_result);
+        }
+        let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+        let log = eval_with_result(app, &window_label, "return window.__mcpNetworkLog || [];", timeout).await?;
+        let items = match log {
+            Value::Array(items) => items,
+            _ => Vec::new(),
+        };
+        buffers.push(
+            items
+                .into_iter()
+                .map(|item| BufferItem { source: BufferSource::Network, item })
+                .collect(),
+        );
+    }
+    if wants(BufferSource::Annotations) {
+        buffers.push(annotation_buffer(app, &window_label));
+    }
+
+    // Context stays within the range too
+    for buffer in &mut buffers {
+        buffer.retain(|item| in_range(&range, item));
+    }
+
+    let context = request.context.unwrap_or(DEFAULT_CONTEXT).min(MAX_CONTEXT);
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    let mut matches = Vec::new();
+    let mut total_matches = 0;
+    for buffer in &buffers {
+        for (index, item) in buffer.iter().enumerate() {
+            let Some((field, matched)) = find(&regex, item) else {
+                continue;
+            };
+            total_matches += 1;
+            if matches.len() < limit {
+                matches.push(BufferMatch {
+                    hit: item.clone(),
+                    field,
+                    matched,
+                    before: buffer[index.saturating_sub(context)..index].to_vec(),
+                    after: buffer[index + 1..(index + 1 + context).min(buffer.len())].to_vec(),
+                });
+            }
+        }
+    }
+
+    let response = SearchBuffersResponse {
+        window_label,
+        truncated: total_matches > matches.len(),
+        matches,
+        total_matches,
+        searched_count: buffers.iter().map(Vec::len).sum(),
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
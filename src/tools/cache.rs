@@ -0,0 +1,77 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+
+/// Expensive read-only commands whose responses may be served from the cache
+const CACHEABLE_COMMANDS: &[&str] = &[
+    commands::GET_DOM,
+    commands::TAKE_SCREENSHOT,
+    commands::QUERY_ELEMENTS,
+    commands::EXTRACT_TABLE,
+    commands::EXTRACT_LIST,
+];
+
+/// Short-lived cache for observation tools, so bursts of identical reads don't hit the webview.
+/// Requests can bypass it with `"fresh": true`.
+pub struct ResponseCache {
+    ttl: Option<Duration>,
+    entries: Mutex<HashMap<String, (Instant, SocketResponse)>>,
+}
+
+impl ResponseCache {
+    /// A `None` TTL disables caching
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache key for a request, or `None` if it must not be served from the cache
+    pub fn key_for(&self, command: &str, payload: &Value) -> Option<String> {
+        self.ttl?;
+        if !CACHEABLE_COMMANDS.contains(&command) {
+            return None;
+        }
+        if payload.get("fresh").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return None;
+        }
+
+        let mut normalized = payload.clone();
+        if let Some(object) = normalized.as_object_mut() {
+            object.remove("fresh");
+        }
+        Some(format!("{}:{}", command, normalized))
+    }
+
+    pub fn get(&self, key: &str) -> Option<SocketResponse> {
+        let ttl = self.ttl?;
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((stored, response)) if stored.elapsed() < ttl => Some(response.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: String, response: SocketResponse) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (stored, _)| stored.elapsed() < ttl);
+        entries.insert(key, (Instant::now(), response));
+    }
+
+    /// Drop everything, e.g. after a command that may have changed the page
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
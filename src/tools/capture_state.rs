@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tauri::webview::PageLoadEvent;
+use tauri::{Manager, Runtime, WindowEvent};
+
+use crate::tools::timeline::now_ms;
+
+/// Console capture installed in one window's current page
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSession {
+    /// `window.__consoleSessionId` reported by the capture script
+    pub session_id: Option<String>,
+    /// When the plugin first saw capture active in this page, milliseconds since the Unix epoch
+    pub installed_at_ms: u64,
+}
+
+/// A page in the same window replaced one console session with another, usually by reloading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTransition {
+    pub previous_session_id: String,
+    pub session_id: String,
+    /// When the plugin noticed the change, milliseconds since the Unix epoch
+    pub observed_at_ms: u64,
+}
+
+/// Transitions remembered per window
+const MAX_TRANSITIONS: usize = 32;
+
+/// Which windows currently have console capture installed. Sessions are dropped when a page
+/// starts loading, since the page scripts go with it; the last session id and the capture
+/// settings are kept so the next page can be linked to it and set up the same way. Everything
+/// about a window is dropped when it is created or destroyed.
+#[derive(Default)]
+pub struct CaptureRegistry {
+    windows: Mutex<HashMap<String, WindowCapture>>,
+}
+
+#[derive(Default)]
+struct WindowCapture {
+    active: Option<CaptureSession>,
+    last_session_id: Option<String>,
+    transitions: VecDeque<SessionTransition>,
+    /// Capture settings requested for this window, replayed in each new page
+    settings: Option<serde_json::Map<String, Value>>,
+}
+
+impl WindowCapture {
+    fn observe(&mut self, session_id: &str) {
+        if let Some(previous) = self.last_session_id.as_deref() {
+            if previous == session_id {
+                return;
+            }
+            if self.transitions.len() == MAX_TRANSITIONS {
+                self.transitions.pop_front();
+            }
+            self.transitions.push_back(SessionTransition {
+                previous_session_id: previous.to_string(),
+                session_id: session_id.to_string(),
+                observed_at_ms: now_ms(),
+            });
+        }
+        self.last_session_id = Some(session_id.to_string());
+    }
+}
+
+impl CaptureRegistry {
+    pub fn get(&self, window_label: &str) -> Option<CaptureSession> {
+        self.windows
+            .lock()
+            .unwrap()
+            .get(window_label)
+            .and_then(|window| window.active.clone())
+    }
+
+    pub fn record(&self, window_label: &str, session_id: Option<String>) -> CaptureSession {
+        let session = CaptureSession {
+            session_id,
+            installed_at_ms: now_ms(),
+        };
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(window_label.to_string()).or_default();
+        if let Some(id) = &session.session_id {
+            window.observe(id);
+        }
+        window.active = Some(session.clone());
+        session
+    }
+
+    /// Note the session id a page reported, linking it to the previous one if it changed
+    pub fn observe(&self, window_label: &str, session_id: &str) {
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(window_label.to_string()).or_default();
+        window.observe(session_id);
+        if let Some(active) = window.active.as_mut() {
+            active.session_id = Some(session_id.to_string());
+        }
+    }
+
+    /// Remember the capture settings of a setup request. Settings it leaves out keep their
+    /// earlier value, as they do in the page.
+    pub fn remember_settings(&self, window_label: &str, settings: Value) {
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.entry(window_label.to_string()).or_default();
+        let remembered = window.settings.get_or_insert_with(serde_json::Map::new);
+        if let Value::Object(settings) = settings {
+            remembered.extend(settings.into_iter().filter(|(_, value)| !value.is_null()));
+        }
+    }
+
+    /// Capture settings to set up the window's next page with, if capture was ever set up in it
+    pub fn settings(&self, window_label: &str) -> Option<Value> {
+        self.windows
+            .lock()
+            .unwrap()
+            .get(window_label)
+            .and_then(|window| window.settings.clone())
+            .map(Value::Object)
+    }
+
+    /// Session changes seen in this window, oldest first
+    pub fn transitions(&self, window_label: &str) -> Vec<SessionTransition> {
+        self.windows
+            .lock()
+            .unwrap()
+            .get(window_label)
+            .map(|window| window.transitions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The page is going away; its capture session ends but stays linkable
+    pub fn page_unloaded(&self, window_label: &str) {
+        if let Some(window) = self.windows.lock().unwrap().get_mut(window_label) {
+            window.active = None;
+        }
+    }
+
+    pub fn forget(&self, window_label: &str) {
+        self.windows.lock().unwrap().remove(window_label);
+    }
+
+    /// Every window with capture active and its session
+    pub fn sessions(&self) -> HashMap<String, CaptureSession> {
+        self.windows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(label, window)| Some((label.clone(), window.active.clone()?)))
+            .collect()
+    }
+}
+
+/// Plugin `on_page_load` hook: a new page has none of the previous page's scripts
+pub fn page_loading<R: Runtime, M: Manager<R>>(manager: &M, window_label: &str, event: PageLoadEvent) {
+    if event == PageLoadEvent::Started {
+        if let Some(registry) = manager.try_state::<CaptureRegistry>() {
+            registry.page_unloaded(window_label);
+        }
+    }
+}
+
+/// Plugin `on_event` hook for window lifecycle changes
+pub fn window_event<R: Runtime, M: Manager<R>>(manager: &M, window_label: &str, event: &WindowEvent) {
+    if matches!(event, WindowEvent::Destroyed) {
+        forget(manager, window_label);
+    }
+}
+
+/// Drop everything recorded under `window_label`, e.g. when a label is reused for a new window
+pub fn forget<R: Runtime, M: Manager<R>>(manager: &M, window_label: &str) {
+    if let Some(registry) = manager.try_state::<CaptureRegistry>() {
+        registry.forget(window_label);
+    }
+}
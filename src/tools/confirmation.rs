@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::timeline::now_ms;
+
+/// How long a held operation waits for `confirm` unless `PluginConfig::confirmation_timeout` says otherwise
+pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct PendingOperation {
+    command: String,
+    payload: Value,
+    expires: Instant,
+}
+
+/// Operations held back until a client confirms them, managed in plugin state. A rule is a tool
+/// name, or `tool:action` to hold only calls whose `action` or `operation` is `action`, e.g.
+/// `manage_local_storage:clear`.
+pub struct ConfirmationGate {
+    rules: Vec<String>,
+    timeout: Duration,
+    pending: Mutex<HashMap<String, PendingOperation>>,
+    next_id: AtomicU64,
+}
+
+/// Response data for a held operation
+#[derive(Debug, Serialize)]
+pub struct ConfirmationRequired {
+    pub confirmation_required: bool,
+    /// Pass to `confirm` to run the operation
+    pub operation_id: String,
+    pub tool: String,
+    /// The payload's `action` or `operation`, if it has one
+    pub action: Option<String>,
+    pub window_label: Option<String>,
+    pub expires_in_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmRequest {
+    pub operation_id: String,
+    /// Drop the operation instead of running it
+    #[serde(default)]
+    pub cancel: bool,
+}
+
+/// The payload's `action` or `operation`, which `tool:action` rules match against
+pub(crate) fn action_of(payload: &Value) -> Option<&str> {
+    payload
+        .get("action")
+        .or_else(|| payload.get("operation"))
+        .and_then(|v| v.as_str())
+}
+
+/// Whether a call matches any `tool` or `tool:action` rule
+pub(crate) fn matches_rules(rules: &[String], command: &str, payload: &Value) -> bool {
+    rules.iter().any(|rule| match rule.split_once(':') {
+        Some((tool, action)) => tool == command && action_of(payload) == Some(action),
+        None => rule == command,
+    })
+}
+
+impl ConfirmationGate {
+    pub fn new(rules: Vec<String>, timeout: Duration) -> Self {
+        Self {
+            rules,
+            timeout,
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Whether a call must be confirmed before it runs
+    pub fn requires(&self, command: &str, payload: &Value) -> bool {
+        matches_rules(&self.rules, command, payload)
+    }
+
+    /// Hold a call until it is confirmed and build the response telling the client so
+    pub fn hold(&self, command: &str, payload: Value) -> crate::Result<SocketResponse> {
+        let operation_id = format!(
+            "op-{}-{}",
+            now_ms(),
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let required = ConfirmationRequired {
+            confirmation_required: true,
+            operation_id: operation_id.clone(),
+            tool: command.to_string(),
+            action: action_of(&payload).map(str::to_string),
+            window_label: payload
+                .get("window_label")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            expires_in_ms: self.timeout.as_millis() as u64,
+        };
+        info!("[TAURI_MCP] Holding {} for confirmation as {}", command, operation_id);
+
+        let mut pending = self.pending.lock().unwrap();
+        let now = Instant::now();
+        pending.retain(|_, operation| operation.expires > now);
+        pending.insert(
+            operation_id.clone(),
+            PendingOperation {
+                command: command.to_string(),
+                payload,
+                expires: now + self.timeout,
+            },
+        );
+
+        Ok(SocketResponse {
+            success: false,
+            data: Some(serde_json::to_value(required).map_err(|e| {
+                Error::Anyhow(format!("Failed to serialize response: {}", e))
+            })?),
+            error: Some(format!(
+                "{} needs confirmation: call confirm with operation_id '{}' within {}s",
+                command,
+                operation_id,
+                self.timeout.as_secs()
+            )),
+            ..Default::default()
+        })
+    }
+
+    /// The command and payload a pending operation holds, for checking the confirming client's
+    /// capabilities
+    pub fn peek(&self, operation_id: &str) -> Option<(String, Value)> {
+        self.pending
+            .lock()
+            .unwrap()
+            .get(operation_id)
+            .map(|operation| (operation.command.clone(), operation.payload.clone()))
+    }
+
+    fn take(&self, operation_id: &str) -> Result<(String, Value), String> {
+        let operation = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(operation_id)
+            .ok_or_else(|| format!("No pending operation '{}'", operation_id))?;
+        if operation.expires <= Instant::now() {
+            return Err(format!(
+                "Operation '{}' expired before it was confirmed; send the request again",
+                operation_id
+            ));
+        }
+        Ok((operation.command, operation.payload))
+    }
+}
+
+/// What a `confirm` call leads to
+pub enum Confirmation {
+    /// Run the held command with its payload
+    Run(String, Value),
+    /// Nothing to run; send this back
+    Respond(SocketResponse),
+}
+
+/// Resolve a `confirm` call against the pending operations
+pub fn confirm<R: Runtime>(app: &AppHandle<R>, payload: Value) -> crate::Result<Confirmation> {
+    let request: ConfirmRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let gate = app.state::<ConfirmationGate>();
+
+    match gate.take(&request.operation_id) {
+        Ok(_) if request.cancel => {
+            info!("[TAURI_MCP] Cancelled held operation {}", request.operation_id);
+            Ok(Confirmation::Respond(SocketResponse {
+                success: true,
+                data: Some(serde_json::json!({
+                    "operation_id": request.operation_id,
+                    "cancelled": true
+                })),
+                error: None,
+                ..Default::default()
+            }))
+        }
+        Ok((command, payload)) => {
+            info!("[TAURI_MCP] Running confirmed operation {}", request.operation_id);
+            Ok(Confirmation::Run(command, payload))
+        }
+        Err(error) => Ok(Confirmation::Respond(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(error),
+            ..Default::default()
+        })),
+    }
+}
@@ -1,34 +1,309 @@
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, Manager, Runtime};
-use log::info;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, Webview};
+use log::{info, warn};
 
+use crate::notifications::NotificationHub;
+use crate::platform::browser_console::{self, BrowserMessage};
 use crate::socket_server::SocketResponse;
+use crate::tools::capture_state::{CaptureRegistry, SessionTransition};
+use crate::tools::js_bridge::{self, eval_with_result};
+use crate::tools::time_range::{parse_iso8601, TimeBound, TimeRange};
+use crate::tools::webview_backend;
 
 #[derive(Debug, Deserialize)]
 pub struct ConsoleOutputRequest {
     pub window_label: Option<String>,
     pub session_id: Option<String>,
     pub timeout_ms: Option<u64>,
+    /// Record the call-site stack trace of `console.error` and `console.warn` entries.
+    /// Applies to the page from now on; leaving it out keeps the current setting.
+    pub capture_stacks: Option<bool>,
+    /// Collapse consecutive identical console messages into one entry with a `repeat_count`
+    /// (the default), or keep every message as its own entry when exact fidelity matters.
+    /// Applies to the page from now on; leaving it out keeps the current setting.
+    pub collapse_repeats: Option<bool>,
+    /// Drop console messages below this level in the page instead of buffering them.
+    /// Defaults to the level set with `PluginConfig::console_min_level`.
+    pub min_level: Option<ConsoleLevel>,
+    /// Replace the page's sampling rules. Defaults to the rules set with `PluginConfig::sample_console`.
+    pub sampling: Option<Vec<SamplingRule>>,
+    /// `get_console_buffer`: only entries and errors whose message contains this
+    pub filter: Option<String>,
+    /// `get_console_buffer`: only entries stored after this cursor, the `next_cursor` of an
+    /// earlier call
+    pub since_cursor: Option<u64>,
+    /// `get_console_buffer`: only entries logged after this ISO 8601 time, as in their `timestamp`
+    pub since_timestamp: Option<String>,
+    /// `get_console_buffer`: only entries and errors logged at or after this time, in milliseconds
+    /// since the Unix epoch or ISO 8601
+    pub from: Option<TimeBound>,
+    /// `get_console_buffer`: only entries and errors logged at or before this time
+    pub to: Option<TimeBound>,
+    /// `get_console_buffer`: only entries at these levels; JavaScript errors count as `error`
+    pub levels: Option<Vec<String>>,
+    /// `get_console_buffer`: only entries and errors whose message matches this regular expression
+    pub message_regex: Option<String>,
+    /// `get_console_buffer`: only entries and errors caused by the tool invocation whose response
+    /// carried this `correlationId`
+    pub correlation_id: Option<String>,
+    /// `get_console_buffer`: this connection's own view of the console, leaving out entries and
+    /// errors caused by other clients' tool calls
+    #[serde(default)]
+    pub client_view: bool,
+    /// `get_console_buffer`: keep the plugin's own signaling lines, which are left out by default;
+    /// see [`is_internal`]
+    #[serde(default)]
+    pub include_internal: bool,
+    /// `get_console_buffer`: matching entries and errors to skip, oldest first
+    pub offset: Option<usize>,
+    /// `get_console_buffer`: most entries and errors to return
+    pub limit: Option<usize>,
+    /// Most entries the window keeps, in the page and in the plugin's store, before evicting the
+    /// oldest. Defaults to `PluginConfig::console_max_entries`.
+    pub max_entries: Option<usize>,
+    /// Approximate bytes of messages and stacks the window keeps before evicting the oldest.
+    /// Defaults to `PluginConfig::console_max_bytes`.
+    pub max_bytes: Option<usize>,
+    /// Relay console output and errors from Web Workers and SharedWorkers (default false). This
+    /// replaces the page's `Worker` and `SharedWorker` constructors with ones that start each
+    /// worker from a `blob:` bootstrap, so it needs `blob:` in the CSP's `worker-src` and a
+    /// SharedWorker is no longer shared with other windows. Stays on until the page reloads.
+    pub capture_workers: Option<bool>,
+    /// Count timers, `Math.random` and clock reads for `detect_nondeterminism` from now on
+    /// (default false; that tool turns it on itself). This wraps `Date`, `Math.random`,
+    /// `setTimeout` and `setInterval`. Stays on until the page reloads.
+    pub track_nondeterminism: Option<bool>,
+    /// Record listeners added from now on, for `get_event_listeners` (default false). This wraps
+    /// `EventTarget.prototype.addEventListener` and `removeEventListener`. Stays on until the
+    /// page reloads.
+    pub track_listeners: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Keep only one in `keep_one_in` console messages containing `pattern`, starting with the first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingRule {
+    pub pattern: String,
+    pub keep_one_in: u32,
+}
+
+/// Console method severity, lowest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsoleLevel {
+    Debug,
+    Log,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ConsoleLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConsoleLevel::Debug => "debug",
+            ConsoleLevel::Log => "log",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warn => "warn",
+            ConsoleLevel::Error => "error",
+        }
+    }
+}
+
+/// Entries kept per window unless `PluginConfig::console_max_entries` says otherwise
+pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+/// Approximate bytes kept per window unless `PluginConfig::console_max_bytes` says otherwise
+pub const DEFAULT_MAX_BYTES: usize = 1024 * 1024;
+
+/// Bounds on a window's console buffers; the oldest entries are evicted to stay within both.
+/// Fields left out keep their current value.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ConsoleLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+}
+
+impl ConsoleLimits {
+    fn is_empty(&self) -> bool {
+        self.max_entries.is_none() && self.max_bytes.is_none()
+    }
+}
+
+/// Capture settings from [`crate::PluginConfig`], managed in plugin state
+#[derive(Debug, Clone, Default)]
+pub struct CaptureDefaults {
+    pub min_level: Option<ConsoleLevel>,
+    pub sampling: Vec<SamplingRule>,
+    pub limits: ConsoleLimits,
+    /// Capture is installed in every page from the start, without a `setup_console_capture` call
+    pub on_load: bool,
+}
+
+/// Script setting the page's console level threshold, read by the capture script on every call
+pub fn min_level_script(level: ConsoleLevel) -> String {
+    format!("window.__mcpConsoleMinLevel = '{}';", level.as_str())
+}
+
+/// Script updating the page's buffer limits, applied from the next entry on
+pub fn limits_script(limits: &ConsoleLimits) -> String {
+    format!(
+        "window.__mcpConsoleLimits = Object.assign({{}}, window.__mcpConsoleLimits, {});",
+        serde_json::to_string(limits).unwrap_or_else(|_| "{}".to_string())
+    )
+}
+
+/// Script replacing the page's sampling rules; suppression counts restart with them
+pub fn sampling_script(rules: &[SamplingRule]) -> String {
+    format!(
+        "window.__mcpConsoleSampling = {}; window.__mcpConsoleSampleState = {{}};",
+        serde_json::to_string(rules).unwrap_or_else(|_| "[]".to_string())
+    )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsoleEntry {
+    /// Monotonic sequence number assigned by the capture script, shared with JS errors
+    #[serde(default)]
+    pub seq: u64,
+    /// Position in the plugin's console store, for `since_cursor`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<u64>,
     pub level: String,
     pub message: String,
     pub timestamp: String,
+    #[serde(alias = "sessionId")]
     pub session_id: String,
+    /// Tool invocation whose script caused this synchronously, as in its response's `correlationId`
+    #[serde(default, alias = "correlationId", skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Connection whose tool call caused this, as in its handshake's `clientId`
+    #[serde(default, alias = "clientId", skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// For `browser` entries, which logged the message the page didn't: `csp`, `deprecation` and
+    /// `intervention` from the page itself, or the webview's own category such as `security`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// For `browser` entries, how serious the browser considers it: `error`, `warn` or `info`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Which page in the window logged this: 1 for the first, one more after every navigation
+    /// or reload. Set by the plugin, not the capture script.
+    #[serde(default)]
+    pub navigation_generation: u64,
+    /// Script URL of the Web Worker or SharedWorker that logged this, if not the page itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker: Option<String>,
+    /// Same-origin iframe that logged this, by id, name or URL, if not the page itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
+    /// Where `console.error`/`console.warn` was called from, when stack capture is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+    /// The arguments the console method was called with, described one by one; `message` is
+    /// their previews joined by spaces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<ConsoleArg>,
+    /// How many consecutive identical messages this entry stands for. A repeat gives the entry
+    /// a new `seq`, so delta readers receive it again and should replace the earlier copy.
+    #[serde(default = "one", alias = "repeatCount")]
+    pub repeat_count: u32,
+    /// When the latest repeat was logged
+    #[serde(default, alias = "lastTimestamp", skip_serializing_if = "Option::is_none")]
+    pub last_timestamp: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn one() -> u32 {
+    1
+}
+
+/// One console argument as the capture script described it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleArg {
+    /// `string`, `number`, `boolean`, `bigint`, `symbol`, `undefined`, `null`, `function`,
+    /// `error`, `element`, `node`, `date`, `regexp`, `map`, `set`, `array`, `object` or `unknown`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The value as devtools would print it, cut off at 10000 characters
+    pub preview: String,
+    /// JSON-safe copy of the value where it says more than the preview: error name, message and
+    /// stack, element tag, id, classes and text, collection contents. Circular references read
+    /// `"[Circular]"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    /// Constructor name of class instances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_name: Option<String>,
+}
+
+impl ConsoleEntry {
+    /// Rough memory cost in the store; see [`entry_size`]
+    fn stored_size(&self) -> usize {
+        let args = if self.args.is_empty() { 0 } else { self.message.len() };
+        entry_size(&self.message, self.stack.as_deref()) + args
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JavaScriptError {
+    #[serde(default)]
+    pub seq: u64,
+    /// Position in the plugin's console store, for `since_cursor`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<u64>,
     pub message: String,
     pub filename: Option<String>,
     pub lineno: Option<u32>,
     pub colno: Option<u32>,
     pub stack: Option<String>,
     pub timestamp: String,
+    #[serde(alias = "sessionId")]
     pub session_id: String,
+    /// Tool invocation whose script caused this synchronously, as in its response's `correlationId`
+    #[serde(default, alias = "correlationId", skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Connection whose tool call caused this, as in its handshake's `clientId`
+    #[serde(default, alias = "clientId", skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Which page in the window threw this; see [`ConsoleEntry::navigation_generation`]
+    #[serde(default)]
+    pub navigation_generation: u64,
+    /// Script URL of the worker the error was thrown in, if not the page itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker: Option<String>,
+    /// Same-origin iframe the error was thrown in, by id, name or URL, if not the page itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkEntry {
+    #[serde(default)]
+    pub seq: u64,
+    /// "fetch" or "xhr"
+    pub kind: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub ok: bool,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+    pub timestamp: String,
+    #[serde(alias = "sessionId")]
+    pub session_id: String,
+    /// Tool invocation whose script started the request; see [`ConsoleEntry::correlation_id`]
+    #[serde(default, alias = "correlationId", skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Connection whose tool call caused this, as in its handshake's `clientId`
+    #[serde(default, alias = "clientId", skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,143 +311,1695 @@ pub struct ConsoleOutputResponse {
     pub entries: Vec<ConsoleEntry>,
     pub total_count: usize,
     pub session_id: String,
+    /// Earlier sessions in this window and what replaced them, oldest first
+    pub session_transitions: Vec<SessionTransition>,
+    /// Entries evicted to stay within the buffer limits
+    pub dropped_count: u64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ConsoleDeltaRequest {
+    pub window_label: Option<String>,
+    /// Highest sequence number the client has already seen (0 for everything)
+    #[serde(default)]
+    pub since_seq: u64,
+    /// Only entries logged at or after this time, in milliseconds since the Unix epoch or ISO 8601
+    pub from: Option<TimeBound>,
+    /// Only entries logged at or before this time
+    pub to: Option<TimeBound>,
+    /// Leave out entries caused by other clients' tool calls
+    #[serde(default)]
+    pub client_view: bool,
+    /// Keep the plugin's own signaling lines; see [`is_internal`]
+    #[serde(default)]
+    pub include_internal: bool,
+    pub timeout_ms: Option<u64>,
+}
 
-/// Setup console capture with event-based communication
-pub async fn handle_setup_console_capture<R: Runtime>(
-    app: &AppHandle<R>,
-    payload: Value,
-) -> crate::Result<SocketResponse> {
-    let request: ConsoleOutputRequest = serde_json::from_value(payload)
-        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
-    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
-    
-    info!("[TAURI_MCP] Setting up event-based console capture for window: {}", window_label);
-    
-    let window = app.get_webview_window(&window_label)
-        .ok_or_else(|| crate::Error::Anyhow(format!("Window '{}' not found", window_label)))?;
-    
-    // Event listeners will be setup individually when needed
-    
-    // Inject our event-based console capture system
-    let capture_code = r#"
-        (function() {
-            if (window.__mcpEventConsoleCapture) return { already_setup: true };
+#[derive(Debug, Serialize)]
+pub struct ConsoleDeltaResponse {
+    pub entries: Vec<ConsoleEntry>,
+    /// Sequence number to pass as `since_seq` on the next call
+    pub high_water_mark: u64,
+    /// True when the page's buffer restarted (e.g. after a reload) and `since_seq` was ignored
+    pub reset: bool,
+    pub session_id: Option<String>,
+    /// Earlier sessions in this window and what replaced them, oldest first
+    pub session_transitions: Vec<SessionTransition>,
+    /// Messages dropped by each sampling pattern since the rules were set in this page
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub suppressed: HashMap<String, u64>,
+    /// Entries the page evicted to stay within its buffer limits. Entries newer than `since_seq`
+    /// may be among them.
+    pub dropped_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClearConsoleRequest {
+    pub window_label: Option<String>,
+    /// Clear only JavaScript errors, keeping console entries
+    #[serde(default)]
+    pub errors_only: bool,
+    /// Clear only what the page with this `session_id` logged
+    pub session_id: Option<String>,
+    /// Clear only what was last logged before this time, in milliseconds since the Unix epoch or
+    /// ISO 8601
+    pub before: Option<TimeBound>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// What a clear removes, in the page and in the plugin's store; each field set narrows it
+#[derive(Debug, Default, Serialize)]
+pub struct ClearScope {
+    pub errors_only: bool,
+    pub session_id: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub before: Option<u64>,
+}
+
+impl ClearScope {
+    /// Whether an entry or error logged by `session_id` at `timestamp` is cleared, as long as
+    /// its kind is. Unreadable timestamps are kept when `before` is set.
+    fn covers(&self, session_id: &str, timestamp: &str) -> bool {
+        self.session_id.as_deref().is_none_or(|wanted| session_id == wanted)
+            && self
+                .before
+                .is_none_or(|before| parse_iso8601(timestamp).is_some_and(|ms| ms < before))
+    }
+}
+
+/// The page's side of a clear: how many it removed, and the session and last `seq` it had
+/// handed out when it did
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageClear {
+    pub removed: u64,
+    pub session_id: String,
+    pub seq: u64,
+}
+
+/// A clear made in the page and the store together. Entries the page logged before clearing but
+/// that only reach the store afterwards are covered by it too.
+struct ClearFence {
+    scope: ClearScope,
+    page: PageClear,
+}
+
+impl ClearFence {
+    fn covers(&self, session_id: &str, seq: u64, timestamp: &str) -> bool {
+        session_id == self.page.session_id
+            && seq <= self.page.seq
+            && self.scope.covers(session_id, timestamp)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JsErrorsRequest {
+    pub window_label: Option<String>,
+    /// Only errors thrown at or after this time, in milliseconds since the Unix epoch or ISO 8601
+    pub from: Option<TimeBound>,
+    /// Only errors thrown at or before this time
+    pub to: Option<TimeBound>,
+    /// Only errors whose message matches this regular expression
+    pub message_regex: Option<String>,
+    /// Most errors to return, keeping the newest
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsErrorsResponse {
+    pub window_label: String,
+    /// Matching errors, oldest first
+    pub errors: Vec<JavaScriptError>,
+    /// Errors matching the request before `limit` was applied
+    pub matched_count: usize,
+}
+
+/// Notification topic carrying every stored console entry and JavaScript error; see `console_subscribe`
+pub const CONSOLE_TOPIC: &str = "console";
+
+/// A console entry or JavaScript error the capture script sends through the plugin's
+/// `console_entry` command
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub(crate) enum ForwardedEntry {
+    Console { entry: ConsoleEntry },
+    Error { entry: JavaScriptError },
+}
+
+/// Opt-in capture instrumentation that patches page globals. Each is installed at most once per
+/// page, and only when a request asks for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Instrument {
+    /// Console relay from Web Workers and SharedWorkers
+    Workers,
+    /// Timer, `Math.random` and clock tracking for `detect_nondeterminism`
+    Nondeterminism,
+    /// `addEventListener` tracking for `get_event_listeners`
+    Listeners,
+}
+
+impl Instrument {
+    fn name(self) -> &'static str {
+        match self {
+            Instrument::Workers => "workers",
+            Instrument::Nondeterminism => "nondeterminism",
+            Instrument::Listeners => "listeners",
+        }
+    }
+
+    /// Script asking for the instrument in a page. It is installed right away where capture is
+    /// already running, and otherwise as soon as the capture script loads.
+    pub(crate) fn request_script(self) -> String {
+        format!(
+            "(window.__mcpInstrumentRequests = window.__mcpInstrumentRequests || []).push('{name}'); \
+             if (window.__mcpInstrument) window.__mcpInstrument('{name}');",
+            name = self.name()
+        )
+    }
+}
+
+/// Whether a console message is the plugin signaling to itself rather than the app logging.
+/// Earlier versions logged these into the page's console: `get_js_result` passed values back as
+/// `MCP_RETRIEVE_*` lines and the capture script announced itself.
+pub fn is_internal(message: &str) -> bool {
+    message.starts_with("MCP_RETRIEVE_SUCCESS_")
+        || message.starts_with("MCP_RETRIEVE_ERROR_")
+        || message == "Event-based console capture system initialized"
+}
+
+/// Rough memory cost of an entry: its message and stack plus a fixed allowance for the rest.
+/// The capture script estimates sizes the same way.
+fn entry_size(message: &str, stack: Option<&str>) -> usize {
+    64 + message.len() + stack.map_or(0, str::len)
+}
+
+#[derive(Default)]
+struct WindowConsole {
+    entries: VecDeque<ConsoleEntry>,
+    errors: VecDeque<JavaScriptError>,
+    /// Estimated size of everything in `entries` and `errors`
+    bytes: usize,
+    /// Limits set for this window by a capture request, over the store's defaults
+    limits: ConsoleLimits,
+    /// Entries and errors evicted to stay within the limits
+    dropped: u64,
+    /// Page loads started in the window so far
+    navigation_generation: u64,
+    /// The last clear made in the page as well, for entries still on their way from it
+    fence: Option<ClearFence>,
+}
+
+impl WindowConsole {
+    /// Evict the oldest entries and errors, by cursor, until both limits hold. The newest
+    /// entry is always kept.
+    fn evict(&mut self, max_entries: usize, max_bytes: usize) {
+        while self.entries.len() + self.errors.len() > 1
+            && (self.entries.len() + self.errors.len() > max_entries || self.bytes > max_bytes)
+        {
+            let entry_first = match (self.entries.front(), self.errors.front()) {
+                (Some(entry), Some(error)) => entry.cursor < error.cursor,
+                (entry, _) => entry.is_some(),
+            };
+            let size = if entry_first {
+                self.entries.pop_front().map_or(0, |e| e.stored_size())
+            } else {
+                self.errors
+                    .pop_front()
+                    .map_or(0, |e| entry_size(&e.message, e.stack.as_deref()))
+            };
+            self.bytes = self.bytes.saturating_sub(size);
+            self.dropped += 1;
+        }
+    }
+}
+
+struct StoredConsole {
+    windows: HashMap<String, WindowConsole>,
+    /// Last cursor handed out, shared by every window
+    cursor: u64,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+/// Console output and errors pushed from each window's capture script, managed in plugin state.
+/// Entries from earlier pages in a window are kept, up to the window's limits; their
+/// `session_id` tells them apart.
+pub struct ConsoleStore {
+    inner: Mutex<StoredConsole>,
+}
+
+/// Which stored entries a read wants
+#[derive(Debug, Default)]
+pub struct ConsoleQuery<'a> {
+    pub filter: Option<&'a str>,
+    pub since_cursor: Option<u64>,
+    pub since_timestamp: Option<&'a str>,
+    pub levels: Option<&'a [String]>,
+    pub message_regex: Option<&'a Regex>,
+    pub correlation_id: Option<&'a str>,
+    /// Connection whose view this is: entries tagged with another client are left out
+    pub client: Option<&'a str>,
+    /// Keep the plugin's own signaling lines
+    pub include_internal: bool,
+    pub range: TimeRange,
+    /// Matches to skip before the page starts
+    pub offset: usize,
+    /// Most matches in the page
+    pub limit: Option<usize>,
+}
+
+impl ConsoleQuery<'_> {
+    fn matches(
+        &self,
+        cursor: Option<u64>,
+        level: &str,
+        message: &str,
+        timestamp: &str,
+        correlation_id: Option<&str>,
+        client_id: Option<&str>,
+    ) -> bool {
+        self.correlation_id.is_none_or(|wanted| correlation_id == Some(wanted))
+            // Entries no client caused belong to every view
+            && self.client.is_none_or(|own| client_id.is_none_or(|client| client == own))
+            && self.levels
+            .is_none_or(|levels| levels.iter().any(|l| l.eq_ignore_ascii_case(level)))
+            && (self.include_internal || !is_internal(message))
+            && self.filter.is_none_or(|filter| message.contains(filter))
+            && self.message_regex.is_none_or(|regex| regex.is_match(message))
+            && self.since_cursor.is_none_or(|since| cursor.unwrap_or(0) > since)
+            // ISO 8601 times from `toISOString` sort as text
+            && self.since_timestamp.is_none_or(|since| timestamp > since)
+            && self.range.contains_timestamp(timestamp)
+    }
+}
+
+impl ConsoleStore {
+    /// A store keeping up to `max_entries` entries and errors and about `max_bytes` per window
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(StoredConsole {
+                windows: HashMap::new(),
+                cursor: 0,
+                max_entries: max_entries.max(1),
+                max_bytes,
+            }),
+        }
+    }
+
+    /// Override the limits of one window, evicting right away if it now holds too much
+    pub fn set_limits(&self, window_label: &str, limits: &ConsoleLimits) {
+        let mut inner = self.inner.lock().unwrap();
+        let (max_entries, max_bytes) = (inner.max_entries, inner.max_bytes);
+        let window = inner.windows.entry(window_label.to_string()).or_default();
+        window.limits.max_entries = limits.max_entries.map(|n| n.max(1)).or(window.limits.max_entries);
+        window.limits.max_bytes = limits.max_bytes.or(window.limits.max_bytes);
+        window.evict(
+            window.limits.max_entries.unwrap_or(max_entries),
+            window.limits.max_bytes.unwrap_or(max_bytes),
+        );
+    }
+
+    /// A window started loading a new page; entries from now on belong to the next generation
+    pub fn navigated(&self, window_label: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.windows.entry(window_label.to_string()).or_default().navigation_generation += 1;
+    }
+
+    /// Generation of the page currently loaded in a window
+    pub fn navigation_generation(&self, window_label: &str) -> u64 {
+        self.inner
+            .lock()
+            .unwrap()
+            .windows
+            .get(window_label)
+            .map_or(0, |window| window.navigation_generation)
+    }
+
+    /// Store an entry from a window and return the notification announcing it, or nothing when a
+    /// clear of the page already removed it
+    fn push(&self, window_label: &str, forwarded: ForwardedEntry) -> Option<Value> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.cursor += 1;
+        let cursor = Some(inner.cursor);
+        let (max_entries, max_bytes) = (inner.max_entries, inner.max_bytes);
+        let windows = &mut inner.windows;
+        match forwarded {
+            ForwardedEntry::Console { mut entry } => {
+                // A repeat also takes a new cursor, so pollers receive it again
+                entry.cursor = cursor;
+                let window = windows.entry(window_label.to_string()).or_default();
+                let logged = entry.last_timestamp.as_deref().unwrap_or(&entry.timestamp);
+                if window.fence.as_ref().is_some_and(|fence| {
+                    !fence.scope.errors_only && fence.covers(&entry.session_id, entry.seq, logged)
+                }) {
+                    return None;
+                }
+                entry.navigation_generation = window.navigation_generation;
+                let notification = serde_json::json!({
+                    "window_label": window_label,
+                    "kind": "console",
+                    "entry": entry,
+                });
+                // A repeat of the last message arrives as that entry with a higher count
+                if let Some(last) = window.entries.back_mut() {
+                    if entry.repeat_count > 1
+                        && last.session_id == entry.session_id
+                        && last.correlation_id == entry.correlation_id
+                        && last.level == entry.level
+                        && last.message == entry.message
+                        && last.worker == entry.worker
+                        && last.frame == entry.frame
+                    {
+                        *last = entry;
+                        return Some(notification);
+                    }
+                }
+                window.bytes += entry.stored_size();
+                window.entries.push_back(entry);
+                window.evict(
+                    window.limits.max_entries.unwrap_or(max_entries),
+                    window.limits.max_bytes.unwrap_or(max_bytes),
+                );
+                Some(notification)
+            }
+            ForwardedEntry::Error { mut entry } => {
+                entry.cursor = cursor;
+                let window = windows.entry(window_label.to_string()).or_default();
+                if window
+                    .fence
+                    .as_ref()
+                    .is_some_and(|fence| fence.covers(&entry.session_id, entry.seq, &entry.timestamp))
+                {
+                    return None;
+                }
+                entry.navigation_generation = window.navigation_generation;
+                let notification = serde_json::json!({
+                    "window_label": window_label,
+                    "kind": "error",
+                    "entry": entry,
+                });
+                window.bytes += entry_size(&entry.message, entry.stack.as_deref());
+                window.errors.push_back(entry);
+                window.evict(
+                    window.limits.max_entries.unwrap_or(max_entries),
+                    window.limits.max_bytes.unwrap_or(max_bytes),
+                );
+                Some(notification)
+            }
+        }
+    }
+
+    /// Stored JavaScript errors of a window that match `query`, oldest first. Only the query's
+    /// filters apply; `offset` and `limit` are left to the caller.
+    pub fn errors(&self, window_label: &str, query: &ConsoleQuery) -> Vec<JavaScriptError> {
+        let inner = self.inner.lock().unwrap();
+        inner.windows.get(window_label).map_or_else(Vec::new, |window| {
+            window
+                .errors
+                .iter()
+                .filter(|e| {
+                    query.matches(
+                        e.cursor,
+                        "error",
+                        &e.message,
+                        &e.timestamp,
+                        e.correlation_id.as_deref(),
+                        e.client_id.as_deref(),
+                    )
+                })
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Forget a window's stored entries and errors in `scope`. Returns how many were removed.
+    ///
+    /// `page` is the page's report of the same clear. What the page logged after it is kept, and
+    /// what it logged before but hasn't reached the store yet is dropped on arrival.
+    pub fn clear(&self, window_label: &str, scope: ClearScope, page: Option<PageClear>) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        let window = inner.windows.entry(window_label.to_string()).or_default();
+        let logged_after_page = |session_id: &str, seq: u64| {
+            page.as_ref()
+                .is_some_and(|page| session_id == page.session_id && seq > page.seq)
+        };
+        let stored = window.entries.len() + window.errors.len();
+        let mut freed = 0;
+        if !scope.errors_only {
+            window.entries.retain(|e| {
+                let logged = e.last_timestamp.as_deref().unwrap_or(&e.timestamp);
+                let cleared =
+                    scope.covers(&e.session_id, logged) && !logged_after_page(&e.session_id, e.seq);
+                if cleared {
+                    freed += e.stored_size();
+                }
+                !cleared
+            });
+        }
+        window.errors.retain(|e| {
+            let cleared =
+                scope.covers(&e.session_id, &e.timestamp) && !logged_after_page(&e.session_id, e.seq);
+            if cleared {
+                freed += entry_size(&e.message, e.stack.as_deref());
+            }
+            !cleared
+        });
+        window.bytes = window.bytes.saturating_sub(freed);
+        let cleared = stored - window.entries.len() - window.errors.len();
+        if let Some(page) = page {
+            window.fence = Some(ClearFence { scope, page });
+        }
+        cleared
+    }
+
+    /// Last cursor handed out; entries stored from now on come after it
+    pub fn cursor(&self) -> u64 {
+        self.inner.lock().unwrap().cursor
+    }
+
+    /// Stored entries and errors of a window that match `query`, oldest first. Entries and
+    /// errors are paged through together, in the order they were stored.
+    pub fn snapshot(&self, window_label: &str, query: &ConsoleQuery) -> ConsoleBufferResponse {
+        let inner = self.inner.lock().unwrap();
+        let (matched_entries, matched_errors, dropped_count, navigation_generation) =
+            match inner.windows.get(window_label) {
+                Some(window) => (
+                    window
+                        .entries
+                        .iter()
+                        .filter(|e| {
+                            let logged = e.last_timestamp.as_deref().unwrap_or(&e.timestamp);
+                            query.matches(
+                                e.cursor,
+                                &e.level,
+                                &e.message,
+                                logged,
+                                e.correlation_id.as_deref(),
+                                e.client_id.as_deref(),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                    window
+                        .errors
+                        .iter()
+                        .filter(|e| {
+                            query.matches(
+                                e.cursor,
+                                "error",
+                                &e.message,
+                                &e.timestamp,
+                                e.correlation_id.as_deref(),
+                                e.client_id.as_deref(),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                    window.dropped,
+                    window.navigation_generation,
+                ),
+                None => (Vec::new(), Vec::new(), 0, 0),
+            };
+
+        let matched_count = matched_entries.len() + matched_errors.len();
+        let end = query
+            .limit
+            .map_or(matched_count, |limit| query.offset.saturating_add(limit))
+            .min(matched_count);
+        let mut pending_entries = matched_entries.into_iter().peekable();
+        let mut pending_errors = matched_errors.into_iter().peekable();
+        let (mut entries, mut errors) = (Vec::new(), Vec::new());
+        let mut last_cursor = None;
+        for index in 0..end {
+            let entry_next = match (pending_entries.peek(), pending_errors.peek()) {
+                (Some(entry), Some(error)) => entry.cursor < error.cursor,
+                (entry, _) => entry.is_some(),
+            };
+            if entry_next {
+                if let Some(entry) = pending_entries.next().filter(|_| index >= query.offset) {
+                    last_cursor = entry.cursor;
+                    entries.push(entry.clone());
+                }
+            } else if let Some(error) = pending_errors.next().filter(|_| index >= query.offset) {
+                last_cursor = error.cursor;
+                errors.push(error.clone());
+            }
+        }
+        let has_more = end < matched_count;
+
+        ConsoleBufferResponse {
+            window_label: window_label.to_string(),
+            total_count: entries.len(),
+            entries,
+            errors,
+            matched_count,
+            has_more,
+            dropped_count,
+            navigation_generation,
+            // A cut-off page continues after its last entry
+            next_cursor: if has_more {
+                last_cursor.unwrap_or(query.since_cursor.unwrap_or(0))
+            } else {
+                inner.cursor
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsoleBufferResponse {
+    pub window_label: String,
+    pub entries: Vec<ConsoleEntry>,
+    pub errors: Vec<JavaScriptError>,
+    pub total_count: usize,
+    /// Entries and errors matching the query before `offset` and `limit` were applied
+    pub matched_count: usize,
+    /// Whether matches remain past this page
+    pub has_more: bool,
+    /// Entries and errors the store evicted to stay within the window's limits
+    pub dropped_count: u64,
+    /// Generation of the page now loaded in the window, to tell its entries from earlier pages'
+    pub navigation_generation: u64,
+    /// Cursor to pass as `since_cursor` on the next call to get only newer entries
+    pub next_cursor: u64,
+}
+
+/// Store an entry the capture script in `window_label` pushed and pass it on to subscribers of
+/// the `console` topic, redacted like `get_console_buffer` responses. The label is the webview
+/// that sent it, never something the page claims.
+pub(crate) fn record_entry<R: Runtime>(app: &AppHandle<R>, window_label: &str, forwarded: ForwardedEntry) {
+    let notification = app.state::<ConsoleStore>().push(window_label, forwarded);
+    if let (Some(notification), Some(hub)) = (notification, app.try_state::<NotificationHub>()) {
+        hub.publish(CONSOLE_TOPIC, notification);
+    }
+}
+
+/// Plugin `on_webview_ready` hook: relay the messages a webview logs to its own console into its
+/// page's capture, where the platform exposes them. Messages the capture script reports itself
+/// are left out so they aren't stored twice.
+pub fn watch_browser_messages<R: Runtime>(webview: &Webview<R>) {
+    let app = webview.app_handle().clone();
+    let window_label = webview.label().to_string();
+    let label = window_label.clone();
+    let watching = browser_console::watch(webview, move |message: BrowserMessage| {
+        let reported_by_page = matches!(
+            message.source.as_str(),
+            "javascript" | "console-api" | "deprecation" | "intervention"
+        ) || message.text.contains("Content Security Policy");
+        if reported_by_page || message.level == "verbose" {
+            return;
+        }
+        let report = serde_json::json!({
+            "source": message.source,
+            "severity": if message.level == "warning" { "warn" } else { message.level.as_str() },
+            "text": message.text,
+            "url": message.url,
+            "line": message.line_number,
+            "timestamp_ms": message.timestamp,
+        });
+        let script = format!(
+            "(window.__mcpBrowserMessage || function(report) {{ \
+             const pending = window.__mcpPendingBrowserMessages || (window.__mcpPendingBrowserMessages = []); \
+             if (pending.push(report) > 100) pending.shift(); }})({});",
+            report
+        );
+        let relayed = webview_backend::backend(&app).and_then(|backend| backend.eval(&label, &script));
+        if let Err(e) = relayed {
+            warn!("[TAURI_MCP] Failed to relay a browser console message to {}: {}", label, e);
+        }
+    });
+    if watching {
+        info!("[TAURI_MCP] Relaying the webview's own console messages in {}", window_label);
+    }
+}
+
+/// Plugin `on_page_load` hook. Each page load starts a new navigation generation, and once it
+/// finishes, capture is set up again in windows that had it, since the page's hooks went with
+/// the old page. With `PluginConfig::capture_console_on_load` every window gets it.
+pub fn page_loaded<R: Runtime>(webview: &Webview<R>, event: PageLoadEvent) {
+    let window_label = webview.label().to_string();
+    if event == PageLoadEvent::Started {
+        if let Some(store) = webview.try_state::<ConsoleStore>() {
+            store.navigated(&window_label);
+        }
+        return;
+    }
+
+    let settings = webview
+        .try_state::<CaptureRegistry>()
+        .and_then(|captures| captures.settings(&window_label));
+    let on_load = webview
+        .try_state::<CaptureDefaults>()
+        .is_some_and(|defaults| defaults.on_load);
+    let mut payload = match settings {
+        Some(settings) => settings,
+        None if on_load => serde_json::json!({}),
+        None => return,
+    };
+    payload["window_label"] = Value::String(window_label.clone());
+
+    let app = webview.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = handle_setup_console_capture(&app, payload).await {
+            warn!("[TAURI_MCP] Failed to set up console capture again in {}: {}", window_label, e);
+        }
+    });
+}
+
+/// Event-based capture of console output, errors, network traffic and page activity.
+/// Idempotent; also registered as an initialization script in [`InjectionMode::InitScript`].
+///
+/// [`InjectionMode::InitScript`]: crate::InjectionMode::InitScript
+pub const CAPTURE_SCRIPT: &str = r#"
+    (function() {
+        if (window.__mcpEventConsoleCapture) {
+            return { already_setup: true, session_id: window.__consoleSessionId, capture_method: 'events' };
+        }
+        
+        window.__mcpEventConsoleCapture = true;
+        window.__consoleBuffer = window.__consoleBuffer || [];
+        window.__consoleSessionId = Date.now().toString();
+        window.__consoleSeq = window.__consoleSeq || 0;
+        window.__mcpTriggerKinds = window.__mcpTriggerKinds || [];
+        window.__mcpConsoleBytes = window.__mcpConsoleBytes || {};
+        window.__mcpConsoleDropped = window.__mcpConsoleDropped || 0;
+        
+        // Patches of page globals beyond console and error reporting are opt-in. Their installers
+        // are registered below and run once per page through __mcpInstrument: for requests made
+        // before capture loaded when it finishes, and for later ones right away.
+        const instruments = {};
+        window.__mcpInstrumented = window.__mcpInstrumented || {};
+        window.__mcpInstrument = function(name) {
+            if (window.__mcpInstrumented[name] || !instruments[name]) return false;
+            window.__mcpInstrumented[name] = true;
+            instruments[name]();
+            return true;
+        };
+        
+        // Buffers are bounded by window.__mcpConsoleLimits, read on every push; the fallbacks match
+        // DEFAULT_MAX_ENTRIES and DEFAULT_MAX_BYTES. Sizes are estimated as in the plugin's store.
+        function entrySize(entry) {
+            // Described arguments take about as much again as the message they make up
+            const message = entry.message ? String(entry.message).length : 0;
+            // Network log entries are mostly their URL and error
+            const network = (entry.url ? String(entry.url).length : 0) + (entry.error ? String(entry.error).length : 0);
+            return 64 + (entry.args ? 2 * message : message) + (entry.stack ? String(entry.stack).length : 0) + network;
+        }
+        function appendBounded(name, entry) {
+            const buffer = window[name] || (window[name] = []);
+            const bytes = window.__mcpConsoleBytes;
+            const limits = window.__mcpConsoleLimits || {};
+            const maxEntries = Math.max(1, limits.max_entries || 1000);
+            const maxBytes = limits.max_bytes === undefined ? 1048576 : limits.max_bytes;
+            buffer.push(entry);
+            bytes[name] = (bytes[name] || 0) + entrySize(entry);
+            let dropped = 0;
+            while (buffer.length > 1 && (buffer.length > maxEntries || bytes[name] > maxBytes)) {
+                bytes[name] -= entrySize(buffer.shift());
+                dropped++;
+            }
+            return dropped;
+        }
+        
+        // Report trigger-worthy events so the plugin can capture artifacts
+        function notifyTrigger(kind, details) {
+            if (window.__mcpTriggerKinds.indexOf(kind) === -1) return;
+            try {
+                const trigger = Object.assign({ kind: kind }, details);
+                Promise.resolve(window.__TAURI_INTERNALS__.invoke('plugin:mcp|capture_trigger', { trigger: trigger }))
+                    .catch(function() {});
+            } catch (e) {}
+        }
+        
+        // Push every captured entry to the plugin's console store, so it can be read without a
+        // round trip to the page. The plugin files it under the webview that made the call.
+        // Failures are swallowed: a rejected call must not be captured itself.
+        function forwardEntry(kind, entry) {
+            try {
+                Promise.resolve(window.__TAURI_INTERNALS__.invoke('plugin:mcp|console_entry', { forwarded: { kind: kind, entry: entry } }))
+                    .catch(function() {});
+            } catch (e) {}
+        }
+        
+        // Tag what a tool's script causes while it runs synchronously with the call and the client
+        // that made it; see js_bridge::correlate
+        function tagCaller(item) {
+            if (window.__mcpCorrelationId && !item.correlationId) {
+                item.correlationId = window.__mcpCorrelationId;
+                if (window.__mcpClientId) item.clientId = window.__mcpClientId;
+            }
+            return item;
+        }
+        
+        // Keep an uncaught error, worker error or rejection for retrieval
+        function pushJsError(info) {
+            tagCaller(info);
+            appendBounded('__mcpJSErrors', info);
+            forwardEntry('error', info);
+            notifyTrigger('uncaught_error', { message: info.message });
+        }
+        
+        // Stack of whoever called a wrapped console method, minus the wrapper's own frame.
+        // Engines differ on the leading "Error" line, so it is dropped when present.
+        function callerStack() {
+            const stack = new Error().stack;
+            if (!stack) return null;
+            const lines = stack.split('\n');
+            if (/^Error\b/.test(lines[0])) lines.shift();
+            return lines.slice(2).join('\n') || null;
+        }
+        
+        // Messages below window.__mcpConsoleMinLevel are passed to the console but not buffered
+        const levelRanks = { debug: 0, log: 1, info: 2, warn: 3, error: 4 };
+        function levelAllowed(level) {
+            const min = levelRanks[window.__mcpConsoleMinLevel];
+            return min === undefined || levelRanks[level] >= min;
+        }
+        
+        // Sampling rules keep one in `keep_one_in` messages containing `pattern`, counting the rest
+        function sampledOut(message) {
+            const rules = window.__mcpConsoleSampling;
+            if (!rules || !rules.length) return false;
+            if (!window.__mcpConsoleSampleState) window.__mcpConsoleSampleState = {};
+            for (const rule of rules) {
+                if (!rule.pattern || message.indexOf(rule.pattern) === -1) continue;
+                const state = window.__mcpConsoleSampleState[rule.pattern] ||
+                    (window.__mcpConsoleSampleState[rule.pattern] = { seen: 0, suppressed: 0 });
+                const keep = state.seen % Math.max(1, rule.keep_one_in) === 0;
+                state.seen++;
+                if (!keep) {
+                    state.suppressed++;
+                    return true;
+                }
+            }
+            return false;
+        }
+        
+        // Store an entry for retrieval. Consecutive identical messages collapse into the previous
+        // entry with a repeat count, as in devtools; it takes the new seq so delta readers see it again.
+        // Setting window.__mcpCollapseRepeats to false keeps every message.
+        function pushConsoleEntry(entry) {
+            tagCaller(entry);
+            const last = window.__consoleBuffer[window.__consoleBuffer.length - 1];
+            if (window.__mcpCollapseRepeats !== false && last && last.seq === entry.seq - 1 && last.level === entry.level &&
+                last.message === entry.message && last.worker === entry.worker &&
+                last.frame === entry.frame && last.stack === entry.stack &&
+                last.correlationId === entry.correlationId) {
+                last.repeatCount = (last.repeatCount || 1) + 1;
+                last.lastTimestamp = entry.timestamp;
+                last.seq = entry.seq;
+                forwardEntry('console', last);
+                return;
+            }
+            window.__mcpConsoleDropped += appendBounded('__consoleBuffer', entry);
+            forwardEntry('console', entry);
             
-            window.__mcpEventConsoleCapture = true;
-            window.__consoleBuffer = window.__consoleBuffer || [];
-            window.__consoleSessionId = Date.now().toString();
+            // Also store in a special MCP messages buffer for easy retrieval
+            appendBounded('__mcpConsoleMessages', entry);
+        }
+        
+        function wantsStack(level) {
+            return !!window.__mcpCaptureStacks && (level === 'error' || level === 'warn');
+        }
+        
+        // Typed description of one console argument: its `type`, a `preview` as devtools would print
+        // it and, where it adds something, a JSON-safe `json` copy. Circular references become
+        // "[Circular]", nesting stops at 6 levels and collections at 100 items. Values may come from
+        // frames, so types are told apart by tag rather than `instanceof`; the function is
+        // self-contained because workers get a copy of its source.
+        function describeArg(value) {
+            const tagOf = function(v) { return Object.prototype.toString.call(v).slice(8, -1); };
+            const isNode = function(v) { return typeof v.nodeType === 'number' && typeof v.nodeName === 'string'; };
+            const cap = function(text) { return text.length > 10000 ? text.slice(0, 10000) + '…' : text; };
+            const elementPreview = function(node) {
+                let text = '<' + node.nodeName.toLowerCase();
+                if (node.id) text += '#' + node.id;
+                if (typeof node.className === 'string' && node.className.trim()) {
+                    text += '.' + node.className.trim().split(/\s+/).join('.');
+                }
+                return text + '>';
+            };
+            const errorJson = function(error) {
+                return { name: error.name, message: error.message, stack: error.stack || null };
+            };
+            // Like JSON.stringify's view of a value, minus its failure modes
+            const toJson = function(v, ancestors, depth) {
+                if (v === null || typeof v === 'boolean' || typeof v === 'string') return v;
+                if (typeof v === 'number') return isFinite(v) ? v : String(v);
+                if (typeof v === 'undefined') return null;
+                if (typeof v === 'bigint') return v.toString() + 'n';
+                if (typeof v === 'symbol') return v.toString();
+                if (typeof v === 'function') return '[Function ' + (v.name || 'anonymous') + ']';
+                if (ancestors.indexOf(v) !== -1) return '[Circular]';
+                if (isNode(v)) return v.nodeType === 1 ? elementPreview(v) : v.nodeName;
+                const tag = tagOf(v);
+                if (tag === 'Error') return errorJson(v);
+                if (tag === 'Date') return isNaN(v.getTime()) ? 'Invalid Date' : v.toISOString();
+                if (tag === 'RegExp') return String(v);
+                if (depth >= 6) return '[' + tag + ']';
+                const inner = ancestors.concat([v]);
+                const items = function(list) {
+                    return list.slice(0, 100).map(function(item) { return toJson(item, inner, depth + 1); });
+                };
+                if (tag === 'Map') return { size: v.size, entries: items(Array.from(v)) };
+                if (tag === 'Set') return { size: v.size, values: items(Array.from(v)) };
+                if (Array.isArray(v)) return items(v);
+                if (typeof v.toJSON === 'function') {
+                    try { return toJson(v.toJSON(), inner, depth + 1); } catch (e) {}
+                }
+                const out = {};
+                Object.keys(v).slice(0, 100).forEach(function(key) {
+                    let item;
+                    try { item = v[key]; } catch (e) { item = '[Thrown]'; }
+                    if (item !== undefined && typeof item !== 'function') out[key] = toJson(item, inner, depth + 1);
+                });
+                return out;
+            };
+            try {
+                const kind = typeof value;
+                if (value === null) return { type: 'null', preview: 'null' };
+                if (kind === 'string') return { type: 'string', preview: value };
+                if (kind === 'boolean') return { type: kind, preview: String(value), json: value };
+                if (kind === 'number') {
+                    return isFinite(value) ? { type: kind, preview: String(value), json: value } : { type: kind, preview: String(value) };
+                }
+                if (kind === 'bigint') return { type: kind, preview: value.toString() + 'n' };
+                if (kind === 'undefined' || kind === 'symbol') return { type: kind, preview: String(value) };
+                if (kind === 'function') {
+                    const isClass = /^class\b/.test(Function.prototype.toString.call(value));
+                    return { type: 'function', preview: (isClass ? 'class ' : 'function ') + (value.name || 'anonymous') };
+                }
+                if (isNode(value)) {
+                    if (value.nodeType !== 1) return { type: 'node', preview: value.nodeName };
+                    return {
+                        type: 'element',
+                        preview: elementPreview(value),
+                        json: {
+                            tag: value.nodeName.toLowerCase(),
+                            id: value.id || null,
+                            classes: typeof value.className === 'string' ? value.className.split(/\s+/).filter(Boolean) : [],
+                            text: (value.textContent || '').trim().slice(0, 100)
+                        }
+                    };
+                }
+                const tag = tagOf(value);
+                if (tag === 'Error') return { type: 'error', preview: value.name + ': ' + value.message, json: errorJson(value) };
+                if (tag === 'Date' || tag === 'RegExp') return { type: tag.toLowerCase(), preview: toJson(value, [], 0) };
+                const json = toJson(value, [], 0);
+                let described;
+                if (tag === 'Map') {
+                    described = { type: 'map', preview: 'Map(' + value.size + ') {' + json.entries.slice(0, 5).map(function(entry) {
+                        return JSON.stringify(entry[0]) + ' => ' + JSON.stringify(entry[1]);
+                    }).join(', ') + (value.size > 5 ? ', …' : '') + '}', json: json };
+                } else if (tag === 'Set') {
+                    described = { type: 'set', preview: 'Set(' + value.size + ') {' + json.values.slice(0, 5).map(function(item) {
+                        return JSON.stringify(item);
+                    }).join(', ') + (value.size > 5 ? ', …' : '') + '}', json: json };
+                } else {
+                    described = { type: Array.isArray(value) ? 'array' : 'object', preview: JSON.stringify(json), json: json };
+                }
+                described.preview = cap(described.preview);
+                const proto = Object.getPrototypeOf(value);
+                const className = proto && proto.constructor && proto.constructor.name;
+                if (className && ['Object', 'Array', 'Map', 'Set'].indexOf(className) === -1) described.class_name = className;
+                return described;
+            } catch (e) {
+                let preview = '[unserializable]';
+                try { preview = String(value); } catch (ignored) {}
+                return { type: 'unknown', preview: preview };
+            }
+        }
+        
+        // Described arguments and the message they read as, previews joined by spaces
+        function formatArgs(args) {
+            const described = args.map(describeArg);
+            return { args: described, message: described.map(function(arg) { return arg.preview; }).join(' ') };
+        }
+        
+        // Store original console methods
+        const originalConsole = {
+            log: console.log,
+            error: console.error,
+            warn: console.warn,
+            info: console.info,
+            debug: console.debug
+        };
+        
+        function wrapConsoleMethod(level, originalMethod) {
+            return function(...args) {
+                // Call original method first
+                originalMethod.apply(console, args);
+                if (!levelAllowed(level)) return;
+                
+                // Capture the message
+                const formatted = formatArgs(args);
+                const message = formatted.message;
+                if (sampledOut(message)) return;
+                
+                const entry = {
+                    seq: ++window.__consoleSeq,
+                    level: level,
+                    message: message,
+                    args: formatted.args,
+                    timestamp: new Date().toISOString(),
+                    sessionId: window.__consoleSessionId
+                };
+                if (wantsStack(level)) entry.stack = callerStack();
+                
+                pushConsoleEntry(entry);
+                
+                if (level === 'error') {
+                    notifyTrigger('console_error', { message: message });
+                }
+            };
+        }
+        
+        // Wrap all console methods
+        console.log = wrapConsoleMethod('log', originalConsole.log);
+        console.error = wrapConsoleMethod('error', originalConsole.error);
+        console.warn = wrapConsoleMethod('warn', originalConsole.warn);
+        console.info = wrapConsoleMethod('info', originalConsole.info);
+        console.debug = wrapConsoleMethod('debug', originalConsole.debug);
+        
+        // Setup global error handlers
+        window.addEventListener('error', function(event) {
+            const errorInfo = {
+                seq: ++window.__consoleSeq,
+                message: event.message,
+                filename: event.filename,
+                lineno: event.lineno,
+                colno: event.colno,
+                stack: event.error ? event.error.stack : null,
+                timestamp: new Date().toISOString(),
+                sessionId: window.__consoleSessionId
+            };
             
-            // Store original console methods
-            const originalConsole = {
-                log: console.log,
-                error: console.error,
-                warn: console.warn,
-                info: console.info,
-                debug: console.debug
+            // Store JavaScript errors in a buffer for retrieval
+            pushJsError(errorInfo);
+        });
+        
+        // Setup unhandled promise rejection handlers
+        window.addEventListener('unhandledrejection', function(event) {
+            const rejectionInfo = {
+                seq: ++window.__consoleSeq,
+                message: 'Unhandled Promise Rejection: ' + String(event.reason),
+                filename: null,
+                lineno: null,
+                colno: null,
+                stack: event.reason && event.reason.stack ? event.reason.stack : null,
+                timestamp: new Date().toISOString(),
+                sessionId: window.__consoleSessionId
             };
             
-            function wrapConsoleMethod(level, originalMethod) {
-                return function(...args) {
-                    // Call original method first
-                    originalMethod.apply(console, args);
-                    
-                    // Capture the message
-                    const message = args.map(arg => 
-                        typeof arg === 'object' ? JSON.stringify(arg) : String(arg)
-                    ).join(' ');
-                    
+            // Store promise rejections in the error buffer
+            pushJsError(rejectionInfo);
+        });
+        
+        // Messages the browser logs itself rather than through console.*, stored at level
+        // 'browser' whatever the minimum level. The plugin relays the webview's own console where
+        // the platform exposes it; messages that arrived before capture was installed are queued.
+        function recordBrowserMessage(report) {
+            let message = String(report.text);
+            if (report.url) message += ' (' + report.url + (report.line ? ':' + report.line : '') + ')';
+            if (sampledOut(message)) return;
+            pushConsoleEntry({
+                seq: ++window.__consoleSeq,
+                level: 'browser',
+                source: report.source,
+                severity: report.severity,
+                message: message,
+                timestamp: new Date(report.timestamp_ms || Date.now()).toISOString(),
+                sessionId: window.__consoleSessionId
+            });
+        }
+        window.__mcpBrowserMessage = recordBrowserMessage;
+        (window.__mcpPendingBrowserMessages || []).splice(0).forEach(recordBrowserMessage);
+        
+        document.addEventListener('securitypolicyviolation', function(event) {
+            const reportOnly = event.disposition === 'report';
+            recordBrowserMessage({
+                source: 'csp',
+                severity: reportOnly ? 'warn' : 'error',
+                text: (reportOnly ? 'Would refuse ' : 'Refused ') + (event.blockedURI || 'inline code') +
+                    ': violates the Content Security Policy directive "' + event.violatedDirective + '"' +
+                    (reportOnly ? ' (report only)' : ''),
+                url: event.sourceFile,
+                line: event.lineNumber
+            });
+        });
+        
+        if (typeof ReportingObserver === 'function') {
+            try {
+                new ReportingObserver(function(reports) {
+                    reports.forEach(function(report) {
+                        const body = report.body || {};
+                        recordBrowserMessage({
+                            source: report.type,
+                            severity: 'warn',
+                            text: body.message || (report.type + ' report'),
+                            url: body.sourceFile || report.url,
+                            line: body.lineNumber
+                        });
+                    });
+                }, { types: ['deprecation', 'intervention'], buffered: true }).observe();
+            } catch (e) {}
+        }
+        
+        // Relay console output and errors from Web Workers and SharedWorkers. Workers start from a
+        // bootstrap that hooks their console, then loads the real script; messages come back over a
+        // BroadcastChannel tagged with the worker's script URL. Blob bootstraps are reused per script
+        // so a SharedWorker is still shared within the page, though not with other windows. Only
+        // installed with `capture_workers`, since it replaces the page's worker constructors.
+        instruments.workers = function() {
+            if (typeof BroadcastChannel !== 'function' || typeof Blob !== 'function') return;
+            const workerChannelName = '__mcp_worker_console_' + window.__consoleSessionId;
+            const workerChannel = new BroadcastChannel(workerChannelName);
+            workerChannel.onmessage = function(event) {
+                const data = event.data || {};
+                if (data.kind === 'console') {
+                    if (!levelAllowed(data.level) || sampledOut(String(data.message))) return;
                     const entry = {
+                        seq: ++window.__consoleSeq,
+                        level: data.level,
+                        message: data.message,
+                        args: data.args,
+                        timestamp: new Date().toISOString(),
+                        sessionId: window.__consoleSessionId,
+                        worker: data.worker
+                    };
+                    pushConsoleEntry(entry);
+                    if (data.level === 'error') {
+                        notifyTrigger('console_error', { message: data.message });
+                    }
+                } else if (data.kind === 'error') {
+                    pushJsError({
+                        seq: ++window.__consoleSeq,
+                        message: data.message,
+                        filename: data.filename || data.worker,
+                        lineno: data.lineno || null,
+                        colno: data.colno || null,
+                        stack: data.stack || null,
+                        timestamp: new Date().toISOString(),
+                        sessionId: window.__consoleSessionId,
+                        worker: data.worker
+                    });
+                }
+            };
+            
+            function workerBootstrap(scriptUrl, isModule) {
+                return `
+                    const channel = new BroadcastChannel(${JSON.stringify(workerChannelName)});
+                    const source = ${JSON.stringify(scriptUrl)};
+                    const post = function(kind, data) {
+                        try { channel.postMessage(Object.assign({ kind: kind, worker: source }, data)); } catch (e) {}
+                    };
+                    const describeArg = ${describeArg.toString()};
+                    ['log', 'error', 'warn', 'info', 'debug'].forEach(function(level) {
+                        const original = console[level];
+                        console[level] = function(...args) {
+                            original.apply(console, args);
+                            const described = args.map(describeArg);
+                            post('console', {
+                                level: level,
+                                message: described.map(function(arg) { return arg.preview; }).join(' '),
+                                args: described
+                            });
+                        };
+                    });
+                    self.addEventListener('error', function(event) {
+                        post('error', { message: event.message, filename: event.filename, lineno: event.lineno, colno: event.colno, stack: event.error ? event.error.stack : null });
+                    });
+                    self.addEventListener('unhandledrejection', function(event) {
+                        post('error', { message: 'Unhandled Promise Rejection: ' + String(event.reason), stack: event.reason && event.reason.stack ? event.reason.stack : null });
+                    });
+                    ${isModule
+                        ? "import(source).catch(function(e) { post('error', { message: String(e), stack: e && e.stack ? e.stack : null }); });"
+                        : 'importScripts(source);'}
+                `;
+            }
+            
+            const bootstrapUrls = new Map();
+            function wrapWorkerConstructor(name) {
+                const Original = window[name];
+                if (typeof Original !== 'function') return;
+                const Wrapped = function(url, options) {
+                    const isModule = !!options && typeof options === 'object' && options.type === 'module';
+                    try {
+                        const scriptUrl = new URL(url, location.href).href;
+                        const key = scriptUrl + (isModule ? '#module' : '#classic');
+                        if (!bootstrapUrls.has(key)) {
+                            const blob = new Blob([workerBootstrap(scriptUrl, isModule)], { type: 'text/javascript' });
+                            bootstrapUrls.set(key, URL.createObjectURL(blob));
+                        }
+                        return new Original(bootstrapUrls.get(key), options);
+                    } catch (e) {
+                        // Unresolvable URL or blob workers unavailable: run the worker unobserved
+                        return new Original(url, options);
+                    }
+                };
+                Wrapped.prototype = Original.prototype;
+                window[name] = Wrapped;
+            }
+            wrapWorkerConstructor('Worker');
+            wrapWorkerConstructor('SharedWorker');
+        };
+        
+        // Hook console and error events in same-origin iframes, so failures in embedded editors
+        // and preview frames land in this page's buffers tagged with the frame they came from.
+        // Frames are hooked on every load, since navigating a frame replaces its window; nested
+        // frames are followed, cross-origin frames are skipped.
+        function frameName(iframe) {
+            try {
+                return iframe.id || iframe.name || iframe.contentWindow.location.href;
+            } catch (e) {
+                return iframe.src || null;
+            }
+        }
+        
+        function hookFrameWindow(iframe) {
+            let frameWindow;
+            try {
+                frameWindow = iframe.contentWindow;
+                if (!frameWindow || frameWindow.__mcpFrameCapture) return;
+                frameWindow.__mcpFrameCapture = true;
+            } catch (e) {
+                // Cross-origin: the frame's window is off limits
+                return;
+            }
+            const frame = frameName(iframe);
+            const frameConsole = frameWindow.console;
+            ['log', 'error', 'warn', 'info', 'debug'].forEach(function(level) {
+                const original = frameConsole[level];
+                frameConsole[level] = function(...args) {
+                    original.apply(frameConsole, args);
+                    if (!levelAllowed(level)) return;
+                    const formatted = formatArgs(args);
+                    const message = formatted.message;
+                    if (sampledOut(message)) return;
+                    const entry = {
+                        seq: ++window.__consoleSeq,
                         level: level,
                         message: message,
+                        args: formatted.args,
                         timestamp: new Date().toISOString(),
-                        sessionId: window.__consoleSessionId
+                        sessionId: window.__consoleSessionId,
+                        frame: frame
                     };
-                    
-                    // Store in buffer for retrieval
-                    window.__consoleBuffer.push(entry);
-                    
-                    // Also store in a special MCP messages buffer for easy retrieval
-                    if (!window.__mcpConsoleMessages) window.__mcpConsoleMessages = [];
-                    window.__mcpConsoleMessages.push(entry);
+                    if (wantsStack(level)) entry.stack = callerStack();
+                    pushConsoleEntry(entry);
+                    if (level === 'error') {
+                        notifyTrigger('console_error', { message: message });
+                    }
                 };
+            });
+            function pushFrameError(info) {
+                info.seq = ++window.__consoleSeq;
+                info.timestamp = new Date().toISOString();
+                info.sessionId = window.__consoleSessionId;
+                info.frame = frame;
+                pushJsError(info);
             }
-            
-            // Wrap all console methods
-            console.log = wrapConsoleMethod('log', originalConsole.log);
-            console.error = wrapConsoleMethod('error', originalConsole.error);
-            console.warn = wrapConsoleMethod('warn', originalConsole.warn);
-            console.info = wrapConsoleMethod('info', originalConsole.info);
-            console.debug = wrapConsoleMethod('debug', originalConsole.debug);
-            
-            // Setup global error handlers
-            window.addEventListener('error', function(event) {
-                const errorInfo = {
+            frameWindow.addEventListener('error', function(event) {
+                pushFrameError({
                     message: event.message,
                     filename: event.filename,
                     lineno: event.lineno,
                     colno: event.colno,
-                    stack: event.error ? event.error.stack : null,
-                    timestamp: new Date().toISOString(),
-                    sessionId: window.__consoleSessionId
-                };
-                
-                // Store JavaScript errors in a buffer for retrieval
-                if (!window.__mcpJSErrors) window.__mcpJSErrors = [];
-                window.__mcpJSErrors.push(errorInfo);
+                    stack: event.error ? event.error.stack : null
+                });
             });
-            
-            // Setup unhandled promise rejection handlers
-            window.addEventListener('unhandledrejection', function(event) {
-                const rejectionInfo = {
+            frameWindow.addEventListener('unhandledrejection', function(event) {
+                pushFrameError({
                     message: 'Unhandled Promise Rejection: ' + String(event.reason),
                     filename: null,
                     lineno: null,
                     colno: null,
-                    stack: event.reason && event.reason.stack ? event.reason.stack : null,
-                    timestamp: new Date().toISOString(),
-                    sessionId: window.__consoleSessionId
-                };
-                
-                // Store promise rejections in the error buffer
-                if (!window.__mcpJSErrors) window.__mcpJSErrors = [];
-                window.__mcpJSErrors.push(rejectionInfo);
+                    stack: event.reason && event.reason.stack ? event.reason.stack : null
+                });
             });
-            
-            // Utility functions
-            window.__getConsoleBuffer = function() {
-                return window.__consoleBuffer || [];
+            try {
+                watchFrames(frameWindow.document);
+            } catch (e) {}
+        }
+        
+        function hookFrame(iframe) {
+            if (iframe.__mcpFrameWatched) return;
+            iframe.__mcpFrameWatched = true;
+            iframe.addEventListener('load', function() { hookFrameWindow(iframe); });
+            hookFrameWindow(iframe);
+        }
+        
+        function watchFrames(doc) {
+            if (!doc || doc.__mcpFramesWatched) return;
+            doc.__mcpFramesWatched = true;
+            Array.prototype.forEach.call(doc.querySelectorAll('iframe, frame'), hookFrame);
+            new MutationObserver(function(mutations) {
+                mutations.forEach(function(mutation) {
+                    mutation.addedNodes.forEach(function(node) {
+                        if (node.nodeType !== 1) return;
+                        if (node.tagName === 'IFRAME' || node.tagName === 'FRAME') hookFrame(node);
+                        if (node.querySelectorAll) Array.prototype.forEach.call(node.querySelectorAll('iframe, frame'), hookFrame);
+                    });
+                });
+            }).observe(doc.documentElement || doc, { childList: true, subtree: true });
+        }
+        
+        if (document.documentElement) {
+            watchFrames(document);
+        } else {
+            document.addEventListener('DOMContentLoaded', function() { watchFrames(document); });
+        }
+        
+        // Record fetch and XMLHttpRequest traffic
+        window.__mcpNetworkLog = window.__mcpNetworkLog || [];
+        window.__mcpPendingRequests = window.__mcpPendingRequests || 0;
+        function recordNetwork(info) {
+            info.seq = ++window.__consoleSeq;
+            info.sessionId = window.__consoleSessionId;
+            appendBounded('__mcpNetworkLog', info);
+            if (info.status >= 500) {
+                notifyTrigger('http_server_error', { status: info.status, url: info.url, message: info.method + ' ' + info.url });
+            }
+        }
+        
+        if (window.fetch) {
+            const originalFetch = window.fetch;
+            window.fetch = function(input, init) {
+                const started = Date.now();
+                const method = (init && init.method) || (input && input.method) || 'GET';
+                const url = typeof input === 'string' ? input : ((input && input.url) || String(input));
+                const base = { kind: 'fetch', method: method.toUpperCase(), url: url, timestamp: new Date(started).toISOString() };
+                tagCaller(base);
+                window.__mcpPendingRequests++;
+                return originalFetch.apply(this, arguments).then(function(response) {
+                    window.__mcpPendingRequests--;
+                    recordNetwork(Object.assign(base, {
+                        status: response.status,
+                        ok: response.ok,
+                        duration_ms: Date.now() - started,
+                        error: null
+                    }));
+                    return response;
+                }, function(error) {
+                    window.__mcpPendingRequests--;
+                    recordNetwork(Object.assign(base, {
+                        status: null,
+                        ok: false,
+                        duration_ms: Date.now() - started,
+                        error: String(error)
+                    }));
+                    throw error;
+                });
             };
-            
-            window.__clearConsoleBuffer = function() {
-                window.__consoleBuffer = [];
+        }
+        
+        const originalXhrOpen = XMLHttpRequest.prototype.open;
+        const originalXhrSend = XMLHttpRequest.prototype.send;
+        XMLHttpRequest.prototype.open = function(method, url) {
+            this.__mcpRequest = { method: String(method).toUpperCase(), url: String(url) };
+            return originalXhrOpen.apply(this, arguments);
+        };
+        XMLHttpRequest.prototype.send = function() {
+            const xhr = this;
+            const started = Date.now();
+            const caller = tagCaller({});
+            window.__mcpPendingRequests++;
+            xhr.addEventListener('loadend', function() {
+                window.__mcpPendingRequests--;
+                const req = xhr.__mcpRequest || { method: 'GET', url: '' };
+                recordNetwork({
+                    kind: 'xhr',
+                    method: req.method,
+                    url: req.url,
+                    status: xhr.status || null,
+                    ok: xhr.status >= 200 && xhr.status < 300,
+                    duration_ms: Date.now() - started,
+                    error: xhr.status ? null : 'Network error',
+                    timestamp: new Date(started).toISOString(),
+                    correlationId: caller.correlationId,
+                    clientId: caller.clientId
+                });
+            });
+            return originalXhrSend.apply(this, arguments);
+        };
+        
+        // Record main-thread blocks and slow input handling for jank reports
+        window.__mcpLongTasks = window.__mcpLongTasks || [];
+        window.__mcpEventTimings = window.__mcpEventTimings || [];
+        function pushBounded(buffer, item) {
+            buffer.push(item);
+            if (buffer.length > 500) buffer.shift();
+        }
+        try {
+            new PerformanceObserver(function(list) {
+                list.getEntries().forEach(function(entry) {
+                    pushBounded(window.__mcpLongTasks, {
+                        start: entry.startTime,
+                        duration: entry.duration,
+                        name: entry.name
+                    });
+                });
+            }).observe({ type: 'longtask', buffered: true });
+        } catch (e) {}
+        try {
+            new PerformanceObserver(function(list) {
+                list.getEntries().forEach(function(entry) {
+                    let target = null;
+                    if (entry.target && entry.target.tagName) {
+                        target = entry.target.tagName.toLowerCase() + (entry.target.id ? '#' + entry.target.id : '');
+                    }
+                    pushBounded(window.__mcpEventTimings, {
+                        start: entry.startTime,
+                        duration: entry.duration,
+                        processing_ms: entry.processingEnd - entry.processingStart,
+                        input_delay_ms: entry.processingStart - entry.startTime,
+                        name: entry.name,
+                        target: target
+                    });
+                });
+            }).observe({ type: 'event', durationThreshold: 16, buffered: true });
+        } catch (e) {}
+        
+        // Track listeners so get_event_listeners can report them per element. Only installed with
+        // `track_listeners`, since it replaces EventTarget.prototype.addEventListener for the whole
+        // page. Targets are WeakMap keys, so tracking never keeps a removed node alive.
+        instruments.listeners = function() {
+            window.__mcpListeners = window.__mcpListeners || new WeakMap();
+            window.__mcpListenersSince = window.__mcpListenersSince || new Date().toISOString();
+            function listenerOptions(options) {
+                if (typeof options === 'boolean') return { capture: options, passive: false, once: false };
+                options = options || {};
+                return { capture: !!options.capture, passive: !!options.passive, once: !!options.once };
+            }
+            const originalAddEventListener = EventTarget.prototype.addEventListener;
+            const originalRemoveEventListener = EventTarget.prototype.removeEventListener;
+            EventTarget.prototype.addEventListener = function(type, listener, options) {
+                try {
+                    if (listener) {
+                        const flags = listenerOptions(options);
+                        let entries = window.__mcpListeners.get(this);
+                        if (!entries) {
+                            entries = [];
+                            window.__mcpListeners.set(this, entries);
+                        }
+                        const duplicate = entries.some(e => e.type === type && e.listener === listener && e.capture === flags.capture);
+                        if (!duplicate) {
+                            entries.push(Object.assign({ type: String(type), listener: listener }, flags));
+                        }
+                    }
+                } catch (e) {}
+                return originalAddEventListener.apply(this, arguments);
             };
-            
-            console.log('Event-based console capture system initialized');
-            return { 
-                setup_complete: true, 
-                session_id: window.__consoleSessionId,
-                capture_method: 'events'
+            EventTarget.prototype.removeEventListener = function(type, listener, options) {
+                try {
+                    const entries = window.__mcpListeners.get(this);
+                    if (entries) {
+                        const capture = listenerOptions(options).capture;
+                        const index = entries.findIndex(e => e.type === type && e.listener === listener && e.capture === capture);
+                        if (index !== -1) entries.splice(index, 1);
+                    }
+                } catch (e) {}
+                return originalRemoveEventListener.apply(this, arguments);
+            };
+        };
+        
+        // Record right-clicks so custom context menu handling can be inspected
+        window.__mcpContextMenuEvents = window.__mcpContextMenuEvents || [];
+        window.addEventListener('contextmenu', function(event) {
+            const target = event.target;
+            const record = {
+                target: target && target.tagName ? target.tagName.toLowerCase() + (target.id ? '#' + target.id : '') : null,
+                x: event.clientX,
+                y: event.clientY,
+                default_prevented: event.defaultPrevented,
+                timestamp: new Date().toISOString()
+            };
+            pushBounded(window.__mcpContextMenuEvents, record);
+            // App handlers run after this capture-phase listener; read the outcome afterwards
+            setTimeout(function() { record.default_prevented = event.defaultPrevented; }, 0);
+        }, true);
+        
+        // Track timers and entropy sources for detect_nondeterminism. Only installed when that tool
+        // runs or with `track_nondeterminism`, since it replaces Date, Math.random and the timer
+        // functions, which apps and test clocks may compare or patch themselves.
+        instruments.nondeterminism = function() {
+            window.__mcpEntropySince = window.__mcpEntropySince || new Date().toISOString();
+            window.__mcpTimers = window.__mcpTimers || new Map();
+            window.__mcpEntropy = window.__mcpEntropy || { random_calls: 0, date_calls: 0, random_sites: {}, date_sites: {} };
+            function callSite() {
+                // Sample call sites; capturing a stack on every call is too slow
+                const stack = new Error().stack || '';
+                const frames = stack.split('\n').slice(3);
+                return frames.length ? frames[0].trim() : 'unknown';
+            }
+            function noteSite(sites, count) {
+                if (count <= 50 || count % 100 === 0) {
+                    const site = callSite();
+                    sites[site] = (sites[site] || 0) + 1;
+                }
+            }
+            const originalSetTimeout = window.setTimeout;
+            const originalSetInterval = window.setInterval;
+            const originalClearTimeout = window.clearTimeout;
+            const originalClearInterval = window.clearInterval;
+            window.setTimeout = function(handler, delay) {
+                let id;
+                const wrapped = typeof handler === 'function'
+                    ? function() { window.__mcpTimers.delete(id); return handler.apply(this, arguments); }
+                    : handler;
+                id = originalSetTimeout.apply(window, [wrapped].concat(Array.prototype.slice.call(arguments, 1)));
+                window.__mcpTimers.set(id, { kind: 'timeout', delay: Number(delay) || 0, created: Date.now() });
+                return id;
+            };
+            window.setInterval = function(handler, delay) {
+                const id = originalSetInterval.apply(window, arguments);
+                window.__mcpTimers.set(id, { kind: 'interval', delay: Number(delay) || 0, created: Date.now() });
+                return id;
             };
-        })()
-    "#;
+            window.clearTimeout = function(id) {
+                window.__mcpTimers.delete(id);
+                return originalClearTimeout.apply(window, arguments);
+            };
+            window.clearInterval = function(id) {
+                window.__mcpTimers.delete(id);
+                return originalClearInterval.apply(window, arguments);
+            };
+            const originalRandom = Math.random;
+            Math.random = function() {
+                const entropy = window.__mcpEntropy;
+                noteSite(entropy.random_sites, ++entropy.random_calls);
+                return originalRandom.call(Math);
+            };
+            const OriginalDate = window.Date;
+            const originalDateNow = OriginalDate.now;
+            OriginalDate.now = function() {
+                const entropy = window.__mcpEntropy;
+                noteSite(entropy.date_sites, ++entropy.date_calls);
+                return originalDateNow.call(OriginalDate);
+            };
+            window.Date = new Proxy(OriginalDate, {
+                construct: function(target, args) {
+                    // Only argument-less construction reads the clock
+                    if (args.length === 0) {
+                        const entropy = window.__mcpEntropy;
+                        noteSite(entropy.date_sites, ++entropy.date_calls);
+                    }
+                    return Reflect.construct(target, args);
+                }
+            });
+        };
+        
+        // Remove buffered entries and errors in a scope, as clear_console_buffer does in the
+        // plugin's store. Returns how many were removed, with the session and the last seq handed
+        // out so the store can tell what was logged before the clear.
+        window.__mcpClearConsole = function(scope) {
+            scope = scope || {};
+            function cleared(item) {
+                if (scope.session_id && item.sessionId !== scope.session_id) return false;
+                if (scope.before !== undefined && scope.before !== null &&
+                    !(Date.parse(item.lastTimestamp || item.timestamp) < scope.before)) return false;
+                return true;
+            }
+            // __mcpConsoleMessages holds the same entries as __consoleBuffer, so it isn't counted
+            const names = scope.errors_only ? ['__mcpJSErrors'] :
+                ['__consoleBuffer', '__mcpConsoleMessages', '__mcpJSErrors'];
+            let removed = 0;
+            names.forEach(function(name) {
+                const buffer = window[name];
+                if (!buffer) return;
+                const kept = buffer.filter(function(item) { return !cleared(item); });
+                if (name !== '__mcpConsoleMessages') removed += buffer.length - kept.length;
+                window[name] = kept;
+                window.__mcpConsoleBytes[name] = kept.reduce(function(sum, item) {
+                    return sum + entrySize(item);
+                }, 0);
+            });
+            return { removed: removed, session_id: window.__consoleSessionId, seq: window.__consoleSeq || 0 };
+        };
+        
+        // Install what was asked for before the capture script loaded
+        (window.__mcpInstrumentRequests || []).forEach(function(name) { window.__mcpInstrument(name); });
+        
+        // Utility functions
+        window.__getConsoleBuffer = function() {
+            return window.__consoleBuffer || [];
+        };
+        
+        window.__clearConsoleBuffer = function() {
+            window.__consoleBuffer = [];
+            window.__mcpConsoleBytes.__consoleBuffer = 0;
+        };
+        
+        return { 
+            setup_complete: true, 
+            session_id: window.__consoleSessionId,
+            capture_method: 'events'
+        };
+    })()
+"#;
+
+/// Setup console capture with event-based communication
+pub async fn handle_setup_console_capture<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ConsoleOutputRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    
+    info!("[TAURI_MCP] Setting up event-based console capture for window: {}", window_label);
+    
+    let backend = webview_backend::backend(app)?;
+    if !backend.has_window(&window_label) {
+        return Err(crate::Error::Anyhow(format!("Window '{}' not found", window_label)));
+    }
+    
+    // The capture script reads these on every call, so they also apply to an installed capture
+    if let Some(capture_stacks) = request.capture_stacks {
+        backend.eval(&window_label, &format!("window.__mcpCaptureStacks = {};", capture_stacks))
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to configure stack capture: {}", e)))?;
+    }
+    if let Some(collapse_repeats) = request.collapse_repeats {
+        backend.eval(&window_label, &format!("window.__mcpCollapseRepeats = {};", collapse_repeats))
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to configure repeat collapsing: {}", e)))?;
+    }
+    if let Some(level) = request.min_level {
+        backend.eval(&window_label, &min_level_script(level))
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to configure console level: {}", e)))?;
+    }
+    if let Some(rules) = &request.sampling {
+        backend.eval(&window_label, &sampling_script(rules))
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to configure console sampling: {}", e)))?;
+    }
+    let instruments = [
+        (Instrument::Workers, request.capture_workers),
+        (Instrument::Nondeterminism, request.track_nondeterminism),
+        (Instrument::Listeners, request.track_listeners),
+    ];
+    for (instrument, requested) in instruments {
+        if requested == Some(true) {
+            backend.eval(&window_label, &instrument.request_script())
+                .map_err(|e| crate::Error::Anyhow(format!("Failed to enable {:?} instrumentation: {}", instrument, e)))?;
+        }
+    }
+    let limits = ConsoleLimits {
+        max_entries: request.max_entries,
+        max_bytes: request.max_bytes,
+    };
+    if !limits.is_empty() {
+        backend.eval(&window_label, &limits_script(&limits))
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to configure console limits: {}", e)))?;
+        app.state::<ConsoleStore>().set_limits(&window_label, &limits);
+    }
+    
+    // The next page in this window gets capture set up again with the same settings
+    let captures = app.state::<CaptureRegistry>();
+    captures.remember_settings(&window_label, serde_json::json!({
+        "capture_stacks": request.capture_stacks,
+        "collapse_repeats": request.collapse_repeats,
+        "min_level": request.min_level,
+        "sampling": request.sampling,
+        "max_entries": request.max_entries,
+        "max_bytes": request.max_bytes,
+        "capture_workers": request.capture_workers,
+        "track_nondeterminism": request.track_nondeterminism,
+        "track_listeners": request.track_listeners
+    }));
     
-    window.eval(capture_code)
+    // Skip the round trip when this page already has capture installed
+    if let Some(session) = captures.get(&window_label) {
+        return Ok(SocketResponse {
+            success: true,
+            data: Some(serde_json::json!({
+                "message": "Event-based console capture already active",
+                "window_label": window_label,
+                "session_id": session.session_id,
+                "installed_at_ms": session.installed_at_ms,
+                "already_installed": true
+            })),
+            error: None,
+            ..Default::default()
+        });
+    }
+    
+    // A new page starts from the configured defaults for whatever the request left out
+    if let Some(defaults) = app.try_state::<CaptureDefaults>() {
+        let mut script = String::new();
+        if let (None, Some(level)) = (request.min_level, defaults.min_level) {
+            script.push_str(&min_level_script(level));
+        }
+        if request.sampling.is_none() && !defaults.sampling.is_empty() {
+            script.push_str(&sampling_script(&defaults.sampling));
+        }
+        let unset_limits = ConsoleLimits {
+            max_entries: defaults.limits.max_entries.filter(|_| request.max_entries.is_none()),
+            max_bytes: defaults.limits.max_bytes.filter(|_| request.max_bytes.is_none()),
+        };
+        if !unset_limits.is_empty() {
+            script.push_str(&limits_script(&unset_limits));
+        }
+        if !script.is_empty() {
+            backend.eval(&window_label, &script)
+                .map_err(|e| crate::Error::Anyhow(format!("Failed to configure console capture: {}", e)))?;
+        }
+    }
+    
+    // Tell the capture script which events should fire automatic artifact captures
+    if let Some(triggers) = app.try_state::<crate::tools::triggers::TriggerState>() {
+        let kinds = serde_json::to_string(&triggers.kinds()).unwrap_or_else(|_| "[]".to_string());
+        backend.eval(&window_label, &format!("window.__mcpTriggerKinds = {};", kinds))
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to configure capture triggers: {}", e)))?;
+    }
+    
+    // Inject our event-based console capture system. Going through the bridge installs the shared
+    // helpers first and confirms the script actually ran, so blocked injection surfaces as an error.
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let code = format!("return ({});", crate::tools::page_helpers::minify(CAPTURE_SCRIPT));
+    let result = eval_with_result(app, &window_label, &code, timeout)
+        .await
         .map_err(|e| crate::Error::Anyhow(format!("Failed to setup console capture: {}", e)))?;
     
+    let session_id = result
+        .get("session_id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let session = captures.record(&window_label, session_id);
+    
     Ok(SocketResponse {
         success: true,
         data: Some(serde_json::json!({
             "message": "Event-based console capture setup complete",
-            "window_label": window_label
+            "window_label": window_label,
+            "session_id": session.session_id,
+            "installed_at_ms": session.installed_at_ms,
+            "already_installed": false
         })),
         error: None,
+        ..Default::default()
     })
 }
 
 
-/// Get JavaScript result using direct console message buffer inspection
+/// Get the value of a page global, by default `window.__mcpLastResult`. The value comes back over
+/// the plugin's own reply channel, so nothing is logged to the page's console.
 pub async fn handle_get_js_result<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
@@ -181,6 +2008,7 @@ pub async fn handle_get_js_result<R: Runtime>(
     struct GetJsResultRequest {
         window_label: Option<String>,
         variable_name: Option<String>,
+        timeout_ms: Option<u64>,
     }
     
     let request: GetJsResultRequest = serde_json::from_value(payload)
@@ -188,117 +2016,33 @@ pub async fn handle_get_js_result<R: Runtime>(
     let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
     let variable_name = request.variable_name.unwrap_or_else(|| "__mcpLastResult".to_string());
     
-    info!("[TAURI_MCP] Getting JS result '{}' from window: {} (buffer-based)", variable_name, window_label);
-    
-    let window = app.get_webview_window(&window_label)
-        .ok_or_else(|| crate::Error::Anyhow(format!("Window '{}' not found", window_label)))?;
+    info!("[TAURI_MCP] Getting JS result '{}' from window: {}", variable_name, window_label);
     
-    // Create a unique key to identify this retrieval
-    let result_key = format!("mcp_result_{}", std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis());
-    
-    // Step 1: Execute JavaScript that will log the result with our unique key
     let retrieve_code = format!(r#"
-        (function() {{
-            try {{
-                const value = window.{};
-                const result = {{
-                    success: true,
-                    value: value,
-                    type: typeof value,
-                    timestamp: new Date().toISOString()
-                }};
-                
-                // Log with unique identifier
-                console.log('MCP_RETRIEVE_SUCCESS_{}:' + JSON.stringify(result));
-                return 'retrieval_logged';
-            }} catch (error) {{
-                console.log('MCP_RETRIEVE_ERROR_{}:' + JSON.stringify({{
-                    success: false,
-                    error: error.message,
-                    stack: error.stack,
-                    timestamp: new Date().toISOString()
-                }}));
-                return 'error_logged';
-            }}
-        }})()
-    "#, variable_name, result_key, result_key);
-    
-    // Execute the retrieval JavaScript
-    window.eval(&retrieve_code)
-        .map_err(|e| crate::Error::Anyhow(format!("Failed to execute retrieval JavaScript: {}", e)))?;
-    
-    // Small delay to let console.log execute
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    
-    // Step 2: Get the console messages and look for our result
-    let search_code = format!(r#"
-        (function() {{
-            if (window.__mcpConsoleMessages) {{
-                const messages = window.__mcpConsoleMessages;
-                for (let i = messages.length - 1; i >= 0; i--) {{
-                    const message = messages[i].message;
-                    if (message.includes('MCP_RETRIEVE_SUCCESS_{}:') || message.includes('MCP_RETRIEVE_ERROR_{}:')) {{
-                        const colonIndex = message.indexOf(':');
-                        if (colonIndex !== -1) {{
-                            const data = message.substring(colonIndex + 1);
-                            window.__mcpLastSearchResult = {{
-                                found: true,
-                                data: data,
-                                timestamp: new Date().toISOString()
-                            }};
-                            return 'found';
-                        }}
-                    }}
-                }}
-            }}
-            window.__mcpLastSearchResult = {{
-                found: false,
-                error: 'Message not found in buffer',
-                timestamp: new Date().toISOString()
-            }};
-            return 'not_found';
-        }})()
-    "#, result_key, result_key);
-    
-    // Execute the search
-    window.eval(&search_code)
-        .map_err(|e| crate::Error::Anyhow(format!("Failed to execute search JavaScript: {}", e)))?;
-    
-    // Small delay to let search execute
-    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-    
-    // Step 3: Get the search result
-    let get_result_code = r#"
-        (function() {
-            if (window.__mcpLastSearchResult) {
-                return JSON.stringify(window.__mcpLastSearchResult);
-            } else {
-                return JSON.stringify({ found: false, error: 'Search result not available' });
-            }
-        })()
-    "#;
+        const value = window.{};
+        return {{ value: value === undefined ? null : value, type: typeof value }};
+    "#, variable_name);
     
-    window.eval(get_result_code)
-        .map_err(|e| crate::Error::Anyhow(format!("Failed to get search result: {}", e)))?;
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &retrieve_code, timeout).await?;
     
-    // Since we can't get the return value from eval, we'll indicate success with our approach
     Ok(SocketResponse {
         success: true,
         data: Some(serde_json::json!({
-            "message": "JavaScript result retrieval executed - check console buffer with get_console_buffer",
+            "window_label": window_label,
             "variable_name": variable_name,
-            "result_key": result_key,
-            "approach": "console_buffer_search",
-            "next_step": "Use get_console_buffer to retrieve the actual data"
+            "value": result.get("value").cloned().unwrap_or(Value::Null),
+            "type": result.get("type").cloned().unwrap_or(Value::Null)
         })),
         error: None,
+        ..Default::default()
     })
 }
 
-/// Execute JavaScript with console capture enabled
+/// Execute JavaScript with console capture enabled and return the console entries it produced.
+///
+/// The code runs as a global script (through indirect `eval`, so top-level `var`s become
+/// globals). If it evaluates to a promise, that promise is awaited before the buffer is read.
 pub async fn handle_execute_with_console<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
@@ -307,6 +2051,9 @@ pub async fn handle_execute_with_console<R: Runtime>(
     struct ExecuteWithConsoleRequest {
         window_label: Option<String>,
         code: String,
+        /// Extra time after completion for asynchronous output such as timers (default 0)
+        settle_ms: Option<u64>,
+        timeout_ms: Option<u64>,
     }
     
     let request: ExecuteWithConsoleRequest = serde_json::from_value(payload)
@@ -315,8 +2062,10 @@ pub async fn handle_execute_with_console<R: Runtime>(
     
     info!("[TAURI_MCP] Executing JS with event-based console capture for window: {}", window_label);
     
-    let window = app.get_webview_window(&window_label)
-        .ok_or_else(|| crate::Error::Anyhow(format!("Window '{}' not found", window_label)))?;
+    let backend = webview_backend::backend(app)?;
+    if !backend.has_window(&window_label) {
+        return Err(crate::Error::Anyhow(format!("Window '{}' not found", window_label)));
+    }
     
     // First ensure console capture is setup
     let setup_result = handle_setup_console_capture(app, serde_json::json!({
@@ -327,73 +2076,475 @@ pub async fn handle_execute_with_console<R: Runtime>(
         return Ok(setup_result);
     }
     
-    // Execute the provided JavaScript code
-    window.eval(&request.code)
+    // Snapshot the sequence number, run the code, then keep what was logged after the snapshot
+    let run_code = format!(r#"
+        const before = window.__consoleSeq || 0;
+        let error = null;
+        let stack = null;
+        try {{
+            const value = (0, eval)({code});
+            if (value && typeof value.then === 'function') await value;
+        }} catch (e) {{
+            error = (e && e.message) ? e.message : String(e);
+            stack = (e && e.stack) ? e.stack : null;
+        }}
+        if ({settle_ms} > 0) await new Promise(resolve => setTimeout(resolve, {settle_ms}));
+        return {{
+            entries: (window.__consoleBuffer || []).filter(entry => entry.seq > before),
+            error: error,
+            stack: stack
+        }};
+    "#,
+        code = serde_json::to_string(&request.code).unwrap_or_else(|_| "\"\"".to_string()),
+        settle_ms = request.settle_ms.unwrap_or(0)
+    );
+    
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(10000));
+    let value = eval_with_result(app, &window_label, &run_code, timeout)
+        .await
         .map_err(|e| crate::Error::Anyhow(format!("Failed to execute JavaScript: {}", e)))?;
     
+    let mut entries: Vec<ConsoleEntry> = serde_json::from_value(
+        value.get("entries").cloned().unwrap_or(Value::Array(vec![])),
+    )
+    .map_err(|e| crate::Error::Anyhow(format!("Failed to parse console entries: {}", e)))?;
+    entries.retain(|entry| !is_internal(&entry.message));
+    let generation = app.state::<ConsoleStore>().navigation_generation(&window_label);
+    for entry in &mut entries {
+        entry.navigation_generation = generation;
+    }
+    let error = value.get("error").and_then(|v| v.as_str()).map(|s| s.to_string());
+    
     Ok(SocketResponse {
-        success: true,
+        success: error.is_none(),
         data: Some(serde_json::json!({
             "message": "JavaScript executed with event-based console capture",
-            "window_label": window_label
+            "window_label": window_label,
+            "entries": entries,
+            "stack": value.get("stack").cloned().unwrap_or(Value::Null)
         })),
-        error: None,
+        error: error.map(|e| format!("JavaScript error: {}", e)),
+        ..Default::default()
     })
 }
 
-/// Get the console buffer with retrieved data
+/// Get the console entries and JavaScript errors the window's capture script has pushed to the
+/// plugin, installing the capture first if the page doesn't have it yet
 pub async fn handle_get_console_buffer<R: Runtime>(
     app: &AppHandle<R>,
     payload: Value,
 ) -> crate::Result<SocketResponse> {
-    #[derive(Debug, Deserialize)]
-    struct GetConsoleBufferRequest {
-        window_label: Option<String>,
-        filter: Option<String>, // Optional filter for specific messages
-    }
-    
-    let request: GetConsoleBufferRequest = serde_json::from_value(payload)
+    let request: ConsoleOutputRequest = serde_json::from_value(payload.clone())
         .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
     let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
     
     info!("[TAURI_MCP] Getting console buffer from window: {}", window_label);
     
-    let window = app.get_webview_window(&window_label)
-        .ok_or_else(|| crate::Error::Anyhow(format!("Window '{}' not found", window_label)))?;
+    // Make sure the capture hooks exist; this is a no-op when already installed. Capture settings
+    // in the request are applied on the way.
+    let setup_result = handle_setup_console_capture(app, payload).await?;
     
-    // Get all console messages and search results
-    let buffer_code = r#"
-        (function() {
-            const result = {
-                consoleBuffer: window.__consoleBuffer || [],
-                mcpMessages: window.__mcpConsoleMessages || [],
-                jsErrors: window.__mcpJSErrors || [],
-                lastSearchResult: window.__mcpLastSearchResult || null,
-                bufferLength: (window.__consoleBuffer || []).length,
-                mcpLength: (window.__mcpConsoleMessages || []).length,
-                errorCount: (window.__mcpJSErrors || []).length
-            };
-            
-            // Store in a global for easy access
-            window.__mcpBufferData = result;
-            
-            return 'buffer_compiled';
-        })()
-    "#;
-    
-    window.eval(buffer_code)
-        .map_err(|e| crate::Error::Anyhow(format!("Failed to compile buffer data: {}", e)))?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
     
-    // Small delay to let the compilation execute
-    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    let range = match TimeRange::new(request.from.as_ref(), request.to.as_ref()) {
+        Ok(range) => range,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                ..Default::default()
+            })
+        }
+    };
+    let message_regex = match request.message_regex.as_deref().map(Regex::new).transpose() {
+        Ok(regex) => regex,
+        Err(e) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Invalid message_regex: {}", e)),
+                ..Default::default()
+            })
+        }
+    };
+    let client = js_bridge::current_client().filter(|_| request.client_view);
+    let query = ConsoleQuery {
+        filter: request.filter.as_deref(),
+        since_cursor: request.since_cursor,
+        since_timestamp: request.since_timestamp.as_deref(),
+        levels: request.levels.as_deref(),
+        message_regex: message_regex.as_ref(),
+        correlation_id: request.correlation_id.as_deref(),
+        client: client.as_deref(),
+        include_internal: request.include_internal,
+        range,
+        offset: request.offset.unwrap_or(0),
+        limit: request.limit,
+    };
+    let response = app.state::<ConsoleStore>().snapshot(&window_label, &query);
     
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Get console entries newer than `since_seq` together with the new high-water mark
+pub async fn handle_get_console_delta<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ConsoleDeltaRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Getting console delta since seq {} from window: {}",
+        request.since_seq, window_label
+    );
+
+    let range = match TimeRange::new(request.from.as_ref(), request.to.as_ref()) {
+        Ok(range) => range,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                ..Default::default()
+            })
+        }
+    };
+
+    // Make sure the capture hooks exist; this is a no-op when already installed
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let delta_code = format!(r#"
+        const since = {};
+        const highWaterMark = window.__consoleSeq || 0;
+        const reset = highWaterMark < since;
+        const from = reset ? 0 : since;
+        return {{
+            entries: (window.__consoleBuffer || []).filter(entry => entry.seq > from),
+            high_water_mark: highWaterMark,
+            reset: reset,
+            session_id: window.__consoleSessionId || null,
+            suppressed: Object.fromEntries(Object.entries(window.__mcpConsoleSampleState || {{}})
+                .filter(([, state]) => state.suppressed > 0)
+                .map(([pattern, state]) => [pattern, state.suppressed])),
+            dropped_count: window.__mcpConsoleDropped || 0
+        }};
+    "#, request.since_seq);
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let value = eval_with_result(app, &window_label, &delta_code, timeout).await?;
+
+    let mut entries: Vec<ConsoleEntry> = serde_json::from_value(
+        value.get("entries").cloned().unwrap_or(Value::Array(vec![])),
+    )
+    .map_err(|e| crate::Error::Anyhow(format!("Failed to parse console entries: {}", e)))?;
+    let client = js_bridge::current_client().filter(|_| request.client_view);
+    entries.retain(|entry| {
+        range.contains_timestamp(entry.last_timestamp.as_deref().unwrap_or(&entry.timestamp))
+            && (request.include_internal || !is_internal(&entry.message))
+            && client.as_deref().is_none_or(|own| {
+                entry.client_id.as_deref().is_none_or(|client| client == own)
+            })
+    });
+    // The page's buffer only holds what the current page logged
+    let generation = app.state::<ConsoleStore>().navigation_generation(&window_label);
+    for entry in &mut entries {
+        entry.navigation_generation = generation;
+    }
+
+    let session_id = value
+        .get("session_id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let captures = app.state::<CaptureRegistry>();
+    if let Some(id) = &session_id {
+        captures.observe(&window_label, id);
+    }
+
+    let response = ConsoleDeltaResponse {
+        entries,
+        high_water_mark: value.get("high_water_mark").and_then(|v| v.as_u64()).unwrap_or(0),
+        reset: value.get("reset").and_then(|v| v.as_bool()).unwrap_or(false),
+        session_id,
+        session_transitions: captures.transitions(&window_label),
+        suppressed: value
+            .get("suppressed")
+            .cloned()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        dropped_count: value.get("dropped_count").and_then(|v| v.as_u64()).unwrap_or(0),
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Get the uncaught errors and unhandled rejections the window's capture script has pushed to the
+/// plugin, without its console entries, installing the capture first if the page doesn't have it yet
+pub async fn handle_get_js_errors<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: JsErrorsRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Getting JavaScript errors from window: {}", window_label);
+
+    let range = match TimeRange::new(request.from.as_ref(), request.to.as_ref()) {
+        Ok(range) => range,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                ..Default::default()
+            })
+        }
+    };
+    let message_regex = match request.message_regex.as_deref().map(Regex::new).transpose() {
+        Ok(regex) => regex,
+        Err(e) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Invalid message_regex: {}", e)),
+                ..Default::default()
+            })
+        }
+    };
+
+    // Errors only reach the store once the page has the capture hooks
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let query = ConsoleQuery {
+        message_regex: message_regex.as_ref(),
+        range,
+        ..Default::default()
+    };
+    let mut errors = app.state::<ConsoleStore>().errors(&window_label, &query);
+    let matched_count = errors.len();
+    if let Some(limit) = request.limit {
+        errors.drain(..matched_count.saturating_sub(limit));
+    }
+
+    let response = JsErrorsResponse {
+        window_label,
+        errors,
+        matched_count,
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Forget the JavaScript errors stored for a window. Console entries, and the errors the page
+/// itself still holds, are left alone.
+pub fn handle_clear_js_errors<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let window_label = payload
+        .get("window_label")
+        .and_then(|v| v.as_str())
+        .unwrap_or("main")
+        .to_string();
+
+    let scope = ClearScope {
+        errors_only: true,
+        ..Default::default()
+    };
+    let cleared_count = app.state::<ConsoleStore>().clear(&window_label, scope, None);
+    info!("[TAURI_MCP] Cleared {} JavaScript error(s) from window: {}", cleared_count, window_label);
+
     Ok(SocketResponse {
         success: true,
         data: Some(serde_json::json!({
-            "message": "Console buffer compiled to window.__mcpBufferData",
             "window_label": window_label,
-            "note": "Access window.__mcpBufferData.lastSearchResult for retrieved data"
+            "cleared_count": cleared_count,
         })),
         error: None,
+        ..Default::default()
     })
-}
\ No newline at end of file
+}
+
+/// Clear a window's console entries and JavaScript errors in the page and in the plugin's store
+/// together, optionally only errors, one page session's output or what was logged before a time
+pub async fn handle_clear_console_buffer<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ClearConsoleRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Clearing console buffer of window: {}", window_label);
+
+    let before = match request.before.as_ref().map(|bound| bound.to_ms("before")).transpose() {
+        Ok(before) => before,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                ..Default::default()
+            })
+        }
+    };
+    let scope = ClearScope {
+        errors_only: request.errors_only,
+        session_id: request.session_id,
+        before,
+    };
+
+    // Entries the page forwarded before its clear may still be on their way to the store, so the
+    // store clears by the page's report: what it logged up to then is dropped, now or on arrival
+    let code = format!(
+        "return window.__mcpClearConsole ? window.__mcpClearConsole({}) : null;",
+        serde_json::to_string(&scope).unwrap_or_else(|_| "{}".to_string())
+    );
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let page: Option<PageClear> =
+        serde_json::from_value(eval_with_result(app, &window_label, &code, timeout).await?)
+            .map_err(|e| crate::Error::Anyhow(format!("Failed to parse page clear: {}", e)))?;
+    let page_cleared_count = page.as_ref().map_or(0, |page| page.removed);
+    let cleared_count = app.state::<ConsoleStore>().clear(&window_label, scope, page);
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({
+            "window_label": window_label,
+            "cleared_count": cleared_count,
+            "page_cleared_count": page_cleared_count,
+        })),
+        error: None,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn console(session_id: &str, seq: u64, message: &str) -> ForwardedEntry {
+        serde_json::from_value(serde_json::json!({
+            "kind": "console",
+            "entry": {
+                "seq": seq,
+                "level": "log",
+                "message": message,
+                "timestamp": "2026-01-01T00:00:00.000Z",
+                "sessionId": session_id
+            }
+        }))
+        .unwrap()
+    }
+
+    fn messages(store: &ConsoleStore) -> Vec<String> {
+        store
+            .snapshot("main", &ConsoleQuery::default())
+            .entries
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect()
+    }
+
+    #[test]
+    fn evicts_the_oldest_entries_past_max_entries() {
+        let store = ConsoleStore::new(3, usize::MAX);
+        for message in ["a", "b", "c", "d", "e"] {
+            store.push("main", console("s", 0, message));
+        }
+        assert_eq!(messages(&store), ["c", "d", "e"]);
+        assert_eq!(store.snapshot("main", &ConsoleQuery::default()).dropped_count, 2);
+    }
+
+    #[test]
+    fn evicts_the_oldest_entries_past_max_bytes() {
+        // Each entry costs 64 bytes plus its 10-byte message
+        let store = ConsoleStore::new(100, 200);
+        for message in ["first-one!", "second-one", "third-one!"] {
+            store.push("main", console("s", 0, message));
+        }
+        assert_eq!(messages(&store), ["second-one", "third-one!"]);
+    }
+
+    #[test]
+    fn keeps_an_entry_larger_than_max_bytes() {
+        let store = ConsoleStore::new(100, 10);
+        store.push("main", console("s", 0, "longer than the limit"));
+        assert_eq!(messages(&store), ["longer than the limit"]);
+    }
+
+    #[test]
+    fn clear_drops_entries_the_page_logged_before_it_that_arrive_late() {
+        let store = ConsoleStore::new(100, usize::MAX);
+        store.push("main", console("s", 1, "one"));
+        store.push("main", console("s", 2, "two"));
+        let page = PageClear {
+            removed: 3,
+            session_id: "s".to_string(),
+            seq: 3,
+        };
+        assert_eq!(store.clear("main", ClearScope::default(), Some(page)), 2);
+
+        // Logged before the clear but still in flight when it ran
+        assert!(store.push("main", console("s", 3, "three")).is_none());
+        assert!(store.push("main", console("s", 4, "four")).is_some());
+        // A later page starts its own session
+        assert!(store.push("main", console("t", 1, "next page")).is_some());
+        assert_eq!(messages(&store), ["four", "next page"]);
+    }
+
+    #[test]
+    fn clearing_errors_leaves_late_console_entries() {
+        let store = ConsoleStore::new(100, usize::MAX);
+        let scope = ClearScope {
+            errors_only: true,
+            ..Default::default()
+        };
+        let page = PageClear {
+            removed: 0,
+            session_id: "s".to_string(),
+            seq: 5,
+        };
+        store.clear("main", scope, Some(page));
+        assert!(store.push("main", console("s", 3, "kept")).is_some());
+        assert_eq!(messages(&store), ["kept"]);
+    }
+}
@@ -0,0 +1,102 @@
+//! Eval results as MCP tool result content, so MCP servers built on the plugin can hand them to
+//! the model as they are instead of dumping response JSON into a text block.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::tools::direct_eval::DirectEvalResponse;
+
+/// One block of an MCP tool result's `content`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    /// Base64 image data
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    /// Structured values, as an embedded `application/json` resource
+    Resource {
+        resource: EmbeddedResource,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddedResource {
+    pub uri: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+    pub text: String,
+}
+
+/// Image the page rendered from a returned canvas, image, bitmap or image Blob
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageImage {
+    pub data: String,
+    pub mime_type: String,
+}
+
+/// Content blocks for an eval: the error and stack when it failed, an image block for returned
+/// images, a text block for strings and an `application/json` resource for other values.
+/// `image_error` explains why a returned image couldn't be read, e.g. a cross-origin canvas.
+pub fn eval_content(
+    response: &DirectEvalResponse,
+    image: Option<PageImage>,
+    image_error: Option<String>,
+) -> Vec<ContentBlock> {
+    if !response.success {
+        let mut text = response
+            .error
+            .clone()
+            .unwrap_or_else(|| "Unknown JavaScript error".to_string());
+        if let Some(stack) = &response.stack {
+            text = format!("{}\n\n{}", text, stack);
+        }
+        return vec![ContentBlock::Text { text }];
+    }
+
+    let mut blocks = Vec::new();
+    if let Some(image) = image {
+        blocks.push(ContentBlock::Image {
+            data: image.data,
+            mime_type: image.mime_type,
+        });
+        return blocks;
+    }
+    if let Some(error) = image_error {
+        blocks.push(ContentBlock::Text {
+            text: format!("The returned image could not be read: {}", error),
+        });
+    }
+
+    match (&response.result, response.value_type.as_deref()) {
+        _ if response.truncated => blocks.push(ContentBlock::Text {
+            text: format!(
+                "The result's JSON is {} characters, more than one response holds. It starts:\n{}\n\nRead the rest with fetch_result_chunk and execution_id \"{}\".",
+                response.original_size.unwrap_or_default(),
+                response.result.as_ref().and_then(|v| v.as_str()).unwrap_or_default(),
+                response.execution_id
+            ),
+        }),
+        (_, Some("undefined")) => blocks.push(ContentBlock::Text {
+            text: "undefined".to_string(),
+        }),
+        (Some(Value::String(text)), _) => blocks.push(ContentBlock::Text { text: text.clone() }),
+        (Some(value), _) => blocks.push(ContentBlock::Resource {
+            resource: EmbeddedResource {
+                uri: format!("eval://{}/result.json", response.execution_id),
+                mime_type: "application/json".to_string(),
+                text: serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
+            },
+        }),
+        (None, _) => blocks.push(ContentBlock::Text {
+            text: "null".to_string(),
+        }),
+    }
+    blocks
+}
@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::elements::{ElementTarget, find_element_code};
+use crate::tools::js_bridge::eval_with_result;
+
+/// Selectors for menus rendered by common component libraries, tried in addition to ARIA roles
+const MENU_SELECTORS: &str = "[role=\"menu\"], [role=\"listbox\"], .context-menu, .contextmenu, [data-radix-menu-content], [data-context-menu]";
+
+#[derive(Debug, Deserialize)]
+pub struct OpenContextMenuRequest {
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenMenuItemsRequest {
+    pub window_label: Option<String>,
+    /// Selector for the app's menu container when it isn't one of the recognized patterns
+    pub menu_selector: Option<String>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Right-click an element by dispatching the pointer, mouse and `contextmenu` event sequence
+pub async fn handle_open_context_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: OpenContextMenuRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Opening context menu on {} in window: {}",
+        request.target, window_label
+    );
+
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let code = format!(
+        r#"
+        {find_element}
+        element.scrollIntoView({{ block: 'center', inline: 'center' }});
+        const point = window.__mcp.center(element);
+        const init = {{
+            bubbles: true,
+            cancelable: true,
+            composed: true,
+            clientX: point.x,
+            clientY: point.y,
+            button: 2,
+            buttons: 2
+        }};
+        if (window.PointerEvent) element.dispatchEvent(new PointerEvent('pointerdown', Object.assign({{ pointerType: 'mouse' }}, init)));
+        element.dispatchEvent(new MouseEvent('mousedown', init));
+        if (window.PointerEvent) element.dispatchEvent(new PointerEvent('pointerup', Object.assign({{ pointerType: 'mouse' }}, init, {{ buttons: 0 }})));
+        element.dispatchEvent(new MouseEvent('mouseup', Object.assign({{}}, init, {{ buttons: 0 }})));
+        const event = new MouseEvent('contextmenu', Object.assign({{}}, init, {{ buttons: 0 }}));
+        element.dispatchEvent(event);
+
+        // Give frameworks a frame to render their menu
+        await new Promise(resolve => requestAnimationFrame(() => setTimeout(resolve, 0)));
+        return {{
+            dispatched: true,
+            // A custom menu normally prevents the native one
+            default_prevented: event.defaultPrevented,
+            x: init.clientX,
+            y: init.clientY
+        }};
+        "#,
+        find_element = find_element_code(&request.target)?
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// List items of visible menus, each with a handle that click/eval tools can target
+pub async fn handle_get_open_menu_items<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: OpenMenuItemsRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Getting open menu items in window: {}", window_label);
+
+    let selector = match &request.menu_selector {
+        Some(custom) => format!("{}, {}", custom, MENU_SELECTORS),
+        None => MENU_SELECTORS.to_string(),
+    };
+    let code = format!(
+        r#"
+        const {{ handles, isVisible, text }} = window.__mcp;
+        const menus = Array.from(document.querySelectorAll({selector})).filter(isVisible);
+        const itemSelector = '[role="menuitem"], [role="menuitemcheckbox"], [role="menuitemradio"], [role="option"], li, button';
+
+        const events = window.__mcpContextMenuEvents || [];
+        return {{
+            last_contextmenu: events.length ? events[events.length - 1] : null,
+            menus: menus.map(menu => ({{
+                handle: handles.handleFor(menu),
+                role: menu.getAttribute('role'),
+                label: menu.getAttribute('aria-label'),
+                items: Array.from(menu.querySelectorAll(itemSelector))
+                    // Skip wrappers like <li><button> so each item appears once
+                    .filter(item => isVisible(item) && !item.querySelector(itemSelector))
+                    .map(item => ({{
+                        handle: handles.handleFor(item),
+                        label: text(item),
+                        role: item.getAttribute('role'),
+                        disabled: item.getAttribute('aria-disabled') === 'true' || !!item.disabled,
+                        checked: item.hasAttribute('aria-checked') ? item.getAttribute('aria-checked') === 'true' : null
+                    }}))
+            }}))
+        }};
+        "#,
+        selector = serde_json::to_string(&selector).unwrap_or_default()
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
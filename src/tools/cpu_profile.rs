@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::artifacts::{ArtifactInfo, ArtifactStore};
+use crate::devtools::{CdpSession, NO_ENDPOINT, connect_window, resolve_port};
+use crate::error::Error;
+use crate::notifications::NotificationHub;
+use crate::socket_server::SocketResponse;
+use crate::tools::timeline::now_ms;
+
+/// A profile being recorded. The DevTools connection stays open until it is stopped, since
+/// closing it discards the profile.
+struct ActiveProfile {
+    session: CdpSession,
+    target_url: String,
+    started_ms: u64,
+}
+
+/// Running CPU profiles by window label, managed in plugin state
+#[derive(Default)]
+pub struct CpuProfiles {
+    active: tokio::sync::Mutex<HashMap<String, ActiveProfile>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartCpuProfileRequest {
+    pub window_label: Option<String>,
+    /// Remote debugging port to use instead of the one set with `PluginConfig::devtools_port`
+    pub port: Option<u16>,
+    /// Time between samples in microseconds (default 100). Lower is more precise but slows the
+    /// page down more.
+    pub sampling_interval_us: Option<u64>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartCpuProfileResponse {
+    pub window_label: String,
+    /// The devtools page being profiled
+    pub target_url: String,
+    pub started_ms: u64,
+}
+
+/// Start sampling a window's JavaScript CPU usage. Perform the interaction to measure, then call
+/// `stop_cpu_profile`. Needs a DevTools Protocol endpoint, which only WebView2 offers (see
+/// `PluginConfig::devtools_port`).
+pub async fn handle_start_cpu_profile<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: StartCpuProfileRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(10000));
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| Error::WindowNotFound(window_label.clone()))?;
+    let Some(port) = resolve_port(app, request.port) else {
+        return Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(NO_ENDPOINT.to_string()),
+            ..Default::default()
+        });
+    };
+
+    let profiles = app.state::<CpuProfiles>();
+    let mut active = profiles.active.lock().await;
+    if active.contains_key(&window_label) {
+        return Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(format!(
+                "A CPU profile is already running in window '{}'; stop it first",
+                window_label
+            )),
+            ..Default::default()
+        });
+    }
+
+    info!("[TAURI_MCP] Starting a CPU profile of window {} through port {}", window_label, port);
+
+    let interval = request.sampling_interval_us.unwrap_or(100).max(1);
+    let start = async {
+        let (mut session, target) = connect_window(&window, port).await?;
+        session.call("Profiler.enable", serde_json::json!({}), |_, _| {}).await?;
+        session
+            .call("Profiler.setSamplingInterval", serde_json::json!({ "interval": interval }), |_, _| {})
+            .await?;
+        session.call("Profiler.start", serde_json::json!({}), |_, _| {}).await?;
+        Ok::<_, Error>((session, target))
+    };
+    let (session, target) = match tokio::time::timeout(timeout, start).await {
+        Ok(Ok(started)) => started,
+        Ok(Err(e)) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to start the CPU profile: {}", e)),
+                ..Default::default()
+            })
+        }
+        Err(_) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Starting the CPU profile timed out after {}ms", timeout.as_millis())),
+                ..Default::default()
+            })
+        }
+    };
+
+    let started_ms = now_ms();
+    active.insert(
+        window_label.clone(),
+        ActiveProfile {
+            session,
+            target_url: target.url.clone(),
+            started_ms,
+        },
+    );
+
+    let response = StartCpuProfileResponse {
+        window_label,
+        target_url: target.url,
+        started_ms,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StopCpuProfileRequest {
+    pub window_label: Option<String>,
+    /// Long profiles take a while to serialize (default 60000)
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CpuProfileResponse {
+    pub artifact: ArtifactInfo,
+    /// Wall time between start and stop
+    pub duration_ms: u64,
+    pub sample_count: usize,
+    /// Distinct call stack nodes in the profile
+    pub node_count: usize,
+    pub target_url: String,
+}
+
+/// Stop a window's CPU profile and save it to the artifacts directory as a `.cpuprofile`, which
+/// the Performance panel of Chromium devtools and most profile viewers open
+pub async fn handle_stop_cpu_profile<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: StopCpuProfileRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(60000));
+
+    let Some(mut profile) = app.state::<CpuProfiles>().active.lock().await.remove(&window_label) else {
+        return Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(format!("No CPU profile is running in window '{}'", window_label)),
+            ..Default::default()
+        });
+    };
+
+    info!("[TAURI_MCP] Stopping the CPU profile of window {}", window_label);
+
+    let stop = profile.session.call("Profiler.stop", serde_json::json!({}), |_, _| {});
+    let profile_data = match tokio::time::timeout(timeout, stop).await {
+        Ok(Ok(result)) => result.get("profile").cloned().unwrap_or(Value::Null),
+        Ok(Err(e)) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to stop the CPU profile: {}", e)),
+                ..Default::default()
+            })
+        }
+        Err(_) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Stopping the CPU profile timed out after {}ms", timeout.as_millis())),
+                ..Default::default()
+            })
+        }
+    };
+    let count = |key: &str| profile_data.get(key).and_then(|v| v.as_array()).map_or(0, Vec::len);
+    let sample_count = count("samples");
+    let node_count = count("nodes");
+    let bytes = serde_json::to_vec(&profile_data)
+        .map_err(|e| Error::Anyhow(format!("Failed to serialize profile: {}", e)))?;
+
+    let created_ms = now_ms();
+    let store = app.state::<ArtifactStore>();
+    let id = store.allocate_id("cpu-profile", created_ms);
+    let file = store.write_file(&id, &format!("{}.cpuprofile", window_label), &bytes)?;
+    let info = ArtifactInfo {
+        id,
+        kind: "cpu-profile".to_string(),
+        reason: "stop_cpu_profile".to_string(),
+        window_label: Some(window_label),
+        created_ms,
+        files: vec![file],
+    };
+    store.register(info.clone());
+    if let Some(hub) = app.try_state::<NotificationHub>() {
+        hub.publish("artifacts", serde_json::to_value(&info).unwrap_or(Value::Null));
+    }
+
+    let response = CpuProfileResponse {
+        artifact: info,
+        duration_ms: created_ms.saturating_sub(profile.started_ms),
+        sample_count,
+        node_count,
+        target_url: profile.target_url,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
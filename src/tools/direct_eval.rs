@@ -1,82 +1,1251 @@
+use futures::future::join_all;
+use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 use tauri::{AppHandle, Manager, Runtime};
 
-#[derive(Debug, Deserialize)]
+use crate::mcp_tool;
+use crate::tools::content_blocks::{ContentBlock, PageImage, eval_content};
+use crate::tools::js_bridge::{eval_with_result, eval_with_strategy};
+use crate::tools::webview_backend::{self, ExecutionStrategy};
+
+/// Extra time allowed for the page to report back after its own watchdog fires
+const REPORT_GRACE: Duration = Duration::from_secs(5);
+
+/// Default limit on a result's JSON text, in characters
+pub const DEFAULT_MAX_RESULT_SIZE: usize = 1 << 20;
+
+/// Truncated results each page keeps for `fetch_result_chunk`, oldest dropped first
+const STORED_RESULTS: usize = 8;
+
+/// Longest JSON text an evaluation sends back in one response; see `PluginConfig::max_eval_result_size`
+#[derive(Debug, Clone, Copy)]
+pub struct ResultSizeLimit(pub usize);
+
+/// Evaluations remembered for `request_id` resubmissions, least recently used dropped first
+const IDEMPOTENT_RESULTS: usize = 256;
+
+struct IdempotentEntry {
+    request_id: String,
+    /// The code first submitted under the id
+    code: String,
+    response: Arc<tokio::sync::OnceCell<DirectEvalResponse>>,
+}
+
+/// Responses of evaluations submitted with a `request_id`, managed in plugin state
+#[derive(Default)]
+pub struct IdempotentResults {
+    entries: Mutex<VecDeque<IdempotentEntry>>,
+}
+
+impl IdempotentResults {
+    /// The response slot for `request_id`, shared by every submission of it
+    fn slot(
+        &self,
+        request_id: &str,
+        code: &str,
+    ) -> crate::Result<Arc<tokio::sync::OnceCell<DirectEvalResponse>>> {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(at) = entries
+            .iter()
+            .position(|entry| entry.request_id == request_id)
+        {
+            let entry = entries.remove(at).expect("position is in range");
+            let response = entry.response.clone();
+            let same_code = entry.code == code;
+            entries.push_back(entry);
+            if !same_code {
+                return Err(crate::Error::Anyhow(format!(
+                    "request_id '{}' was already used for different code",
+                    request_id
+                )));
+            }
+            return Ok(response);
+        }
+        if entries.len() >= IDEMPOTENT_RESULTS {
+            entries.pop_front();
+        }
+        let response = Arc::new(tokio::sync::OnceCell::new());
+        entries.push_back(IdempotentEntry {
+            request_id: request_id.to_string(),
+            code: code.to_string(),
+            response: response.clone(),
+        });
+        Ok(response)
+    }
+}
+
+static NEXT_EXECUTION_ID: AtomicU64 = AtomicU64::new(1);
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_execution_id() -> String {
+    format!("eval-{}", NEXT_EXECUTION_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct DirectEvalRequest {
+    /// JavaScript statements to run; `return` a value to get it back
     pub code: String,
     pub window_label: Option<String>,
+    /// Give up on the code after this long (default 5000); see `isolate`
+    pub timeout_ms: Option<u64>,
+    /// Id for `cancel_eval`. Defaults to `request_id`, or else a generated one, reported in the
+    /// response.
+    pub execution_id: Option<String>,
+    /// Idempotency key. Resubmitting the same id, e.g. after a reconnect, returns the first
+    /// submission's response with `cached: true` instead of running the code again; a submission
+    /// still running is waited for. The last 256 ids are remembered.
+    pub request_id: Option<String>,
+    /// Run in a Web Worker, which is terminated on timeout or cancellation. The code has no DOM
+    /// access there and is run as an async function. Without this, code blocking the page's main
+    /// thread (e.g. an endless loop) can't be stopped; the call only stops waiting for it.
+    pub isolate: Option<bool>,
+    /// Globals the code runs against (default `page`). `isolated` runs it in a hidden same-origin
+    /// frame with its own globals: variables the code sets on `window` stay there between such
+    /// evaluations instead of landing on the page, and the page's patches to built-ins don't
+    /// apply. `document` is still the page's document and the page's window is available as
+    /// `page`. Unlike `isolate`, the code still runs on the page's main thread.
+    pub realm: Option<Realm>,
+    /// `typescript` strips type annotations and other TypeScript syntax before running the code;
+    /// syntax errors are reported in `error` without running anything. Types aren't checked.
+    /// Needs the plugin's `ts` feature.
+    pub language: Option<Language>,
+    /// Also return the result as MCP tool result content in `content` (default false). Returned
+    /// canvases, images, `ImageBitmap`s, `ImageData` and image Blobs become image blocks.
+    pub content_blocks: Option<bool>,
+    /// `module` runs the code as an ES module so `import` statements, top-level `await` and
+    /// dynamic `import()` work. The module's default export is returned instead of a `return`
+    /// value. Relative specifiers in static imports resolve against the page URL. The module is
+    /// loaded from a `blob:` URL, so the page's Content-Security-Policy must allow those.
+    #[serde(rename = "type")]
+    pub script_type: Option<ScriptType>,
+    /// Map stack frames in the app's own scripts through the source maps they publish (default
+    /// false). Frames in `code` itself are always reported relative to it.
+    pub source_maps: Option<bool>,
+    #[serde(flatten)]
+    pub frame: FrameTarget,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptType {
+    Classic,
+    Module,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Realm {
+    /// The page's own `window`
+    Page,
+    /// A hidden same-origin frame the helpers add to the page
+    Isolated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Javascript,
+    Typescript,
+}
+
+/// The JavaScript to run for code written in `language`, or the compile error to report
+pub(crate) fn source_for(
+    code: String,
+    language: Option<Language>,
+    module: bool,
+) -> Result<String, String> {
+    match language {
+        None | Some(Language::Javascript) => Ok(code),
+        #[cfg(feature = "ts")]
+        Some(Language::Typescript) => crate::tools::typescript::transpile(&code, module)
+            .map_err(|e| format!("TypeScript compile error at {}", e)),
+        #[cfg(not(feature = "ts"))]
+        Some(Language::Typescript) => {
+            let _ = module;
+            Err("TypeScript evals need the plugin's `ts` feature".to_string())
+        }
+    }
+}
+
+/// Run in a same-origin child frame instead of the top document; see the `list_frames` tool
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct FrameTarget {
+    /// CSS selector of the `<iframe>`, matched in the top document first, then in nested frames
+    pub frame_selector: Option<String>,
+    /// URL of the frame's document, matched exactly or as a prefix
+    pub frame_url: Option<String>,
+}
+
+impl FrameTarget {
+    /// Expression for the frame's window, or `None` for the top document
+    fn window_expr(&self) -> Option<String> {
+        if self.frame_selector.is_none() && self.frame_url.is_none() {
+            return None;
+        }
+        let target = serde_json::json!({
+            "frame_selector": self.frame_selector,
+            "frame_url": self.frame_url,
+        });
+        Some(format!("window.__mcp.frameWindow({})", target))
+    }
+}
+
+/// Quote `code` as a JavaScript string for the frame's own `Function`/`eval`
+fn quoted(code: &str) -> String {
+    serde_json::to_string(code).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectEvalResponse {
     pub success: bool,
-    pub result: Option<String>,
+    /// The returned value as JSON. Values JSON can't represent (functions, DOM nodes, cycles)
+    /// come back as their string form.
+    pub result: Option<Value>,
+    /// `typeof` the returned value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+    pub execution_id: String,
+    /// The value's JSON text was longer than the result size limit. `result` then holds the
+    /// start of that text as a string; page through the rest with `fetch_result_chunk`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Length of the full JSON text in characters, when truncated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_size: Option<u64>,
+    /// How the code reached the page: webview eval, or the event bridge fallback for pages whose
+    /// CSP drops it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<ExecutionStrategy>,
+    /// The outcome as MCP tool result content, when `content_blocks` was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Vec<ContentBlock>>,
+    /// This is the response of an earlier submission with the same `request_id`; the code
+    /// didn't run again
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+}
+
+impl DirectEvalResponse {
+    /// Unsuccessful response with nothing filled in, to complete with struct update syntax
+    fn new(execution_id: String) -> Self {
+        Self {
+            success: false,
+            result: None,
+            value_type: None,
+            error: None,
+            stack: None,
+            execution_id,
+            truncated: false,
+            original_size: None,
+            strategy: None,
+            content: None,
+            cached: false,
+        }
+    }
+
+    /// Response for code that never ran because it didn't compile
+    fn compile_error(execution_id: Option<String>, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(execution_id.unwrap_or_else(next_execution_id))
+        }
+    }
 }
 
+#[mcp_tool(
+    name = "direct_eval",
+    description = "Run JavaScript in a window and return the value it returns",
+    capability = Eval
+)]
 pub async fn handle_direct_eval<R: Runtime>(
     app: &AppHandle<R>,
-    payload: serde_json::Value,
-) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
-    let request: DirectEvalRequest = serde_json::from_value(payload)?;
+    request: DirectEvalRequest,
+) -> crate::Result<DirectEvalResponse> {
+    let module = request.script_type == Some(ScriptType::Module);
+    let code = match source_for(request.code, request.language, module) {
+        Ok(code) => code,
+        Err(error) => {
+            return Ok(DirectEvalResponse::compile_error(
+                request.execution_id,
+                error,
+            ));
+        }
+    };
+    let evaluation = Evaluation {
+        execution_id: request.execution_id.or_else(|| request.request_id.clone()),
+        isolate: request.isolate.unwrap_or(false),
+        isolated: request.realm == Some(Realm::Isolated),
+        content_blocks: request.content_blocks.unwrap_or(false),
+        frame: request.frame,
+        module,
+        source_maps: request.source_maps.unwrap_or(false),
+        ..Evaluation::new(
+            request.window_label.unwrap_or_else(|| "main".to_string()),
+            code,
+            Duration::from_millis(request.timeout_ms.unwrap_or(5000)),
+        )
+    };
+    evaluate_once(app, request.request_id, evaluation).await
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EvalAsyncRequest {
+    /// Body of an async function: `await` freely and `return` the value to get back
+    pub code: String,
+    pub window_label: Option<String>,
+    /// How long to wait for the returned promise to settle (default 30000)
+    pub timeout_ms: Option<u64>,
+    /// Id for `cancel_eval`. Defaults to `request_id`, or else a generated one, reported in the
+    /// response.
+    pub execution_id: Option<String>,
+    /// Idempotency key; see `direct_eval`
+    pub request_id: Option<String>,
+    /// Run in a Web Worker, which is terminated on timeout or cancellation; see `direct_eval`
+    pub isolate: Option<bool>,
+    /// `isolated` runs in a hidden frame with its own globals; see `direct_eval`
+    pub realm: Option<Realm>,
+    /// `typescript` transpiles the code first; see `direct_eval`
+    pub language: Option<Language>,
+    /// Also return the result as MCP content blocks; see `direct_eval`
+    pub content_blocks: Option<bool>,
+    /// Map stack frames in the app's own scripts through their source maps; see `direct_eval`
+    pub source_maps: Option<bool>,
+    #[serde(flatten)]
+    pub frame: FrameTarget,
+}
+
+#[mcp_tool(
+    name = "eval_async",
+    description = "Run JavaScript as an async function in a window and return the value its promise resolves to",
+    capability = Eval
+)]
+pub async fn handle_eval_async<R: Runtime>(
+    app: &AppHandle<R>,
+    request: EvalAsyncRequest,
+) -> crate::Result<DirectEvalResponse> {
+    let code = match source_for(request.code, request.language, false) {
+        Ok(code) => code,
+        Err(error) => {
+            return Ok(DirectEvalResponse::compile_error(
+                request.execution_id,
+                error,
+            ));
+        }
+    };
+    let evaluation = Evaluation {
+        awaited: true,
+        execution_id: request.execution_id.or_else(|| request.request_id.clone()),
+        isolate: request.isolate.unwrap_or(false),
+        isolated: request.realm == Some(Realm::Isolated),
+        content_blocks: request.content_blocks.unwrap_or(false),
+        frame: request.frame,
+        source_maps: request.source_maps.unwrap_or(false),
+        ..Evaluation::new(
+            request.window_label.unwrap_or_else(|| "main".to_string()),
+            code,
+            Duration::from_millis(request.timeout_ms.unwrap_or(30000)),
+        )
+    };
+    evaluate_once(app, request.request_id, evaluation).await
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EvalExpressionRequest {
+    /// A single JavaScript expression, e.g. `store.getState().cart`. Statements are rejected.
+    pub expression: String,
+    pub window_label: Option<String>,
+    /// How long to wait for the value, including a returned promise settling (default 5000)
+    pub timeout_ms: Option<u64>,
+    /// Nesting deeper than this is reported as `{ "$type": "Truncated" }` (default 20)
+    pub max_depth: Option<u32>,
+    /// Array, set and map entries and object keys kept per collection (default 1000)
+    pub max_items: Option<u32>,
+    #[serde(flatten)]
+    pub frame: FrameTarget,
+}
+
+/// The expression is compiled inside both `( )` and `[ ]` before it runs. Text that closes the
+/// wrapper early to smuggle in statements can only balance one of the two, so it never compiles.
+/// Calls in the expression can still have side effects.
+#[mcp_tool(
+    name = "eval_expression",
+    description = "Evaluate a single JavaScript expression in a window and return its value, with Map, Set, Date, BigInt and circular references preserved",
+    capability = Eval
+)]
+pub async fn handle_eval_expression<R: Runtime>(
+    app: &AppHandle<R>,
+    request: EvalExpressionRequest,
+) -> crate::Result<DirectEvalResponse> {
+    let options = serde_json::json!({
+        "maxDepth": request.max_depth.unwrap_or(20),
+        "maxItems": request.max_items.unwrap_or(1000),
+    });
+    let code = format!(
+        r#"
+        const __Function = {function};
+        const __source = {source};
+        try {{
+            __Function('return (\n' + __source + '\n);');
+            __Function('return [\n' + __source + '\n];');
+        }} catch (e) {{
+            throw new SyntaxError('Expected a single expression: ' + e.message);
+        }}
+        const __value = await __Function('return (\n' + __source + '\n);')();
+        return {{ type: typeof __value, value: window.__mcp.serialize(__value, {options}) }};
+        "#,
+        function = request
+            .frame
+            .window_expr()
+            .map(|frame| format!("{}.Function", frame))
+            .unwrap_or_else(|| "Function".to_string()),
+        source = quoted(&request.expression),
+        options = options
+    );
+    let evaluation = Evaluation {
+        awaited: true,
+        ..Evaluation::new(
+            request.window_label.unwrap_or_else(|| "main".to_string()),
+            code,
+            Duration::from_millis(request.timeout_ms.unwrap_or(5000)),
+        )
+    };
+
+    // Unwrap the `{ type, value }` pair so the response reads like direct_eval's
+    let mut response = evaluate(app, evaluation).await?;
+    if let Some(Value::Object(mut outcome)) = response.result.take() {
+        response.value_type = outcome
+            .remove("type")
+            .and_then(|v| v.as_str().map(str::to_string));
+        response.result = outcome.remove("value");
+    }
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EvalAllWindowsRequest {
+    /// JavaScript statements to run in each window; `return` a value to get it back
+    pub code: String,
+    /// Window labels to run in, with `*` and `?` wildcards (default `*`, every webview window)
+    pub window_pattern: Option<String>,
+    /// Give up on each window after this long (default 5000)
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EvalAllWindowsResponse {
+    /// Outcome per window label, as `direct_eval` would report it
+    pub results: BTreeMap<String, DirectEvalResponse>,
+}
+
+/// Whether `label` matches a glob where `*` is any run of characters and `?` any one character
+fn glob_matches(pattern: &str, label: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let label: Vec<char> = label.chars().collect();
+    let (mut p, mut l) = (0, 0);
+    // Last `*` seen and the label position it currently stands in for
+    let mut star = None;
+    while l < label.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == label[l]) {
+            p += 1;
+            l += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, l));
+            p += 1;
+        } else if let Some((star_p, star_l)) = star {
+            p = star_p + 1;
+            l = star_l + 1;
+            star = Some((star_p, star_l + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Windows run concurrently, so a slow window only holds up its own result
+#[mcp_tool(
+    name = "eval_all_windows",
+    description = "Run JavaScript in every window whose label matches a glob and return each window's result",
+    capability = Eval
+)]
+pub async fn handle_eval_all_windows<R: Runtime>(
+    app: &AppHandle<R>,
+    request: EvalAllWindowsRequest,
+) -> crate::Result<EvalAllWindowsResponse> {
+    let pattern = request.window_pattern.as_deref().unwrap_or("*");
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let mut labels: Vec<String> = webview_backend::backend(app)?
+        .window_labels()
+        .into_iter()
+        .filter(|label| glob_matches(pattern, label))
+        .collect();
+    labels.sort();
+
+    let runs = labels.into_iter().map(|window_label| {
+        let execution_id = next_execution_id();
+        let evaluation = Evaluation {
+            execution_id: Some(execution_id.clone()),
+            ..Evaluation::new(window_label.clone(), request.code.clone(), timeout)
+        };
+        async move {
+            let response = evaluate(app, evaluation)
+                .await
+                .unwrap_or_else(|e| DirectEvalResponse {
+                    error: Some(e.to_string()),
+                    ..DirectEvalResponse::new(execution_id)
+                });
+            (window_label, response)
+        }
+    });
+
+    Ok(EvalAllWindowsResponse {
+        results: join_all(runs).await.into_iter().collect(),
+    })
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancelEvalRequest {
+    /// Id reported by, or given to, `direct_eval`/`eval_async`
+    pub execution_id: String,
+    pub window_label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CancelEvalResponse {
+    pub execution_id: String,
+    /// False when the evaluation had already finished or never existed
+    pub cancelled: bool,
+}
+
+/// Cancelling reaches the page only while its main thread is free, so it stops worker-isolated
+/// and awaiting evaluations; the cancelled call then returns an error. Send it from another
+/// connection, since each connection handles one request at a time.
+#[mcp_tool(
+    name = "cancel_eval",
+    description = "Stop a running direct_eval or eval_async by execution id",
+    capability = Eval
+)]
+pub async fn handle_cancel_eval<R: Runtime>(
+    app: &AppHandle<R>,
+    request: CancelEvalRequest,
+) -> crate::Result<CancelEvalResponse> {
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let code = format!(
+        r#"
+        const realm = window.__mcp.isolatedRealm(false);
+        const entry = (window.__mcpEvals || {{}})[{id}] || ((realm && realm.__mcpEvals) || {{}})[{id}];
+        if (!entry) return false;
+        entry.cancel();
+        return true;
+        "#,
+        id = quoted(&request.execution_id)
+    );
+    let cancelled = eval_with_result(app, &window_label, &code, Duration::from_secs(5))
+        .await?
+        .as_bool()
+        .unwrap_or(false);
+    Ok(CancelEvalResponse {
+        execution_id: request.execution_id,
+        cancelled,
+    })
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FetchResultChunkRequest {
+    /// Id of the evaluation whose response was truncated
+    pub execution_id: String,
+    pub window_label: Option<String>,
+    /// Character offset into the JSON text (default 0)
+    pub offset: Option<u64>,
+    /// Characters to return, at most the result size limit (default the limit)
+    pub length: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchResultChunkResponse {
+    pub execution_id: String,
+    pub offset: u64,
+    /// This part of the JSON text
+    pub data: String,
+    pub total_size: u64,
+    /// Whether this chunk reaches the end of the text
+    pub done: bool,
+}
+
+/// The page keeps the full text of its last few truncated results until their final chunk is
+/// fetched or it reloads. Concatenate the chunks and parse them as JSON to rebuild the value.
+#[mcp_tool(
+    name = "fetch_result_chunk",
+    description = "Read part of an eval result that was too large to return whole",
+    capability = Eval
+)]
+pub async fn handle_fetch_result_chunk<R: Runtime>(
+    app: &AppHandle<R>,
+    request: FetchResultChunkRequest,
+) -> crate::Result<FetchResultChunkResponse> {
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let limit = max_result_size(app) as u64;
+    let offset = request.offset.unwrap_or(0);
+    let length = request.length.unwrap_or(limit).clamp(1, limit);
+    let code = format!(
+        r#"
+        const realm = window.__mcp.isolatedRealm(false);
+        const stored = [window.__mcpStoredResults, realm && realm.__mcpStoredResults]
+            .find(results => results && results.has({id}));
+        const json = stored && stored.get({id});
+        if (json === undefined) return null;
+        const data = json.slice({offset}, {end});
+        const done = {end} >= json.length;
+        if (done) stored.delete({id});
+        return {{ data: data, total_size: json.length, done: done }};
+        "#,
+        id = quoted(&request.execution_id),
+        offset = offset,
+        end = offset + length
+    );
+    let chunk = eval_with_result(app, &window_label, &code, Duration::from_secs(10)).await?;
+    if chunk.is_null() {
+        return Err(crate::Error::Anyhow(format!(
+            "No stored result for '{}'; it was fully fetched, evicted or the page reloaded",
+            request.execution_id
+        )));
+    }
+    Ok(FetchResultChunkResponse {
+        execution_id: request.execution_id,
+        offset,
+        data: chunk
+            .get("data")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        total_size: chunk
+            .get("total_size")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        done: chunk.get("done").and_then(|v| v.as_bool()).unwrap_or(true),
+    })
+}
+
+/// A REPL-like scope in one page: top-level `let`, `const`, `var`, `function` and `class`
+/// bindings made by one `eval_in_session` call are visible to the next. Sessions live in the
+/// page and end when it reloads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalSession {
+    pub session_id: String,
+    pub window_label: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EvalSessionCreateRequest {
+    pub window_label: Option<String>,
+}
+
+#[mcp_tool(
+    name = "eval_session_create",
+    description = "Start an eval session whose bindings persist between eval_in_session calls",
+    capability = Eval
+)]
+pub async fn handle_eval_session_create<R: Runtime>(
+    app: &AppHandle<R>,
+    request: EvalSessionCreateRequest,
+) -> crate::Result<EvalSession> {
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let session_id = format!(
+        "session-{}",
+        NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let code = format!(
+        r#"
+        window.__mcpEvalSessions = window.__mcpEvalSessions || {{}};
+        window.__mcpEvalSessions[{}] = {{ scope: Object.create(null) }};
+        return true;
+        "#,
+        quoted(&session_id)
+    );
+    eval_with_result(app, &window_label, &code, Duration::from_secs(5)).await?;
+    Ok(EvalSession {
+        session_id,
+        window_label,
+    })
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EvalInSessionRequest {
+    /// Returned by `eval_session_create`
+    pub session_id: String,
+    /// Body of an async function. Top-level declarations are kept in the session; plain
+    /// destructuring patterns are understood, declarations with several declarators only keep the first.
+    pub code: String,
+    pub window_label: Option<String>,
+    /// How long to wait for the code to finish (default 30000)
+    pub timeout_ms: Option<u64>,
+    /// Id for `cancel_eval`. Defaults to a generated one, reported in the response.
+    pub execution_id: Option<String>,
+}
+
+#[mcp_tool(
+    name = "eval_in_session",
+    description = "Run JavaScript in an eval session, sharing bindings with earlier calls",
+    capability = Eval
+)]
+pub async fn handle_eval_in_session<R: Runtime>(
+    app: &AppHandle<R>,
+    request: EvalInSessionRequest,
+) -> crate::Result<DirectEvalResponse> {
+    let evaluation = Evaluation {
+        awaited: true,
+        execution_id: request.execution_id,
+        session_id: Some(request.session_id),
+        ..Evaluation::new(
+            request.window_label.unwrap_or_else(|| "main".to_string()),
+            request.code,
+            Duration::from_millis(request.timeout_ms.unwrap_or(30000)),
+        )
+    };
+    evaluate(app, evaluation).await
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EvalSessionDestroyRequest {
+    pub session_id: String,
+    pub window_label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EvalSessionDestroyResponse {
+    pub session_id: String,
+    /// False when the page no longer had the session, e.g. after a reload
+    pub destroyed: bool,
+}
+
+#[mcp_tool(
+    name = "eval_session_destroy",
+    description = "End an eval session and release its bindings",
+    capability = Eval
+)]
+pub async fn handle_eval_session_destroy<R: Runtime>(
+    app: &AppHandle<R>,
+    request: EvalSessionDestroyRequest,
+) -> crate::Result<EvalSessionDestroyResponse> {
     let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
-    
-    // Get the window
-    let window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| format!("Window '{}' not found", window_label))?;
-    
-    // Wrap the code to capture return value via a global variable
+    let code = format!(
+        r#"
+        const sessions = window.__mcpEvalSessions || {{}};
+        const existed = {id} in sessions;
+        delete sessions[{id}];
+        return existed;
+        "#,
+        id = quoted(&request.session_id)
+    );
+    let destroyed = eval_with_result(app, &window_label, &code, Duration::from_secs(5))
+        .await?
+        .as_bool()
+        .unwrap_or(false);
+    Ok(EvalSessionDestroyResponse {
+        session_id: request.session_id,
+        destroyed,
+    })
+}
+
+/// Names declared by `code`, found textually. Declarations in nested scopes are included too;
+/// copying those out simply fails or finds nothing at the end of the call.
+fn declared_names(code: &str) -> Vec<String> {
+    static SIMPLE: OnceLock<Regex> = OnceLock::new();
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let simple = SIMPLE.get_or_init(|| {
+        Regex::new(r"\b(?:let|const|var|function\*?|class)\s+([A-Za-z_$][\w$]*)").unwrap()
+    });
+    let pattern = PATTERN
+        .get_or_init(|| Regex::new(r"\b(?:let|const|var)\s*[\{\[]([^=;]*?)[\}\]]\s*=").unwrap());
+
+    let mut names: Vec<String> = simple
+        .captures_iter(code)
+        .map(|c| c[1].to_string())
+        .collect();
+    for captures in pattern.captures_iter(code) {
+        for part in captures[1].split(',') {
+            // `a`, `a = 1`, `key: a`, `...rest`
+            let name = part
+                .rsplit(':')
+                .next()
+                .unwrap_or("")
+                .split('=')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .trim_start_matches("...");
+            let valid = name.chars().enumerate().all(|(i, c)| {
+                c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+            });
+            if !name.is_empty() && valid {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Async call running `code` against a session scope. The scope object is the nearest `with`
+/// scope, so earlier bindings resolve to it; when the call completes, this call's declarations
+/// are copied into it by a closure living in the same block as them.
+fn session_call(session_id: &str, code: &str, mark: &str) -> String {
+    let saves: String = declared_names(code)
+        .iter()
+        .map(|name| {
+            format!(
+                "try {{ __mcpScope[{:?}] = {}; }} catch (e) {{}}\n",
+                name, name
+            )
+        })
+        .collect();
+    format!(
+        r#"(async function() {{
+            const __mcpSession = (window.__mcpEvalSessions || {{}})[{id}];
+            if (!__mcpSession) throw new Error('Eval session ' + {id} + ' does not exist in this page; it may have been reloaded');
+            const __mcpScope = __mcpSession.scope;
+            let __mcpSessionSave = function() {{}};
+            with (__mcpScope) {{
+                try {{
+                    __mcpSessionSave = function() {{
+                        {saves}
+                    }};
+                    {mark}{code}
+                }} finally {{
+                    __mcpSessionSave();
+                }}
+            }}
+        }})()"#,
+        id = quoted(session_id),
+        saves = saves,
+        mark = mark,
+        code = code
+    )
+}
+
+struct Evaluation {
+    window_label: String,
+    code: String,
+    /// Await a returned promise instead of reporting the promise itself
+    awaited: bool,
+    timeout: Duration,
+    execution_id: Option<String>,
+    isolate: bool,
+    /// Run in the helpers' isolated realm instead of the page's own globals
+    isolated: bool,
+    /// Render a returned image for MCP content blocks
+    content_blocks: bool,
+    frame: FrameTarget,
+    /// Eval session whose scope the code runs in
+    session_id: Option<String>,
+    /// Load the code as an ES module and report its default export
+    module: bool,
+    /// Map stack frames outside the code through the app's source maps
+    source_maps: bool,
+}
+
+impl Evaluation {
+    /// Plain evaluation of `code`: not awaited, in the page's top frame, with every option off.
+    /// Callers set the rest with struct update syntax.
+    fn new(window_label: String, code: String, timeout: Duration) -> Self {
+        Self {
+            window_label,
+            code,
+            awaited: false,
+            timeout,
+            execution_id: None,
+            isolate: false,
+            isolated: false,
+            content_blocks: false,
+            frame: FrameTarget::default(),
+            session_id: None,
+            module: false,
+            source_maps: false,
+        }
+    }
+
+    /// Script starting the code and calling `finish` with its outcome, or `fail` with an error.
+    /// The code is preceded by [`mark_line`] so error stacks can be mapped back onto it.
+    fn run_script(&self, execution_id: &str) -> crate::Result<String> {
+        let mark = mark_line(execution_id, self.isolated);
+        if let Some(session_id) = &self.session_id {
+            return Ok(format!(
+                "Promise.resolve().then(function() {{ return {}; }}).then(function(result) {{ finish(__describe(result)); }}, fail);",
+                session_call(session_id, &self.code, &mark)
+            ));
+        }
+        let marked_code = format!("{}{}", mark, self.code);
+        if self.module {
+            if self.isolate || self.isolated || self.frame.window_expr().is_some() {
+                return Err(crate::Error::Anyhow(
+                    "`type: \"module\"` can't be combined with `isolate`, `realm: \"isolated\"`, `frame_selector` or `frame_url`".to_string(),
+                ));
+            }
+            // The module is imported from a blob URL, which has no base of its own, so relative
+            // specifiers are resolved against the page first. Loading it through import() rather
+            // than a <script type="module"> element surfaces syntax and evaluation errors here.
+            return Ok(format!(
+                r#"
+                const source = {}.replace(/(\bfrom\s*|\bimport\s*\(?\s*)(['"])(\.{{0,2}}\/[^'"]*)\2/g, function(match, prefix, quote, specifier) {{
+                    return prefix + quote + new URL(specifier, document.baseURI).href + quote;
+                }});
+                const url = URL.createObjectURL(new Blob([source], {{ type: 'text/javascript' }}));
+                import(url).then(function(module) {{
+                    URL.revokeObjectURL(url);
+                    const names = Object.keys(module);
+                    finish(__describe('default' in module ? module.default : (names.length ? Object.assign({{}}, module) : undefined)));
+                }}, function(e) {{
+                    URL.revokeObjectURL(url);
+                    fail(e);
+                }});
+                "#,
+                quoted(&marked_code)
+            ));
+        }
+        if self.isolated {
+            if self.isolate || self.frame.window_expr().is_some() {
+                return Err(crate::Error::Anyhow(
+                    "`realm: \"isolated\"` can't be combined with `isolate`, `frame_selector` or `frame_url`"
+                        .to_string(),
+                ));
+            }
+            // Compiled by the realm's own Function so its globals are the realm's
+            let call = if self.awaited {
+                format!(
+                    "__home.eval('(async function(document, page) {{\\n' + {} + '\\n}})')(window.document, window)",
+                    quoted(&marked_code)
+                )
+            } else {
+                format!(
+                    "__home.Function('document', 'page', {})(window.document, window)",
+                    quoted(&marked_code)
+                )
+            };
+            return Ok(settle(&call, self.awaited));
+        }
+        if self.isolate {
+            if self.frame.window_expr().is_some() {
+                return Err(crate::Error::Anyhow(
+                    "`isolate` can't be combined with `frame_selector` or `frame_url`".to_string(),
+                ));
+            }
+            return Ok(format!(
+                r#"
+                const source = 'const __maxResultSize = ' + __maxResultSize + ';\n' +
+                    'const __describe = ' + __describeValue.toString() + ';\n' +
+                    'const __failure = ' + __failure.toString() + ';\n' +
+                    'self.onmessage = async function() {{ try {{ postMessage(__describe(await (async function() {{\n' +
+                    {} + '\n}})())); }} catch (e) {{ postMessage(Object.assign(__failure(e), {{ markStack: self.__mcpMark }})); }} }};';
+                const url = URL.createObjectURL(new Blob([source], {{ type: 'text/javascript' }}));
+                const worker = new Worker(url);
+                stop = function() {{ worker.terminate(); URL.revokeObjectURL(url); }};
+                worker.onmessage = function(event) {{ stop(); finish(event.data); }};
+                worker.onerror = function(event) {{ event.preventDefault(); stop(); fail(new Error(event.message)); }};
+                worker.postMessage(null);
+                "#,
+                quoted(&format!(
+                    "self.__mcpMark = new Error().stack;\n{}",
+                    self.code
+                ))
+            ));
+        }
+
+        // Code for a frame is compiled by the frame's own Function so it sees that frame's globals
+        let call = match (self.frame.window_expr(), self.awaited) {
+            (Some(frame), false) => format!("{}.Function({})()", frame, quoted(&marked_code)),
+            (Some(frame), true) => format!(
+                "{}.eval('(async function() {{\\n' + {} + '\\n}})')()",
+                frame,
+                quoted(&marked_code)
+            ),
+            (None, false) => format!("(function() {{ {}\n}})()", marked_code),
+            (None, true) => format!("(async function() {{ {}\n}})()", marked_code),
+        };
+        Ok(settle(&call, self.awaited))
+    }
+}
+
+/// Script calling `finish` with the value of `call`, awaited or not. A synchronous result is
+/// boxed so a returned promise is reported rather than awaited.
+fn settle(call: &str, awaited: bool) -> String {
+    if awaited {
+        format!(
+            "Promise.resolve().then(function() {{ return {}; }}).then(function(result) {{ finish(__describe(result)); }}, fail);",
+            call
+        )
+    } else {
+        format!(
+            "Promise.resolve().then(function() {{ return [{}]; }}).then(function(boxed) {{ finish(__describe(boxed[0])); }}, fail);",
+            call
+        )
+    }
+}
+
+/// Statement recording, on its own line, the stack of an Error created just before the code.
+/// Code compiled by a frame's `Function` shares the top window's registry through `top`;
+/// isolated code keeps it in the realm's own `window`.
+fn mark_line(execution_id: &str, isolated: bool) -> String {
+    let registry = if isolated { "window" } else { "top" };
+    format!(
+        "({registry}.__mcpEvalMarks = {registry}.__mcpEvalMarks || {{}})[{}] = new Error().stack;\n",
+        quoted(execution_id),
+        registry = registry
+    )
+}
+
+fn max_result_size<R: Runtime>(app: &AppHandle<R>) -> usize {
+    app.try_state::<ResultSizeLimit>()
+        .map(|limit| limit.0)
+        .unwrap_or(DEFAULT_MAX_RESULT_SIZE)
+        // A zero limit would truncate every result to nothing and leave no room for chunks
+        .max(1)
+}
+
+/// Run an evaluation once per `request_id`. Resubmissions get the first run's response, marked
+/// `cached`; a failure to start the evaluation isn't remembered, so it can be retried.
+async fn evaluate_once<R: Runtime>(
+    app: &AppHandle<R>,
+    request_id: Option<String>,
+    evaluation: Evaluation,
+) -> crate::Result<DirectEvalResponse> {
+    let Some(request_id) = request_id else {
+        return evaluate(app, evaluation).await;
+    };
+    let slot = app
+        .state::<IdempotentResults>()
+        .slot(&request_id, &evaluation.code)?;
+    let mut ran = false;
+    let response = slot
+        .get_or_try_init(|| {
+            ran = true;
+            evaluate(app, evaluation)
+        })
+        .await?;
+    let mut response = response.clone();
+    response.cached = !ran;
+    Ok(response)
+}
+
+/// Run an evaluation under a page-side watchdog and report its value or the exception it threw.
+/// Rejected promises count as exceptions when the evaluation awaits them.
+async fn evaluate<R: Runtime>(
+    app: &AppHandle<R>,
+    evaluation: Evaluation,
+) -> crate::Result<DirectEvalResponse> {
+    let window_label = evaluation.window_label.as_str();
+    if !webview_backend::backend(app)?.has_window(window_label) {
+        return Err(crate::Error::Anyhow(format!(
+            "Window '{}' not found",
+            window_label
+        )));
+    }
+    let execution_id = evaluation
+        .execution_id
+        .clone()
+        .unwrap_or_else(next_execution_id);
+    let run = evaluation.run_script(&execution_id)?;
+
+    // Register the evaluation for cancel_eval and settle it on completion, timeout or
+    // cancellation. The outcome is also kept in window.__mcpLastResult for get_js_result. Isolated
+    // evaluations keep this bookkeeping in the realm and describe results with its built-ins.
     let wrapped_code = format!(
         r#"
-        (function() {{
+        const __home = {home};
+        const JSON = __home.JSON;
+        const String = __home.String;
+        const __id = {id};
+        const __timeoutMs = {timeout_ms};
+        const __maxResultSize = {max_result_size};
+        // Oversized JSON text is handed back whole as `json` instead of being parsed
+        const __describeValue = function(result) {{
+            let value = null;
+            let json;
+            if (result !== undefined) {{
+                try {{
+                    json = JSON.stringify(result);
+                    if (json === undefined) {{
+                        value = String(result);
+                    }} else if (json.length <= __maxResultSize) {{
+                        value = JSON.parse(json);
+                    }}
+                }} catch (e) {{
+                    value = String(result);
+                }}
+            }}
+            if (typeof json === 'string' && json.length > __maxResultSize) {{
+                return {{ success: true, value: null, json: json, type: typeof result, stringValue: json.slice(0, 200) }};
+            }}
+            return {{ success: true, value: value, type: typeof result, stringValue: String(result) }};
+        }};
+        // The value itself is kept for rendering returned images
+        let __raw;
+        const __describe = function(result) {{
+            __raw = result;
+            return __describeValue(result);
+        }};
+        const __failure = function(e) {{
+            return {{
+                success: false,
+                error: (e && e.message) ? e.message : String(e),
+                stack: (e && e.stack) ? e.stack : null
+            }};
+        }};
+        __home.__mcpEvals = __home.__mcpEvals || {{}};
+        const __outcome = await new Promise(function(resolve) {{
+            let done = false;
+            let stop = function() {{}};
+            const finish = function(outcome) {{
+                if (done) return;
+                done = true;
+                clearTimeout(watchdog);
+                delete __home.__mcpEvals[__id];
+                resolve(outcome);
+            }};
+            const fail = function(e) {{ finish(__failure(e)); }};
+            const watchdog = setTimeout(function() {{
+                stop();
+                fail(new Error('Evaluation timed out after ' + __timeoutMs + 'ms'));
+            }}, __timeoutMs);
+            __home.__mcpEvals[__id] = {{
+                cancel: function() {{
+                    stop();
+                    fail(new Error('Evaluation cancelled'));
+                }}
+            }};
             try {{
-                const __result = (function() {{ {} }})();
-                window.__mcpLastResult = {{
-                    success: true,
-                    value: __result,
-                    type: typeof __result,
-                    stringValue: String(__result)
-                }};
-                return __result;
+                {run}
+            }} catch (e) {{
+                fail(e);
+            }}
+        }});
+        const __marks = __home.__mcpEvalMarks || {{}};
+        const __markStack = __outcome.markStack || __marks[__id];
+        delete __marks[__id];
+        delete __outcome.markStack;
+        if (!__outcome.success && __outcome.stack) {{
+            try {{
+                const __mapped = await window.__mcp.mapStack(__outcome.stack, __markStack, {code_lines}, {source_maps});
+                __outcome.stack = __mapped.stack;
+                if (__mapped.location) __outcome.error += ' (at ' + __mapped.location + ')';
+            }} catch (e) {{}}
+        }}
+        if (typeof __outcome.json === 'string') {{
+            const stored = __home.__mcpStoredResults || (__home.__mcpStoredResults = new __home.Map());
+            stored.set(__id, __outcome.json);
+            while (stored.size > {stored_results}) stored.delete(stored.keys().next().value);
+            __outcome.truncated = true;
+            __outcome.original_size = __outcome.json.length;
+            __outcome.value = __outcome.json.slice(0, __maxResultSize);
+            delete __outcome.json;
+        }}
+        __home.__mcpLastResult = __outcome;
+        if ({content_blocks} && __outcome.success) {{
+            try {{
+                const image = await window.__mcp.imageData(__raw);
+                if (image) return Object.assign({{}}, __outcome, {{ image: image }});
             }} catch (e) {{
-                window.__mcpLastResult = {{
-                    success: false,
-                    error: e.message,
-                    stack: e.stack
-                }};
-                throw e;
+                return Object.assign({{}}, __outcome, {{ image_error: (e && e.message) ? e.message : String(e) }});
             }}
-        }})()
+        }}
+        return __outcome;
         "#,
-        request.code
+        home = if evaluation.isolated {
+            "window.__mcp.isolatedRealm()"
+        } else {
+            "window"
+        },
+        id = quoted(&execution_id),
+        timeout_ms = evaluation.timeout.as_millis(),
+        max_result_size = max_result_size(app),
+        stored_results = STORED_RESULTS,
+        code_lines = evaluation.code.lines().count().max(1),
+        source_maps = evaluation.source_maps,
+        content_blocks = evaluation.content_blocks,
+        run = run
     );
-    
-    // Execute the wrapped code
-    match window.eval(&wrapped_code) {
-        Ok(_) => {
-            // Now try to read back the result
-            let read_result_code = r#"
-                if (window.__mcpLastResult) {
-                    JSON.stringify(window.__mcpLastResult);
-                } else {
-                    JSON.stringify({ success: true, value: undefined });
+
+    let mut image = None;
+    let mut image_error = None;
+    let mut response = match eval_with_strategy(
+        app,
+        window_label,
+        &wrapped_code,
+        evaluation.timeout + REPORT_GRACE,
+    )
+    .await
+    {
+        Ok((outcome, strategy)) => {
+            let text = |key: &str| {
+                outcome
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            image = outcome
+                .get("image")
+                .and_then(|v| serde_json::from_value::<PageImage>(v.clone()).ok());
+            image_error = text("image_error");
+            if outcome
+                .get("success")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+            {
+                DirectEvalResponse {
+                    success: true,
+                    result: Some(outcome.get("value").cloned().unwrap_or(Value::Null)),
+                    value_type: text("type"),
+                    truncated: outcome
+                        .get("truncated")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                    original_size: outcome.get("original_size").and_then(|v| v.as_u64()),
+                    strategy,
+                    ..DirectEvalResponse::new(execution_id)
                 }
-            "#;
-            
-            // We can't get the result directly, but we've stored it
-            Ok(serde_json::json!(DirectEvalResponse {
-                success: true,
-                result: Some("Code executed successfully. Result stored in window.__mcpLastResult".to_string()),
-                error: None,
-            }))
-        }
-        Err(e) => {
-            Ok(serde_json::json!(DirectEvalResponse {
-                success: false,
-                result: None,
-                error: Some(format!("Eval error: {}", e)),
-            }))
+            } else {
+                DirectEvalResponse {
+                    error: text("error").or_else(|| Some("Unknown JavaScript error".to_string())),
+                    stack: text("stack"),
+                    strategy,
+                    ..DirectEvalResponse::new(execution_id)
+                }
+            }
         }
+        Err(e) => DirectEvalResponse {
+            error: Some(format!("Eval error: {}", e)),
+            ..DirectEvalResponse::new(execution_id)
+        },
+    };
+    if evaluation.content_blocks {
+        response.content = Some(eval_content(&response, image, image_error));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_matches;
+
+    #[test]
+    fn glob_matches_wildcards() {
+        assert!(glob_matches("*", "main"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("editor-*", "editor-1"));
+        assert!(glob_matches("editor-?", "editor-1"));
+        assert!(!glob_matches("editor-?", "editor-12"));
+        assert!(glob_matches("*-panel-*", "left-panel-2"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(!glob_matches("main", "main2"));
+        assert!(!glob_matches("*-panel", "panel"));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,428 @@
+//! Dry runs of mutating tools. A request with `"dry_run": true` is validated, the window and
+//! element it targets are resolved, and a report of what the tool would do comes back instead
+//! of the tool running.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use log::info;
+
+use crate::error::Error;
+use crate::models::{LocalStorageRequest, MouseMovementRequest, TextInputRequest, WindowManagerRequest};
+use crate::shared::commands;
+use crate::socket_server::SocketResponse;
+use crate::tools::context_menu::OpenContextMenuRequest;
+use crate::tools::direct_eval::{DirectEvalRequest, EvalAsyncRequest, Language, source_for};
+use crate::tools::elements::{
+    DispatchEventRequest, ElementTarget, EvalOnElementRequest, ForcePseudoStateRequest, PasteRequest,
+};
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::window_manager::{SetFullscreenRequest, SetKioskModeRequest, WindowStyleRequest};
+
+/// Window operations `manage_window` knows
+const WINDOW_OPERATIONS: &[&str] = &[
+    "minimize",
+    "maximize",
+    "unmaximize",
+    "close",
+    "show",
+    "hide",
+    "setPosition",
+    "setSize",
+    "center",
+    "toggleFullscreen",
+    "focus",
+];
+
+/// Time allowed for each look at the page
+const PAGE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a mutating tool would have done
+#[derive(Debug, Serialize)]
+pub struct DryRunReport {
+    pub dry_run: bool,
+    pub tool: String,
+    /// Window the tool would act on; `None` for OS-level input
+    pub window_label: Option<String>,
+    /// What the tool would do
+    pub action: String,
+    /// Element the tool would act on, as found now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<Value>,
+    /// State the tool would change, as it is now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Value>,
+    /// Reasons the real call would fail
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+}
+
+/// Whether a request asks for a dry run
+pub fn requested(payload: &Value) -> bool {
+    payload.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+fn parse<T: DeserializeOwned>(payload: &Value) -> crate::Result<T> {
+    serde_json::from_value(payload.clone())
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))
+}
+
+fn window_label_of(payload: &Value) -> String {
+    payload
+        .get("window_label")
+        .and_then(|v| v.as_str())
+        .unwrap_or("main")
+        .to_string()
+}
+
+/// Report what `command` would do with `payload`. The response fails when the real call would.
+/// Mutating tools without dry run support are refused rather than run.
+pub async fn handle_dry_run<R: Runtime>(
+    app: &AppHandle<R>,
+    command: &str,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    info!("[TAURI_MCP] Dry run of {}", command);
+
+    let window_label = window_label_of(&payload);
+    // Simulated mouse and keyboard input goes to the OS, not to a window
+    let os_input = matches!(
+        command,
+        commands::SIMULATE_MOUSE_MOVEMENT | commands::SIMULATE_TEXT_INPUT
+    );
+    let mut report = DryRunReport {
+        dry_run: true,
+        tool: command.to_string(),
+        window_label: (!os_input).then(|| window_label.clone()),
+        action: String::new(),
+        target: None,
+        current: None,
+        problems: Vec::new(),
+    };
+    let window = app.get_webview_window(&window_label);
+    if window.is_none() && !os_input {
+        report.problems.push(format!("Window '{}' not found", window_label));
+    }
+
+    match command {
+        "direct_eval" | "eval_async" | commands::EXECUTE_JS | "execute_with_console" => {
+            if command == "direct_eval" {
+                parse::<DirectEvalRequest>(&payload)?;
+            } else if command == "eval_async" {
+                parse::<EvalAsyncRequest>(&payload)?;
+            }
+            let code = payload
+                .get("code")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| Error::Anyhow("Invalid request format: missing field `code`".to_string()))?;
+            report.action = format!("run {} characters of JavaScript", code.chars().count());
+            let module = payload.get("type").and_then(|v| v.as_str()) == Some("module");
+            let language: Option<Language> = payload
+                .get("language")
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+            match source_for(code.to_string(), language, module) {
+                Ok(source) if window.is_some() && !module => {
+                    if let Some(error) = syntax_error(app, &window_label, &source).await? {
+                        report.problems.push(format!("SyntaxError: {}", error));
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => report.problems.push(error),
+            }
+        }
+        commands::EVAL_ON_ELEMENT => {
+            let request: EvalOnElementRequest = parse(&payload)?;
+            report.action = format!("call a function with {}", request.target);
+            let source = format!("return ({});", request.function);
+            if window.is_some() {
+                if let Some(error) = syntax_error(app, &window_label, &source).await? {
+                    report.problems.push(format!("SyntaxError: {}", error));
+                }
+            }
+            resolve_element(app, &mut report, &request.target).await?;
+        }
+        commands::DISPATCH_EVENT => {
+            let request: DispatchEventRequest = parse(&payload)?;
+            report.action = format!(
+                "dispatch a {} '{}' on {}",
+                request.event_class.as_deref().unwrap_or("Event"),
+                request.event_type,
+                request.target
+            );
+            resolve_element(app, &mut report, &request.target).await?;
+        }
+        commands::FORCE_PSEUDO_STATE => {
+            let request: ForcePseudoStateRequest = parse(&payload)?;
+            report.action = format!(
+                "{} :{} on {}",
+                if request.enabled.unwrap_or(true) { "force" } else { "clear" },
+                request.states.join(", :"),
+                request.target
+            );
+            resolve_element(app, &mut report, &request.target).await?;
+        }
+        commands::PASTE => {
+            let request: PasteRequest = parse(&payload)?;
+            let kinds: Vec<&str> = [
+                request.text.as_ref().map(|_| "text"),
+                request.html.as_ref().map(|_| "HTML"),
+                request.image.as_ref().map(|_| "an image"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            report.action = format!("focus {} and paste {}", request.target, kinds.join(" and "));
+            if kinds.is_empty() {
+                report.problems.push("Nothing to paste: set text, html or image".to_string());
+            }
+            resolve_element(app, &mut report, &request.target).await?;
+        }
+        commands::OPEN_CONTEXT_MENU => {
+            let request: OpenContextMenuRequest = parse(&payload)?;
+            report.action = format!("right-click {}", request.target);
+            resolve_element(app, &mut report, &request.target).await?;
+        }
+        commands::SEND_TEXT_TO_ELEMENT | commands::GET_ELEMENT_POSITION => {
+            let text = payload.get("text").and_then(|v| v.as_str());
+            report.action = match text {
+                Some(text) => format!("type {} characters into the element", text.chars().count()),
+                None => "click the element".to_string(),
+            };
+            if command == commands::SEND_TEXT_TO_ELEMENT && text.is_none() {
+                report.problems.push("Invalid request format: missing field `text`".to_string());
+            }
+            resolve_target(app, &mut report, &payload).await?;
+        }
+        commands::SIMULATE_MOUSE_MOVEMENT => {
+            let request: MouseMovementRequest = parse(&payload)?;
+            report.action = format!(
+                "move the pointer {} ({}, {}){}",
+                if request.relative.unwrap_or(false) { "by" } else { "to" },
+                request.x,
+                request.y,
+                if request.click.unwrap_or(false) {
+                    format!(" and {}-click", request.button.as_deref().unwrap_or("left"))
+                } else {
+                    String::new()
+                }
+            );
+            if let Some(button) = request.button.as_deref() {
+                if !["left", "right", "middle"].contains(&button) {
+                    report.problems.push(format!("Unknown mouse button: {}", button));
+                }
+            }
+        }
+        commands::SIMULATE_TEXT_INPUT => {
+            let request: TextInputRequest = parse(&payload)?;
+            report.action = format!(
+                "type {} characters into whatever has keyboard focus",
+                request.text.chars().count()
+            );
+        }
+        commands::MANAGE_LOCAL_STORAGE => {
+            let request: LocalStorageRequest = parse(&payload)?;
+            let key = request.key.as_deref();
+            report.action = match (request.action.as_str(), key) {
+                ("set", Some(key)) => format!("set localStorage '{}'", key),
+                ("remove", Some(key)) => format!("remove localStorage '{}'", key),
+                ("clear", _) => "remove every localStorage key".to_string(),
+                ("get", _) | ("keys", _) => "read localStorage without changing it".to_string(),
+                (action, _) => action.to_string(),
+            };
+            match request.action.as_str() {
+                "set" if key.is_none() || request.value.is_none() => report
+                    .problems
+                    .push("Both key and value are required for set operation".to_string()),
+                "remove" if key.is_none() => {
+                    report.problems.push("Key is required for remove operations".to_string())
+                }
+                "get" | "keys" | "set" | "remove" | "clear" => {}
+                action => report.problems.push(format!("Unsupported localStorage action: {}", action)),
+            }
+            if window.is_some() {
+                let code = format!(
+                    "const key = {}; return key === null ? {{ keys: localStorage.length }} : {{ key: key, value: localStorage.getItem(key) }};",
+                    serde_json::to_string(&key).unwrap_or_else(|_| "null".to_string())
+                );
+                match eval_with_result(app, &window_label, &code, PAGE_TIMEOUT).await {
+                    Ok(current) => report.current = Some(current),
+                    Err(e) => report.problems.push(format!("Failed to read localStorage: {}", e)),
+                }
+            }
+        }
+        commands::MANAGE_WINDOW => {
+            let request: WindowManagerRequest = parse(&payload)?;
+            report.action = format!("{} the window", request.operation);
+            if !WINDOW_OPERATIONS.contains(&request.operation.as_str()) {
+                report.problems.push(format!("Unknown window operation: {}", request.operation));
+            }
+            if request.operation == "setPosition" && (request.x.is_none() || request.y.is_none()) {
+                report.problems.push("setPosition requires x and y coordinates".to_string());
+            }
+            if request.operation == "setSize" && (request.width.is_none() || request.height.is_none()) {
+                report.problems.push("setSize requires width and height parameters".to_string());
+            }
+            report.current = window.as_ref().map(window_state);
+        }
+        commands::SET_WINDOW_STYLE => {
+            let request: WindowStyleRequest = parse(&payload)?;
+            report.action = "change the window's style flags".to_string();
+            if let Some(opacity) = request.opacity {
+                if !(0.0..=1.0).contains(&opacity) {
+                    report.problems.push(format!("`opacity` must be between 0 and 1, got {}", opacity));
+                }
+            }
+            report.current = window.as_ref().map(window_state);
+        }
+        commands::SET_FULLSCREEN => {
+            let request: SetFullscreenRequest = parse(&payload)?;
+            report.action = match request.fullscreen {
+                Some(true) => "enter fullscreen",
+                Some(false) => "leave fullscreen",
+                None => "toggle fullscreen",
+            }
+            .to_string();
+            report.current = window.as_ref().map(window_state);
+        }
+        commands::SET_KIOSK_MODE => {
+            let request: SetKioskModeRequest = parse(&payload)?;
+            report.action = match request.enabled {
+                Some(true) => "enter kiosk mode",
+                Some(false) => "leave kiosk mode",
+                None => "toggle kiosk mode",
+            }
+            .to_string();
+            report.current = window.as_ref().map(window_state);
+        }
+        _ => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("{} does not support dry_run; nothing was done", command)),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(SocketResponse {
+        success: report.problems.is_empty(),
+        error: (!report.problems.is_empty()).then(|| report.problems.join("; ")),
+        data: Some(serde_json::to_value(&report).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        ..Default::default()
+    })
+}
+
+/// Compile `source` as an async function body in the page without running it
+async fn syntax_error<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    source: &str,
+) -> crate::Result<Option<String>> {
+    let code = format!(
+        r#"
+        const AsyncFunction = Object.getPrototypeOf(async function() {{}}).constructor;
+        try {{
+            new AsyncFunction({});
+            return null;
+        }} catch (e) {{
+            // A CSP blocking eval says nothing about the code
+            return e instanceof SyntaxError ? e.message : null;
+        }}
+        "#,
+        serde_json::to_string(source).unwrap_or_else(|_| "\"\"".to_string())
+    );
+    let value = eval_with_result(app, window_label, &code, PAGE_TIMEOUT).await?;
+    Ok(value.as_str().map(str::to_string))
+}
+
+async fn resolve_element<R: Runtime>(
+    app: &AppHandle<R>,
+    report: &mut DryRunReport,
+    target: &ElementTarget,
+) -> crate::Result<()> {
+    if target.handle.is_none() && target.selector.is_none() {
+        report.problems.push("Either selector or handle is required".to_string());
+        return Ok(());
+    }
+    resolve_target(app, report, &serde_json::to_value(target).unwrap_or_default()).await
+}
+
+/// Find the element a request targets, by `selector`/`handle` or `selector_type`/`selector_value`,
+/// and describe it in the report
+async fn resolve_target<R: Runtime>(
+    app: &AppHandle<R>,
+    report: &mut DryRunReport,
+    target: &Value,
+) -> crate::Result<()> {
+    let Some(window_label) = report.window_label.clone() else {
+        return Ok(());
+    };
+    if app.get_webview_window(&window_label).is_none() {
+        return Ok(());
+    }
+    let target = json!({
+        "selector": target.get("selector"),
+        "handle": target.get("handle"),
+        "selector_type": target.get("selector_type"),
+        "selector_value": target.get("selector_value"),
+    });
+    let code = format!(
+        r#"
+        const target = {};
+        let element;
+        try {{
+            if (target.selector_type) {{
+                const value = target.selector_value;
+                switch (target.selector_type) {{
+                    case 'id': element = document.getElementById(value); break;
+                    case 'class': element = document.getElementsByClassName(value)[0]; break;
+                    case 'tag': element = document.getElementsByTagName(value)[0]; break;
+                    case 'handle': element = window.__mcp.handles.resolve(value); break;
+                    case 'text':
+                        element = Array.from(document.querySelectorAll('body *'))
+                            .find(el => el.children.length === 0 && (el.textContent || '').trim() === value);
+                        break;
+                    default: throw new Error('Unsupported selector type: ' + target.selector_type);
+                }}
+                if (!element) throw new Error('No element matches ' + target.selector_type + '="' + value + '"');
+            }} else {{
+                element = window.__mcp.find(target);
+            }}
+        }} catch (e) {{
+            return {{ error: e.message }};
+        }}
+        return {{
+            element: window.__mcp.describe(element),
+            text: window.__mcp.text(element, 80),
+            bounds: window.__mcp.bounds(element),
+            visible: window.__mcp.isVisible(element)
+        }};
+        "#,
+        target
+    );
+    let found = eval_with_result(app, &window_label, &code, PAGE_TIMEOUT).await?;
+    match found.get("error").and_then(|v| v.as_str()) {
+        Some(error) => report.problems.push(error.to_string()),
+        None => report.target = Some(found),
+    }
+    Ok(())
+}
+
+fn window_state<R: Runtime>(window: &WebviewWindow<R>) -> Value {
+    json!({
+        "visible": window.is_visible().ok(),
+        "focused": window.is_focused().ok(),
+        "minimized": window.is_minimized().ok(),
+        "maximized": window.is_maximized().ok(),
+        "fullscreen": window.is_fullscreen().ok(),
+        "position": window.outer_position().ok().map(|p| json!({ "x": p.x, "y": p.y })),
+        "size": window.outer_size().ok().map(|s| json!({ "width": s.width, "height": s.height })),
+    })
+}
@@ -0,0 +1,478 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::js_bridge::eval_with_result;
+
+/// Identifies an element by CSS selector or by a handle returned from `query_elements`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElementTarget {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handle: Option<String>,
+}
+
+impl std::fmt::Display for ElementTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.handle, &self.selector) {
+            (Some(handle), _) => write!(f, "handle {}", handle),
+            (None, Some(selector)) => write!(f, "'{}'", selector),
+            (None, None) => write!(f, "<no target>"),
+        }
+    }
+}
+
+/// JavaScript statement binding `element` to the target element, throwing if it can't be found
+pub fn find_element_code(target: &ElementTarget) -> crate::Result<String> {
+    if target.handle.is_none() && target.selector.is_none() {
+        return Err(crate::Error::Anyhow(
+            "Either selector or handle is required".to_string(),
+        ));
+    }
+    let target = serde_json::to_string(target)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid element target: {}", e)))?;
+    Ok(format!("const element = window.__mcp.find({});", target))
+}
+
+/// Pseudo-classes `force_pseudo_state` can emulate
+const FORCEABLE_STATES: &[&str] = &["hover", "focus", "focus-visible", "focus-within", "active"];
+
+#[derive(Debug, Deserialize)]
+pub struct ForcePseudoStateRequest {
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// Pseudo-classes to emulate, e.g. `["hover"]`
+    pub states: Vec<String>,
+    /// `false` clears previously forced states (default true)
+    pub enabled: Option<bool>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryElementsRequest {
+    pub window_label: Option<String>,
+    /// CSS selector; every match gets a handle
+    pub selector: String,
+    /// Maximum number of handles to return (default 50)
+    pub limit: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EvalOnElementRequest {
+    pub window_label: Option<String>,
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// JavaScript function source called with the element first, e.g. `"(el, attr) => el.getAttribute(attr)"`
+    pub function: String,
+    /// Extra JSON arguments passed after the element
+    #[serde(default)]
+    pub args: Vec<Value>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasteImage {
+    /// Base64-encoded image bytes (a `data:` URL prefix is accepted)
+    pub data: String,
+    /// e.g. `"image/png"` (default)
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasteRequest {
+    pub window_label: Option<String>,
+    /// Element receiving the paste; it is focused first
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    pub text: Option<String>,
+    pub html: Option<String>,
+    pub image: Option<PasteImage>,
+    /// Also write the contents to the system clipboard (default true; best effort)
+    pub set_clipboard: Option<bool>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DispatchEventRequest {
+    pub window_label: Option<String>,
+    /// Event target
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// Event type, e.g. `"input"` or `"my-app:refresh"`
+    pub event_type: String,
+    /// Event constructor, e.g. `"CustomEvent"`, `"PointerEvent"` (default `"Event"`)
+    pub event_class: Option<String>,
+    /// Init dictionary passed to the constructor; `bubbles`, `cancelable` and `composed` default to true
+    #[serde(default)]
+    pub init: Value,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Resolve a selector to element handles that later tools can target with `handle`
+pub async fn handle_query_elements<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: QueryElementsRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Querying elements '{}' in window: {}",
+        request.selector, window_label
+    );
+
+    let selector = serde_json::to_string(&request.selector)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid selector: {}", e)))?;
+    let code = format!(
+        r#"
+        const matches = Array.from(document.querySelectorAll({selector}));
+        return {{
+            total_count: matches.length,
+            elements: matches.slice(0, {limit}).map(element => ({{
+                handle: window.__mcp.handles.handleFor(element),
+                tag: element.tagName.toLowerCase(),
+                id: element.id || null,
+                text: window.__mcp.text(element, 200),
+                visible: window.__mcp.isVisible(element),
+                bounds: window.__mcp.bounds(element)
+            }}))
+        }};
+        "#,
+        selector = selector,
+        limit = request.limit.unwrap_or(50)
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Emulate `:hover`/`:focus`/`:active` by mirroring matching stylesheet rules onto generated classes
+pub async fn handle_force_pseudo_state<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ForcePseudoStateRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let enabled = request.enabled.unwrap_or(true);
+
+    if let Some(state) = request
+        .states
+        .iter()
+        .find(|s| !FORCEABLE_STATES.contains(&s.as_str()))
+    {
+        return Err(crate::Error::Anyhow(format!(
+            "Unsupported pseudo state '{}'. Supported: {}",
+            state,
+            FORCEABLE_STATES.join(", ")
+        )));
+    }
+
+    info!(
+        "[TAURI_MCP] {} pseudo states {:?} on {} in window: {}",
+        if enabled { "Forcing" } else { "Clearing" },
+        request.states,
+        request.target,
+        window_label
+    );
+
+    let code = format!(
+        r#"
+        {find_element}
+        const states = {states};
+        const enabled = {enabled};
+        const styleId = '__mcp-forced-pseudo-states';
+        const className = (state) => '__mcp-force-' + state;
+
+        // :hover and :active also apply to every ancestor of the real target
+        const targetsFor = (state) => {{
+            if (state !== 'hover' && state !== 'active' && state !== 'focus-within') return [element];
+            const targets = [];
+            for (let node = element; node; node = node.parentElement) targets.push(node);
+            return targets;
+        }};
+        for (const state of states) {{
+            for (const target of targetsFor(state)) {{
+                target.classList.toggle(className(state), enabled);
+            }}
+        }}
+
+        // Rebuild the mirrored rules for every state still forced somewhere in the document
+        const allStates = {all_states}.filter(state => document.querySelector('.' + className(state)));
+        let style = document.getElementById(styleId);
+        if (allStates.length === 0) {{
+            if (style) style.remove();
+            return {{ forced_states: [], mirrored_rules: 0 }};
+        }}
+
+        const pattern = new RegExp(':(' + allStates.sort((a, b) => b.length - a.length).join('|') + ')(?![\\w-])', 'g');
+        let mirrored = 0;
+        const mirror = (rules) => {{
+            let css = '';
+            for (const rule of Array.from(rules)) {{
+                if (rule.selectorText) {{
+                    pattern.lastIndex = 0;
+                    if (pattern.test(rule.selectorText)) {{
+                        css += rule.selectorText.replace(pattern, (_, state) => '.' + className(state)) + ' {{ ' + rule.style.cssText + ' }}\n';
+                        mirrored++;
+                    }}
+                }} else if (rule.cssRules) {{
+                    const inner = mirror(rule.cssRules);
+                    if (!inner) continue;
+                    if (rule.media) css += '@media ' + rule.media.mediaText + ' {{\n' + inner + '}}\n';
+                    else if (rule.conditionText) css += '@supports ' + rule.conditionText + ' {{\n' + inner + '}}\n';
+                    else css += inner;
+                }}
+            }}
+            return css;
+        }};
+
+        let css = '';
+        for (const sheet of Array.from(document.styleSheets)) {{
+            if (sheet.ownerNode && sheet.ownerNode.id === styleId) continue;
+            try {{
+                css += mirror(sheet.cssRules);
+            }} catch (e) {{
+                // Cross-origin stylesheets cannot be read
+            }}
+        }}
+
+        if (!style) {{
+            style = document.createElement('style');
+            style.id = styleId;
+            (document.head || document.documentElement).appendChild(style);
+        }}
+        style.textContent = css;
+
+        return {{ forced_states: allStates, mirrored_rules: mirrored }};
+        "#,
+        find_element = find_element_code(&request.target)?,
+        states = serde_json::to_string(&request.states).unwrap_or_default(),
+        all_states = serde_json::to_string(FORCEABLE_STATES).unwrap_or_default(),
+        enabled = enabled
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Run a function with a resolved element as its first argument and return the serialized result
+pub async fn handle_eval_on_element<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: EvalOnElementRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Evaluating function on {} in window: {}",
+        request.target, window_label
+    );
+
+    let code = format!(
+        r#"
+        {find_element}
+        const fn = ({function});
+        if (typeof fn !== 'function') throw new Error('function must evaluate to a JavaScript function');
+        const value = await fn(element, ...{args});
+        // DOM nodes are not serializable; describe them instead
+        if (value instanceof Node) {{
+            return {{
+                handle: window.__mcp.handles.handleFor(value),
+                node_name: value.nodeName.toLowerCase(),
+                id: value.id || null,
+                outer_html: value.outerHTML !== undefined ? value.outerHTML.slice(0, 500) : null,
+                text: (value.textContent || '').slice(0, 500)
+            }};
+        }}
+        return value;
+        "#,
+        find_element = find_element_code(&request.target)?,
+        function = request.function,
+        args = Value::Array(request.args)
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Dispatch a paste event carrying text, HTML and/or an image to an element
+pub async fn handle_paste<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: PasteRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    if request.text.is_none() && request.html.is_none() && request.image.is_none() {
+        return Err(crate::Error::Anyhow(
+            "paste requires at least one of text, html or image".to_string(),
+        ));
+    }
+
+    info!(
+        "[TAURI_MCP] Pasting into {} in window: {}",
+        request.target, window_label
+    );
+
+    let code = format!(
+        r#"
+        {find_element}
+        const text = {text};
+        const html = {html};
+        const image = {image};
+        const setClipboard = {set_clipboard};
+
+        let imageFile = null;
+        if (image) {{
+            const base64 = image.data.includes(',') ? image.data.split(',')[1] : image.data;
+            const bytes = Uint8Array.from(atob(base64), c => c.charCodeAt(0));
+            const type = image.mime_type || 'image/png';
+            imageFile = new File([bytes], 'pasted.' + type.split('/')[1], {{ type: type }});
+        }}
+
+        const data = new DataTransfer();
+        if (text !== null) data.setData('text/plain', text);
+        if (html !== null) data.setData('text/html', html);
+        if (imageFile) data.items.add(imageFile);
+
+        let clipboardSet = false;
+        if (setClipboard && navigator.clipboard && window.ClipboardItem) {{
+            try {{
+                const parts = {{}};
+                if (text !== null) parts['text/plain'] = new Blob([text], {{ type: 'text/plain' }});
+                if (html !== null) parts['text/html'] = new Blob([html], {{ type: 'text/html' }});
+                if (imageFile) parts[imageFile.type] = imageFile;
+                await navigator.clipboard.write([new ClipboardItem(parts)]);
+                clipboardSet = true;
+            }} catch (e) {{
+                // Clipboard access needs focus and permission; the synthetic event still carries the data
+            }}
+        }}
+
+        element.focus();
+        const event = new ClipboardEvent('paste', {{ clipboardData: data, bubbles: true, cancelable: true, composed: true }});
+        // Some engines drop clipboardData from the constructor
+        if (!event.clipboardData) Object.defineProperty(event, 'clipboardData', {{ value: data }});
+        element.dispatchEvent(event);
+
+        // Emulate the browser's default insertion when the page didn't handle the paste itself
+        let inserted = false;
+        if (!event.defaultPrevented) {{
+            const editable = element.isContentEditable || element.tagName === 'INPUT' || element.tagName === 'TEXTAREA';
+            if (editable && html !== null && element.isContentEditable) {{
+                inserted = document.execCommand('insertHTML', false, html);
+            }} else if (editable && text !== null) {{
+                inserted = document.execCommand('insertText', false, text);
+            }}
+        }}
+
+        return {{
+            dispatched: true,
+            default_prevented: event.defaultPrevented,
+            inserted_by_default: inserted,
+            clipboard_set: clipboardSet,
+            types: Array.from(data.types)
+        }};
+        "#,
+        find_element = find_element_code(&request.target)?,
+        text = serde_json::to_string(&request.text).unwrap_or_default(),
+        html = serde_json::to_string(&request.html).unwrap_or_default(),
+        image = serde_json::to_string(&request.image).unwrap_or_default(),
+        set_clipboard = request.set_clipboard.unwrap_or(true)
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Construct and dispatch an arbitrary DOM event on an element
+pub async fn handle_dispatch_event<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: DispatchEventRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let event_class = request.event_class.unwrap_or_else(|| "Event".to_string());
+
+    info!(
+        "[TAURI_MCP] Dispatching {} '{}' on {} in window: {}",
+        event_class, request.event_type, request.target, window_label
+    );
+
+    let init = if request.init.is_null() {
+        Value::Object(Default::default())
+    } else {
+        request.init
+    };
+    let code = format!(
+        r#"
+        {find_element}
+        const eventClass = {event_class};
+        const Ctor = window[eventClass];
+        if (typeof Ctor !== 'function' || !(Ctor === Event || Ctor.prototype instanceof Event)) {{
+            throw new Error(eventClass + ' is not an Event constructor');
+        }}
+        const init = Object.assign({{ bubbles: true, cancelable: true, composed: true }}, {init});
+        const event = new Ctor({event_type}, init);
+        element.dispatchEvent(event);
+        return {{
+            dispatched: true,
+            event_class: eventClass,
+            default_prevented: event.defaultPrevented
+        }};
+        "#,
+        find_element = find_element_code(&request.target)?,
+        event_class = serde_json::to_string(&event_class).unwrap_or_default(),
+        event_type = serde_json::to_string(&request.event_type).unwrap_or_default(),
+        init = init
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
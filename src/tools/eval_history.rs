@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::registry::ToolRegistry;
+use crate::tools::timeline::now_ms;
+
+/// Evals kept by default
+pub const DEFAULT_EVAL_HISTORY_SIZE: usize = 200;
+
+/// Commands whose invocations are recorded
+pub const RECORDED_COMMANDS: &[&str] = &["direct_eval", "execute_with_console"];
+
+/// One recorded eval
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalRecord {
+    /// Increases with every recorded eval, replays included
+    pub id: u64,
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    pub duration_ms: u64,
+    pub command: String,
+    pub window_label: String,
+    pub code: String,
+    /// The full request, as replayed by `replay_eval`
+    pub request: Value,
+    pub success: bool,
+    /// Response data, redacted like the response itself
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    /// The record this one replayed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<u64>,
+}
+
+/// Ring buffer of the most recent evals, managed in plugin state
+pub struct EvalHistory {
+    capacity: usize,
+    entries: Mutex<VecDeque<EvalRecord>>,
+    next_id: AtomicU64,
+}
+
+impl EvalHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Record a finished eval. Its result and error are redacted before they are stored.
+    pub fn record<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        command: &str,
+        request: Value,
+        outcome: &crate::Result<SocketResponse>,
+        started: (u64, Instant),
+        replay_of: Option<u64>,
+    ) -> Option<EvalRecord> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let redactor = app.tauri_mcp().redactor();
+        let (success, mut result, error) = match outcome {
+            Ok(response) => (response.success, response.data.clone(), response.error.clone()),
+            Err(e) => (false, None, Some(e.to_string())),
+        };
+        if let Some(data) = result.as_mut() {
+            redactor.redact_value(data);
+        }
+
+        let record = EvalRecord {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            timestamp_ms: started.0,
+            duration_ms: started.1.elapsed().as_millis() as u64,
+            command: command.to_string(),
+            window_label: request
+                .get("window_label")
+                .and_then(|v| v.as_str())
+                .unwrap_or("main")
+                .to_string(),
+            code: request
+                .get("code")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            request,
+            success,
+            result,
+            error: error.map(|e| redactor.redact_str(&e)),
+            replay_of,
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(record.clone());
+        Some(record)
+    }
+
+    pub fn get(&self, id: u64) -> Option<EvalRecord> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|record| record.id == id)
+            .cloned()
+    }
+
+    pub fn snapshot(&self) -> Vec<EvalRecord> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// `handle_command` hook: record the invocation if `command` is an eval
+pub fn record_command<R: Runtime>(
+    app: &AppHandle<R>,
+    command: &str,
+    request: Value,
+    outcome: &crate::Result<SocketResponse>,
+    started: (u64, Instant),
+) {
+    if let Some(history) = app.try_state::<EvalHistory>() {
+        history.record(app, command, request, outcome, started, None);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetEvalHistoryRequest {
+    /// Only evals run in this window
+    pub window_label: Option<String>,
+    /// Only evals recorded after this id
+    pub since_id: Option<u64>,
+    /// Most recent entries to return (default all)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EvalHistoryResponse {
+    /// Oldest first
+    pub entries: Vec<EvalRecord>,
+    /// Entries kept before older ones are dropped
+    pub capacity: usize,
+}
+
+/// List recorded `direct_eval` and `execute_with_console` invocations, oldest first
+pub async fn handle_get_eval_history<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: GetEvalHistoryRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let history = app.state::<EvalHistory>();
+
+    info!("[TAURI_MCP] Reading eval history");
+
+    let mut entries: Vec<EvalRecord> = history
+        .snapshot()
+        .into_iter()
+        .filter(|record| request.since_id.is_none_or(|since| record.id > since))
+        .filter(|record| {
+            request
+                .window_label
+                .as_ref()
+                .is_none_or(|label| &record.window_label == label)
+        })
+        .collect();
+    if let Some(limit) = request.limit {
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+
+    let response = EvalHistoryResponse {
+        entries,
+        capacity: history.capacity,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayEvalRequest {
+    /// Recorded evals to run again, in this order
+    pub ids: Vec<u64>,
+    /// Run them in this window instead of the one each was recorded in
+    pub window_label: Option<String>,
+    /// Stop at the first replay that fails
+    #[serde(default)]
+    pub stop_on_failure: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayOutcome {
+    pub original_id: u64,
+    /// The history entry recorded for the replay
+    pub replay: EvalRecord,
+    /// Whether the replay succeeded and returned the same data as the original, ignoring
+    /// execution ids
+    pub matches: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplayEvalResponse {
+    pub replays: Vec<ReplayOutcome>,
+    /// Ids not run because an earlier replay failed with `stop_on_failure`
+    pub skipped: Vec<u64>,
+}
+
+/// Response data without the parts that differ on every run
+fn comparable(data: &Option<Value>) -> Option<Value> {
+    let mut data = data.clone()?;
+    if let Some(object) = data.as_object_mut() {
+        object.remove("execution_id");
+    }
+    Some(data)
+}
+
+/// Run recorded evals again, in order, to reproduce what a client did to the app. Each replay is
+/// recorded in the history too, pointing back at its original.
+pub async fn handle_replay_eval<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ReplayEvalRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let history = app.state::<EvalHistory>();
+    let registry = app.state::<ToolRegistry<R>>();
+
+    // Check every id first so a bad one doesn't leave the app half replayed
+    let mut originals = Vec::new();
+    for id in &request.ids {
+        let record = history.get(*id).ok_or_else(|| {
+            Error::Anyhow(format!("Eval {} is not in the history (it may have been dropped)", id))
+        })?;
+        originals.push(record);
+    }
+
+    info!("[TAURI_MCP] Replaying {} eval(s)", originals.len());
+
+    let mut replays = Vec::new();
+    let mut skipped = Vec::new();
+    for original in originals {
+        if request.stop_on_failure && replays.iter().any(|outcome: &ReplayOutcome| !outcome.replay.success) {
+            skipped.push(original.id);
+            continue;
+        }
+        let handler = registry
+            .get(&original.command)
+            .ok_or_else(|| Error::Anyhow(format!("Unknown command: {}", original.command)))?;
+
+        let mut payload = original.request.clone();
+        if let Some(object) = payload.as_object_mut() {
+            // Fresh ids, so the replay runs again and can't collide with the original's stored
+            // results
+            object.remove("execution_id");
+            object.remove("request_id");
+            if let Some(label) = &request.window_label {
+                object.insert("window_label".to_string(), Value::String(label.clone()));
+            }
+        }
+
+        let started = (now_ms(), Instant::now());
+        let outcome = handler.call(app, payload.clone()).await;
+        let Some(replay) = history.record(app, &original.command, payload, &outcome, started, Some(original.id)) else {
+            return Err(Error::Anyhow("Eval history is disabled".to_string()));
+        };
+        let matches = replay.success
+            && original.success
+            && comparable(&replay.result) == comparable(&original.result);
+        replays.push(ReplayOutcome {
+            original_id: original.id,
+            replay,
+            matches,
+        });
+    }
+
+    let response = ReplayEvalResponse { replays, skipped };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,121 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::elements::{ElementTarget, find_element_code};
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct EventListenersRequest {
+    pub window_label: Option<String>,
+    /// Element to inspect
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// Also report listeners on ancestors, document and window, which receive the event when it bubbles
+    #[serde(default)]
+    pub include_ancestors: bool,
+    /// Maximum characters of handler source to include (default 200)
+    pub source_length: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Report listeners attached to an element: inline `on*` handler properties, plus listeners added
+/// with `addEventListener` once `setup_console_capture` ran with `track_listeners`. Tracking is
+/// opt-in because it patches `EventTarget.prototype`; `tracking` says whether it is on.
+pub async fn handle_get_event_listeners<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: EventListenersRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Getting event listeners for {} in window: {}",
+        request.target, window_label
+    );
+
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let code = format!(r#"
+        {find_element}
+        const sourceLength = {source_length};
+
+        const describe = window.__mcp.describe;
+        const snippet = (fn) => {{
+            try {{
+                const source = typeof fn === 'function' ? String(fn) : (fn && fn.handleEvent ? String(fn.handleEvent) : String(fn));
+                return source.length > sourceLength ? source.slice(0, sourceLength) + '...' : source;
+            }} catch (e) {{
+                return null;
+            }}
+        }};
+        const listenersOf = (target) => {{
+            const tracked = (window.__mcpListeners && window.__mcpListeners.get(target)) || [];
+            const result = tracked.map(entry => ({{
+                type: entry.type,
+                capture: entry.capture,
+                passive: entry.passive,
+                once: entry.once,
+                inline: false,
+                source: snippet(entry.listener)
+            }}));
+            for (const key in target) {{
+                if (key.startsWith('on') && typeof target[key] === 'function') {{
+                    result.push({{
+                        type: key.slice(2),
+                        capture: false,
+                        passive: false,
+                        once: false,
+                        inline: true,
+                        source: snippet(target[key])
+                    }});
+                }}
+            }}
+            return result;
+        }};
+
+        const targets = [element];
+        if ({include_ancestors}) {{
+            let parent = element.parentElement;
+            while (parent) {{
+                targets.push(parent);
+                parent = parent.parentElement;
+            }}
+            targets.push(document, window);
+        }}
+
+        return {{
+            handle: window.__mcp.handles.handleFor(element),
+            tracking: !!(window.__mcpInstrumented && window.__mcpInstrumented.listeners),
+            tracked_since: window.__mcpListenersSince || null,
+            targets: targets.map(target => ({{
+                target: describe(target),
+                listeners: listenersOf(target)
+            }})).filter((entry, index) => index === 0 || entry.listeners.length > 0)
+        }};
+    "#,
+        find_element = find_element_code(&request.target)?,
+        source_length = request.source_length.unwrap_or(200),
+        include_ancestors = request.include_ancestors
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
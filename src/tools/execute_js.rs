@@ -3,10 +3,11 @@ use serde_json::Value;
 use std::fmt;
 use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Listener, Runtime};
 
 use crate::error::Error;
 use crate::socket_server::SocketResponse;
+use crate::tools::webview_backend;
 
 // Define a custom error type for JavaScript execution operations
 #[derive(Debug)]
@@ -76,9 +77,9 @@ pub async fn handle_execute_js<R: Runtime>(
         .unwrap_or_else(|| "main".to_string());
 
     // Verify the window exists
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::Anyhow(format!("Window not found: {}", window_label)))?;
+    if !webview_backend::backend(app)?.has_window(&window_label) {
+        return Err(Error::Anyhow(format!("Window not found: {}", window_label)));
+    }
 
     // Execute JavaScript and get the result
     let result = execute_js_in_window(app.clone(), request).await;
@@ -94,12 +95,14 @@ pub async fn handle_execute_js<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                ..Default::default()
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
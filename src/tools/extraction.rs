@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::elements::{ElementTarget, find_element_code};
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct ExtractTableRequest {
+    pub window_label: Option<String>,
+    /// A `<table>` or an element with role `grid`, `treegrid` or `table`
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// Maximum number of body rows to return (default: all)
+    pub limit: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// How to read one field from a list item
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldSpec {
+    /// Text content of the first descendant matching this selector
+    Selector(String),
+    Detailed {
+        /// Descendant selector; omitted means the item itself
+        selector: Option<String>,
+        /// Read this attribute (e.g. `href`) instead of the text content
+        attribute: Option<String>,
+        /// Collect values from every match instead of the first
+        #[serde(default)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExtractListRequest {
+    pub window_label: Option<String>,
+    /// Selector matching each repeated item, e.g. `".product-card"`
+    pub item_selector: String,
+    /// Output field name to how it is read from the item
+    pub fields: BTreeMap<String, FieldSpec>,
+    /// Maximum number of items to return (default: all)
+    pub limit: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Read repeated UI items into an array of objects using per-field child selectors
+pub async fn handle_extract_list<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ExtractListRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Extracting list '{}' with {} fields in window: {}",
+        request.item_selector,
+        request.fields.len(),
+        window_label
+    );
+
+    let fields = serde_json::to_string(&request.fields)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid fields: {}", e)))?;
+    let item_selector = serde_json::to_string(&request.item_selector)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid item selector: {}", e)))?;
+    let code = format!(
+        r#"
+        const fields = {fields};
+        const limit = {limit};
+        const items = Array.from(document.querySelectorAll({item_selector}));
+        const read = (node, attribute) => {{
+            if (!node) return null;
+            if (attribute) return node.getAttribute(attribute);
+            return window.__mcp.text(node);
+        }};
+        const extract = (item, spec) => {{
+            if (typeof spec === 'string') spec = {{ selector: spec }};
+            const attribute = spec.attribute || null;
+            if (!spec.selector) return spec.all ? [read(item, attribute)] : read(item, attribute);
+            if (spec.all) return Array.from(item.querySelectorAll(spec.selector)).map(node => read(node, attribute));
+            return read(item.querySelector(spec.selector), attribute);
+        }};
+
+        const limited = limit === null ? items : items.slice(0, limit);
+        return {{
+            total_count: items.length,
+            items: limited.map(item => {{
+                const record = {{}};
+                for (const name of Object.keys(fields)) record[name] = extract(item, fields[name]);
+                return record;
+            }})
+        }};
+        "#,
+        fields = fields,
+        item_selector = item_selector,
+        limit = request
+            .limit
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "null".to_string())
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Convert an HTML table or ARIA grid into header-keyed rows
+pub async fn handle_extract_table<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ExtractTableRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Extracting table {} in window: {}",
+        request.target, window_label
+    );
+
+    let code = format!(
+        r#"
+        {find_element}
+        const limit = {limit};
+        const cellText = (cell) => window.__mcp.text(cell);
+
+        // Expand colspans so every row lines up with the header columns
+        const expand = (cells) => {{
+            const values = [];
+            for (const cell of cells) {{
+                const span = Math.max(1, parseInt(cell.getAttribute('colspan') || '1', 10) || 1);
+                const text = cellText(cell);
+                for (let i = 0; i < span; i++) values.push(text);
+            }}
+            return values;
+        }};
+
+        let headerCells = null;
+        let bodyRows = [];
+        if (element.tagName === 'TABLE') {{
+            const rows = Array.from(element.rows);
+            const headRow = element.tHead && element.tHead.rows.length
+                ? element.tHead.rows[element.tHead.rows.length - 1]
+                : (rows.length && Array.from(rows[0].cells).every(c => c.tagName === 'TH') ? rows[0] : null);
+            if (headRow) headerCells = Array.from(headRow.cells);
+            bodyRows = rows
+                .filter(row => row !== headRow && !(element.tHead && element.tHead.contains(row)))
+                .map(row => Array.from(row.cells));
+        }} else {{
+            const role = element.getAttribute('role');
+            if (!['grid', 'treegrid', 'table'].includes(role)) {{
+                throw new Error('Target is neither a <table> nor an element with role grid/treegrid/table');
+            }}
+            const rows = Array.from(element.querySelectorAll('[role="row"]'));
+            const cellsOf = (row) => Array.from(row.querySelectorAll('[role="gridcell"], [role="cell"], [role="columnheader"], [role="rowheader"]'));
+            const headRow = rows.find(row => row.querySelector('[role="columnheader"]'));
+            if (headRow) headerCells = Array.from(headRow.querySelectorAll('[role="columnheader"]'));
+            bodyRows = rows.filter(row => row !== headRow).map(cellsOf);
+        }}
+
+        const rawRows = bodyRows.map(expand).filter(values => values.length > 0);
+        const width = Math.max(headerCells ? expand(headerCells).length : 0, ...rawRows.map(r => r.length), 0);
+
+        // Blank or repeated headers get positional names so keys stay unique
+        const seen = {{}};
+        const headers = [];
+        const headerValues = headerCells ? expand(headerCells) : [];
+        for (let i = 0; i < width; i++) {{
+            let name = headerValues[i] || ('column_' + (i + 1));
+            if (seen[name]) name = name + '_' + (i + 1);
+            seen[name] = true;
+            headers.push(name);
+        }}
+
+        const limited = limit === null ? rawRows : rawRows.slice(0, limit);
+        return {{
+            headers: headers,
+            has_header_row: headerCells !== null,
+            row_count: rawRows.length,
+            rows: limited.map(values => {{
+                const row = {{}};
+                headers.forEach((header, i) => {{ row[header] = values[i] !== undefined ? values[i] : null; }});
+                return row;
+            }}),
+            raw_rows: limited
+        }};
+        "#,
+        find_element = find_element_code(&request.target)?,
+        limit = request
+            .limit
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "null".to_string())
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::artifacts::{ArtifactInfo, ArtifactStore};
+use crate::notifications::NotificationHub;
+use crate::socket_server::SocketResponse;
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::timeline::now_ms;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameTimingAction {
+    /// Begin recording frame timestamps in the page
+    Start,
+    /// Stop recording and save the timings as a `frame-timing` artifact
+    Stop,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FrameTimingRequest {
+    pub window_label: Option<String>,
+    pub action: FrameTimingAction,
+    /// Frames kept at most; later frames are counted but not stored (default 36000, ten
+    /// minutes at 60fps)
+    pub max_frames: Option<u32>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Render timing the browser reports for a slow frame (Long Animation Frames API, where supported)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnimationFrameTiming {
+    /// Milliseconds since the Unix epoch
+    pub start_ms: f64,
+    pub duration_ms: f64,
+    /// Time spent in script beyond the 50ms budget
+    pub blocking_ms: f64,
+    /// When rendering began, milliseconds since the Unix epoch
+    pub render_start_ms: Option<f64>,
+    /// When style and layout began, milliseconds since the Unix epoch
+    pub style_and_layout_start_ms: Option<f64>,
+}
+
+/// What the page hands back when recording stops
+#[derive(Debug, Deserialize)]
+struct PageFrameTiming {
+    time_origin: f64,
+    started_at: f64,
+    frames: Vec<f64>,
+    missed_frames: u64,
+    animation_frames: Option<Vec<AnimationFrameTiming>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FrameTimingSummary {
+    pub frame_count: u64,
+    pub duration_ms: f64,
+    pub average_fps: f64,
+    /// Typical interval between frames, taken as the display's refresh interval
+    pub median_interval_ms: f64,
+    pub max_interval_ms: f64,
+    /// Refresh intervals that passed without a frame, estimated from gaps of more than 1.5
+    /// median intervals
+    pub dropped_frames: u64,
+    /// Frames rendered after `max_frames` was reached, which are not in the sidecar
+    pub unstored_frames: u64,
+}
+
+/// Sidecar written next to a recording. Timestamps are milliseconds since the Unix epoch so
+/// they can be lined up with video frames captured by any means.
+#[derive(Debug, Serialize)]
+struct FrameTimingSidecar<'a> {
+    window_label: &'a str,
+    started_at_ms: f64,
+    stopped_at_ms: u64,
+    summary: &'a FrameTimingSummary,
+    /// Timestamp of each requestAnimationFrame callback
+    frames_ms: Vec<f64>,
+    /// `null` when the webview doesn't support the Long Animation Frames API
+    animation_frames: Option<&'a [AnimationFrameTiming]>,
+}
+
+/// Starts a requestAnimationFrame loop that records frame timestamps. The loop uses the native
+/// requestAnimationFrame so `control_animation_frames` pausing doesn't stop it.
+fn start_script(max_frames: u32) -> String {
+    format!(
+        r#"
+        const existing = window.__mcpFrameTiming;
+        if (existing && existing.running) {{
+            return {{ started: false, started_at: performance.timeOrigin + existing.startedAt }};
+        }}
+        const raf = window.__mcpFrameControl
+            ? window.__mcpFrameControl.nativeRaf
+            : window.requestAnimationFrame.bind(window);
+        const timing = {{ running: true, startedAt: performance.now(), frames: [], missed: 0, animationFrames: null, observer: null }};
+        const tick = (timestamp) => {{
+            if (!timing.running) return;
+            if (timing.frames.length < {max_frames}) {{
+                timing.frames.push(timestamp);
+            }} else {{
+                timing.missed++;
+            }}
+            raf(tick);
+        }};
+        raf(tick);
+        if (window.PerformanceObserver && (PerformanceObserver.supportedEntryTypes || []).includes('long-animation-frame')) {{
+            timing.animationFrames = [];
+            timing.observer = new PerformanceObserver(list => {{
+                list.getEntries().forEach(entry => timing.animationFrames.push(entry));
+            }});
+            timing.observer.observe({{ type: 'long-animation-frame' }});
+        }}
+        window.__mcpFrameTiming = timing;
+        return {{ started: true, started_at: performance.timeOrigin + timing.startedAt }};
+        "#
+    )
+}
+
+const STOP_SCRIPT: &str = r#"
+    const timing = window.__mcpFrameTiming;
+    if (!timing || !timing.running) return null;
+    timing.running = false;
+    if (timing.observer) timing.observer.disconnect();
+    delete window.__mcpFrameTiming;
+    const origin = performance.timeOrigin;
+    const epoch = (value) => (typeof value === 'number' && value > 0) ? origin + value : null;
+    return {
+        time_origin: origin,
+        started_at: origin + timing.startedAt,
+        frames: timing.frames.map(timestamp => origin + timestamp),
+        missed_frames: timing.missed,
+        animation_frames: timing.animationFrames && timing.animationFrames.map(entry => ({
+            start_ms: origin + entry.startTime,
+            duration_ms: entry.duration,
+            blocking_ms: entry.blockingDuration || 0,
+            render_start_ms: epoch(entry.renderStart),
+            style_and_layout_start_ms: epoch(entry.styleAndLayoutStart)
+        }))
+    };
+"#;
+
+fn summarize(timing: &PageFrameTiming) -> FrameTimingSummary {
+    let intervals: Vec<f64> = timing.frames.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let mut sorted = intervals.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+    let dropped_frames = if median > 0.0 {
+        intervals
+            .iter()
+            .filter(|&&interval| interval > median * 1.5)
+            .map(|&interval| (interval / median).round() as u64 - 1)
+            .sum()
+    } else {
+        0
+    };
+    let duration_ms = intervals.iter().sum::<f64>();
+
+    FrameTimingSummary {
+        frame_count: timing.frames.len() as u64 + timing.missed_frames,
+        duration_ms,
+        average_fps: if duration_ms > 0.0 {
+            intervals.len() as f64 * 1000.0 / duration_ms
+        } else {
+            0.0
+        },
+        median_interval_ms: median,
+        max_interval_ms: sorted.last().copied().unwrap_or(0.0),
+        dropped_frames,
+        unstored_frames: timing.missed_frames,
+    }
+}
+
+/// Record per-frame timestamps in a window while a recording runs, then save them as a JSON
+/// sidecar for dropped-frame analysis. Compositor timing isn't visible to pages; slow frames
+/// carry render and layout start times where the webview supports Long Animation Frames.
+pub async fn handle_record_frame_timing<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: FrameTimingRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+
+    info!(
+        "[TAURI_MCP] Frame timing {:?} for window: {}",
+        request.action, window_label
+    );
+
+    let data = match request.action {
+        FrameTimingAction::Start => {
+            let script = start_script(request.max_frames.unwrap_or(36_000));
+            eval_with_result(app, &window_label, &script, timeout).await?
+        }
+        FrameTimingAction::Stop => {
+            let value = eval_with_result(app, &window_label, STOP_SCRIPT, timeout).await?;
+            if value.is_null() {
+                return Ok(SocketResponse {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Frame timing is not running in window '{}'", window_label)),
+                    ..Default::default()
+                });
+            }
+            let timing: PageFrameTiming = serde_json::from_value(value)
+                .map_err(|e| crate::Error::Anyhow(format!("Failed to parse frame timing: {}", e)))?;
+            let summary = summarize(&timing);
+            let stopped_at_ms = now_ms();
+            let sidecar = FrameTimingSidecar {
+                window_label: &window_label,
+                started_at_ms: timing.started_at,
+                stopped_at_ms,
+                summary: &summary,
+                frames_ms: timing.frames,
+                animation_frames: timing.animation_frames.as_deref(),
+            };
+            let json = serde_json::to_vec_pretty(&sidecar)
+                .map_err(|e| crate::Error::Anyhow(format!("Failed to serialize frame timing: {}", e)))?;
+
+            let store = app.state::<ArtifactStore>();
+            let id = store.allocate_id("frame-timing", stopped_at_ms);
+            let file = store.write_file(&id, "frame_timing.json", &json)?;
+            let info = ArtifactInfo {
+                id,
+                kind: "frame-timing".to_string(),
+                reason: "record_frame_timing".to_string(),
+                window_label: Some(window_label.clone()),
+                created_ms: stopped_at_ms,
+                files: vec![file],
+            };
+            store.register(info.clone());
+            if let Some(hub) = app.try_state::<NotificationHub>() {
+                hub.publish("artifacts", serde_json::to_value(&info).unwrap_or(Value::Null));
+            }
+
+            serde_json::json!({
+                "summary": summary,
+                "time_origin_ms": timing.time_origin,
+                "artifact": info,
+            })
+        }
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct ListFramesRequest {
+    pub window_label: Option<String>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FrameInfo {
+    /// Position among `<iframe>`/`<frame>` elements at each level, outermost first
+    pub path: Vec<usize>,
+    /// Short `tag#id.class` label of the frame element
+    pub element: String,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// Document URL for same-origin frames, the `src` attribute otherwise
+    pub url: Option<String>,
+    /// Whether scripts can run in the frame via `frame_selector`/`frame_url`
+    pub same_origin: bool,
+    pub visible: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListFramesResponse {
+    pub frames: Vec<FrameInfo>,
+}
+
+/// List the frames embedded in a window, including nested same-origin frames
+pub async fn handle_list_frames<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ListFramesRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Listing frames in window: {}", window_label);
+
+    let code = r#"
+        return window.__mcp.frames().map(frame => ({
+            path: frame.path,
+            element: window.__mcp.describe(frame.element),
+            id: frame.element.id || null,
+            name: frame.element.name || null,
+            url: frame.url,
+            same_origin: !!frame.window,
+            visible: window.__mcp.isVisible(frame.element)
+        }));
+    "#;
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let value = eval_with_result(app, &window_label, code, timeout).await?;
+    let frames: Vec<FrameInfo> = serde_json::from_value(value)
+        .map_err(|e| crate::Error::Anyhow(format!("Failed to parse frame list: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(ListFramesResponse { frames }).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
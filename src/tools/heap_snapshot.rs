@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::artifacts::{ArtifactInfo, ArtifactStore};
+use crate::devtools::{NO_ENDPOINT, connect_window, resolve_port};
+use crate::error::Error;
+use crate::notifications::NotificationHub;
+use crate::socket_server::SocketResponse;
+use crate::tools::timeline::now_ms;
+
+#[derive(Debug, Deserialize)]
+pub struct CaptureHeapSnapshotRequest {
+    pub window_label: Option<String>,
+    /// Remote debugging port to use instead of the one set with `PluginConfig::devtools_port`
+    pub port: Option<u16>,
+    /// Run a full garbage collection first, so only reachable objects are in the snapshot
+    /// (default true)
+    pub collect_garbage: Option<bool>,
+    /// Large heaps take a while to serialize (default 120000)
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeapSnapshotResponse {
+    pub artifact: ArtifactInfo,
+    pub size_bytes: usize,
+    /// The devtools page the snapshot was taken from
+    pub target_url: String,
+}
+
+/// Save a V8 heap snapshot of a window's page to the artifacts directory, for loading into the
+/// Memory panel of Chromium devtools. Needs a DevTools Protocol endpoint, which only WebView2
+/// offers (see `PluginConfig::devtools_port`).
+pub async fn handle_capture_heap_snapshot<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: CaptureHeapSnapshotRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(120000));
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| Error::WindowNotFound(window_label.clone()))?;
+    let Some(port) = resolve_port(app, request.port) else {
+        return Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(NO_ENDPOINT.to_string()),
+            ..Default::default()
+        });
+    };
+
+    info!("[TAURI_MCP] Capturing a heap snapshot of window {} through port {}", window_label, port);
+
+    let capture = async {
+        let (mut session, target) = connect_window(&window, port).await?;
+        if request.collect_garbage.unwrap_or(true) {
+            session.call("HeapProfiler.collectGarbage", serde_json::json!({}), |_, _| {}).await?;
+        }
+        let mut snapshot = String::new();
+        session
+            .call(
+                "HeapProfiler.takeHeapSnapshot",
+                serde_json::json!({ "reportProgress": false }),
+                |method, params| {
+                    if method == "HeapProfiler.addHeapSnapshotChunk" {
+                        if let Some(chunk) = params.get("chunk").and_then(|v| v.as_str()) {
+                            snapshot.push_str(chunk);
+                        }
+                    }
+                },
+            )
+            .await?;
+        Ok::<_, Error>((snapshot, target))
+    };
+    let (snapshot, target) = match tokio::time::timeout(timeout, capture).await {
+        Ok(Ok(captured)) => captured,
+        Ok(Err(e)) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Heap snapshot failed: {}", e)),
+                ..Default::default()
+            })
+        }
+        Err(_) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Heap snapshot timed out after {}ms", timeout.as_millis())),
+                ..Default::default()
+            })
+        }
+    };
+
+    let created_ms = now_ms();
+    let store = app.state::<ArtifactStore>();
+    let id = store.allocate_id("heap-snapshot", created_ms);
+    let file = store.write_file(&id, &format!("{}.heapsnapshot", window_label), snapshot.as_bytes())?;
+    let info = ArtifactInfo {
+        id,
+        kind: "heap-snapshot".to_string(),
+        reason: "capture_heap_snapshot".to_string(),
+        window_label: Some(window_label),
+        created_ms,
+        files: vec![file],
+    };
+    store.register(info.clone());
+    if let Some(hub) = app.try_state::<NotificationHub>() {
+        hub.publish("artifacts", serde_json::to_value(&info).unwrap_or(Value::Null));
+    }
+
+    let response = HeapSnapshotResponse {
+        artifact: info,
+        size_bytes: snapshot.len(),
+        target_url: target.url,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct WaitForIdleRequest {
+    pub window_label: Option<String>,
+    /// How long the page must stay quiet to count as idle (default 500ms)
+    pub quiet_ms: Option<u64>,
+    /// Give up after this long (default 10000ms)
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WaitForIdleResponse {
+    pub idle: bool,
+    pub waited_ms: u64,
+    /// Network requests still in flight when the wait ended
+    pub pending_requests: u64,
+    /// DOM mutation records observed while waiting
+    pub mutations: u64,
+    /// Long tasks (>50ms main-thread blocks) observed while waiting
+    pub long_tasks: u64,
+}
+
+/// Extra time allowed for the page to report back after its own deadline
+const REPORT_GRACE: Duration = Duration::from_secs(5);
+
+/// Resolve once the page has no pending requests, long tasks or DOM mutations for `quiet_ms`
+pub async fn handle_wait_for_idle<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: WaitForIdleRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let quiet_ms = request.quiet_ms.unwrap_or(500);
+    let timeout_ms = request.timeout_ms.unwrap_or(10_000);
+
+    info!(
+        "[TAURI_MCP] Waiting for window {} to be idle for {}ms (timeout {}ms)",
+        window_label, quiet_ms, timeout_ms
+    );
+
+    // Pending request tracking lives in the capture script
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let idle_code = format!(r#"
+        const quietMs = {quiet_ms};
+        const started = Date.now();
+        const deadline = started + {timeout_ms};
+        let lastActivity = started;
+        let mutations = 0;
+        let longTasks = 0;
+
+        const mutationObserver = new MutationObserver(records => {{
+            mutations += records.length;
+            lastActivity = Date.now();
+        }});
+        mutationObserver.observe(document, {{ subtree: true, childList: true, attributes: true, characterData: true }});
+
+        let taskObserver = null;
+        try {{
+            taskObserver = new PerformanceObserver(list => {{
+                longTasks += list.getEntries().length;
+                lastActivity = Date.now();
+            }});
+            taskObserver.observe({{ entryTypes: ['longtask'] }});
+        }} catch (e) {{
+            taskObserver = null;
+        }}
+
+        try {{
+            return await new Promise(resolve => {{
+                const check = () => {{
+                    const now = Date.now();
+                    const pending = Math.max(0, window.__mcpPendingRequests || 0);
+                    if (pending > 0) lastActivity = now;
+                    const idle = now - lastActivity >= quietMs;
+                    if (idle || now >= deadline) {{
+                        resolve({{
+                            idle: idle,
+                            waited_ms: now - started,
+                            pending_requests: pending,
+                            mutations: mutations,
+                            long_tasks: longTasks
+                        }});
+                        return;
+                    }}
+                    setTimeout(check, 50);
+                }};
+                check();
+            }});
+        }} finally {{
+            mutationObserver.disconnect();
+            if (taskObserver) taskObserver.disconnect();
+        }}
+    "#);
+
+    let timeout = Duration::from_millis(timeout_ms) + REPORT_GRACE;
+    let value = eval_with_result(app, &window_label, &idle_code, timeout).await?;
+    let response: WaitForIdleResponse = serde_json::from_value(value)
+        .map_err(|e| crate::Error::Anyhow(format!("Failed to parse idle result: {}", e)))?;
+
+    let error = if response.idle {
+        None
+    } else {
+        Some(format!(
+            "Window '{}' did not become idle within {}ms",
+            window_label, timeout_ms
+        ))
+    };
+
+    Ok(SocketResponse {
+        success: response.idle,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,157 @@
+use serde_json::Value;
+use std::borrow::Cow;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::Error;
+use crate::tools::page_helpers;
+use crate::tools::webview_backend::{self, ExecutionStrategy};
+
+/// Correlation id of one tool invocation, shared by every script it runs
+struct Correlation {
+    id: String,
+    /// Connection the invocation came from; see [`as_client`]
+    client: Option<String>,
+    /// Whether a script carried the id into a page
+    used: AtomicBool,
+}
+
+tokio::task_local! {
+    static CORRELATION: Correlation;
+    static CLIENT: String;
+}
+
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Run a client's request with its connection id in scope, so what its tool calls cause in the
+/// page is tagged with the client as well as the call
+pub async fn as_client<F: Future>(client_id: String, future: F) -> F::Output {
+    CLIENT.scope(client_id, future).await
+}
+
+/// Connection id of the client whose request is running, if it came over the socket
+pub fn current_client() -> Option<String> {
+    CLIENT.try_with(Clone::clone).ok()
+}
+
+/// Run a tool invocation under a fresh correlation id. Console entries, JavaScript errors and
+/// network requests its scripts cause synchronously are tagged with the id in the page; it is
+/// returned when a script ran, so the caller can look up what the invocation caused.
+pub async fn correlate<F: Future>(future: F) -> (F::Output, Option<String>) {
+    let correlation = Correlation {
+        id: format!("corr-{}", NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed)),
+        client: current_client(),
+        used: AtomicBool::new(false),
+    };
+    CORRELATION
+        .scope(correlation, async {
+            let output = future.await;
+            let id = CORRELATION.with(|c| c.used.load(Ordering::Relaxed).then(|| c.id.clone()));
+            (output, id)
+        })
+        .await
+}
+
+/// `body` prefixed to set `window.__mcpCorrelationId` and `window.__mcpClientId` for its
+/// synchronous part. Both are cleared in the first microtask, which runs before anything the body
+/// awaits resumes.
+fn correlated(body: &str) -> Cow<'_, str> {
+    let ids = CORRELATION
+        .try_with(|c| {
+            c.used.store(true, Ordering::Relaxed);
+            (
+                serde_json::to_string(&c.id).unwrap_or_default(),
+                serde_json::to_string(&c.client).unwrap_or_else(|_| "null".to_string()),
+            )
+        })
+        .ok();
+    match ids {
+        Some((id, client)) => Cow::Owned(format!(
+            "window.__mcpCorrelationId = {id}; window.__mcpClientId = {client}; \
+             Promise.resolve().then(function() {{ if (window.__mcpCorrelationId === {id}) \
+             {{ window.__mcpCorrelationId = null; window.__mcpClientId = null; }} }});\n{body}",
+            id = id,
+            client = client,
+            body = body
+        )),
+        None => Cow::Borrowed(body),
+    }
+}
+
+/// Run `body` (the body of an async JavaScript function) in a window and wait for its return value.
+///
+/// The value must be JSON-serializable. Exceptions and rejected promises are reported as errors.
+/// `body` may use the `window.__mcp` helpers; they are installed on first use in each page.
+pub async fn eval_with_result<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    body: &str,
+    timeout: Duration,
+) -> crate::Result<Value> {
+    eval_with_strategy(app, window_label, body, timeout)
+        .await
+        .map(|(value, _)| value)
+}
+
+/// [`eval_with_result`], also reporting how the code reached the page when the backend says
+pub async fn eval_with_strategy<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    body: &str,
+    timeout: Duration,
+) -> crate::Result<(Value, Option<ExecutionStrategy>)> {
+    let backend = webview_backend::backend(app)?;
+    if !backend.has_window(window_label) {
+        return Err(Error::WindowNotFound(window_label.to_string()));
+    }
+
+    let helpers_missing =
+        |response: &Value| response.get("helpers_missing").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    let body = correlated(body);
+    let mut response = backend.call(window_label, &body, timeout).await?;
+    if helpers_missing(&response) {
+        // First call since the page loaded: install the bundle and run the body again
+        page_helpers::install(backend.as_ref(), window_label)?;
+        response = backend.call(window_label, &body, timeout).await?;
+        if helpers_missing(&response) {
+            return Err(injection_blocked(app, window_label));
+        }
+    }
+
+    let strategy = response
+        .get("strategy")
+        .and_then(|v| serde_json::from_value(v.clone()).ok());
+    if response.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
+        Ok((response.get("value").cloned().unwrap_or(Value::Null), strategy))
+    } else {
+        let message = response
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown JavaScript error");
+        Err(Error::Anyhow(format!("JavaScript error: {}", message)))
+    }
+}
+
+/// The page accepted our script but the helper bundle never took effect, which points at a
+/// Content-Security-Policy or similar restriction rather than a bug in the tool
+fn injection_blocked<R: Runtime>(app: &AppHandle<R>, window_label: &str) -> Error {
+    let mode = app
+        .try_state::<page_helpers::InjectionMode>()
+        .map(|mode| *mode)
+        .unwrap_or_default();
+    let hint = match mode {
+        page_helpers::InjectionMode::Eval => {
+            "Configure the plugin with `injection_mode(InjectionMode::InitScript)` so the helpers load as initialization scripts"
+        }
+        page_helpers::InjectionMode::InitScript => {
+            "The initialization script did not run in this page; reload the window, and check that the page's CSP does not block inline scripts"
+        }
+    };
+    Error::Anyhow(format!(
+        "Script injection is blocked in window '{}': the MCP page helpers could not be installed. {}.",
+        window_label, hint
+    ))
+}
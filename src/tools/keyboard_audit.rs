@@ -0,0 +1,148 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct KeyboardAuditRequest {
+    pub window_label: Option<String>,
+    /// Dispatch Enter/Space to each element to check it reacts to the keyboard (default true).
+    /// App key handlers run, so this can have side effects.
+    pub try_activation: Option<bool>,
+    /// Stop after this many focusable elements (default 200)
+    pub max_elements: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Walk the sequential focus order and report unreachable, non-activatable and focus-trapping elements
+pub async fn handle_audit_keyboard_navigation<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: KeyboardAuditRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Auditing keyboard navigation in window: {}", window_label);
+
+    // Listener tracking tells us which non-focusable elements still handle clicks
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let code = format!(
+        r#"
+        const tryActivation = {try_activation};
+        const maxElements = {max_elements};
+        const previousFocus = document.activeElement;
+
+        const describe = (node) => {{
+            const label = window.__mcp.describe(node);
+            const name = node.getAttribute('aria-label') || (node.innerText || node.value || '').trim().slice(0, 60);
+            return name ? label + ' "' + name + '"' : label;
+        }};
+        const entry = (node, extra) => Object.assign({{
+            handle: window.__mcp.handles.handleFor(node),
+            element: describe(node),
+            role: node.getAttribute('role')
+        }}, extra || {{}});
+        const isRendered = (node) => {{
+            const style = getComputedStyle(node);
+            const rect = node.getBoundingClientRect();
+            return style.visibility !== 'hidden' && style.display !== 'none' && (rect.width > 0 || rect.height > 0);
+        }};
+        const nativelyFocusable = 'a[href], area[href], button, input:not([type="hidden"]), select, textarea, iframe, summary, [contenteditable=""], [contenteditable="true"]';
+        const isDisabled = (node) => node.disabled || !!node.closest('[inert]') || !!node.closest('fieldset[disabled]');
+
+        // Sequential focus order: positive tabindex ascending, then tabindex 0 / native in document order
+        const candidates = Array.from(document.querySelectorAll(nativelyFocusable + ', [tabindex]'))
+            .filter(node => node.tabIndex >= 0 && !isDisabled(node) && isRendered(node));
+        const positive = candidates.filter(n => n.tabIndex > 0).sort((a, b) => a.tabIndex - b.tabIndex);
+        const order = positive.concat(candidates.filter(n => n.tabIndex === 0)).slice(0, maxElements);
+
+        const tabOrder = [];
+        const notFocusable = [];
+        const focusTraps = [];
+        const notActivatable = [];
+        for (const node of order) {{
+            node.focus();
+            const focused = document.activeElement === node;
+            if (!focused) {{
+                notFocusable.push(entry(node));
+                continue;
+            }}
+            tabOrder.push(entry(node, {{ tab_index: node.tabIndex }}));
+
+            const tab = new KeyboardEvent('keydown', {{ key: 'Tab', code: 'Tab', bubbles: true, cancelable: true, composed: true }});
+            node.dispatchEvent(tab);
+            if (tab.defaultPrevented) {{
+                focusTraps.push(entry(node, {{ reason: 'Tab keydown is prevented while this element has focus' }}));
+            }}
+
+            // Native controls activate on Enter/Space by themselves; custom widgets need key handlers
+            const native = node.matches('a[href], button, input, select, textarea, summary');
+            if (tryActivation && !native) {{
+                let activated = false;
+                const onClick = () => {{ activated = true; }};
+                node.addEventListener('click', onClick, true);
+                for (const key of ['Enter', ' ']) {{
+                    const down = new KeyboardEvent('keydown', {{ key: key, code: key === ' ' ? 'Space' : 'Enter', bubbles: true, cancelable: true, composed: true }});
+                    node.dispatchEvent(down);
+                    node.dispatchEvent(new KeyboardEvent('keyup', {{ key: key, code: key === ' ' ? 'Space' : 'Enter', bubbles: true, cancelable: true, composed: true }}));
+                    if (down.defaultPrevented) activated = true;
+                }}
+                node.removeEventListener('click', onClick, true);
+                if (!activated) {{
+                    notActivatable.push(entry(node, {{ reason: 'Enter/Space neither clicked nor were handled' }}));
+                }}
+            }}
+        }}
+
+        // Elements that handle clicks or claim an interactive role but can't be reached with Tab
+        const listeners = window.__mcpListeners;
+        const interactiveRoles = ['button', 'link', 'checkbox', 'menuitem', 'tab', 'switch', 'option', 'radio'];
+        const unreachable = Array.from(document.querySelectorAll('body *'))
+            .filter(node => !order.includes(node) && isRendered(node) && !isDisabled(node) && node.tabIndex < 0)
+            .filter(node => {{
+                const role = node.getAttribute('role');
+                const clickable = typeof node.onclick === 'function'
+                    || (listeners && (listeners.get(node) || []).some(l => l.type === 'click' || l.type === 'mousedown' || l.type === 'pointerdown'));
+                return clickable || (role && interactiveRoles.includes(role));
+            }})
+            .slice(0, maxElements)
+            .map(node => entry(node, {{ reason: 'Interactive but not in the tab order' }}));
+
+        if (previousFocus && previousFocus.focus) previousFocus.focus();
+        else if (document.activeElement && document.activeElement.blur) document.activeElement.blur();
+
+        return {{
+            tab_order: tabOrder,
+            unreachable: unreachable,
+            not_focusable: notFocusable,
+            focus_traps: focusTraps,
+            not_keyboard_activatable: notActivatable,
+            issue_count: unreachable.length + notFocusable.length + focusTraps.length + notActivatable.length
+        }};
+        "#,
+        try_activation = request.try_activation.unwrap_or(true),
+        max_elements = request.max_elements.unwrap_or(200)
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(15000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
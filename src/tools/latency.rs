@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::{info, warn};
+
+use crate::error::Error;
+use crate::notifications::NotificationHub;
+use crate::socket_server::SocketResponse;
+
+/// Durations kept per tool for the percentiles
+const SAMPLES_PER_TOOL: usize = 1000;
+
+/// Budget key that applies to every tool without its own budget
+pub const ANY_TOOL: &str = "*";
+
+/// Notification topic for calls over budget
+pub const LATENCY_TOPIC: &str = "latency";
+
+#[derive(Default)]
+struct ToolSamples {
+    /// Most recent durations in milliseconds
+    recent: VecDeque<f64>,
+    calls: u64,
+    over_budget: u64,
+}
+
+/// Per-tool call durations and budgets, managed in plugin state
+pub struct LatencyTracker {
+    budgets: HashMap<String, Duration>,
+    tools: Mutex<HashMap<String, ToolSamples>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyStats {
+    pub tool: String,
+    /// Calls since the plugin started
+    pub calls: u64,
+    /// Calls that took longer than the budget
+    pub over_budget: u64,
+    pub budget_ms: Option<u64>,
+    /// Percentiles over the last 1000 calls
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1]
+}
+
+impl LatencyTracker {
+    pub fn new(budgets: HashMap<String, Duration>) -> Self {
+        Self {
+            budgets,
+            tools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn budget(&self, tool: &str) -> Option<Duration> {
+        self.budgets
+            .get(tool)
+            .or_else(|| self.budgets.get(ANY_TOOL))
+            .copied()
+    }
+
+    /// Add a call's duration; returns the tool's stats when the call went over budget
+    fn record(&self, tool: &str, elapsed: Duration) -> Option<LatencyStats> {
+        let budget = self.budget(tool);
+        let over = budget.is_some_and(|budget| elapsed > budget);
+        let mut tools = self.tools.lock().unwrap();
+        let samples = tools.entry(tool.to_string()).or_default();
+        if samples.recent.len() >= SAMPLES_PER_TOOL {
+            samples.recent.pop_front();
+        }
+        samples.recent.push_back(elapsed.as_secs_f64() * 1000.0);
+        samples.calls += 1;
+        if over {
+            samples.over_budget += 1;
+        }
+        over.then(|| self.stats_of(tool, samples))
+    }
+
+    fn stats_of(&self, tool: &str, samples: &ToolSamples) -> LatencyStats {
+        let mut sorted: Vec<f64> = samples.recent.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        LatencyStats {
+            tool: tool.to_string(),
+            calls: samples.calls,
+            over_budget: samples.over_budget,
+            budget_ms: self.budget(tool).map(|budget| budget.as_millis() as u64),
+            p50_ms: percentile(&sorted, 0.5),
+            p95_ms: percentile(&sorted, 0.95),
+            p99_ms: percentile(&sorted, 0.99),
+            max_ms: sorted.last().copied().unwrap_or(0.0),
+        }
+    }
+
+    /// Stats for every tool called so far, slowest p95 first
+    pub fn snapshot(&self) -> Vec<LatencyStats> {
+        let tools = self.tools.lock().unwrap();
+        let mut stats: Vec<LatencyStats> = tools
+            .iter()
+            .map(|(tool, samples)| self.stats_of(tool, samples))
+            .collect();
+        stats.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+        stats
+    }
+}
+
+/// `handle_command` hook: record how long `command` took and warn subscribers of the `latency`
+/// topic when it went over its budget
+pub fn record_call<R: Runtime>(app: &AppHandle<R>, command: &str, elapsed: Duration) {
+    let Some(tracker) = app.try_state::<LatencyTracker>() else {
+        return;
+    };
+    let Some(stats) = tracker.record(command, elapsed) else {
+        return;
+    };
+    let duration_ms = elapsed.as_millis() as u64;
+    warn!(
+        "[TAURI_MCP] {} took {}ms, over its {}ms budget (p95 {:.0}ms)",
+        command,
+        duration_ms,
+        stats.budget_ms.unwrap_or_default(),
+        stats.p95_ms
+    );
+    if let Some(hub) = app.try_state::<NotificationHub>() {
+        let mut data = serde_json::to_value(&stats).unwrap_or(Value::Null);
+        if let Some(object) = data.as_object_mut() {
+            object.insert("duration_ms".to_string(), duration_ms.into());
+        }
+        hub.publish(LATENCY_TOPIC, data);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetToolLatencyRequest {
+    /// Only this tool
+    pub tool: Option<String>,
+}
+
+/// Per-tool latency percentiles and how often each tool went over its budget
+pub async fn handle_get_tool_latency<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: GetToolLatencyRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let tracker = app.state::<LatencyTracker>();
+
+    info!("[TAURI_MCP] Reporting tool latency");
+
+    let tools: Vec<LatencyStats> = tracker
+        .snapshot()
+        .into_iter()
+        .filter(|stats| request.tool.as_ref().is_none_or(|tool| &stats.tool == tool))
+        .collect();
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({ "tools": tools })),
+        error: None,
+        ..Default::default()
+    })
+}
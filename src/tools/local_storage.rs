@@ -3,11 +3,12 @@ use serde_json::Value;
 use std::fmt;
 use std::sync::mpsc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Listener, Runtime};
 
 use crate::error::Error;
 use crate::models::LocalStorageRequest;
 use crate::socket_server::SocketResponse;
+use crate::tools::webview_backend;
 
 // Define a custom error type for localStorage operations
 #[derive(Debug)]
@@ -69,6 +70,7 @@ pub async fn handle_get_local_storage<R: Runtime>(
                     success: false,
                     data: None,
                     error: Some("Key is required for remove operations".to_string()),
+                    ..Default::default()
                 });
             }
         }
@@ -78,6 +80,7 @@ pub async fn handle_get_local_storage<R: Runtime>(
                     success: false,
                     data: None,
                     error: Some("Both key and value are required for set operation".to_string()),
+                    ..Default::default()
                 });
             }
         }
@@ -92,6 +95,7 @@ pub async fn handle_get_local_storage<R: Runtime>(
                     "Unsupported localStorage action: {}",
                     params.action
                 )),
+                ..Default::default()
             });
         }
     };
@@ -101,9 +105,9 @@ pub async fn handle_get_local_storage<R: Runtime>(
         .window_label
         .clone()
         .unwrap_or_else(|| "main".to_string());
-    let _window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| Error::Anyhow(format!("Window not found: {}", window_label)))?;
+    if !webview_backend::backend(app)?.has_window(&window_label) {
+        return Err(Error::Anyhow(format!("Window not found: {}", window_label)));
+    }
 
     // Call the implementation function with cloned app handle and params
     let result = perform_local_storage_operation(app.clone(), params.clone()).await;
@@ -117,11 +121,13 @@ pub async fn handle_get_local_storage<R: Runtime>(
                     .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?,
             ),
             error: None,
+            ..Default::default()
         }),
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
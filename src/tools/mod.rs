@@ -1,39 +1,426 @@
 use serde_json::Value;
-use tauri::{AppHandle, Runtime};
+use std::future::ready;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, Runtime};
 use log::info;
 
+use crate::TauriMcpExt;
+use crate::security::Capability;
 use crate::shared::commands;
 use crate::socket_server::SocketResponse;
 
 // Export command modules
+pub mod artifacts;
+pub mod buffer_search;
+pub mod cache;
+pub mod capture_state;
+pub mod confirmation;
+pub mod cpu_profile;
+pub mod elements;
+pub mod event_listeners;
 pub mod execute_js;
+pub mod extraction;
+pub mod frame_timing;
+pub mod frames;
+pub mod heap_snapshot;
+pub mod idle;
+pub mod latency;
 pub mod direct_eval;
+pub mod dry_run;
+pub mod eval_history;
 pub mod local_storage;
 pub mod mouse_movement;
+pub mod performance;
+pub mod nondeterminism;
+#[cfg(feature = "ocr")]
+pub mod ocr;
 pub mod ping;
+pub mod registry;
+pub mod rendering;
+pub mod responsiveness;
+pub mod sensitive_regions;
+pub mod server_status;
+pub mod startup;
+pub mod startup_benchmark;
 pub mod take_screenshot;
+pub mod text_diff;
 pub mod text_input;
+pub mod time_range;
+pub mod timeline;
+pub mod transient_ui;
+#[cfg(feature = "ts")]
+pub mod typescript;
+pub mod triggers;
+pub mod user_scripts;
+pub mod visual_diff;
 pub mod webview;
+pub mod webview_backend;
 pub mod window_manager;
+pub mod window_order;
 pub mod console_capture;
+pub mod content_blocks;
+pub mod context_menu;
+pub mod js_bridge;
+pub mod page_helpers;
+pub mod keyboard_audit;
 
 // Re-export command handler functions
+pub use artifacts::handle_list_artifacts;
+pub use buffer_search::handle_search_buffers;
+pub use cpu_profile::{handle_start_cpu_profile, handle_stop_cpu_profile};
+pub use elements::{
+    handle_dispatch_event, handle_eval_on_element, handle_force_pseudo_state, handle_paste,
+    handle_query_elements,
+};
+pub use eval_history::{handle_get_eval_history, handle_replay_eval};
+pub use event_listeners::handle_get_event_listeners;
 pub use execute_js::handle_execute_js;
-pub use direct_eval::handle_direct_eval;
+pub use extraction::{handle_extract_list, handle_extract_table};
+pub use frame_timing::handle_record_frame_timing;
+pub use frames::handle_list_frames;
+pub use heap_snapshot::handle_capture_heap_snapshot;
+pub use idle::handle_wait_for_idle;
+pub use latency::handle_get_tool_latency;
+pub use direct_eval::{
+    CancelEvalTool, DirectEvalTool, EvalAllWindowsTool, EvalAsyncTool, EvalExpressionTool,
+    EvalInSessionTool, EvalSessionCreateTool, EvalSessionDestroyTool, FetchResultChunkTool,
+    handle_cancel_eval, handle_direct_eval, handle_eval_all_windows, handle_eval_async,
+    handle_eval_expression, handle_eval_in_session, handle_eval_session_create,
+    handle_eval_session_destroy, handle_fetch_result_chunk,
+};
+pub use keyboard_audit::handle_audit_keyboard_navigation;
+#[cfg(feature = "ocr")]
+pub use ocr::handle_ocr_screenshot;
 pub use local_storage::handle_get_local_storage;
 pub use mouse_movement::handle_simulate_mouse_movement;
+pub use nondeterminism::handle_detect_nondeterminism;
+pub use performance::{
+    handle_get_jank_report, handle_get_performance_metrics, handle_get_webview_memory,
+};
 pub use ping::handle_ping;
+pub use visual_diff::{handle_diff_screenshots, handle_screenshot_hash};
+pub use registry::{McpTool, ToolHandler, ToolInfo, ToolRegistry};
+pub use rendering::{handle_control_animation_frames, handle_set_animations};
+pub use server_status::handle_server_status;
+pub use startup::{handle_get_splash_window, handle_wait_for_splash_close};
+pub use startup_benchmark::handle_benchmark_startup;
 pub use take_screenshot::handle_take_screenshot;
+pub use text_diff::handle_diff_text;
 pub use text_input::handle_simulate_text_input;
+pub use timeline::{handle_add_annotation, handle_get_timeline};
+pub use transient_ui::handle_capture_transient_ui;
+pub use user_scripts::handle_inject_script;
 pub use webview::{handle_get_dom, handle_get_element_position, handle_send_text_to_element};
-pub use window_manager::handle_manage_window;
-pub use console_capture::{handle_setup_console_capture, handle_get_js_result, handle_execute_with_console, handle_get_console_buffer};
+pub use window_manager::{
+    handle_manage_window, handle_set_fullscreen, handle_set_kiosk_mode, handle_set_window_style,
+};
+pub use window_order::handle_get_window_z_order;
+pub use context_menu::{handle_get_open_menu_items, handle_open_context_menu};
+pub use console_capture::{handle_setup_console_capture, handle_get_js_result, handle_execute_with_console, handle_get_console_buffer, handle_get_console_delta, handle_get_js_errors, handle_clear_js_errors, handle_clear_console_buffer};
+
+/// Commands whose responses skip redaction. They carry encoded images and hashes of them, which
+/// hooks written for text could only slow down or corrupt. Every other response is redacted,
+/// including those of tools registered with [`ToolRegistry`] or `#[mcp_tool]`.
+const UNREDACTED_COMMANDS: &[&str] = &[
+    commands::TAKE_SCREENSHOT,
+    commands::DIFF_SCREENSHOTS,
+    commands::SCREENSHOT_HASH,
+];
+
+/// Capability a client must hold to run a command. `None` means the command is always allowed.
+pub fn required_capability(command: &str, payload: &Value) -> Option<Capability> {
+    match command {
+        commands::PING => None,
+        // Checked against the held operation instead; see `capability_for`
+        commands::CONFIRM => None,
+        commands::SERVER_STATUS
+        | commands::TAKE_SCREENSHOT
+        | commands::GET_DOM
+        | "setup_console_capture"
+        | "get_console_buffer"
+        | commands::GET_CONSOLE_DELTA
+        | commands::GET_TIMELINE
+        | commands::SEARCH_BUFFERS
+        | commands::GET_JS_ERRORS
+        | commands::CLEAR_JS_ERRORS
+        | commands::CLEAR_CONSOLE_BUFFER
+        | commands::LIST_ARTIFACTS
+        | commands::SUBSCRIBE
+        | commands::UNSUBSCRIBE
+        | commands::CONSOLE_SUBSCRIBE
+        | commands::CONSOLE_UNSUBSCRIBE
+        | commands::ADD_ANNOTATION
+        | commands::WAIT_FOR_IDLE
+        | commands::GET_JANK_REPORT
+        | commands::GET_EVENT_LISTENERS
+        | commands::QUERY_ELEMENTS
+        | commands::EXTRACT_TABLE
+        | commands::EXTRACT_LIST
+        | commands::DIFF_TEXT
+        | commands::GET_OPEN_MENU_ITEMS
+        | commands::DETECT_NONDETERMINISM
+        | commands::LIST_FRAMES
+        | commands::DIFF_SCREENSHOTS
+        | commands::SCREENSHOT_HASH
+        | commands::OCR_SCREENSHOT
+        | commands::RECORD_FRAME_TIMING
+        | commands::GET_WINDOW_Z_ORDER
+        | commands::GET_PERFORMANCE_METRICS
+        | commands::GET_SPLASH_WINDOW
+        | commands::WAIT_FOR_SPLASH_CLOSE
+        | commands::BENCHMARK_STARTUP
+        | commands::GET_TOOL_LATENCY
+        | commands::GET_WEBVIEW_MEMORY
+        | commands::CAPTURE_HEAP_SNAPSHOT
+        | commands::START_CPU_PROFILE
+        | commands::STOP_CPU_PROFILE => Some(Capability::Read),
+        // Looking up an element position may also click it
+        commands::GET_ELEMENT_POSITION => {
+            let clicks = payload
+                .get("should_click")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            Some(if clicks { Capability::Input } else { Capability::Read })
+        }
+        commands::SIMULATE_TEXT_INPUT
+        | commands::SIMULATE_MOUSE_MOVEMENT
+        | commands::SEND_TEXT_TO_ELEMENT
+        | commands::SET_ANIMATIONS
+        | commands::CONTROL_ANIMATION_FRAMES
+        | commands::DISPATCH_EVENT
+        | commands::FORCE_PSEUDO_STATE
+        | commands::PASTE
+        | commands::OPEN_CONTEXT_MENU
+        | commands::CAPTURE_TRANSIENT_UI
+        | commands::AUDIT_KEYBOARD_NAVIGATION => Some(Capability::Input),
+        commands::EXECUTE_JS
+        | "direct_eval"
+        | "eval_async"
+        | "eval_expression"
+        | "eval_all_windows"
+        | "cancel_eval"
+        | "fetch_result_chunk"
+        | "eval_session_create"
+        | "eval_in_session"
+        | "eval_session_destroy"
+        | "execute_with_console"
+        | "get_js_result"
+        | commands::EVAL_ON_ELEMENT
+        | commands::INJECT_SCRIPT
+        | commands::GET_EVAL_HISTORY
+        | commands::REPLAY_EVAL => Some(Capability::Eval),
+        commands::MANAGE_WINDOW
+        | commands::SET_WINDOW_STYLE
+        | commands::SET_FULLSCREEN
+        | commands::SET_KIOSK_MODE => Some(Capability::Window),
+        commands::MANAGE_LOCAL_STORAGE => Some(Capability::Storage),
+        // Unknown commands are rejected by the router; require the broadest grant meanwhile
+        _ => Some(Capability::Eval),
+    }
+}
+
+/// Capability for a command, preferring the one a registered `#[mcp_tool]` declared
+pub fn capability_for<R: Runtime>(
+    app: &AppHandle<R>,
+    command: &str,
+    payload: &Value,
+) -> Option<Capability> {
+    // Confirming an operation takes whatever running it directly would
+    if command == commands::CONFIRM {
+        let held = payload
+            .get("operation_id")
+            .and_then(|v| v.as_str())
+            .and_then(|id| app.try_state::<confirmation::ConfirmationGate>()?.peek(id));
+        return match held {
+            Some((command, payload)) => capability_for(app, &command, &payload),
+            None => None,
+        };
+    }
+    app.try_state::<ToolRegistry<R>>()
+        .and_then(|registry| registry.info(command))
+        .and_then(|info| info.capability)
+        .or_else(|| required_capability(command, payload))
+}
+
+/// Dispatch table holding every built-in tool
+pub fn builtin_tools<R: Runtime>() -> ToolRegistry<R> {
+    let tools = ToolRegistry::default();
+    tools.register(commands::PING, |app, payload| Box::pin(ready(handle_ping(app, payload))));
+    tools.register(commands::SERVER_STATUS, |app, payload| {
+        Box::pin(ready(handle_server_status(app, payload)))
+    });
+    tools.register(commands::TAKE_SCREENSHOT, |app, payload| Box::pin(handle_take_screenshot(app, payload)));
+    tools.register(commands::GET_DOM, |app, payload| Box::pin(handle_get_dom(app, payload)));
+    tools.register(commands::MANAGE_LOCAL_STORAGE, |app, payload| {
+        Box::pin(handle_get_local_storage(app, payload))
+    });
+    tools.register(commands::EXECUTE_JS, |app, payload| Box::pin(handle_execute_js(app, payload)));
+    tools.add::<DirectEvalTool>();
+    tools.add::<EvalAsyncTool>();
+    tools.add::<EvalExpressionTool>();
+    tools.add::<EvalAllWindowsTool>();
+    tools.add::<CancelEvalTool>();
+    tools.add::<FetchResultChunkTool>();
+    tools.add::<EvalSessionCreateTool>();
+    tools.add::<EvalInSessionTool>();
+    tools.add::<EvalSessionDestroyTool>();
+    // Event-based console capture commands
+    tools.register("setup_console_capture", |app, payload| {
+        Box::pin(handle_setup_console_capture(app, payload))
+    });
+    tools.register("get_js_result", |app, payload| Box::pin(handle_get_js_result(app, payload)));
+    tools.register("execute_with_console", |app, payload| {
+        Box::pin(handle_execute_with_console(app, payload))
+    });
+    tools.register("get_console_buffer", |app, payload| {
+        Box::pin(handle_get_console_buffer(app, payload))
+    });
+    tools.register(commands::GET_CONSOLE_DELTA, |app, payload| {
+        Box::pin(handle_get_console_delta(app, payload))
+    });
+    tools.register(commands::GET_TIMELINE, |app, payload| Box::pin(handle_get_timeline(app, payload)));
+    tools.register(commands::SEARCH_BUFFERS, |app, payload| {
+        Box::pin(handle_search_buffers(app, payload))
+    });
+    tools.register(commands::GET_JS_ERRORS, |app, payload| Box::pin(handle_get_js_errors(app, payload)));
+    tools.register(commands::CLEAR_JS_ERRORS, |app, payload| {
+        Box::pin(ready(handle_clear_js_errors(app, payload)))
+    });
+    tools.register(commands::CLEAR_CONSOLE_BUFFER, |app, payload| {
+        Box::pin(handle_clear_console_buffer(app, payload))
+    });
+    tools.register(commands::LIST_ARTIFACTS, |app, payload| {
+        Box::pin(ready(handle_list_artifacts(app, payload)))
+    });
+    tools.register(commands::ADD_ANNOTATION, |app, payload| {
+        Box::pin(ready(handle_add_annotation(app, payload)))
+    });
+    tools.register(commands::WAIT_FOR_IDLE, |app, payload| Box::pin(handle_wait_for_idle(app, payload)));
+    tools.register(commands::LIST_FRAMES, |app, payload| Box::pin(handle_list_frames(app, payload)));
+    tools.register(commands::DIFF_SCREENSHOTS, |app, payload| {
+        Box::pin(handle_diff_screenshots(app, payload))
+    });
+    tools.register(commands::SCREENSHOT_HASH, |app, payload| {
+        Box::pin(handle_screenshot_hash(app, payload))
+    });
+    #[cfg(feature = "ocr")]
+    tools.register(commands::OCR_SCREENSHOT, |app, payload| {
+        Box::pin(handle_ocr_screenshot(app, payload))
+    });
+    tools.register(commands::RECORD_FRAME_TIMING, |app, payload| {
+        Box::pin(handle_record_frame_timing(app, payload))
+    });
+    tools.register(commands::SET_ANIMATIONS, |app, payload| Box::pin(handle_set_animations(app, payload)));
+    tools.register(commands::CONTROL_ANIMATION_FRAMES, |app, payload| {
+        Box::pin(handle_control_animation_frames(app, payload))
+    });
+    tools.register(commands::GET_JANK_REPORT, |app, payload| Box::pin(handle_get_jank_report(app, payload)));
+    tools.register(commands::GET_PERFORMANCE_METRICS, |app, payload| {
+        Box::pin(handle_get_performance_metrics(app, payload))
+    });
+    tools.register(commands::GET_WEBVIEW_MEMORY, |app, payload| {
+        Box::pin(handle_get_webview_memory(app, payload))
+    });
+    tools.register(commands::CAPTURE_HEAP_SNAPSHOT, |app, payload| {
+        Box::pin(handle_capture_heap_snapshot(app, payload))
+    });
+    tools.register(commands::START_CPU_PROFILE, |app, payload| Box::pin(handle_start_cpu_profile(app, payload)));
+    tools.register(commands::STOP_CPU_PROFILE, |app, payload| Box::pin(handle_stop_cpu_profile(app, payload)));
+    tools.register(commands::GET_SPLASH_WINDOW, |app, payload| Box::pin(handle_get_splash_window(app, payload)));
+    tools.register(commands::WAIT_FOR_SPLASH_CLOSE, |app, payload| {
+        Box::pin(handle_wait_for_splash_close(app, payload))
+    });
+    tools.register(commands::BENCHMARK_STARTUP, |app, payload| {
+        Box::pin(handle_benchmark_startup(app, payload))
+    });
+    tools.register(commands::GET_EVENT_LISTENERS, |app, payload| {
+        Box::pin(handle_get_event_listeners(app, payload))
+    });
+    tools.register(commands::DISPATCH_EVENT, |app, payload| Box::pin(handle_dispatch_event(app, payload)));
+    tools.register(commands::FORCE_PSEUDO_STATE, |app, payload| {
+        Box::pin(handle_force_pseudo_state(app, payload))
+    });
+    tools.register(commands::EVAL_ON_ELEMENT, |app, payload| Box::pin(handle_eval_on_element(app, payload)));
+    tools.register(commands::INJECT_SCRIPT, |app, payload| Box::pin(handle_inject_script(app, payload)));
+    tools.register(commands::GET_EVAL_HISTORY, |app, payload| {
+        Box::pin(handle_get_eval_history(app, payload))
+    });
+    tools.register(commands::REPLAY_EVAL, |app, payload| Box::pin(handle_replay_eval(app, payload)));
+    tools.register(commands::GET_TOOL_LATENCY, |app, payload| {
+        Box::pin(handle_get_tool_latency(app, payload))
+    });
+    tools.register(commands::QUERY_ELEMENTS, |app, payload| Box::pin(handle_query_elements(app, payload)));
+    tools.register(commands::EXTRACT_TABLE, |app, payload| Box::pin(handle_extract_table(app, payload)));
+    tools.register(commands::EXTRACT_LIST, |app, payload| Box::pin(handle_extract_list(app, payload)));
+    tools.register(commands::DIFF_TEXT, |app, payload| Box::pin(handle_diff_text(app, payload)));
+    tools.register(commands::PASTE, |app, payload| Box::pin(handle_paste(app, payload)));
+    tools.register(commands::OPEN_CONTEXT_MENU, |app, payload| {
+        Box::pin(handle_open_context_menu(app, payload))
+    });
+    tools.register(commands::GET_OPEN_MENU_ITEMS, |app, payload| {
+        Box::pin(handle_get_open_menu_items(app, payload))
+    });
+    tools.register(commands::CAPTURE_TRANSIENT_UI, |app, payload| {
+        Box::pin(handle_capture_transient_ui(app, payload))
+    });
+    tools.register(commands::AUDIT_KEYBOARD_NAVIGATION, |app, payload| {
+        Box::pin(handle_audit_keyboard_navigation(app, payload))
+    });
+    tools.register(commands::DETECT_NONDETERMINISM, |app, payload| {
+        Box::pin(handle_detect_nondeterminism(app, payload))
+    });
+    tools.register(commands::GET_WINDOW_Z_ORDER, |app, payload| {
+        Box::pin(handle_get_window_z_order(app, payload))
+    });
+    tools.register(commands::MANAGE_WINDOW, |app, payload| Box::pin(handle_manage_window(app, payload)));
+    tools.register(commands::SET_WINDOW_STYLE, |app, payload| {
+        Box::pin(handle_set_window_style(app, payload))
+    });
+    tools.register(commands::SET_FULLSCREEN, |app, payload| Box::pin(handle_set_fullscreen(app, payload)));
+    tools.register(commands::SET_KIOSK_MODE, |app, payload| Box::pin(handle_set_kiosk_mode(app, payload)));
+    tools.register(commands::SIMULATE_TEXT_INPUT, |app, payload| {
+        Box::pin(handle_simulate_text_input(app, payload))
+    });
+    tools.register(commands::SIMULATE_MOUSE_MOVEMENT, |app, payload| {
+        Box::pin(handle_simulate_mouse_movement(app, payload))
+    });
+    tools.register(commands::GET_ELEMENT_POSITION, |app, payload| {
+        Box::pin(handle_get_element_position(app, payload))
+    });
+    tools.register(commands::SEND_TEXT_TO_ELEMENT, |app, payload| {
+        Box::pin(handle_send_text_to_element(app, payload))
+    });
+    tools
+}
 
 /// Handle command routing for socket requests
 pub async fn handle_command<R: Runtime>(
     app: &AppHandle<R>,
     command: &str,
     payload: Value,
+) -> crate::Result<SocketResponse> {
+    if command == commands::CONFIRM {
+        return match confirmation::confirm(app, payload)? {
+            confirmation::Confirmation::Run(command, payload) => {
+                run_command(app, &command, payload, true).await
+            }
+            confirmation::Confirmation::Respond(response) => Ok(response),
+        };
+    }
+    run_command(app, command, payload, false).await
+}
+
+/// Whether a call can change the page or the app, as opposed to only reading it
+pub fn mutates_page<R: Runtime>(app: &AppHandle<R>, command: &str, payload: &Value) -> bool {
+    !matches!(capability_for(app, command, payload), None | Some(Capability::Read))
+}
+
+/// Run a command through the registry. `confirmed` commands skip the confirmation gate.
+async fn run_command<R: Runtime>(
+    app: &AppHandle<R>,
+    command: &str,
+    payload: Value,
+    confirmed: bool,
 ) -> crate::Result<SocketResponse> {
     // Log the full request payload
     info!(
@@ -43,41 +430,65 @@ pub async fn handle_command<R: Runtime>(
             .unwrap_or_else(|_| "[failed to serialize]".to_string())
     );
 
-    let result = match command {
-        commands::PING => handle_ping(app, payload),
-        commands::TAKE_SCREENSHOT => handle_take_screenshot(app, payload).await,
-        commands::GET_DOM => handle_get_dom(app, payload).await,
-        commands::MANAGE_LOCAL_STORAGE => handle_get_local_storage(app, payload).await,
-        commands::EXECUTE_JS => handle_execute_js(app, payload).await,
-        "direct_eval" => match handle_direct_eval(app, payload).await {
-            Ok(json_val) => Ok(SocketResponse {
-                success: true,
-                data: Some(json_val),
-                error: None,
-            }),
-            Err(e) => Ok(SocketResponse {
-                success: false,
-                data: None,
-                error: Some(e.to_string()),
-            }),
-        },
-        // Event-based console capture commands
-        "setup_console_capture" => handle_setup_console_capture(app, payload).await,
-        "get_js_result" => handle_get_js_result(app, payload).await,
-        "execute_with_console" => handle_execute_with_console(app, payload).await,
-        "get_console_buffer" => handle_get_console_buffer(app, payload).await,
-        commands::MANAGE_WINDOW => handle_manage_window(app, payload).await,
-        commands::SIMULATE_TEXT_INPUT => handle_simulate_text_input(app, payload).await,
-        commands::SIMULATE_MOUSE_MOVEMENT => handle_simulate_mouse_movement(app, payload).await,
-        commands::GET_ELEMENT_POSITION => handle_get_element_position(app, payload).await,
-        commands::SEND_TEXT_TO_ELEMENT => handle_send_text_to_element(app, payload).await,
-        _ => Ok(SocketResponse {
+    let cache = app.try_state::<cache::ResponseCache>();
+    let cache_key = cache.as_ref().and_then(|c| c.key_for(command, &payload));
+    if let (Some(cache), Some(key)) = (&cache, &cache_key) {
+        if let Some(hit) = cache.get(key) {
+            info!("[TAURI_MCP] Serving {} from the response cache", command);
+            return Ok(hit);
+        }
+    }
+    let mutates_page = mutates_page(app, command, &payload);
+    // Read-only tools have nothing to hold back, so they ignore `dry_run`
+    let dry_run = mutates_page && dry_run::requested(&payload);
+    // Designated operations wait for a `confirm` call instead of running
+    let gate = app
+        .try_state::<confirmation::ConfirmationGate>()
+        .filter(|gate| !confirmed && !dry_run && gate.requires(command, &payload));
+    let runs = !dry_run && gate.is_none();
+
+    let recorded = (eval_history::RECORDED_COMMANDS.contains(&command) && runs)
+        .then(|| (payload.clone(), (timeline::now_ms(), Instant::now())));
+
+    let registry = app.try_state::<ToolRegistry<R>>();
+    let handler = registry.as_ref().and_then(|registry| registry.get(command));
+    let mut result = match (handler, gate) {
+        (Some(_), _) if dry_run => dry_run::handle_dry_run(app, command, payload).await,
+        (Some(_), Some(gate)) => gate.hold(command, payload),
+        (Some(handler), None) => {
+            let started = Instant::now();
+            let (mut result, correlation_id) = js_bridge::correlate(handler.call(app, payload)).await;
+            latency::record_call(app, command, started.elapsed());
+            if let Ok(response) = result.as_mut() {
+                response.correlation_id = correlation_id;
+            }
+            result
+        }
+        (None, _) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(format!("Unknown command: {}", command)),
+            ..Default::default()
         }),
     };
 
+    // Redact sensitive content before it is logged or sent to the client
+    if let Ok(ref mut response) = result {
+        if !UNREDACTED_COMMANDS.contains(&command) {
+            let redactor = app.tauri_mcp().redactor();
+            if let Some(ref mut data) = response.data {
+                redactor.redact_value(data);
+            }
+            if let Some(ref mut err) = response.error {
+                *err = redactor.redact_str(err);
+            }
+        }
+    }
+
+    if let Some((request, started)) = recorded {
+        eval_history::record_command(app, command, request, &result, started);
+    }
+
     // Log the response before returning it
     if let Ok(ref response) = result {
         let success_str = if response.success {
@@ -118,5 +529,15 @@ pub async fn handle_command<R: Runtime>(
         );
     }
 
+    if let Some(cache) = cache {
+        if mutates_page && runs {
+            cache.clear();
+        } else if let (Some(key), Ok(response)) = (cache_key, &result) {
+            if response.success {
+                cache.insert(key, response.clone());
+            }
+        }
+    }
+
     result
 }
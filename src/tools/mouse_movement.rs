@@ -219,12 +219,14 @@ pub async fn handle_simulate_mouse_movement<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                ..Default::default()
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
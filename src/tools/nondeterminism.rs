@@ -0,0 +1,125 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct NondeterminismRequest {
+    pub window_label: Option<String>,
+    /// Pending timers with at least this delay are reported (default 1000ms)
+    pub long_timer_ms: Option<u64>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Flag likely sources of flakiness: long timers, running animations, randomness and clock-dependent text.
+/// Timers, randomness and clock reads are tracked from the first call in a page, reported as
+/// `tracked_since`; call it once before the flow under test to cover all of it.
+pub async fn handle_detect_nondeterminism<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: NondeterminismRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Detecting nondeterminism in window: {}", window_label);
+
+    // Timer and entropy tracking is opt-in capture instrumentation; the first call turns it on,
+    // so its counts cover what happened since then
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label,
+        "track_nondeterminism": true
+    })).await?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let code = format!(
+        r#"
+        const longTimerMs = {long_timer_ms};
+        const now = Date.now();
+        const findings = [];
+
+        const timers = Array.from((window.__mcpTimers || new Map()).entries())
+            .map(([id, timer]) => ({{
+                id: typeof id === 'number' ? id : String(id),
+                kind: timer.kind,
+                delay_ms: timer.delay,
+                age_ms: now - timer.created
+            }}));
+        const longTimers = timers.filter(t => t.delay_ms >= longTimerMs);
+        const intervals = timers.filter(t => t.kind === 'interval');
+        if (longTimers.length) findings.push({{ kind: 'long_timers', message: longTimers.length + ' pending timer(s) with delays of ' + longTimerMs + 'ms or more' }});
+        if (intervals.length) findings.push({{ kind: 'intervals', message: intervals.length + ' active interval(s) keep changing state' }});
+
+        const animations = document.getAnimations ? document.getAnimations().filter(a => a.playState === 'running') : [];
+        const infinite = animations.filter(a => {{
+            const timing = a.effect && a.effect.getComputedTiming ? a.effect.getComputedTiming() : null;
+            return timing && timing.iterations === Infinity;
+        }});
+        if (animations.length) findings.push({{ kind: 'animations', message: animations.length + ' running animation(s), ' + infinite.length + ' infinite' }});
+
+        const entropy = window.__mcpEntropy || {{ random_calls: 0, date_calls: 0, random_sites: {{}}, date_sites: {{}} }};
+        const topSites = (sites) => Object.entries(sites).sort((a, b) => b[1] - a[1]).slice(0, 10).map(([site, count]) => ({{ site: site, samples: count }}));
+        if (entropy.random_calls) findings.push({{ kind: 'random', message: 'Math.random was called ' + entropy.random_calls + ' time(s) without a seed' }});
+        if (entropy.date_calls) findings.push({{ kind: 'clock', message: 'The current time was read ' + entropy.date_calls + ' time(s)' }});
+
+        // Visible text that looks derived from the current date or time
+        const today = new Date();
+        const markers = [
+            {{ label: 'current year', pattern: new RegExp('\\b' + today.getFullYear() + '\\b') }},
+            {{ label: 'clock time', pattern: /\b\d{{1,2}}:\d{{2}}(:\d{{2}})?\s?(am|pm)?\b/i }},
+            {{ label: 'relative time', pattern: /\b(\d+|a|an)\s+(second|minute|hour|day|week|month|year)s?\s+ago\b|\bjust now\b|\btoday\b|\byesterday\b/i }},
+            {{ label: 'today\'s date', pattern: new RegExp(today.toLocaleDateString().replace(/[.*+?^${{}}()|[\]\\]/g, '\\$&')) }}
+        ];
+        const dateMarkers = [];
+        const walker = document.createTreeWalker(document.body || document.documentElement, NodeFilter.SHOW_TEXT);
+        while (walker.nextNode() && dateMarkers.length < 50) {{
+            const text = walker.currentNode.textContent.trim();
+            if (!text) continue;
+            for (const marker of markers) {{
+                const match = text.match(marker.pattern);
+                if (match) {{
+                    const parent = walker.currentNode.parentElement;
+                    dateMarkers.push({{
+                        marker: marker.label,
+                        text: match[0],
+                        element: parent ? parent.tagName.toLowerCase() + (parent.id ? '#' + parent.id : '') : null
+                    }});
+                    break;
+                }}
+            }}
+        }}
+        document.querySelectorAll('time').forEach(node => {{
+            if (dateMarkers.length < 50) dateMarkers.push({{ marker: '<time> element', text: node.textContent.trim(), element: 'time' }});
+        }});
+        if (dateMarkers.length) findings.push({{ kind: 'date_rendering', message: dateMarkers.length + ' visible text marker(s) depend on the current date or time' }});
+
+        return {{
+            findings: findings,
+            timers: {{ pending: timers.length, long: longTimers, intervals: intervals }},
+            animations: {{ running: animations.length, infinite: infinite.length }},
+            random: {{ calls: entropy.random_calls, top_call_sites: topSites(entropy.random_sites) }},
+            clock: {{ calls: entropy.date_calls, top_call_sites: topSites(entropy.date_sites) }},
+            tracked_since: window.__mcpEntropySince || null,
+            date_markers: dateMarkers
+        }};
+        "#,
+        long_timer_ms = request.long_timer_ms.unwrap_or(1000)
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,158 @@
+use rusty_tesseract::{Args, Image};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::visual_diff::{MaskRect, capture_area};
+
+static NEXT_CAPTURE_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Deserialize)]
+pub struct OcrScreenshotRequest {
+    pub window_label: Option<String>,
+    /// Read only the first element matching this selector
+    pub selector: Option<String>,
+    /// Read only this area, in pixels of the capture (after cropping to `selector`)
+    pub region: Option<MaskRect>,
+    /// Tesseract language codes, e.g. `eng` or `eng+deu` (default `eng`)
+    pub lang: Option<String>,
+    /// Words recognized with less confidence than this (0-100) are left out (default 50)
+    pub min_confidence: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OcrWord {
+    pub text: String,
+    /// Box in pixels of the capture, relative to `selector`/`region` when given
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub confidence: f32,
+    /// Words sharing a line number were read as one line
+    pub line: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OcrScreenshotResponse {
+    /// Recognized words joined with spaces, one line of text per line
+    pub text: String,
+    pub words: Vec<OcrWord>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Run Tesseract over a capture. `image_to_data` reads from a file, so the capture goes through
+/// a temporary PNG.
+fn recognize(
+    image: &image::DynamicImage,
+    lang: &str,
+    min_confidence: f32,
+) -> crate::Result<Vec<OcrWord>> {
+    let path = std::env::temp_dir().join(format!(
+        "tauri-mcp-ocr-{}-{}.png",
+        std::process::id(),
+        NEXT_CAPTURE_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    image
+        .save(&path)
+        .map_err(|e| Error::Anyhow(format!("Failed to write OCR input: {}", e)))?;
+
+    let args = Args {
+        lang: lang.to_string(),
+        ..Args::default()
+    };
+    let result = Image::from_path(&path).and_then(|input| rusty_tesseract::image_to_data(&input, &args));
+    let _ = std::fs::remove_file(&path);
+    let data = result.map_err(|e| Error::Anyhow(format!("OCR failed (is tesseract installed?): {}", e)))?;
+
+    // Line numbers restart in every block and paragraph; renumber them across the page
+    let mut words = Vec::new();
+    let mut current_line = None;
+    let mut line = 0;
+    for entry in data.data {
+        let text = entry.text.trim();
+        if text.is_empty() || entry.conf < min_confidence {
+            continue;
+        }
+        let key = (entry.block_num, entry.par_num, entry.line_num);
+        if current_line != Some(key) {
+            if current_line.is_some() {
+                line += 1;
+            }
+            current_line = Some(key);
+        }
+        words.push(OcrWord {
+            text: text.to_string(),
+            x: entry.left,
+            y: entry.top,
+            width: entry.width,
+            height: entry.height,
+            confidence: entry.conf,
+            line,
+        });
+    }
+    Ok(words)
+}
+
+fn joined_text(words: &[OcrWord]) -> String {
+    let mut text = String::new();
+    for (index, word) in words.iter().enumerate() {
+        if index > 0 {
+            text.push(if words[index - 1].line == word.line { ' ' } else { '\n' });
+        }
+        text.push_str(&word.text);
+    }
+    text
+}
+
+/// Recognize text in a window, element or region, for text the DOM doesn't hold (canvas,
+/// images). Needs the `ocr` feature and the `tesseract` executable on the PATH.
+pub async fn handle_ocr_screenshot<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: OcrScreenshotRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let lang = request.lang.unwrap_or_else(|| "eng".to_string());
+    let min_confidence = request.min_confidence.unwrap_or(50.0);
+
+    info!("[TAURI_MCP] Running OCR on {} ({})", window_label, lang);
+
+    let result = match capture_area(app, &window_label, request.selector.as_deref(), request.region).await {
+        Ok(image) => tauri::async_runtime::spawn_blocking(move || {
+            let words = recognize(&image, &lang, min_confidence)?;
+            Ok::<_, Error>(OcrScreenshotResponse {
+                text: joined_text(&words),
+                words,
+                width: image.width(),
+                height: image.height(),
+            })
+        })
+        .await
+        .map_err(|e| Error::Anyhow(format!("OCR failed: {}", e)))?,
+        Err(e) => Err(e),
+    };
+
+    Ok(match result {
+        Ok(response) => SocketResponse {
+            success: true,
+            data: Some(serde_json::to_value(response).map_err(|e| {
+                Error::Anyhow(format!("Failed to serialize response: {}", e))
+            })?),
+            error: None,
+            ..Default::default()
+        },
+        Err(e) => SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            ..Default::default()
+        },
+    })
+}
@@ -0,0 +1,456 @@
+use std::sync::OnceLock;
+
+use crate::error::Error;
+use crate::tools::console_capture::{self, CaptureDefaults};
+use crate::tools::webview_backend::WebviewBackend;
+
+/// How the helper bundle and capture script reach a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InjectionMode {
+    /// Evaluate them into the page the first time a tool needs them
+    #[default]
+    Eval,
+    /// Register them as initialization scripts that run before the page's own scripts on every
+    /// load. Use this for pages whose Content-Security-Policy blocks evaluated scripts.
+    InitScript,
+}
+
+/// Bump whenever [`HELPERS_BUNDLE`] changes so pages holding an older copy are upgraded
+pub const HELPERS_VERSION: u32 = 7;
+
+/// Shared page-side helpers, installed once per page as `window.__mcp`. Tool scripts call into
+/// this instead of carrying their own copies of the event bridge, handle registry and serializers.
+///
+/// Runs inside a function taking `version`; see [`install_script`].
+const HELPERS_BUNDLE: &str = r#"
+    if (window.__mcp && window.__mcp.version >= version) return;
+
+    // Post an event back to Rust whether or not `withGlobalTauri` is enabled
+    const emit = (event, payload) => (window.__TAURI__ && window.__TAURI__.event)
+        ? window.__TAURI__.event.emit(event, payload)
+        : window.__TAURI_INTERNALS__.invoke('plugin:event|emit', { event, payload });
+
+    // Opaque element handles backed by WeakRefs. The registry survives bundle upgrades
+    // so handles already given to clients stay valid.
+    const handles = window.__mcpHandles || { next: 1, refs: new Map(), ids: new WeakMap() };
+    handles.handleFor = function(node) {
+        let id = handles.ids.get(node);
+        if (!id || !handles.refs.has(id)) {
+            id = 'el-' + (handles.next++);
+            handles.ids.set(node, id);
+            handles.refs.set(id, new WeakRef(node));
+        }
+        return id;
+    };
+    handles.resolve = function(id) {
+        const ref = handles.refs.get(id);
+        const node = ref && ref.deref();
+        if (!node || !node.isConnected) {
+            handles.refs.delete(id);
+            throw new Error('Element handle ' + id + ' is stale or unknown');
+        }
+        return node;
+    };
+    window.__mcpHandles = handles;
+
+    // Resolve `{ handle }` or `{ selector }`, throwing if nothing matches
+    const find = (target) => {
+        if (target.handle) return handles.resolve(target.handle);
+        const element = document.querySelector(target.selector);
+        if (!element) throw new Error('No element matches selector ' + target.selector);
+        return element;
+    };
+
+    const bounds = (node) => {
+        const rect = node.getBoundingClientRect();
+        return { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+    };
+
+    const center = (node) => {
+        const rect = node.getBoundingClientRect();
+        return { x: rect.left + rect.width / 2, y: rect.top + rect.height / 2 };
+    };
+
+    const isVisible = (node) => {
+        const rect = node.getBoundingClientRect();
+        const style = getComputedStyle(node);
+        return rect.width > 0 && rect.height > 0 && style.visibility !== 'hidden' && style.display !== 'none';
+    };
+
+    const text = (node, max) => {
+        const value = (node.innerText || node.textContent || '').trim();
+        return max ? value.slice(0, max) : value;
+    };
+
+    // Short `tag#id.class` label for elements, `window` and `document`
+    const describe = (target) => {
+        if (target === window) return 'window';
+        if (target === document) return 'document';
+        if (!target || !target.tagName) return String(target);
+        let label = target.tagName.toLowerCase();
+        if (target.id) label += '#' + target.id;
+        if (typeof target.className === 'string' && target.className.trim()) {
+            label += '.' + target.className.trim().split(/\s+/).join('.');
+        }
+        return label;
+    };
+
+    // Every frame below this page, depth first. Cross-origin frames are listed without a window.
+    const frames = () => {
+        const found = [];
+        const walk = (doc, path) => {
+            Array.prototype.forEach.call(doc.querySelectorAll('iframe, frame'), (element, index) => {
+                let frameWindow = null;
+                let url = element.src || null;
+                try {
+                    url = element.contentWindow.location.href;
+                    frameWindow = element.contentWindow.document ? element.contentWindow : null;
+                } catch (e) {}
+                const entry = { element, window: frameWindow, path: path.concat(index), url };
+                found.push(entry);
+                if (frameWindow) walk(frameWindow.document, entry.path);
+            });
+        };
+        walk(document, []);
+        return found;
+    };
+
+    // Window of the frame matching `{ frame_selector }` or `{ frame_url }` (exact or prefix).
+    // Selectors are tried against this document first, then inside same-origin frames.
+    const frameWindow = (target) => {
+        const all = frames();
+        const match = target.frame_selector
+            ? (all.find(frame => frame.element.matches(target.frame_selector) && frame.path.length === 1) ||
+               all.find(frame => frame.element.matches(target.frame_selector)))
+            : all.find(frame => frame.url && (frame.url === target.frame_url || frame.url.startsWith(target.frame_url)));
+        const wanted = target.frame_selector || target.frame_url;
+        if (!match) throw new Error('No frame matches ' + wanted);
+        if (!match.window) throw new Error('Frame ' + wanted + ' is cross-origin; scripts can only run in same-origin frames');
+        return match.window;
+    };
+
+    // Window of a hidden same-origin frame for `isolated` evaluations. Its globals and built-ins
+    // are its own, so code run there doesn't touch the page's `window` and the page's patches to
+    // its built-ins don't reach it. Created on first use and again if the page removes it;
+    // `create === false` only returns an existing one.
+    let realmFrame = null;
+    const isolatedRealm = (create) => {
+        if (realmFrame && realmFrame.isConnected && realmFrame.contentWindow) return realmFrame.contentWindow;
+        if (create === false) return null;
+        realmFrame = document.createElement('iframe');
+        realmFrame.setAttribute('data-mcp-realm', '');
+        realmFrame.setAttribute('aria-hidden', 'true');
+        realmFrame.tabIndex = -1;
+        realmFrame.style.cssText = 'display: none !important';
+        (document.body || document.documentElement).appendChild(realmFrame);
+        return realmFrame.contentWindow;
+    };
+
+    // `{ data, mimeType }` (base64) for a canvas, image, video frame, ImageBitmap, ImageData or
+    // image Blob, and null for any other value. Pixel sources are encoded as PNG; Blobs keep
+    // their own type. Reading a canvas tainted by cross-origin images throws.
+    const imageData = async (value) => {
+        const kind = value == null ? '' : Object.prototype.toString.call(value).slice(8, -1);
+        const fromDataUrl = (url) => {
+            const match = /^data:([^;,]+)[^,]*,(.*)$/.exec(url);
+            return match ? { data: match[2], mimeType: match[1] } : null;
+        };
+        const fromBlob = (blob) => new Promise((resolve, reject) => {
+            const reader = new FileReader();
+            reader.onload = () => resolve(fromDataUrl(reader.result));
+            reader.onerror = () => reject(reader.error);
+            reader.readAsDataURL(blob);
+        });
+        if (kind === 'Blob' || kind === 'File') return /^image\//.test(value.type) ? fromBlob(value) : null;
+        if (kind === 'HTMLCanvasElement') return fromDataUrl(value.toDataURL('image/png'));
+        if (kind === 'OffscreenCanvas') return fromBlob(await value.convertToBlob({ type: 'image/png' }));
+        if (!['ImageBitmap', 'ImageData', 'HTMLImageElement', 'HTMLVideoElement'].includes(kind)) return null;
+        const canvas = document.createElement('canvas');
+        canvas.width = value.naturalWidth || value.videoWidth || value.width;
+        canvas.height = value.naturalHeight || value.videoHeight || value.height;
+        const context = canvas.getContext('2d');
+        if (kind === 'ImageData') context.putImageData(value, 0, 0);
+        else context.drawImage(value, 0, 0);
+        return fromDataUrl(canvas.toDataURL('image/png'));
+    };
+
+    // JSON form of any value, modelled on structured clone. JSON-safe values come back as
+    // themselves; everything else is an object tagged with `$type`. Objects reached a second
+    // time (including cycles) become `{ $type: 'Ref', path }` pointing at their first position.
+    // Types are checked by tag rather than instanceof so values from other frames work too.
+    const serialize = (root, options) => {
+        const maxDepth = (options && options.maxDepth) || 20;
+        const maxItems = (options && options.maxItems) || 1000;
+        const seen = new Map();
+        const kindOf = (value) => Object.prototype.toString.call(value).slice(8, -1);
+        const keyPath = (key) => /^[A-Za-z_$][\w$]*$/.test(key) ? '.' + key : '[' + JSON.stringify(key) + ']';
+        const walk = (value, path, depth) => {
+            if (value === null) return null;
+            switch (typeof value) {
+                case 'undefined': return { $type: 'undefined' };
+                case 'boolean':
+                case 'string': return value;
+                case 'number':
+                    if (!Number.isFinite(value)) return { $type: 'Number', value: String(value) };
+                    return Object.is(value, -0) ? { $type: 'Number', value: '-0' } : value;
+                case 'bigint': return { $type: 'BigInt', value: value.toString() };
+                case 'symbol': return { $type: 'Symbol', description: value.description === undefined ? null : value.description };
+                case 'function': return { $type: 'Function', name: value.name || null };
+            }
+            if (seen.has(value)) return { $type: 'Ref', path: seen.get(value) };
+            seen.set(value, path);
+            const kind = kindOf(value);
+            if (value === value.window) return { $type: 'Window', url: String(value.location && value.location.href) };
+            if (typeof value.nodeType === 'number' && typeof value.nodeName === 'string') {
+                return { $type: 'Node', description: describe(value) };
+            }
+            if (kind === 'Date') return { $type: 'Date', value: isNaN(value.getTime()) ? null : value.toISOString() };
+            if (kind === 'RegExp') return { $type: 'RegExp', source: value.source, flags: value.flags };
+            if (kind === 'Error' || value instanceof Error) {
+                return { $type: 'Error', name: value.name, message: value.message, stack: value.stack || null };
+            }
+            if (kind === 'Promise' || kind === 'WeakMap' || kind === 'WeakSet' || kind === 'WeakRef') return { $type: kind };
+            if (kind === 'ArrayBuffer' || kind === 'SharedArrayBuffer') return { $type: kind, byteLength: value.byteLength };
+            if (depth >= maxDepth) return { $type: 'Truncated', kind: kind };
+            const items = (list, prefix) => {
+                const out = list.slice(0, maxItems).map((item, index) => walk(item, path + prefix + '[' + index + ']', depth + 1));
+                if (list.length > maxItems) out.push({ $type: 'Truncated', remaining: list.length - maxItems });
+                return out;
+            };
+            if (kind === 'Map') {
+                const entries = Array.from(value).slice(0, maxItems).map((entry, index) => [
+                    walk(entry[0], path + '.entries[' + index + '][0]', depth + 1),
+                    walk(entry[1], path + '.entries[' + index + '][1]', depth + 1)
+                ]);
+                return { $type: 'Map', size: value.size, entries };
+            }
+            if (kind === 'Set') return { $type: 'Set', size: value.size, values: items(Array.from(value), '.values') };
+            if (ArrayBuffer.isView(value) && kind !== 'DataView') {
+                return { $type: kind, length: value.length, values: items(Array.from(value), '.values') };
+            }
+            if (Array.isArray(value)) return items(value, '');
+            const out = {};
+            const keys = Object.keys(value);
+            keys.slice(0, maxItems).forEach(key => {
+                let item;
+                try {
+                    item = value[key];
+                } catch (e) {
+                    out[key] = { $type: 'Thrown', message: (e && e.message) ? e.message : String(e) };
+                    return;
+                }
+                out[key] = walk(item, path + keyPath(key), depth + 1);
+            });
+            if (keys.length > maxItems) out.$truncated = keys.length - maxItems;
+            return out;
+        };
+        return walk(root, '$', 0);
+    };
+
+    // `url:line:column` at the end of a stack frame, in either V8 (`at f (url:1:2)`, or
+    // `(eval at g (url:3:4), <anonymous>:1:2)` for compiled code) or JavaScriptCore (`f@url:1:2`) form
+    const stackLocation = (frame) => {
+        const match = /(?:\(|@|\s|^)([^()@\s]*):(\d+):(\d+)\)?\s*$/.exec(frame);
+        return match && { url: match[1], line: Number(match[2]), column: Number(match[3]), text: match[1] + ':' + match[2] + ':' + match[3] };
+    };
+
+    // Source map `mappings` decoded into, per generated line, [column, source, line, column]
+    // segments with zero-based positions
+    const BASE64 = 'ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/';
+    const decodeMappings = (mappings) => {
+        let source = 0, sourceLine = 0, sourceColumn = 0;
+        return mappings.split(';').map(text => {
+            let column = 0;
+            const segments = [];
+            text.split(',').forEach(segment => {
+                if (!segment) return;
+                const values = [];
+                let value = 0, shift = 0;
+                for (const char of segment) {
+                    const digit = BASE64.indexOf(char);
+                    value += (digit & 31) * Math.pow(2, shift);
+                    if (digit & 32) {
+                        shift += 5;
+                    } else {
+                        values.push(value % 2 ? -(value - 1) / 2 : value / 2);
+                        value = 0;
+                        shift = 0;
+                    }
+                }
+                column += values[0];
+                if (values.length >= 4) {
+                    source += values[1];
+                    sourceLine += values[2];
+                    sourceColumn += values[3];
+                    segments.push([column, source, sourceLine, sourceColumn]);
+                }
+            });
+            return segments;
+        });
+    };
+
+    // Source map published by a script through `//# sourceMappingURL`, fetched once per page.
+    // Resolves to null when the script has none or it can't be fetched.
+    const sourceMaps = window.__mcpSourceMaps || new Map();
+    window.__mcpSourceMaps = sourceMaps;
+    const sourceMapFor = (url) => {
+        if (!sourceMaps.has(url)) {
+            sourceMaps.set(url, (async () => {
+                const script = await (await fetch(url)).text();
+                const references = script.match(/\/\/[#@]\s*sourceMappingURL=\S+/g);
+                if (!references) return null;
+                const reference = references[references.length - 1].replace(/^\/\/[#@]\s*sourceMappingURL=/, '');
+                const map = reference.startsWith('data:')
+                    ? JSON.parse(atob(reference.slice(reference.indexOf(',') + 1)))
+                    : await (await fetch(new URL(reference, url).href)).json();
+                return { sources: map.sources.map(source => (map.sourceRoot || '') + source), lines: decodeMappings(map.mappings) };
+            })().catch(() => null));
+        }
+        return sourceMaps.get(url);
+    };
+
+    const originalLocation = async (location) => {
+        const map = await sourceMapFor(location.url);
+        const segments = map && map.lines[location.line - 1];
+        if (!segments) return null;
+        let found = null;
+        for (const segment of segments) {
+            if (segment[0] > location.column - 1) break;
+            found = segment;
+        }
+        return found && map.sources[found[1]] + ':' + (found[2] + 1) + ':' + (found[3] + 1);
+    };
+
+    // Rewrite an error stack so frames inside evaluated code read `<eval>:line:column`, counted
+    // from the code's first line. `markStack` is the stack of an Error created on the line just
+    // before the code, which tells where the code starts whatever wrapping it went through. With
+    // `useSourceMaps`, frames in the app's own scripts are mapped to their original sources.
+    // Also returns the first frame in the evaluated code (or else the first mapped frame).
+    const mapStack = async (stack, markStack, codeLines, useSourceMaps) => {
+        const mark = (markStack || '').split('\n').map(stackLocation).find(Boolean);
+        let location = null, mappedLocation = null;
+        const frames = [];
+        for (const frame of stack.split('\n')) {
+            const at = stackLocation(frame);
+            let replacement = null;
+            if (at && mark && at.url === mark.url && at.line > mark.line && at.line <= mark.line + codeLines) {
+                replacement = '<eval>:' + (at.line - mark.line) + ':' + at.column;
+                location = location || replacement;
+            } else if (at && useSourceMaps && /^[a-z][\w+.-]*:\/\//i.test(at.url)) {
+                replacement = await originalLocation(at);
+                mappedLocation = mappedLocation || replacement;
+            }
+            frames.push(replacement ? frame.slice(0, frame.lastIndexOf(at.text)) + replacement + frame.slice(frame.lastIndexOf(at.text) + at.text.length) : frame);
+        }
+        return { stack: frames.join('\n'), location: location || mappedLocation };
+    };
+
+    // Sensitive regions declared by the host app, passed in as `{ selectors, mask }`. Masking
+    // filters the live elements until `unmaskRegions`, waiting for a paint (or 100ms in a hidden
+    // window) so native captures see it. Earlier values are restored newest first, so an element
+    // matched by two selectors gets its own filter back.
+    const masked = window.__mcpMaskedRegions || [];
+    window.__mcpMaskedRegions = masked;
+    const unmaskRegions = () => {
+        masked.splice(0).reverse().forEach(([element, value, priority]) => {
+            if (value) element.style.setProperty('filter', value, priority);
+            else element.style.removeProperty('filter');
+        });
+    };
+    const maskRegions = async (regions) => {
+        unmaskRegions();
+        const filter = regions.mask === 'omit' ? 'brightness(0)' : 'blur(16px)';
+        regions.selectors.forEach(selector => document.querySelectorAll(selector).forEach(element => {
+            masked.push([element, element.style.getPropertyValue('filter'), element.style.getPropertyPriority('filter')]);
+            element.style.setProperty('filter', filter, 'important');
+        }));
+        await Promise.race([
+            new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(resolve))),
+            new Promise(resolve => setTimeout(resolve, 100))
+        ]);
+        return masked.length;
+    };
+    const regionMatches = (root, selector) => {
+        const matches = Array.from(root.querySelectorAll(selector));
+        if (root.matches && root.matches(selector)) matches.push(root);
+        return matches;
+    };
+    // Markup of `root` with sensitive elements emptied down to their id and class
+    const redactedHtml = (root, regions) => {
+        if (!regions || !regions.selectors.length) return root.outerHTML;
+        const clone = root.cloneNode(true);
+        regions.selectors.forEach(selector => regionMatches(clone, selector).forEach(element => {
+            Array.from(element.attributes).forEach(attribute => {
+                if (attribute.name !== 'id' && attribute.name !== 'class') element.removeAttribute(attribute.name);
+            });
+            element.setAttribute('data-mcp-redacted', '');
+            element.textContent = '[redacted]';
+        }));
+        return clone.outerHTML;
+    };
+    // Rendered text of `element` with every line shown by a sensitive element replaced
+    const redactedText = (element, regions) => {
+        let value = element.innerText || element.textContent || '';
+        if (!regions || !regions.selectors.length) return value;
+        if (regions.selectors.some(selector => element.closest && element.closest(selector))) return '[redacted]';
+        regions.selectors.forEach(selector => regionMatches(element, selector).forEach(region => {
+            (region.innerText || region.textContent || '').split('\n').map(line => line.trim()).filter(Boolean)
+                .forEach(line => { value = value.split(line).join('[redacted]'); });
+        }));
+        return value;
+    };
+
+    window.__mcp = { version, emit, handles, find, bounds, center, isVisible, text, describe, frames, frameWindow, isolatedRealm, imageData, serialize, mapStack, maskRegions, unmaskRegions, redactedHtml, redactedText };
+"#;
+
+/// Script installing the helper bundle. Safe to evaluate repeatedly; pages that already hold
+/// this version or newer are left alone.
+pub fn install_script() -> &'static str {
+    static SCRIPT: OnceLock<String> = OnceLock::new();
+    SCRIPT.get_or_init(|| {
+        format!(
+            "(function(version) {{\n{}\n}})({});",
+            minify(HELPERS_BUNDLE),
+            HELPERS_VERSION
+        )
+    })
+}
+
+/// Initialization script for [`InjectionMode::InitScript`]: capture settings, helpers and capture
+pub fn init_script(trigger_kinds: &[&str], defaults: &CaptureDefaults) -> String {
+    format!(
+        "window.__mcpTriggerKinds = {};\n{}\n{}\n{}\n{}\n{};",
+        serde_json::to_string(trigger_kinds).unwrap_or_else(|_| "[]".to_string()),
+        defaults.min_level.map(console_capture::min_level_script).unwrap_or_default(),
+        console_capture::sampling_script(&defaults.sampling),
+        console_capture::limits_script(&defaults.limits),
+        install_script(),
+        minify(console_capture::CAPTURE_SCRIPT)
+    )
+}
+
+/// Strip indentation, blank lines and whole-line `//` comments. Line breaks are kept so
+/// automatic semicolon insertion behaves exactly as in the source.
+pub fn minify(script: &str) -> String {
+    script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// JavaScript condition that is true when the page lacks the current helper bundle
+pub fn missing_check() -> String {
+    format!(
+        "(!window.__mcp || window.__mcp.version < {})",
+        HELPERS_VERSION
+    )
+}
+
+/// Install the helper bundle into a window's current page
+pub fn install(backend: &dyn WebviewBackend, window_label: &str) -> crate::Result<()> {
+    backend
+        .eval(window_label, install_script())
+        .map_err(|e| Error::Anyhow(format!("Failed to install page helpers: {}", e)))
+}
@@ -0,0 +1,373 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::platform::shared::WebviewProcess;
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::startup::{SplashWindowInfo, StartupTracker, splash_info};
+
+#[derive(Debug, Deserialize)]
+pub struct JankReportRequest {
+    pub window_label: Option<String>,
+    /// Only consider activity from the last `window_ms` milliseconds (default 30000)
+    pub window_ms: Option<u64>,
+    /// Number of worst offenders to include per list (default 10)
+    pub top: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LongTask {
+    /// Milliseconds since navigation start
+    pub start: f64,
+    pub duration: f64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlowEvent {
+    /// Milliseconds since navigation start
+    pub start: f64,
+    pub duration: f64,
+    pub processing_ms: f64,
+    pub input_delay_ms: f64,
+    pub name: String,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JankReport {
+    pub window_ms: u64,
+    pub long_task_count: usize,
+    /// Sum of the time each long task exceeded the 50ms budget
+    pub total_blocking_ms: f64,
+    pub max_long_task_ms: f64,
+    pub slow_event_count: usize,
+    pub max_event_duration_ms: f64,
+    pub worst_long_tasks: Vec<LongTask>,
+    pub worst_events: Vec<SlowEvent>,
+}
+
+/// Summarize long tasks and slow event handling recorded by the capture script
+pub async fn handle_get_jank_report<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: JankReportRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let window_ms = request.window_ms.unwrap_or(30_000);
+    let top = request.top.unwrap_or(10);
+
+    info!(
+        "[TAURI_MCP] Building jank report for the last {}ms in window: {}",
+        window_ms, window_label
+    );
+
+    let setup_result = handle_setup_console_capture(app, serde_json::json!({
+        "window_label": window_label
+    })).await?;
+    if !setup_result.success {
+        return Ok(setup_result);
+    }
+
+    let code = format!(r#"
+        const cutoff = performance.now() - {window_ms};
+        const recent = (items) => (items || []).filter(item => item.start >= cutoff);
+        const worst = (items) => items.slice().sort((a, b) => b.duration - a.duration).slice(0, {top});
+        const longTasks = recent(window.__mcpLongTasks);
+        const events = recent(window.__mcpEventTimings);
+        return {{
+            window_ms: {window_ms},
+            long_task_count: longTasks.length,
+            total_blocking_ms: longTasks.reduce((sum, task) => sum + Math.max(0, task.duration - 50), 0),
+            max_long_task_ms: longTasks.reduce((max, task) => Math.max(max, task.duration), 0),
+            slow_event_count: events.length,
+            max_event_duration_ms: events.reduce((max, event) => Math.max(max, event.duration), 0),
+            worst_long_tasks: worst(longTasks),
+            worst_events: worst(events)
+        }};
+    "#);
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let value = eval_with_result(app, &window_label, &code, timeout).await?;
+    let report: JankReport = serde_json::from_value(value)
+        .map_err(|e| crate::Error::Anyhow(format!("Failed to parse jank report: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(report).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PerformanceMetricsRequest {
+    /// The main window, whose page timings are reported and whose readiness ends startup
+    /// (default "main")
+    pub window_label: Option<String>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Navigation timings of the window's current document, in milliseconds since navigation start
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageTimings {
+    /// Milliseconds since the Unix epoch at which the document's navigation started
+    pub time_origin_ms: f64,
+    pub dom_interactive_ms: Option<f64>,
+    pub dom_content_loaded_ms: Option<f64>,
+    pub load_ms: Option<f64>,
+    pub first_paint_ms: Option<f64>,
+    pub first_contentful_paint_ms: Option<f64>,
+}
+
+/// Startup milestones, all in milliseconds since the Unix epoch except the durations
+#[derive(Debug, Serialize)]
+pub struct StartupMetrics {
+    /// When the plugin was initialized, shortly after the process started
+    pub launched_ms: u64,
+    pub splash: Option<SplashWindowInfo>,
+    pub main_window_created_ms: Option<u64>,
+    /// When the main window's document became interactive. Only known while the main window
+    /// still shows its first document; later navigations don't count as startup.
+    pub main_interactive_ms: Option<u64>,
+    /// When the main window first finished loading a page
+    pub main_loaded_ms: Option<u64>,
+    pub launch_to_main_window_ms: Option<u64>,
+    pub launch_to_interactive_ms: Option<u64>,
+    pub launch_to_loaded_ms: Option<u64>,
+    /// How long the splash window stayed up after the main window became interactive; negative
+    /// when it closed first
+    pub splash_overlap_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PerformanceMetrics {
+    pub window_label: String,
+    pub startup: StartupMetrics,
+    /// `None` when the page couldn't be queried
+    pub page: Option<PageTimings>,
+}
+
+const PAGE_TIMINGS_SCRIPT: &str = r#"
+    const navigation = performance.getEntriesByType('navigation')[0];
+    const paint = (name) => {
+        const entry = performance.getEntriesByName(name)[0];
+        return entry ? entry.startTime : null;
+    };
+    const at = (value) => (navigation && value > 0) ? value : null;
+    return {
+        time_origin_ms: performance.timeOrigin,
+        dom_interactive_ms: at(navigation && navigation.domInteractive),
+        dom_content_loaded_ms: at(navigation && navigation.domContentLoadedEventEnd),
+        load_ms: at(navigation && navigation.loadEventEnd),
+        first_paint_ms: paint('first-paint'),
+        first_contentful_paint_ms: paint('first-contentful-paint')
+    };
+"#;
+
+/// Startup milestones for `window_label` as the main window, with its current page timings
+/// when the page can be queried
+pub async fn collect_metrics<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    timeout: Duration,
+) -> (StartupMetrics, Option<PageTimings>) {
+    let page: Option<PageTimings> = match eval_with_result(app, window_label, PAGE_TIMINGS_SCRIPT, timeout).await {
+        Ok(value) => serde_json::from_value(value).ok(),
+        Err(e) => {
+            info!("[TAURI_MCP] Page timings unavailable for {}: {}", window_label, e);
+            None
+        }
+    };
+
+    let tracker = app.state::<StartupTracker>();
+    let launched_ms = tracker.launched_ms();
+    let main_window_created_ms = tracker.created_ms(window_label);
+    let main_loaded_ms = tracker.first_load_ms(window_label);
+    // The current document is the first one if its navigation began before the first load finished
+    let main_interactive_ms = page.as_ref().and_then(|page| {
+        let first_document = main_loaded_ms.is_some_and(|loaded| page.time_origin_ms <= loaded as f64);
+        let interactive = page.dom_interactive_ms?;
+        first_document.then(|| (page.time_origin_ms + interactive) as u64)
+    });
+    let since_launch = |at: Option<u64>| at.map(|at| at.saturating_sub(launched_ms));
+    let splash = splash_info(app);
+    let splash_overlap_ms = splash
+        .as_ref()
+        .and_then(|splash| splash.closed_ms)
+        .zip(main_interactive_ms.or(main_loaded_ms))
+        .map(|(closed, ready)| closed as i64 - ready as i64);
+
+    let startup = StartupMetrics {
+        launched_ms,
+        splash,
+        main_window_created_ms,
+        main_interactive_ms,
+        main_loaded_ms,
+        launch_to_main_window_ms: since_launch(main_window_created_ms),
+        launch_to_interactive_ms: since_launch(main_interactive_ms),
+        launch_to_loaded_ms: since_launch(main_loaded_ms),
+        splash_overlap_ms,
+    };
+    (startup, page)
+}
+
+/// Startup metrics (launch to main window interactive, splash window lifetime) and the main
+/// window's navigation timings
+pub async fn handle_get_performance_metrics<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: PerformanceMetricsRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+
+    info!("[TAURI_MCP] Collecting performance metrics for window: {}", window_label);
+
+    let (startup, page) = collect_metrics(app, &window_label, timeout).await;
+    let metrics = PerformanceMetrics {
+        window_label,
+        startup,
+        page,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(metrics).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebviewMemoryRequest {
+    /// Only this window (default every window)
+    pub window_label: Option<String>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// The page's JavaScript heap, from `performance.memory` (Chromium-based webviews only)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsHeap {
+    pub used_bytes: u64,
+    pub total_bytes: u64,
+    pub limit_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebviewProcessInfo {
+    #[serde(flatten)]
+    pub process: WebviewProcess,
+    /// Other windows served by the same process, whose memory this figure includes
+    pub shared_with: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebviewMemory {
+    pub window_label: String,
+    /// `None` where the platform doesn't expose the webview's process
+    pub process: Option<WebviewProcessInfo>,
+    pub js_heap: Option<JsHeap>,
+    /// Elements in the document, a rough measure of DOM growth
+    pub dom_nodes: Option<u64>,
+    /// Why the page figures are missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_error: Option<String>,
+}
+
+const PAGE_MEMORY_SCRIPT: &str = r#"
+    const memory = performance.memory;
+    return {
+        js_heap: memory ? {
+            used_bytes: memory.usedJSHeapSize,
+            total_bytes: memory.totalJSHeapSize,
+            limit_bytes: memory.jsHeapSizeLimit
+        } : null,
+        dom_nodes: document.getElementsByTagName('*').length
+    };
+"#;
+
+/// Memory used by each window's webview: the OS process behind it where the platform exposes one
+/// (the web content process on macOS, WebView2's shared browser process on Windows) and the
+/// page's JavaScript heap and DOM size. WebView2 reports the heap per window, which is what tells
+/// its windows apart.
+pub async fn handle_get_webview_memory<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: WebviewMemoryRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+
+    let mut windows: Vec<_> = app.webview_windows().into_iter().collect();
+    if let Some(label) = &request.window_label {
+        windows.retain(|(window_label, _)| window_label == label);
+        if windows.is_empty() {
+            return Err(crate::Error::WindowNotFound(label.clone()));
+        }
+    }
+    windows.sort_by(|a, b| a.0.cmp(&b.0));
+
+    info!("[TAURI_MCP] Measuring webview memory for {} window(s)", windows.len());
+
+    let mut processes = Vec::new();
+    for (label, window) in &windows {
+        // Asking the webview for its process waits on the main thread
+        let window = window.clone();
+        let process = tauri::async_runtime::spawn_blocking(move || {
+            crate::platform::current::webview_process(&window)
+        })
+        .await
+        .map_err(|e| crate::Error::Anyhow(format!("Failed to read webview process: {}", e)))?;
+        processes.push((label.clone(), process));
+    }
+
+    let mut reports = Vec::new();
+    for (label, process) in &processes {
+        let (js_heap, dom_nodes, page_error) =
+            match eval_with_result(app, label, PAGE_MEMORY_SCRIPT, timeout).await {
+                Ok(page) => (
+                    page.get("js_heap")
+                        .cloned()
+                        .and_then(|heap| serde_json::from_value(heap).ok()),
+                    page.get("dom_nodes").and_then(|v| v.as_u64()),
+                    None,
+                ),
+                Err(e) => (None, None, Some(e.to_string())),
+            };
+        let process = process.clone().map(|process| WebviewProcessInfo {
+            shared_with: processes
+                .iter()
+                .filter(|(other, other_process)| {
+                    other != label && other_process.as_ref().is_some_and(|p| p.pid == process.pid)
+                })
+                .map(|(other, _)| other.clone())
+                .collect(),
+            process,
+        });
+        reports.push(WebviewMemory {
+            window_label: label.clone(),
+            process,
+            js_heap,
+            dom_nodes,
+            page_error,
+        });
+    }
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({ "windows": reports })),
+        error: None,
+        ..Default::default()
+    })
+}
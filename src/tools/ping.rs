@@ -21,12 +21,14 @@ pub fn handle_ping<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                ..Default::default()
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
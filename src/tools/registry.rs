@@ -0,0 +1,156 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::Error;
+use crate::security::Capability;
+use crate::socket_server::SocketResponse;
+
+/// Future returned by tool handlers. Handlers run to completion on the connection's own
+/// runtime, so they are not required to be `Send`.
+pub type ToolFuture<'a, T = SocketResponse> = Pin<Box<dyn Future<Output = crate::Result<T>> + 'a>>;
+
+/// Handler taking the raw payload and building its own response
+pub type HandlerFn<R> = for<'a> fn(&'a AppHandle<R>, Value) -> ToolFuture<'a>;
+
+/// Handler with typed request and response; see [`Typed`]
+pub type TypedHandlerFn<R, Req, Resp> = for<'a> fn(&'a AppHandle<R>, Req) -> ToolFuture<'a, Resp>;
+
+/// The one signature every tool is dispatched through
+pub trait ToolHandler<R: Runtime>: Send + Sync {
+    /// Run the tool. `Err` means the request itself was unusable (malformed payload, unknown
+    /// window); failures while doing the work are reported in the response.
+    fn call<'a>(&'a self, app: &'a AppHandle<R>, payload: Value) -> ToolFuture<'a>;
+}
+
+impl<R: Runtime> ToolHandler<R> for HandlerFn<R> {
+    fn call<'a>(&'a self, app: &'a AppHandle<R>, payload: Value) -> ToolFuture<'a> {
+        self(app, payload)
+    }
+}
+
+/// Adapts a typed handler: the payload is deserialized into `Req`, and `Ok(Resp)` or `Err`
+/// become a successful or failed [`SocketResponse`]
+pub struct Typed<R: Runtime, Req, Resp>(pub TypedHandlerFn<R, Req, Resp>);
+
+impl<R, Req, Resp> ToolHandler<R> for Typed<R, Req, Resp>
+where
+    R: Runtime,
+    Req: DeserializeOwned + 'static,
+    Resp: Serialize + 'static,
+{
+    fn call<'a>(&'a self, app: &'a AppHandle<R>, payload: Value) -> ToolFuture<'a> {
+        let handler = self.0;
+        Box::pin(async move {
+            let request: Req = serde_json::from_value(payload)
+                .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+
+            Ok(match handler(app, request).await {
+                Ok(response) => SocketResponse {
+                    success: true,
+                    data: Some(serde_json::to_value(response).map_err(|e| {
+                        Error::Anyhow(format!("Failed to serialize response: {}", e))
+                    })?),
+                    error: None,
+                    ..Default::default()
+                },
+                Err(e) => SocketResponse {
+                    success: false,
+                    data: None,
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                },
+            })
+        })
+    }
+}
+
+/// Static description of a tool declared with `#[mcp_tool]`
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Capability restricted clients need; `None` falls back to the built-in rules
+    pub capability: Option<Capability>,
+    /// JSON schema of the request payload
+    pub input_schema: Value,
+}
+
+/// Implemented by the struct `#[mcp_tool]` generates for each tool
+pub trait McpTool {
+    fn info() -> ToolInfo;
+
+    /// Add the tool's handler to `registry`
+    fn register<R: Runtime>(registry: &ToolRegistry<R>);
+}
+
+/// JSON schema for a request type, used by `#[mcp_tool]`
+pub fn schema_of<T: JsonSchema>() -> Value {
+    serde_json::to_value(schemars::schema_for!(T)).unwrap_or(Value::Null)
+}
+
+/// Dispatch table from command name to handler, managed in plugin state. Apps can add their
+/// own tools at any time through [`crate::TauriMcpExt`] or `app.state::<ToolRegistry<R>>()`.
+pub struct ToolRegistry<R: Runtime> {
+    tools: RwLock<HashMap<&'static str, Arc<dyn ToolHandler<R>>>>,
+    info: RwLock<HashMap<&'static str, ToolInfo>>,
+}
+
+impl<R: Runtime> Default for ToolRegistry<R> {
+    fn default() -> Self {
+        Self {
+            tools: RwLock::new(HashMap::new()),
+            info: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<R: Runtime> ToolRegistry<R> {
+    /// Register a handler under `name`, replacing any previous one
+    pub fn register_handler(&self, name: &'static str, handler: impl ToolHandler<R> + 'static) {
+        self.tools.write().unwrap().insert(name, Arc::new(handler));
+    }
+
+    /// Register a raw handler, e.g. `|app, payload| Box::pin(handle_ping(app, payload))`
+    pub fn register(&self, name: &'static str, handler: HandlerFn<R>) {
+        self.register_handler(name, handler);
+    }
+
+    /// Register a typed handler
+    pub fn register_typed<Req, Resp>(&self, name: &'static str, handler: TypedHandlerFn<R, Req, Resp>)
+    where
+        Req: DeserializeOwned + 'static,
+        Resp: Serialize + 'static,
+    {
+        self.register_handler(name, Typed(handler));
+    }
+
+    /// Register a tool declared with `#[mcp_tool]`, together with its description and schema
+    pub fn add<T: McpTool>(&self) {
+        T::register(self);
+        let info = T::info();
+        self.info.write().unwrap().insert(info.name, info);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ToolHandler<R>>> {
+        self.tools.read().unwrap().get(name).cloned()
+    }
+
+    /// Description and schema of a tool added with [`Self::add`]
+    pub fn info(&self, name: &str) -> Option<ToolInfo> {
+        self.info.read().unwrap().get(name).cloned()
+    }
+
+    /// Registered command names, sorted
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.tools.read().unwrap().keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+}
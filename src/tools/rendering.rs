@@ -0,0 +1,189 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct SetAnimationsRequest {
+    pub window_label: Option<String>,
+    /// `false` injects a global style that disables CSS animations and transitions
+    pub enabled: bool,
+    /// Jump running Web Animations to their end state (default: true when disabling)
+    pub finish_running: Option<bool>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameAction {
+    /// Hold back requestAnimationFrame callbacks instead of running them
+    Pause,
+    /// Run the queued callbacks for the given number of frames while staying paused
+    Step,
+    /// Flush queued callbacks and return to normal rendering
+    Resume,
+    Status,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FrameControlRequest {
+    pub window_label: Option<String>,
+    pub action: FrameAction,
+    /// Frames to advance for `step` (default 1)
+    pub frames: Option<u32>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Wraps requestAnimationFrame once per page so callbacks can be queued while paused
+const FRAME_CONTROL_SETUP: &str = r#"
+    if (!window.__mcpFrameControl) {
+        const nativeRaf = window.requestAnimationFrame.bind(window);
+        const nativeCancel = window.cancelAnimationFrame.bind(window);
+        const control = { paused: false, frame: 0, nextId: 1, queue: new Map(), nativeRaf: nativeRaf };
+        window.requestAnimationFrame = function(callback) {
+            if (!control.paused) return nativeRaf(callback);
+            // Queued ids are negative so they never collide with native ids
+            const id = -(control.nextId++);
+            control.queue.set(id, callback);
+            return id;
+        };
+        window.cancelAnimationFrame = function(id) {
+            if (control.queue.has(id)) {
+                control.queue.delete(id);
+            } else {
+                nativeCancel(id);
+            }
+        };
+        window.__mcpFrameControl = control;
+    }
+    const control = window.__mcpFrameControl;
+    const status = () => ({ paused: control.paused, frame: control.frame, queued_callbacks: control.queue.size });
+"#;
+
+/// Pause, single-step or resume the page's requestAnimationFrame loop
+pub async fn handle_control_animation_frames<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: FrameControlRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Animation frame control {:?} for window: {}",
+        request.action, window_label
+    );
+
+    let action_code = match request.action {
+        FrameAction::Pause => "control.paused = true;".to_string(),
+        FrameAction::Step => format!(
+            r#"
+            control.paused = true;
+            for (let i = 0; i < {frames}; i++) {{
+                const callbacks = Array.from(control.queue.values());
+                control.queue.clear();
+                const timestamp = performance.now();
+                for (const callback of callbacks) {{
+                    try {{
+                        callback(timestamp);
+                    }} catch (e) {{
+                        console.error(e);
+                    }}
+                }}
+                control.frame++;
+                // Let the browser paint the frame before the next step
+                await new Promise(resolve => control.nativeRaf(() => resolve()));
+            }}
+            "#,
+            frames = request.frames.unwrap_or(1)
+        ),
+        FrameAction::Resume => r#"
+            control.paused = false;
+            const callbacks = Array.from(control.queue.values());
+            control.queue.clear();
+            callbacks.forEach(callback => control.nativeRaf(callback));
+            "#
+        .to_string(),
+        FrameAction::Status => String::new(),
+    };
+
+    let code = format!("{}\n{}\nreturn status();", FRAME_CONTROL_SETUP, action_code);
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Disable or re-enable CSS animations/transitions so screenshots and queries don't race them
+pub async fn handle_set_animations<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: SetAnimationsRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let finish_running = request.finish_running.unwrap_or(!request.enabled);
+
+    info!(
+        "[TAURI_MCP] Setting animations {} for window: {}",
+        if request.enabled { "on" } else { "off" },
+        window_label
+    );
+
+    let code = format!(r#"
+        const enabled = {enabled};
+        const finishRunning = {finish_running};
+        const styleId = '__mcp-disable-animations';
+        let style = document.getElementById(styleId);
+        if (!enabled && !style) {{
+            style = document.createElement('style');
+            style.id = styleId;
+            style.textContent = '*, *::before, *::after {{ animation: none !important; transition: none !important; }}';
+            (document.head || document.documentElement).appendChild(style);
+        }} else if (enabled && style) {{
+            style.remove();
+        }}
+
+        let finished = 0;
+        let skipped = 0;
+        if (finishRunning && document.getAnimations) {{
+            for (const animation of document.getAnimations()) {{
+                try {{
+                    animation.finish();
+                    finished++;
+                }} catch (e) {{
+                    // Infinite animations cannot be finished
+                    skipped++;
+                }}
+            }}
+        }}
+
+        return {{
+            animations_enabled: enabled,
+            finished_animations: finished,
+            skipped_animations: skipped
+        }};
+    "#,
+        enabled = request.enabled,
+        finish_running = finish_running
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let result = eval_with_result(app, &window_label, &code, timeout).await?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(result),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+use log::warn;
+
+/// Time between probes of each loop
+const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Probes kept per loop for the percentiles: one minute's worth
+const SAMPLES: usize = 240;
+
+/// Lag above this counts as a stall
+const STALL_THRESHOLD: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+struct LoopSamples {
+    /// Most recent lags in milliseconds
+    recent: VecDeque<f64>,
+    stalls: u64,
+    longest_ms: f64,
+    /// When the probe still waiting for the loop was sent
+    pending_since: Option<Instant>,
+}
+
+impl LoopSamples {
+    fn record(&mut self, lag: Duration) {
+        let lag_ms = lag.as_secs_f64() * 1000.0;
+        if self.recent.len() >= SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(lag_ms);
+        if lag > STALL_THRESHOLD {
+            self.stalls += 1;
+        }
+        self.longest_ms = self.longest_ms.max(lag_ms);
+        self.pending_since = None;
+    }
+
+    fn stats(&self) -> LoopLag {
+        let mut sorted: Vec<f64> = self.recent.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let p95_ms = match sorted.len() {
+            0 => 0.0,
+            len => sorted[((0.95 * len as f64).ceil() as usize).clamp(1, len) - 1],
+        };
+        LoopLag {
+            last_ms: self.recent.back().copied().unwrap_or(0.0),
+            p95_ms,
+            max_recent_ms: sorted.last().copied().unwrap_or(0.0),
+            longest_ms: self.longest_ms,
+            stalls: self.stalls,
+            blocked_for_ms: self
+                .pending_since
+                .map(|since| since.elapsed())
+                .filter(|blocked| *blocked > STALL_THRESHOLD)
+                .map(|blocked| blocked.as_millis() as u64),
+        }
+    }
+}
+
+/// How late one loop ran its probes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopLag {
+    pub last_ms: f64,
+    /// Over the last minute
+    pub p95_ms: f64,
+    pub max_recent_ms: f64,
+    /// Since the plugin started
+    pub longest_ms: f64,
+    /// Probes that ran more than 100ms late, since the plugin started
+    pub stalls: u64,
+    /// Set while the loop hasn't run a probe sent this long ago: it is stalled right now
+    pub blocked_for_ms: Option<u64>,
+}
+
+/// Responsiveness of the async runtime the plugin's tools run on and of the main thread, which
+/// runs window operations and `eval`. A stall on one of them while the webview itself is fine
+/// points at blocking host code rather than a slow page.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsivenessStatus {
+    pub async_runtime: LoopLag,
+    pub main_thread: LoopLag,
+    pub probe_interval_ms: u64,
+}
+
+/// Lag measured by the probes started with [`start`], managed in plugin state
+#[derive(Default)]
+pub struct LagMonitor {
+    runtime: Mutex<LoopSamples>,
+    main_thread: Mutex<LoopSamples>,
+}
+
+impl LagMonitor {
+    pub fn status(&self) -> ResponsivenessStatus {
+        ResponsivenessStatus {
+            async_runtime: self.runtime.lock().unwrap().stats(),
+            main_thread: self.main_thread.lock().unwrap().stats(),
+            probe_interval_ms: PROBE_INTERVAL.as_millis() as u64,
+        }
+    }
+}
+
+/// Start probing both loops. The runtime probe measures how late a timer fires; the main
+/// thread probe measures how long a task posted to it waits before it runs.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let due = Instant::now() + PROBE_INTERVAL;
+            handle.state::<LagMonitor>().runtime.lock().unwrap().pending_since = Some(due);
+            tokio::time::sleep(PROBE_INTERVAL).await;
+            let lag = Instant::now().saturating_duration_since(due);
+            handle.state::<LagMonitor>().runtime.lock().unwrap().record(lag);
+        }
+    });
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(PROBE_INTERVAL).await;
+            let sent = Instant::now();
+            handle.state::<LagMonitor>().main_thread.lock().unwrap().pending_since = Some(sent);
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            if let Err(e) = handle.run_on_main_thread(move || {
+                let _ = tx.send(Instant::now());
+            }) {
+                warn!("[TAURI_MCP] Stopping the main thread probe: {}", e);
+                break;
+            }
+            // The event loop has shut down when the task is dropped unrun
+            let Ok(ran) = rx.await else {
+                break;
+            };
+            let lag = ran.saturating_duration_since(sent);
+            handle.state::<LagMonitor>().main_thread.lock().unwrap().record(lag);
+        }
+    });
+}
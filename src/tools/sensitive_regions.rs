@@ -0,0 +1,77 @@
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::{info, warn};
+
+use crate::tools::js_bridge::eval_with_result;
+
+/// How sensitive regions look in screenshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegionMask {
+    /// Blurred beyond reading, keeping the layout recognizable
+    #[default]
+    Blur,
+    /// Blacked out
+    Omit,
+}
+
+/// DOM regions the host app declared sensitive, managed in plugin state. Screenshots mask them
+/// and DOM and text snapshots replace their content with `[redacted]`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SensitiveRegions {
+    pub selectors: Vec<String>,
+    pub mask: RegionMask,
+}
+
+impl SensitiveRegions {
+    pub fn is_empty(&self) -> bool {
+        self.selectors.is_empty()
+    }
+
+    /// The regions as the argument the page helpers take
+    pub fn to_js(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "null".to_string())
+    }
+}
+
+/// The app's sensitive regions; none when the plugin isn't set up
+pub fn regions<R: Runtime>(app: &AppHandle<R>) -> SensitiveRegions {
+    app.try_state::<SensitiveRegions>()
+        .map(|regions| regions.inner().clone())
+        .unwrap_or_default()
+}
+
+/// Code returning the page's markup with sensitive regions emptied
+pub fn html_script<R: Runtime>(app: &AppHandle<R>) -> String {
+    format!(
+        "return window.__mcp.redactedHtml(document.documentElement, {});",
+        regions(app).to_js()
+    )
+}
+
+/// Expression for the rendered text of the element in `element`, sensitive lines replaced
+pub fn text_expression<R: Runtime>(app: &AppHandle<R>, element: &str) -> String {
+    format!("window.__mcp.redactedText({}, {})", element, regions(app).to_js())
+}
+
+/// Mask a window's sensitive regions for a screenshot. A failure means they may be visible, so
+/// callers must not capture.
+pub async fn mask<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    regions: &SensitiveRegions,
+) -> crate::Result<()> {
+    let code = format!("return await window.__mcp.maskRegions({});", regions.to_js());
+    let count = eval_with_result(app, window_label, &code, Duration::from_secs(5)).await?;
+    info!("[TAURI_MCP] Masked {} sensitive element(s) in {}", count, window_label);
+    Ok(())
+}
+
+/// Undo [`mask`]
+pub async fn unmask<R: Runtime>(app: &AppHandle<R>, window_label: &str) {
+    let code = "window.__mcp.unmaskRegions(); return null;";
+    if let Err(e) = eval_with_result(app, window_label, code, Duration::from_secs(5)).await {
+        warn!("[TAURI_MCP] Failed to unmask sensitive regions in {}: {}", window_label, e);
+    }
+}
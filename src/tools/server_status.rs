@@ -0,0 +1,32 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::TauriMcpExt;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::responsiveness::LagMonitor;
+
+pub fn handle_server_status<R: Runtime>(
+    app: &AppHandle<R>,
+    _payload: Value,
+) -> Result<SocketResponse, Error> {
+    match app.tauri_mcp().server_status() {
+        Ok(mut response) => {
+            response.responsiveness = app.try_state::<LagMonitor>().map(|monitor| monitor.status());
+            let data = serde_json::to_value(response)
+                .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?;
+            Ok(SocketResponse {
+                success: true,
+                data: Some(data),
+                error: None,
+                ..Default::default()
+            })
+        }
+        Err(e) => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            ..Default::default()
+        }),
+    }
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, WindowEvent};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::timeline::now_ms;
+
+/// When each window was created, first finished loading and closed, in milliseconds since the
+/// Unix epoch
+#[derive(Debug, Clone, Copy, Default)]
+struct WindowTimes {
+    created_ms: Option<u64>,
+    first_load_ms: Option<u64>,
+    closed_ms: Option<u64>,
+}
+
+/// Startup milestones, recorded from the plugin's window and page load hooks
+pub struct StartupTracker {
+    launched_ms: u64,
+    /// Label configured with `PluginConfig::splash_window`; otherwise the first window whose
+    /// label contains "splash" counts as the splash window
+    splash_label: Option<String>,
+    windows: Mutex<HashMap<String, WindowTimes>>,
+    /// When host code called `TauriMcp::mark_app_ready`
+    app_ready_ms: Mutex<Option<u64>>,
+}
+
+impl StartupTracker {
+    pub fn new(launched_ms: u64, splash_label: Option<String>) -> Self {
+        Self {
+            launched_ms,
+            splash_label,
+            windows: Mutex::new(HashMap::new()),
+            app_ready_ms: Mutex::new(None),
+        }
+    }
+
+    pub fn launched_ms(&self) -> u64 {
+        self.launched_ms
+    }
+
+    fn update(&self, window_label: &str, update: impl FnOnce(&mut WindowTimes)) {
+        let mut windows = self.windows.lock().unwrap();
+        update(windows.entry(window_label.to_string()).or_default());
+    }
+
+    fn times(&self, window_label: &str) -> WindowTimes {
+        self.windows
+            .lock()
+            .unwrap()
+            .get(window_label)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The configured splash window, or the earliest created window labelled like one
+    pub fn splash_label(&self) -> Option<String> {
+        if let Some(label) = &self.splash_label {
+            return Some(label.clone());
+        }
+        self.windows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(label, _)| label.to_lowercase().contains("splash"))
+            .min_by_key(|(_, times)| times.created_ms)
+            .map(|(label, _)| label.clone())
+    }
+
+    /// Milliseconds since the Unix epoch at which `window_label` first finished loading a page
+    pub fn first_load_ms(&self, window_label: &str) -> Option<u64> {
+        self.times(window_label).first_load_ms
+    }
+
+    pub fn created_ms(&self, window_label: &str) -> Option<u64> {
+        self.times(window_label).created_ms
+    }
+
+    /// When the first window of this launch was created
+    pub fn first_window_ms(&self) -> Option<u64> {
+        self.windows
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|times| times.created_ms)
+            .min()
+    }
+
+    /// Record that the app considers itself ready. Only the first call counts.
+    pub fn mark_app_ready(&self) {
+        self.app_ready_ms.lock().unwrap().get_or_insert_with(now_ms);
+    }
+
+    pub fn app_ready_ms(&self) -> Option<u64> {
+        *self.app_ready_ms.lock().unwrap()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SplashWindowInfo {
+    pub label: String,
+    /// Still open and visible
+    pub open: bool,
+    /// Milliseconds since the Unix epoch
+    pub created_ms: Option<u64>,
+    /// When it was closed or, if only hidden, when a tool first saw it hidden
+    pub closed_ms: Option<u64>,
+    /// How long it was up
+    pub shown_ms: Option<u64>,
+}
+
+/// Current state of the splash window, if one exists or existed. A hidden splash window is
+/// recorded as closed the first time it is seen hidden.
+pub fn splash_info<R: Runtime>(app: &AppHandle<R>) -> Option<SplashWindowInfo> {
+    let tracker = app.try_state::<StartupTracker>()?;
+    let label = tracker.splash_label()?;
+    let visible = app
+        .get_webview_window(&label)
+        .map(|window| window.is_visible().unwrap_or(false));
+    if visible == Some(false) {
+        tracker.update(&label, |times| {
+            times.closed_ms.get_or_insert_with(now_ms);
+        });
+    }
+    let times = tracker.times(&label);
+    if times.created_ms.is_none() && visible.is_none() {
+        // Configured but never created
+        return None;
+    }
+    Some(SplashWindowInfo {
+        open: visible == Some(true) && times.closed_ms.is_none(),
+        created_ms: times.created_ms,
+        closed_ms: times.closed_ms,
+        shown_ms: times
+            .created_ms
+            .zip(times.closed_ms)
+            .map(|(created, closed)| closed.saturating_sub(created)),
+        label,
+    })
+}
+
+/// Plugin `on_window_ready` hook
+pub fn window_created<R: Runtime, M: Manager<R>>(manager: &M, window_label: &str) {
+    if let Some(tracker) = manager.try_state::<StartupTracker>() {
+        // A label may be reused for a new window; its milestones start over
+        tracker.update(window_label, |times| {
+            *times = WindowTimes {
+                created_ms: Some(now_ms()),
+                ..WindowTimes::default()
+            };
+        });
+    }
+}
+
+/// Plugin `on_page_load` hook
+pub fn page_loaded<R: Runtime, M: Manager<R>>(manager: &M, window_label: &str, event: PageLoadEvent) {
+    if event != PageLoadEvent::Finished {
+        return;
+    }
+    if let Some(tracker) = manager.try_state::<StartupTracker>() {
+        tracker.update(window_label, |times| {
+            times.first_load_ms.get_or_insert_with(now_ms);
+        });
+    }
+}
+
+/// Plugin `on_event` hook for window lifecycle changes
+pub fn window_event<R: Runtime, M: Manager<R>>(manager: &M, window_label: &str, event: &WindowEvent) {
+    if !matches!(event, WindowEvent::Destroyed) {
+        return;
+    }
+    if let Some(tracker) = manager.try_state::<StartupTracker>() {
+        tracker.update(window_label, |times| {
+            times.closed_ms.get_or_insert_with(now_ms);
+        });
+    }
+}
+
+fn respond(info: Option<SplashWindowInfo>) -> crate::Result<SocketResponse> {
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({ "splash": info })),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Report the splash window: the one configured with `PluginConfig::splash_window`, otherwise
+/// the first window whose label contains "splash". `splash` is null when there is none.
+pub async fn handle_get_splash_window<R: Runtime>(
+    app: &AppHandle<R>,
+    _payload: Value,
+) -> crate::Result<SocketResponse> {
+    info!("[TAURI_MCP] Looking up the splash window");
+    respond(splash_info(app))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WaitForSplashCloseRequest {
+    /// How long to wait (default 30000)
+    pub timeout_ms: Option<u64>,
+}
+
+/// Wait until the splash window is closed or hidden
+pub async fn handle_wait_for_splash_close<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: WaitForSplashCloseRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(30000));
+
+    info!("[TAURI_MCP] Waiting for the splash window to close");
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let Some(info) = splash_info(app) else {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some("No splash window found".to_string()),
+                ..Default::default()
+            });
+        };
+        if !info.open {
+            return respond(Some(info));
+        }
+        if Instant::now() >= deadline {
+            return Ok(SocketResponse {
+                success: false,
+                data: Some(serde_json::json!({ "splash": info })),
+                error: Some(format!(
+                    "Splash window '{}' still open after {}ms",
+                    info.label,
+                    timeout.as_millis()
+                )),
+                ..Default::default()
+            });
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::artifacts::ArtifactStore;
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::performance::collect_metrics;
+use crate::tools::startup::StartupTracker;
+use crate::tools::timeline::now_ms;
+
+/// Runs are appended here, in the artifacts directory, so they survive relaunches
+const RUNS_FILE: &str = "startup-benchmark.jsonl";
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchmarkAction {
+    /// Add this launch's timings to the recorded runs
+    Record,
+    /// Aggregate statistics over the recorded runs
+    Report,
+    /// Forget the recorded runs
+    Reset,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BenchmarkStartupRequest {
+    pub action: BenchmarkAction,
+    /// The main window (default "main")
+    pub window_label: Option<String>,
+    /// When the launcher started the process, in milliseconds since the Unix epoch. Timings count
+    /// from here when given, otherwise from plugin initialization.
+    pub spawned_ms: Option<u64>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadySignal {
+    /// Host code called `TauriMcp::mark_app_ready`
+    App,
+    /// The main window's first document became interactive
+    Interactive,
+    /// The main window first finished loading
+    Loaded,
+}
+
+/// One launch, in milliseconds from process spawn (or plugin initialization)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupRun {
+    /// Milliseconds since the Unix epoch
+    pub recorded_ms: u64,
+    /// Whether timings count from the launcher's `spawned_ms`
+    pub from_spawn: bool,
+    /// Spawn to plugin initialization; only known when `from_spawn`
+    pub plugin_init_ms: Option<u64>,
+    pub first_window_ms: Option<u64>,
+    /// First contentful paint of the main window (first paint where unsupported). Pages can't
+    /// observe the compositor, so this is a proxy for the first frame on screen.
+    pub first_paint_ms: Option<u64>,
+    pub app_ready_ms: u64,
+    pub ready_signal: ReadySignal,
+}
+
+/// The run recorded for this launch, so repeated `record` calls don't add it twice
+#[derive(Default)]
+pub struct StartupBenchmarkState {
+    recorded: Mutex<Option<StartupRun>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricStats {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub std_dev: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartupReport {
+    pub runs: usize,
+    pub plugin_init_ms: Option<MetricStats>,
+    pub first_window_ms: Option<MetricStats>,
+    pub first_paint_ms: Option<MetricStats>,
+    pub app_ready_ms: Option<MetricStats>,
+    pub file: String,
+}
+
+fn stats(mut values: Vec<f64>) -> Option<MetricStats> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let count = values.len();
+    let mean = values.iter().sum::<f64>() / count as f64;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / count as f64;
+    // Nearest-rank percentile
+    let percentile = |p: f64| values[((p * count as f64).ceil() as usize).clamp(1, count) - 1];
+    Some(MetricStats {
+        count,
+        min: values[0],
+        max: values[count - 1],
+        mean,
+        median: percentile(0.5),
+        p95: percentile(0.95),
+        std_dev: variance.sqrt(),
+    })
+}
+
+fn report(runs: &[StartupRun], file: &std::path::Path) -> StartupReport {
+    let metric = |pick: fn(&StartupRun) -> Option<u64>| {
+        stats(runs.iter().filter_map(pick).map(|value| value as f64).collect())
+    };
+    StartupReport {
+        runs: runs.len(),
+        plugin_init_ms: metric(|run| run.plugin_init_ms),
+        first_window_ms: metric(|run| run.first_window_ms),
+        first_paint_ms: metric(|run| run.first_paint_ms),
+        app_ready_ms: metric(|run| Some(run.app_ready_ms)),
+        file: file.display().to_string(),
+    }
+}
+
+fn read_runs(path: &std::path::Path) -> crate::Result<Vec<StartupRun>> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(Error::Anyhow(format!("Failed to read {}: {}", path.display(), e))),
+    }
+}
+
+fn append_run(path: &std::path::Path, run: &StartupRun) -> crate::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| Error::Anyhow(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let line = serde_json::to_string(run)
+        .map_err(|e| Error::Anyhow(format!("Failed to serialize run: {}", e)))?;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| Error::Anyhow(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Cold start benchmarking. A launcher (such as the client crate's `StartupBenchmark`) starts the
+/// app repeatedly and calls `record` once per launch; `record` fails until the app is ready, so
+/// the launcher can poll it. `report` aggregates every recorded run.
+pub async fn handle_benchmark_startup<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: BenchmarkStartupRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let path = app.state::<ArtifactStore>().dir().join(RUNS_FILE);
+
+    info!("[TAURI_MCP] Startup benchmark {:?}", request.action);
+
+    let data = match request.action {
+        BenchmarkAction::Reset => {
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    return Err(Error::Anyhow(format!("Failed to remove {}: {}", path.display(), e)));
+                }
+            }
+            if let Some(state) = app.try_state::<StartupBenchmarkState>() {
+                state.recorded.lock().unwrap().take();
+            }
+            serde_json::json!({ "reset": true })
+        }
+        BenchmarkAction::Report => serde_json::to_value(report(&read_runs(&path)?, &path))
+            .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?,
+        BenchmarkAction::Record => {
+            let state = app.state::<StartupBenchmarkState>();
+            if let Some(run) = state.recorded.lock().unwrap().clone() {
+                return Ok(SocketResponse {
+                    success: true,
+                    data: Some(serde_json::json!({ "run": run, "recorded": false })),
+                    error: None,
+                    ..Default::default()
+                });
+            }
+
+            let (startup, page) = collect_metrics(app, &window_label, timeout).await;
+            let tracker = app.state::<StartupTracker>();
+            let ready = tracker
+                .app_ready_ms()
+                .map(|at| (at, ReadySignal::App))
+                .or_else(|| startup.main_interactive_ms.map(|at| (at, ReadySignal::Interactive)))
+                .or_else(|| startup.main_loaded_ms.map(|at| (at, ReadySignal::Loaded)));
+            let Some((ready_ms, ready_signal)) = ready else {
+                return Ok(SocketResponse {
+                    success: false,
+                    data: None,
+                    error: Some(format!("The app isn't ready yet; window '{}' hasn't loaded", window_label)),
+                    ..Default::default()
+                });
+            };
+
+            let origin = request.spawned_ms.unwrap_or(startup.launched_ms);
+            let since_origin = |at: u64| at.saturating_sub(origin);
+            // Paint timings belong to this launch only while the first document is showing
+            let first_paint_ms = page.as_ref().and_then(|page| {
+                let first_document = startup
+                    .main_loaded_ms
+                    .is_some_and(|loaded| page.time_origin_ms <= loaded as f64);
+                let paint = page.first_contentful_paint_ms.or(page.first_paint_ms)?;
+                first_document.then(|| since_origin((page.time_origin_ms + paint) as u64))
+            });
+            let run = StartupRun {
+                recorded_ms: now_ms(),
+                from_spawn: request.spawned_ms.is_some(),
+                plugin_init_ms: request.spawned_ms.map(|_| since_origin(startup.launched_ms)),
+                first_window_ms: tracker.first_window_ms().map(since_origin),
+                first_paint_ms,
+                app_ready_ms: since_origin(ready_ms),
+                ready_signal,
+            };
+            append_run(&path, &run)?;
+            *state.recorded.lock().unwrap() = Some(run.clone());
+            serde_json::json!({ "run": run, "recorded": true })
+        }
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(data),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -1,8 +1,9 @@
 use crate::error::{Error, Result};
+use crate::encoding::encode_blocking;
 use crate::shared::ScreenshotParams;
-use base64;
 use image::DynamicImage;
 use serde_json::Value;
+use std::sync::Arc;
 use tauri::{AppHandle, Runtime};
 use log::info;
 use crate::TauriMcpExt;
@@ -12,6 +13,7 @@ use crate::socket_server::SocketResponse;
 /// Common function to process and compress an image - used by platform implementations
 pub fn process_image(mut dynamic_image: DynamicImage, params: &ScreenshotParams) -> Result<String> {
     // Extract parameters from the shared struct
+    let format = params.format.unwrap_or_default();
     let quality = params.quality.unwrap_or(85) as u8;
     let max_width = params.max_width.map(|w| w as u32);
     let max_size_bytes = params
@@ -48,103 +50,70 @@ pub fn process_image(mut dynamic_image: DynamicImage, params: &ScreenshotParams)
         );
     }
 
-    // Use JPG for better compression
-    let mut output_data = Vec::new();
+    // Encoding runs on the dedicated encoder pool
+    let mut frame = Arc::new(dynamic_image);
     let mut current_quality = quality;
+    let mut encoded = encode_blocking(frame.clone(), format, current_quality)?;
 
-    // Try encoding with JPEG
-    match dynamic_image.write_to(
-        &mut std::io::Cursor::new(&mut output_data),
-        image::ImageOutputFormat::Jpeg(current_quality),
-    ) {
-        Ok(_) => {
-            // Reduce quality if needed to meet max size
-            while output_data.len() as u64 > max_size_bytes && current_quality > 30 {
-                info!(
-                    "[SCREENSHOT] Output size {} bytes exceeds max {}. Reducing quality to {}",
-                    output_data.len(),
-                    max_size_bytes,
-                    current_quality - 10
-                );
-
-                // Reduce quality and try again
-                current_quality -= 10;
-                output_data.clear();
-
-                if let Err(e) = dynamic_image.write_to(
-                    &mut std::io::Cursor::new(&mut output_data),
-                    image::ImageOutputFormat::Jpeg(current_quality),
-                ) {
-                    return Err(Error::WindowOperationFailed(format!(
-                        "Failed to re-encode JPEG: {}",
-                        e
-                    )));
-                }
-            }
+    // Reduce quality if needed to meet max size
+    while format.is_lossy() && encoded.byte_len as u64 > max_size_bytes && current_quality > 30 {
+        info!(
+            "[SCREENSHOT] Output size {} bytes exceeds max {}. Reducing quality to {}",
+            encoded.byte_len,
+            max_size_bytes,
+            current_quality - 10
+        );
 
-            // If still too large, resize the image
-            if output_data.len() as u64 > max_size_bytes && dynamic_image.width() > 800 {
-                info!("[SCREENSHOT] Image still too large after quality reduction. Resizing...");
-                let scale_factor = 0.8; // reduce by 20% each iteration
-
-                while output_data.len() as u64 > max_size_bytes && dynamic_image.width() > 800 {
-                    // Resize image
-                    let new_width = (dynamic_image.width() as f32 * scale_factor) as u32;
-                    let new_height = (dynamic_image.height() as f32 * scale_factor) as u32;
-
-                    info!("[SCREENSHOT] Resizing to {}x{}", new_width, new_height);
-                    dynamic_image = dynamic_image.resize(
-                        new_width,
-                        new_height,
-                        image::imageops::FilterType::Triangle,
-                    );
-
-                    // Re-encode with current quality
-                    output_data.clear();
-                    if let Err(e) = dynamic_image.write_to(
-                        &mut std::io::Cursor::new(&mut output_data),
-                        image::ImageOutputFormat::Jpeg(current_quality),
-                    ) {
-                        return Err(Error::WindowOperationFailed(format!(
-                            "Failed to encode resized image: {}",
-                            e
-                        )));
-                    }
-
-                    // Give up if we're getting very small
-                    if dynamic_image.width() <= 800 {
-                        break;
-                    }
-                }
-            }
+        // Reduce quality and try again
+        current_quality -= 10;
+        encoded = encode_blocking(frame.clone(), format, current_quality)?;
+    }
 
-            // Convert to base64
-            let base64_data = base64::encode(&output_data);
-            let data_url = format!("data:image/jpeg;base64,{}", base64_data);
-
-            info!(
-                "[SCREENSHOT] Final image size: {}x{}, data size: {} bytes, quality: {}",
-                dynamic_image.width(),
-                dynamic_image.height(),
-                output_data.len(),
-                current_quality
-            );
-
-            // Final check - reject if still too large
-            if base64_data.len() > 5 * 1024 * 1024 {
-                return Err(Error::WindowOperationFailed(format!(
-                    "Screenshot is still too large: {} bytes. Try using a smaller max_width.",
-                    base64_data.len()
-                )));
+    // If still too large, resize the image
+    if encoded.byte_len as u64 > max_size_bytes && frame.width() > 800 {
+        info!("[SCREENSHOT] Image still too large after quality reduction. Resizing...");
+        let scale_factor = 0.8; // reduce by 20% each iteration
+
+        while encoded.byte_len as u64 > max_size_bytes && frame.width() > 800 {
+            // Resize image
+            let new_width = (frame.width() as f32 * scale_factor) as u32;
+            let new_height = (frame.height() as f32 * scale_factor) as u32;
+
+            info!("[SCREENSHOT] Resizing to {}x{}", new_width, new_height);
+            frame = Arc::new(frame.resize(
+                new_width,
+                new_height,
+                image::imageops::FilterType::Triangle,
+            ));
+
+            // Re-encode with current quality
+            encoded = encode_blocking(frame.clone(), format, current_quality)?;
+
+            // Give up if we're getting very small
+            if frame.width() <= 800 {
+                break;
             }
-
-            Ok(data_url)
         }
-        Err(e) => Err(Error::WindowOperationFailed(format!(
-            "Failed to encode JPEG: {}",
-            e
-        ))),
     }
+
+    info!(
+        "[SCREENSHOT] Final image size: {}x{}, data size: {} bytes, format: {:?}, quality: {}",
+        frame.width(),
+        frame.height(),
+        encoded.byte_len,
+        format,
+        current_quality
+    );
+
+    // Final check - reject if still too large
+    if encoded.data_url.len() > 5 * 1024 * 1024 {
+        return Err(Error::WindowOperationFailed(format!(
+            "Screenshot is still too large: {} bytes. Try using a smaller max_width.",
+            encoded.data_url.len()
+        )));
+    }
+
+    Ok(encoded.data_url)
 }
 
 pub async fn handle_take_screenshot<R: Runtime>(
@@ -164,12 +133,14 @@ pub async fn handle_take_screenshot<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                ..Default::default()
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
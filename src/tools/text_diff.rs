@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::elements::{ElementTarget, find_element_code};
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::sensitive_regions;
+
+/// Lines of unchanged context around each hunk
+const DIFF_CONTEXT: usize = 3;
+
+/// Above this many cells the LCS table is skipped and the changed region is reported wholesale
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Named text snapshots kept between `diff_text` calls
+#[derive(Default)]
+pub struct TextSnapshotStore {
+    snapshots: Mutex<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffTextRequest {
+    pub window_label: Option<String>,
+    /// Name the snapshot is stored under
+    pub name: String,
+    /// Region to read; defaults to the whole body
+    #[serde(flatten)]
+    pub target: ElementTarget,
+    /// Replace the stored snapshot with the new text after diffing (default true)
+    pub update: Option<bool>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffTextResponse {
+    pub name: String,
+    /// True when no snapshot existed yet; the text was stored and nothing was compared
+    pub first_capture: bool,
+    pub changed: bool,
+    pub diff: String,
+    pub line_count: usize,
+}
+
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Line diff via longest common subsequence after trimming the shared prefix and suffix
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<DiffOp> = old[..prefix].iter().map(|&l| DiffOp::Equal(l)).collect();
+
+    if old_mid.len() * new_mid.len() > MAX_LCS_CELLS {
+        ops.extend(old_mid.iter().map(|&l| DiffOp::Delete(l)));
+        ops.extend(new_mid.iter().map(|&l| DiffOp::Insert(l)));
+    } else {
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if old_mid[i] == new_mid[j] {
+                ops.push(DiffOp::Equal(old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                ops.push(DiffOp::Delete(old_mid[i]));
+                i += 1;
+            } else {
+                ops.push(DiffOp::Insert(new_mid[j]));
+                j += 1;
+            }
+        }
+        ops.extend(old_mid[i..].iter().map(|&l| DiffOp::Delete(l)));
+        ops.extend(new_mid[j..].iter().map(|&l| DiffOp::Insert(l)));
+    }
+
+    ops.extend(old[old.len() - suffix..].iter().map(|&l| DiffOp::Equal(l)));
+    ops
+}
+
+/// Render a unified diff between two texts, or an empty string when they are equal
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // Group changes whose context windows overlap into hunks of op indices
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &index in &changed {
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let end = (index + DIFF_CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in hunks {
+        // Line numbers where the hunk begins in each text
+        let (mut old_line, mut new_line) = (1, 1);
+        for op in &ops[..start] {
+            match op {
+                DiffOp::Equal(_) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+                DiffOp::Delete(_) => old_line += 1,
+                DiffOp::Insert(_) => new_line += 1,
+            }
+        }
+
+        let mut body = String::new();
+        let (mut old_count, mut new_count) = (0, 0);
+        for op in &ops[start..end] {
+            match op {
+                DiffOp::Equal(line) => {
+                    body.push_str(&format!(" {}\n", line));
+                    old_count += 1;
+                    new_count += 1;
+                }
+                DiffOp::Delete(line) => {
+                    body.push_str(&format!("-{}\n", line));
+                    old_count += 1;
+                }
+                DiffOp::Insert(line) => {
+                    body.push_str(&format!("+{}\n", line));
+                    new_count += 1;
+                }
+            }
+        }
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n{}",
+            old_line, old_count, new_line, new_count, body
+        ));
+    }
+    out
+}
+
+/// Capture a region's visible text and diff it against the snapshot stored under the same name
+pub async fn handle_diff_text<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: DiffTextRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!(
+        "[TAURI_MCP] Diffing text snapshot '{}' in window: {}",
+        request.name, window_label
+    );
+
+    let find_element = if request.target.selector.is_none() && request.target.handle.is_none() {
+        "const element = document.body;".to_string()
+    } else {
+        find_element_code(&request.target)?
+    };
+    let code = format!(
+        r#"
+        {find_element}
+        // Normalize whitespace so layout-only changes don't show up as diffs
+        return {text}
+            .split('\n')
+            .map(line => line.replace(/\s+/g, ' ').trim())
+            .filter(line => line.length > 0)
+            .join('\n');
+        "#,
+        find_element = find_element,
+        text = sensitive_regions::text_expression(app, "element")
+    );
+
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+    let text = match eval_with_result(app, &window_label, &code, timeout).await? {
+        Value::String(text) => text,
+        other => other.to_string(),
+    };
+
+    let store = app.state::<TextSnapshotStore>();
+    let mut snapshots = store.snapshots.lock().unwrap();
+    let previous = snapshots.get(&request.name).cloned();
+
+    let diff = previous
+        .as_deref()
+        .map(|old| {
+            unified_diff(
+                old,
+                &text,
+                &format!("{} (stored)", request.name),
+                &format!("{} (current)", request.name),
+            )
+        })
+        .unwrap_or_default();
+
+    if previous.is_none() || request.update.unwrap_or(true) {
+        snapshots.insert(request.name.clone(), text.clone());
+    }
+
+    let response = DiffTextResponse {
+        name: request.name,
+        first_capture: previous.is_none(),
+        changed: !diff.is_empty(),
+        diff,
+        line_count: text.lines().count(),
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
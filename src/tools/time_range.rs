@@ -0,0 +1,140 @@
+use serde::Deserialize;
+
+/// One end of a time range: milliseconds since the Unix epoch, or an ISO 8601 time such as
+/// `2024-05-01T12:30:00.250Z` (UTC unless it carries an offset)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TimeBound {
+    Millis(u64),
+    Text(String),
+}
+
+impl TimeBound {
+    /// The bound in milliseconds since the Unix epoch; `name` labels it in the error
+    pub fn to_ms(&self, name: &str) -> Result<u64, String> {
+        match self {
+            Self::Millis(ms) => Ok(*ms),
+            Self::Text(text) => parse_iso8601(text).ok_or_else(|| {
+                format!(
+                    "Invalid {} '{}': expected milliseconds since the Unix epoch or an ISO 8601 time",
+                    name, text
+                )
+            }),
+        }
+    }
+}
+
+/// Inclusive time range requested with `from` and `to`; either end may be open
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeRange {
+    pub from_ms: Option<u64>,
+    pub to_ms: Option<u64>,
+}
+
+impl TimeRange {
+    /// Validate the bounds of a request
+    pub fn new(from: Option<&TimeBound>, to: Option<&TimeBound>) -> Result<Self, String> {
+        let range = Self {
+            from_ms: from.map(|bound| bound.to_ms("from")).transpose()?,
+            to_ms: to.map(|bound| bound.to_ms("to")).transpose()?,
+        };
+        if let (Some(from), Some(to)) = (range.from_ms, range.to_ms) {
+            if from > to {
+                return Err(format!("Invalid time range: from ({}) is after to ({})", from, to));
+            }
+        }
+        Ok(range)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.from_ms.is_none() && self.to_ms.is_none()
+    }
+
+    pub fn contains_ms(&self, ms: u64) -> bool {
+        self.from_ms.is_none_or(|from| ms >= from) && self.to_ms.is_none_or(|to| ms <= to)
+    }
+
+    /// Whether an ISO 8601 timestamp, as the capture script records them, falls in the range.
+    /// Unreadable timestamps only pass an unbounded range.
+    pub fn contains_timestamp(&self, timestamp: &str) -> bool {
+        self.is_unbounded() || parse_iso8601(timestamp).is_some_and(|ms| self.contains_ms(ms))
+    }
+}
+
+/// Days from 1970-01-01 to a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Milliseconds since the Unix epoch of `YYYY-MM-DD`, optionally followed by `THH:MM[:SS[.fff]]`
+/// and `Z` or a `±HH:MM` offset. Times before the epoch are rejected.
+pub fn parse_iso8601(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = text.get(range)?;
+        digits.bytes().all(|b| b.is_ascii_digit()).then(|| digits.parse().ok())?
+    };
+    let separator = |index: usize, expected: &[u8]| {
+        text.as_bytes().get(index).is_some_and(|b| expected.contains(b))
+    };
+
+    if !separator(4, b"-") || !separator(7, b"-") {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut ms = days_from_civil(year, month, day) * 86_400_000;
+
+    let mut rest = &text[10..];
+    if !rest.is_empty() {
+        if !separator(10, b"Tt ") || !separator(13, b":") {
+            return None;
+        }
+        let (hour, minute) = (number(11..13)?, number(14..16)?);
+        let mut end = 16;
+        let mut second = 0;
+        if separator(16, b":") {
+            second = number(17..19)?;
+            end = 19;
+            if separator(19, b".,") {
+                let fraction: String = text[20..].chars().take_while(char::is_ascii_digit).collect();
+                if fraction.is_empty() {
+                    return None;
+                }
+                let millis: String = fraction.chars().chain("00".chars()).take(3).collect();
+                ms += millis.parse::<i64>().ok()?;
+                end = 20 + fraction.len();
+            }
+        }
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        ms += ((hour * 60 + minute) * 60 + second) * 1000;
+
+        rest = &text[end..];
+        match rest.as_bytes().first() {
+            None | Some(b'Z' | b'z') if rest.len() <= 1 => {}
+            Some(&sign) if sign == b'+' || sign == b'-' => {
+                let offset = &rest[1..];
+                let (hours, minutes) = match offset.len() {
+                    5 if offset.as_bytes()[2] == b':' => (offset.get(0..2)?, offset.get(3..5)?),
+                    4 => (offset.get(0..2)?, offset.get(2..4)?),
+                    2 => (offset.get(0..2)?, "00"),
+                    _ => return None,
+                };
+                let offset_ms = (hours.parse::<i64>().ok()? * 60 + minutes.parse::<i64>().ok()?) * 60_000;
+                ms += if sign == b'+' { -offset_ms } else { offset_ms };
+            }
+            _ => return None,
+        }
+    }
+
+    u64::try_from(ms).ok()
+}
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, Webview, WindowEvent};
+use log::info;
+
+use crate::socket_server::SocketResponse;
+use crate::tools::console_capture::handle_setup_console_capture;
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::time_range::{TimeBound, TimeRange};
+
+/// Maximum number of Rust-side events kept for the timeline
+const MAX_TIMELINE_EVENTS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineCategory {
+    Console,
+    Error,
+    Network,
+    Navigation,
+    Window,
+    /// Client-supplied breadcrumb added with `add_annotation`
+    Annotation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// Milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    pub category: TimelineCategory,
+    pub window_label: Option<String>,
+    pub summary: String,
+    pub details: Value,
+}
+
+/// Events observed on the Rust side (navigation, window lifecycle), merged into `get_timeline`
+#[derive(Default)]
+pub struct TimelineStore {
+    events: Mutex<VecDeque<TimelineEvent>>,
+}
+
+impl TimelineStore {
+    pub fn record(
+        &self,
+        category: TimelineCategory,
+        window_label: Option<String>,
+        summary: String,
+        details: Value,
+    ) {
+        let mut events = self.events.lock().unwrap();
+        if events.len() >= MAX_TIMELINE_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(TimelineEvent {
+            timestamp_ms: now_ms(),
+            category,
+            window_label,
+            summary,
+            details,
+        });
+    }
+
+    pub fn snapshot(&self) -> Vec<TimelineEvent> {
+        self.events.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Record an event if the plugin state is available on this manager
+pub fn record_event<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    category: TimelineCategory,
+    window_label: Option<String>,
+    summary: String,
+    details: Value,
+) {
+    if let Some(store) = manager.try_state::<TimelineStore>() {
+        store.record(category, window_label, summary, details);
+    }
+}
+
+/// Plugin `on_page_load` hook
+pub fn record_page_load<R: Runtime>(webview: &Webview<R>, url: &str, event: PageLoadEvent) {
+    let phase = match event {
+        PageLoadEvent::Started => "started",
+        PageLoadEvent::Finished => "finished",
+    };
+    record_event(
+        webview,
+        TimelineCategory::Navigation,
+        Some(webview.label().to_string()),
+        format!("Page load {}: {}", phase, url),
+        serde_json::json!({ "url": url, "phase": phase }),
+    );
+}
+
+/// Plugin `on_event` hook for window lifecycle changes
+pub fn record_window_event<R: Runtime>(app: &AppHandle<R>, label: &str, event: &WindowEvent) {
+    let (summary, details) = match event {
+        WindowEvent::Focused(true) => ("Window focused".to_string(), Value::Null),
+        WindowEvent::Focused(false) => ("Window blurred".to_string(), Value::Null),
+        WindowEvent::CloseRequested { .. } => ("Window close requested".to_string(), Value::Null),
+        WindowEvent::Destroyed => ("Window destroyed".to_string(), Value::Null),
+        WindowEvent::Resized(size) => (
+            format!("Window resized to {}x{}", size.width, size.height),
+            serde_json::json!({ "width": size.width, "height": size.height }),
+        ),
+        _ => return,
+    };
+    record_event(
+        app,
+        TimelineCategory::Window,
+        Some(label.to_string()),
+        summary,
+        details,
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimelineRequest {
+    pub window_label: Option<String>,
+    /// Only include these categories (default: all)
+    pub categories: Option<Vec<TimelineCategory>>,
+    /// Inclusive lower bound, in milliseconds since the Unix epoch or ISO 8601
+    pub from: Option<TimeBound>,
+    /// Inclusive upper bound, in milliseconds since the Unix epoch or ISO 8601
+    pub to: Option<TimeBound>,
+    /// `from` in milliseconds since the Unix epoch
+    pub from_ms: Option<u64>,
+    /// `to` in milliseconds since the Unix epoch
+    pub to_ms: Option<u64>,
+    /// Keep only the most recent `limit` events
+    pub limit: Option<usize>,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnotationRequest {
+    /// Human-readable marker, e.g. "step 3: submitted form"
+    pub label: String,
+    /// Attach the marker to one window; omitted markers show up in every window's timeline
+    pub window_label: Option<String>,
+    #[serde(default)]
+    pub details: Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineResponse {
+    pub events: Vec<TimelineEvent>,
+    pub total_count: usize,
+}
+
+/// Collect console entries, JS errors and network requests from the page, tagged with epoch times
+const PAGE_EVENTS_CODE: &str = r#"
+    const withTime = (items) => (items || []).map(item => Object.assign({}, item, {
+        timestamp_ms: Date.parse(item.timestamp) || 0
+    }));
+    return {
+        console: withTime(window.__consoleBuffer),
+        errors: withTime(window.__mcpJSErrors),
+        network: withTime(window.__mcpNetworkLog)
+    };
+"#;
+
+fn page_events(
+    value: &Value,
+    key: &str,
+    category: TimelineCategory,
+    window_label: &str,
+) -> Vec<TimelineEvent> {
+    let items = match value.get(key).and_then(|v| v.as_array()) {
+        Some(items) => items,
+        None => return Vec::new(),
+    };
+
+    items
+        .iter()
+        .map(|item| {
+            let text = |field: &str| item.get(field).and_then(|v| v.as_str()).unwrap_or("");
+            let summary = match category {
+                TimelineCategory::Console => {
+                    match item.get("repeatCount").and_then(|v| v.as_u64()).filter(|&n| n > 1) {
+                        Some(count) => format!("[{}] {} (x{})", text("level"), text("message"), count),
+                        None => format!("[{}] {}", text("level"), text("message")),
+                    }
+                }
+                TimelineCategory::Network => format!(
+                    "{} {} -> {}",
+                    text("method"),
+                    text("url"),
+                    item.get("status")
+                        .and_then(|v| v.as_u64())
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| text("error").to_string())
+                ),
+                _ => text("message").to_string(),
+            };
+            TimelineEvent {
+                timestamp_ms: item.get("timestamp_ms").and_then(|v| v.as_u64()).unwrap_or(0),
+                category,
+                window_label: Some(window_label.to_string()),
+                summary,
+                details: item.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Insert a labeled breadcrumb into the Rust-side timeline
+pub fn handle_add_annotation<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: AnnotationRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+
+    info!("[TAURI_MCP] Annotation: {}", request.label);
+
+    let timestamp_ms = now_ms();
+    app.state::<TimelineStore>().record(
+        TimelineCategory::Annotation,
+        request.window_label,
+        request.label,
+        request.details,
+    );
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::json!({ "timestamp_ms": timestamp_ms })),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Merge page and Rust-side events into one time-ordered stream
+pub async fn handle_get_timeline<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: TimelineRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+
+    info!("[TAURI_MCP] Building timeline for window: {}", window_label);
+
+    let range = match TimeRange::new(
+        request.from.or(request.from_ms.map(TimeBound::Millis)).as_ref(),
+        request.to.or(request.to_ms.map(TimeBound::Millis)).as_ref(),
+    ) {
+        Ok(range) => range,
+        Err(error) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(error),
+                ..Default::default()
+            })
+        }
+    };
+
+    let wants = |category: TimelineCategory| {
+        request
+            .categories
+            .as_ref()
+            .map(|c| c.contains(&category))
+            .unwrap_or(true)
+    };
+
+    let mut events: Vec<TimelineEvent> = app
+        .state::<TimelineStore>()
+        .snapshot()
+        .into_iter()
+        .filter(|e| e.window_label.as_deref().map(|l| l == window_label).unwrap_or(true))
+        .collect();
+
+    if wants(TimelineCategory::Console) || wants(TimelineCategory::Error) || wants(TimelineCategory::Network) {
+        let setup_result = handle_setup_console_capture(app, serde_json::json!({
+            "window_label": window_label
+        })).await?;
+        if !setup_result.success {
+            return Ok(setup_result);
+        }
+
+        let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(5000));
+        let page = eval_with_result(app, &window_label, PAGE_EVENTS_CODE, timeout).await?;
+        events.extend(page_events(&page, "console", TimelineCategory::Console, &window_label));
+        events.extend(page_events(&page, "errors", TimelineCategory::Error, &window_label));
+        events.extend(page_events(&page, "network", TimelineCategory::Network, &window_label));
+    }
+
+    events.retain(|e| wants(e.category) && range.contains_ms(e.timestamp_ms));
+    events.sort_by_key(|e| e.timestamp_ms);
+
+    let total_count = events.len();
+    if let Some(limit) = request.limit {
+        if events.len() > limit {
+            events.drain(..events.len() - limit);
+        }
+    }
+
+    let response = TimelineResponse {
+        events,
+        total_count,
+    };
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            crate::Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
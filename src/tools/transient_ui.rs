@@ -0,0 +1,190 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::{info, warn};
+
+use crate::TauriMcpExt;
+use crate::models::ScreenshotRequest;
+use crate::socket_server::SocketResponse;
+use crate::tools::elements::{ElementTarget, find_element_code, handle_force_pseudo_state};
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::rendering::handle_set_animations;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RevealMethod {
+    Hover,
+    Focus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CaptureTransientUiRequest {
+    pub window_label: Option<String>,
+    /// Element that reveals the tooltip/popover
+    #[serde(flatten)]
+    pub trigger: ElementTarget,
+    /// Selector of the tooltip/popover to wait for
+    pub popover_selector: String,
+    /// How to reveal it (default hover)
+    pub method: Option<RevealMethod>,
+    /// How long to wait for the popover to become visible (default 3000ms)
+    pub wait_ms: Option<u64>,
+}
+
+/// Reveal a tooltip or popover, freeze it, screenshot it and restore the page
+pub async fn handle_capture_transient_ui<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: CaptureTransientUiRequest = serde_json::from_value(payload)
+        .map_err(|e| crate::Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let method = request.method.unwrap_or(RevealMethod::Hover);
+    let wait_ms = request.wait_ms.unwrap_or(3000);
+    let timeout = Duration::from_millis(wait_ms) + Duration::from_secs(5);
+
+    info!(
+        "[TAURI_MCP] Capturing transient UI '{}' from {} in window: {}",
+        request.popover_selector, request.trigger, window_label
+    );
+
+    let state = match method {
+        RevealMethod::Hover => "hover",
+        RevealMethod::Focus => "focus",
+    };
+    let pseudo_payload = |enabled: bool| {
+        let mut payload =
+            serde_json::to_value(&request.trigger).unwrap_or_else(|_| serde_json::json!({}));
+        payload["window_label"] = Value::String(window_label.clone());
+        payload["states"] = serde_json::json!([state]);
+        payload["enabled"] = Value::Bool(enabled);
+        payload
+    };
+
+    // Real events drive JS-controlled popovers; forced pseudo classes cover CSS-only tooltips
+    let reveal_code = format!(
+        r#"
+        {find_element}
+        element.scrollIntoView({{ block: 'center', inline: 'center' }});
+        if ({hover}) {{
+            const point = window.__mcp.center(element);
+            const init = {{ bubbles: true, composed: true, clientX: point.x, clientY: point.y }};
+            if (window.PointerEvent) {{
+                element.dispatchEvent(new PointerEvent('pointerover', init));
+                element.dispatchEvent(new PointerEvent('pointerenter', Object.assign({{}}, init, {{ bubbles: false }})));
+                element.dispatchEvent(new PointerEvent('pointermove', init));
+            }}
+            element.dispatchEvent(new MouseEvent('mouseover', init));
+            element.dispatchEvent(new MouseEvent('mouseenter', Object.assign({{}}, init, {{ bubbles: false }})));
+            element.dispatchEvent(new MouseEvent('mousemove', init));
+        }} else {{
+            element.focus();
+        }}
+        return true;
+        "#,
+        find_element = find_element_code(&request.trigger)?,
+        hover = method == RevealMethod::Hover
+    );
+    eval_with_result(app, &window_label, &reveal_code, timeout).await?;
+    handle_force_pseudo_state(app, pseudo_payload(true)).await?;
+
+    let wait_code = format!(
+        r#"
+        const {{ handles, isVisible, text, bounds }} = window.__mcp;
+        const deadline = Date.now() + {wait_ms};
+        while (true) {{
+            const popover = Array.from(document.querySelectorAll({popover_selector}))
+                .find(node => isVisible(node) && getComputedStyle(node).opacity !== '0');
+            if (popover) {{
+                return {{
+                    handle: handles.handleFor(popover),
+                    text: text(popover),
+                    bounds: bounds(popover),
+                    device_pixel_ratio: window.devicePixelRatio
+                }};
+            }}
+            if (Date.now() >= deadline) return null;
+            await new Promise(resolve => setTimeout(resolve, 50));
+        }}
+        "#,
+        wait_ms = wait_ms,
+        popover_selector = serde_json::to_string(&request.popover_selector).unwrap_or_default()
+    );
+    let popover = eval_with_result(app, &window_label, &wait_code, timeout).await;
+
+    let mut screenshot = None;
+    if let Ok(ref found) = popover {
+        if !found.is_null() {
+            // Freeze the popover mid-transition so the screenshot is stable
+            handle_set_animations(app, serde_json::json!({
+                "window_label": window_label,
+                "enabled": false
+            })).await?;
+            let response = app
+                .tauri_mcp()
+                .take_screenshot_async(ScreenshotRequest {
+                    window_label: window_label.clone(),
+                    ..Default::default()
+                })
+                .await?;
+            screenshot = response.data;
+        }
+    }
+
+    // Restore the page whether or not the popover appeared
+    let restore_code = format!(
+        r#"
+        {find_element}
+        if ({hover}) {{
+            const init = {{ bubbles: true, composed: true }};
+            if (window.PointerEvent) {{
+                element.dispatchEvent(new PointerEvent('pointerout', init));
+                element.dispatchEvent(new PointerEvent('pointerleave', Object.assign({{}}, init, {{ bubbles: false }})));
+            }}
+            element.dispatchEvent(new MouseEvent('mouseout', init));
+            element.dispatchEvent(new MouseEvent('mouseleave', Object.assign({{}}, init, {{ bubbles: false }})));
+        }} else {{
+            element.blur();
+        }}
+        return true;
+        "#,
+        find_element = find_element_code(&request.trigger)?,
+        hover = method == RevealMethod::Hover
+    );
+    if let Err(e) = eval_with_result(app, &window_label, &restore_code, timeout).await {
+        warn!("[TAURI_MCP] Failed to restore trigger state: {}", e);
+    }
+    if let Err(e) = handle_force_pseudo_state(app, pseudo_payload(false)).await {
+        warn!("[TAURI_MCP] Failed to clear forced pseudo state: {}", e);
+    }
+    if screenshot.is_some() {
+        if let Err(e) = handle_set_animations(app, serde_json::json!({
+            "window_label": window_label,
+            "enabled": true
+        })).await {
+            warn!("[TAURI_MCP] Failed to re-enable animations: {}", e);
+        }
+    }
+
+    match popover? {
+        Value::Null => Ok(SocketResponse {
+            success: false,
+            data: None,
+            error: Some(format!(
+                "No visible element matching '{}' appeared within {}ms",
+                request.popover_selector, wait_ms
+            )),
+            ..Default::default()
+        }),
+        found => Ok(SocketResponse {
+            success: true,
+            data: Some(serde_json::json!({
+                "popover": found,
+                "screenshot": screenshot,
+            })),
+            error: None,
+            ..Default::default()
+        }),
+    }
+}
@@ -0,0 +1,258 @@
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Runtime, Webview};
+use log::{info, warn};
+
+use crate::TauriMcpExt;
+use crate::artifacts::{ArtifactInfo, ArtifactStore};
+use crate::models::ScreenshotRequest;
+use crate::notifications::NotificationHub;
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::sensitive_regions;
+use crate::tools::timeline::{handle_get_timeline, now_ms};
+
+/// How much timeline history is saved with a trigger capture
+const RECENT_TIMELINE_MS: u64 = 60_000;
+
+/// Minimum time between two automatic captures of the same window
+const CAPTURE_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// Conditions that automatically capture a screenshot, DOM snapshot and recent timeline
+#[derive(Clone, Debug)]
+pub enum CaptureTrigger {
+    /// An uncaught exception or unhandled promise rejection
+    UncaughtError,
+    /// A fetch/XHR response with a 5xx status
+    HttpServerError,
+    /// A `console.error` call whose message matches the pattern
+    ConsoleErrorMatching(Regex),
+}
+
+impl CaptureTrigger {
+    /// Event kind reported by the capture script for this trigger
+    fn kind(&self) -> &'static str {
+        match self {
+            CaptureTrigger::UncaughtError => "uncaught_error",
+            CaptureTrigger::HttpServerError => "http_server_error",
+            CaptureTrigger::ConsoleErrorMatching(_) => "console_error",
+        }
+    }
+
+    fn matches(&self, event: &TriggerEvent) -> bool {
+        if event.kind != self.kind() {
+            return false;
+        }
+        match self {
+            CaptureTrigger::ConsoleErrorMatching(pattern) => event
+                .message
+                .as_deref()
+                .map(|m| pattern.is_match(m))
+                .unwrap_or(false),
+            _ => true,
+        }
+    }
+}
+
+/// Something trigger-worthy the capture script reports through the plugin's `capture_trigger`
+/// command
+#[derive(Debug, Deserialize)]
+pub(crate) struct TriggerEvent {
+    kind: String,
+    message: Option<String>,
+    status: Option<u16>,
+    url: Option<String>,
+}
+
+impl TriggerEvent {
+    fn describe(&self) -> String {
+        match (self.status, &self.url, &self.message) {
+            (Some(status), Some(url), _) => format!("{}: HTTP {} from {}", self.kind, status, url),
+            (_, _, Some(message)) => format!("{}: {}", self.kind, message),
+            _ => self.kind.clone(),
+        }
+    }
+}
+
+/// Configured triggers plus per-window cooldown bookkeeping
+pub struct TriggerState {
+    triggers: Vec<CaptureTrigger>,
+    last_capture: Mutex<HashMap<String, Instant>>,
+}
+
+impl TriggerState {
+    pub fn new(triggers: Vec<CaptureTrigger>) -> Self {
+        Self {
+            triggers,
+            last_capture: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+
+    /// Event kinds the capture script should report
+    pub fn kinds(&self) -> Vec<&'static str> {
+        let mut kinds: Vec<&'static str> = self.triggers.iter().map(|t| t.kind()).collect();
+        kinds.dedup();
+        kinds
+    }
+
+    /// Returns true if a capture for this window may start now, and records it
+    fn try_begin_capture(&self, window_label: &str) -> bool {
+        let mut last_capture = self.last_capture.lock().unwrap();
+        let now = Instant::now();
+        match last_capture.get(window_label) {
+            Some(last) if now.duration_since(*last) < CAPTURE_COOLDOWN => false,
+            _ => {
+                last_capture.insert(window_label.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+/// Capture artifacts for `window_label` when a trigger event it reported matches a configured
+/// trigger. The label is the webview that reported it, never something the page claims.
+pub(crate) fn fire<R: Runtime>(app: &AppHandle<R>, window_label: &str, trigger_event: TriggerEvent) {
+    let state = app.state::<TriggerState>();
+    if !state.triggers.iter().any(|t| t.matches(&trigger_event)) {
+        return;
+    }
+    // Captures need a window; a bare webview has nothing to screenshot
+    if app.get_webview_window(window_label).is_none() || !state.try_begin_capture(window_label) {
+        return;
+    }
+
+    let reason = trigger_event.describe();
+    info!("[TAURI_MCP] Capture trigger fired for {}: {}", window_label, reason);
+
+    let app = app.clone();
+    let window_label = window_label.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = capture_artifacts(&app, &window_label, &reason).await {
+            warn!("[TAURI_MCP] Automatic capture failed: {}", e);
+        }
+    });
+}
+
+/// Plugin `on_page_load` hook: install the capture script on every page once triggers are configured
+pub fn arm_page<R: Runtime>(webview: &Webview<R>, event: PageLoadEvent) {
+    if !matches!(event, PageLoadEvent::Finished) {
+        return;
+    }
+    let armed = webview
+        .try_state::<TriggerState>()
+        .map(|state| !state.is_empty())
+        .unwrap_or(false);
+    if !armed {
+        return;
+    }
+
+    let app = webview.app_handle().clone();
+    let window_label = webview.label().to_string();
+    tauri::async_runtime::spawn(async move {
+        let payload = serde_json::json!({ "window_label": window_label });
+        if let Err(e) = crate::tools::handle_setup_console_capture(&app, payload).await {
+            warn!("[TAURI_MCP] Failed to arm capture triggers for {}: {}", window_label, e);
+        }
+    });
+}
+
+/// Save a screenshot, DOM snapshot and recent timeline for a window, then notify subscribers
+pub async fn capture_artifacts<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    reason: &str,
+) -> crate::Result<ArtifactInfo> {
+    let store = app.state::<ArtifactStore>();
+    let redactor = app.tauri_mcp().redactor().clone();
+    let created_ms = now_ms();
+    let id = store.allocate_id("trigger", created_ms);
+    let mut files = Vec::new();
+
+    let screenshot = app
+        .tauri_mcp()
+        .take_screenshot_async(ScreenshotRequest {
+            window_label: window_label.to_string(),
+            format: Some(crate::ImageFormat::Jpeg),
+            ..Default::default()
+        })
+        .await;
+    match screenshot {
+        Ok(response) => match response.data {
+            Some(data_url) => {
+                let encoded = data_url.split(',').nth(1).unwrap_or(&data_url);
+                match base64::decode(encoded) {
+                    Ok(bytes) => files.push(store.write_file(&id, "screenshot.jpg", &bytes)?),
+                    Err(e) => warn!("[TAURI_MCP] Could not decode trigger screenshot: {}", e),
+                }
+            }
+            None => warn!(
+                "[TAURI_MCP] Trigger screenshot failed: {}",
+                response.error.unwrap_or_default()
+            ),
+        },
+        Err(e) => warn!("[TAURI_MCP] Trigger screenshot failed: {}", e),
+    }
+
+    let dom = eval_with_result(
+        app,
+        window_label,
+        &sensitive_regions::html_script(app),
+        Duration::from_secs(5),
+    )
+    .await;
+    match dom {
+        Ok(Value::String(html)) => {
+            files.push(store.write_file(&id, "dom.html", redactor.redact_str(&html).as_bytes())?)
+        }
+        Ok(_) => warn!("[TAURI_MCP] Trigger DOM snapshot returned no HTML"),
+        Err(e) => warn!("[TAURI_MCP] Trigger DOM snapshot failed: {}", e),
+    }
+
+    let timeline = handle_get_timeline(
+        app,
+        serde_json::json!({
+            "window_label": window_label,
+            "from_ms": created_ms.saturating_sub(RECENT_TIMELINE_MS),
+        }),
+    )
+    .await;
+    match timeline {
+        Ok(response) => {
+            if let Some(mut data) = response.data {
+                redactor.redact_value(&mut data);
+                let json = serde_json::to_vec_pretty(&data).map_err(|e| {
+                    crate::Error::Anyhow(format!("Failed to serialize timeline: {}", e))
+                })?;
+                files.push(store.write_file(&id, "timeline.json", &json)?);
+            }
+        }
+        Err(e) => warn!("[TAURI_MCP] Trigger timeline capture failed: {}", e),
+    }
+
+    let info = ArtifactInfo {
+        id,
+        kind: "trigger".to_string(),
+        reason: redactor.redact_str(reason),
+        window_label: Some(window_label.to_string()),
+        created_ms,
+        files,
+    };
+    store.register(info.clone());
+
+    if let Some(hub) = app.try_state::<NotificationHub>() {
+        hub.publish(
+            "artifacts",
+            serde_json::to_value(&info).unwrap_or(Value::Null),
+        );
+    }
+
+    Ok(info)
+}
@@ -0,0 +1,78 @@
+//! TypeScript support for eval payloads: type annotations and other TypeScript-only syntax are
+//! stripped with swc before the code is sent to the page. Nothing is type-checked.
+
+use swc_core::common::sync::Lrc;
+use swc_core::common::{FileName, GLOBALS, Globals, Mark, SourceMap, Spanned};
+use swc_core::ecma::ast::{EsVersion, Program};
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::codegen::{Config, Emitter};
+use swc_core::ecma::parser::{Syntax, TsConfig, parse_file_as_module, parse_file_as_script};
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::typescript::strip;
+use swc_core::ecma::visit::FoldWith;
+
+/// Name of the function the code is wrapped in so `return` and `await` parse at its top level
+const WRAPPER: &str = "async function __mcpTypescript() {";
+
+/// Turn TypeScript `code` into JavaScript. `module` code is parsed as an ES module; anything else
+/// as the body of an async function, like every other eval. Syntax errors are reported as
+/// `line:column: message`, counted within `code`.
+pub fn transpile(code: &str, module: bool) -> Result<String, String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let (source, line_offset) = if module {
+        (code.to_string(), 0)
+    } else {
+        (format!("{}\n{}\n}}", WRAPPER, code), 1)
+    };
+    let file = cm.new_source_file(FileName::Anon.into(), source);
+    let syntax = Syntax::Typescript(TsConfig::default());
+
+    let mut recovered = Vec::new();
+    let parsed = if module {
+        parse_file_as_module(&file, syntax, EsVersion::latest(), None, &mut recovered).map(Program::Module)
+    } else {
+        parse_file_as_script(&file, syntax, EsVersion::latest(), None, &mut recovered).map(Program::Script)
+    };
+    let describe = |error: &swc_core::ecma::parser::error::Error| {
+        let at = cm.lookup_char_pos(error.span().lo);
+        format!(
+            "{}:{}: {}",
+            at.line.saturating_sub(line_offset),
+            at.col_display + 1,
+            error.kind().msg()
+        )
+    };
+    let program = match parsed {
+        Ok(program) if recovered.is_empty() => program,
+        Ok(_) => return Err(describe(&recovered[0])),
+        Err(error) => return Err(describe(&error)),
+    };
+
+    let output = GLOBALS.set(&Globals::new(), || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+        let program = program
+            .fold_with(&mut resolver(unresolved_mark, top_level_mark, true))
+            .fold_with(&mut strip(top_level_mark));
+
+        let mut buffer = Vec::new();
+        {
+            let mut emitter = Emitter {
+                cfg: Config::default(),
+                cm: cm.clone(),
+                comments: None,
+                wr: JsWriter::new(cm.clone(), "\n", &mut buffer, None),
+            };
+            emitter.emit_program(&program).map_err(|e| e.to_string())?;
+        }
+        String::from_utf8(buffer).map_err(|e| e.to_string())
+    })?;
+
+    if module {
+        return Ok(output);
+    }
+    // Unwrap the function body again
+    let start = output.find('{').map(|at| at + 1).unwrap_or(0);
+    let end = output.rfind('}').unwrap_or(output.len());
+    Ok(output[start..end].trim_matches('\n').to_string())
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+use crate::tools::js_bridge::eval_with_result;
+
+#[derive(Debug, Deserialize)]
+pub struct InjectScriptRequest {
+    pub window_label: Option<String>,
+    /// Script to run, e.g. a library bundle. It runs inside a function called with `this` set to
+    /// `window`, so anything later evals should see must be assigned to `window`.
+    pub script: String,
+    /// Name to track the script under (defaults to a hash of its source). Injecting a different
+    /// source under an installed name replaces it.
+    pub id: Option<String>,
+    /// Run the script even if this exact source is already installed
+    #[serde(default)]
+    pub force: bool,
+    pub timeout_ms: Option<u64>,
+}
+
+/// What the page reports after the guarded script ran
+#[derive(Debug, Deserialize)]
+struct PageInjection {
+    installed: bool,
+    replaced: bool,
+    installed_scripts: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InjectScriptResponse {
+    pub id: String,
+    /// Whether the script ran; `false` when the page already had this source
+    pub installed: bool,
+    /// Whether an earlier source under the same id was installed
+    pub replaced: bool,
+    /// Ids of every user script installed in the page
+    pub installed_scripts: Vec<String>,
+}
+
+/// Hash of a script's source, used as its id when none is given and to spot changed sources
+pub fn source_hash(source: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Expression that runs `source` unless the page already recorded it under `id`, evaluating to
+/// `{ installed, replaced, installed_scripts }`. The id is only recorded once the script ran
+/// without throwing.
+fn guarded_script(id: &str, source: &str, force: bool) -> String {
+    let id = serde_json::to_string(id).unwrap_or_default();
+    let hash = serde_json::to_string(&source_hash(source)).unwrap_or_default();
+    format!(
+        r#"(function (id, hash, force) {{
+    const installed = window.__mcpUserScripts || (window.__mcpUserScripts = {{}});
+    const previous = installed[id];
+    if (previous === hash && !force) {{
+        return {{ installed: false, replaced: false, installed_scripts: Object.keys(installed) }};
+    }}
+    (function () {{
+{source}
+    }}).call(window);
+    installed[id] = hash;
+    return {{ installed: true, replaced: previous !== undefined && previous !== hash, installed_scripts: Object.keys(installed) }};
+}})({id}, {hash}, {force})"#
+    )
+}
+
+/// Initialization script installing `scripts` in order on every page load. A script that throws
+/// is reported on the console and doesn't stop the ones after it.
+pub fn init_script(scripts: &[String]) -> String {
+    scripts
+        .iter()
+        .map(|source| {
+            format!(
+                "try {{ {}; }} catch (e) {{ console.error('[TAURI_MCP] Init script failed:', e); }}",
+                guarded_script(&source_hash(source), source, false)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Install a helper script into a window's current page once, so later evals can rely on it.
+/// Scripts registered with `PluginConfig::init_scripts` are tracked under their source hash, so
+/// injecting the same source again is a no-op.
+pub async fn handle_inject_script<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: InjectScriptRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let id = request.id.unwrap_or_else(|| source_hash(&request.script));
+    let timeout = Duration::from_millis(request.timeout_ms.unwrap_or(10000));
+
+    info!("[TAURI_MCP] Injecting script '{}' into window: {}", id, window_label);
+
+    let body = format!("return {};", guarded_script(&id, &request.script, request.force));
+    let result = match eval_with_result(app, &window_label, &body, timeout).await {
+        Ok(value) => value,
+        Err(e) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Script '{}' failed: {}", id, e)),
+                ..Default::default()
+            })
+        }
+    };
+
+    let page: PageInjection = serde_json::from_value(result)
+        .map_err(|e| Error::Anyhow(format!("Failed to parse injection result: {}", e)))?;
+    let response = InjectScriptResponse {
+        id,
+        installed: page.installed,
+        replaced: page.replaced,
+        installed_scripts: page.installed_scripts,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
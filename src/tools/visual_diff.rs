@@ -0,0 +1,626 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::TauriMcpExt;
+use crate::encoding::{ImageFormat, encode_blocking};
+use crate::error::Error;
+use crate::models::ScreenshotRequest;
+use crate::socket_server::SocketResponse;
+use crate::tools::js_bridge::eval_with_result;
+
+/// Side of the square cells changed pixels are grouped into when building regions
+const REGION_CELL: u32 = 16;
+
+/// Regions reported at most, largest first
+const MAX_REGIONS: usize = 50;
+
+/// What a diff response carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOutput {
+    /// PNG of the `after` image with changed pixels in red and the rest faded
+    Image,
+    /// Bounding boxes of changed areas
+    Regions,
+    /// Percentage of pixels that changed
+    Score,
+}
+
+/// Rectangle in screenshot pixels
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MaskRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MaskRect {
+    fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x - self.x < self.width && y - self.y < self.height
+    }
+}
+
+/// A reference screenshot and the areas ignored when comparing against it
+struct Baseline {
+    image: String,
+    masks: Vec<MaskRect>,
+}
+
+/// Named baselines kept between `diff_screenshots` calls
+#[derive(Default)]
+pub struct ScreenshotBaselineStore {
+    baselines: Mutex<HashMap<String, Arc<Baseline>>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiffScreenshotsRequest {
+    /// Screenshot data URL (as returned by `take_screenshot`) or bare base64. May be left out
+    /// when `baseline` names a stored screenshot.
+    pub before: Option<String>,
+    pub after: String,
+    /// Stored baseline to compare `after` against. A baseline that doesn't exist yet is created
+    /// from `after` and the masks, and nothing is compared.
+    pub baseline: Option<String>,
+    /// Replace the baseline's image and masks with `after` and this request's masks (default false)
+    pub update_baseline: Option<bool>,
+    /// Areas to ignore, in screenshot pixels
+    pub masks: Option<Vec<MaskRect>>,
+    /// Elements to ignore (timestamps, avatars, ads), located in `window_label` at request time.
+    /// Their positions are scaled to the screenshot, which should show the whole viewport.
+    pub mask_selectors: Option<Vec<String>>,
+    pub window_label: Option<String>,
+    /// Largest per-channel difference still counted as unchanged (default 16), which absorbs
+    /// lossy encoding noise
+    pub threshold: Option<u8>,
+    /// Parts to include in the response (default `["score", "regions"]`)
+    pub output: Option<Vec<DiffOutput>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Changed pixels inside the box
+    pub changed_pixels: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffScreenshotsResponse {
+    /// True when a new baseline was stored and nothing was compared
+    pub first_capture: bool,
+    pub width: u32,
+    pub height: u32,
+    pub changed_pixels: u64,
+    /// Pixels excluded by masks; the score is relative to the rest
+    pub masked_pixels: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<Vec<DiffRegion>>,
+    /// `data:image/png;base64,...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_image: Option<String>,
+}
+
+fn decode_image(label: &str, data: &str) -> crate::Result<RgbaImage> {
+    let encoded = match data.find(";base64,") {
+        Some(index) if data.starts_with("data:") => &data[index + ";base64,".len()..],
+        _ => data,
+    };
+    let bytes = base64::decode(encoded.trim())
+        .map_err(|e| Error::Anyhow(format!("Invalid base64 in `{}`: {}", label, e)))?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| Error::Anyhow(format!("Failed to decode `{}` image: {}", label, e)))?;
+    Ok(image.to_rgba8())
+}
+
+fn pixel_changed(a: &Rgba<u8>, b: &Rgba<u8>, threshold: u8) -> bool {
+    a.0.iter().zip(b.0.iter()).any(|(x, y)| x.abs_diff(*y) > threshold)
+}
+
+/// Group changed cells into connected areas and return their bounding boxes
+fn changed_regions(cells: &[u64], columns: u32, rows: u32, width: u32, height: u32) -> Vec<DiffRegion> {
+    let mut seen = vec![false; cells.len()];
+    let mut regions = Vec::new();
+
+    for start in 0..cells.len() {
+        if cells[start] == 0 || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let (mut min_col, mut min_row, mut max_col, mut max_row) = (columns, rows, 0, 0);
+        let mut changed_pixels = 0;
+
+        while let Some(index) = stack.pop() {
+            let col = index as u32 % columns;
+            let row = index as u32 / columns;
+            min_col = min_col.min(col);
+            min_row = min_row.min(row);
+            max_col = max_col.max(col);
+            max_row = max_row.max(row);
+            changed_pixels += cells[index];
+
+            let mut visit = |c: u32, r: u32| {
+                let neighbour = (r * columns + c) as usize;
+                if cells[neighbour] > 0 && !seen[neighbour] {
+                    seen[neighbour] = true;
+                    stack.push(neighbour);
+                }
+            };
+            if col > 0 {
+                visit(col - 1, row);
+            }
+            if col + 1 < columns {
+                visit(col + 1, row);
+            }
+            if row > 0 {
+                visit(col, row - 1);
+            }
+            if row + 1 < rows {
+                visit(col, row + 1);
+            }
+        }
+
+        let x = min_col * REGION_CELL;
+        let y = min_row * REGION_CELL;
+        regions.push(DiffRegion {
+            x,
+            y,
+            width: ((max_col + 1) * REGION_CELL).min(width) - x,
+            height: ((max_row + 1) * REGION_CELL).min(height) - y,
+            changed_pixels,
+        });
+    }
+
+    regions.sort_by(|a, b| b.changed_pixels.cmp(&a.changed_pixels));
+    regions.truncate(MAX_REGIONS);
+    regions
+}
+
+fn diff_images(
+    before: &RgbaImage,
+    after: &RgbaImage,
+    masks: &[MaskRect],
+    threshold: u8,
+    output: &[DiffOutput],
+) -> crate::Result<DiffScreenshotsResponse> {
+    let (width, height) = after.dimensions();
+    if before.dimensions() != (width, height) {
+        return Err(Error::Anyhow(format!(
+            "Screenshots differ in size: {}x{} vs {}x{}",
+            before.width(),
+            before.height(),
+            width,
+            height
+        )));
+    }
+
+    let columns = width.div_ceil(REGION_CELL);
+    let rows = height.div_ceil(REGION_CELL);
+    let mut cells = vec![0u64; (columns * rows) as usize];
+    let want_image = output.contains(&DiffOutput::Image);
+    let mut diff_image = want_image.then(|| RgbaImage::new(width, height));
+    let mut changed_pixels = 0u64;
+    let mut masked_pixels = 0u64;
+
+    for (x, y, pixel) in after.enumerate_pixels() {
+        let masked = masks.iter().any(|mask| mask.contains(x, y));
+        let changed = !masked && pixel_changed(before.get_pixel(x, y), pixel, threshold);
+        if masked {
+            masked_pixels += 1;
+        }
+        if changed {
+            changed_pixels += 1;
+            cells[((y / REGION_CELL) * columns + x / REGION_CELL) as usize] += 1;
+        }
+        if let Some(image) = diff_image.as_mut() {
+            let shown = if changed {
+                Rgba([255, 0, 0, 255])
+            } else if masked {
+                Rgba([128, 128, 128, 255])
+            } else {
+                // Fade unchanged pixels towards white so the red stands out
+                let [r, g, b, _] = pixel.0;
+                Rgba([
+                    (r / 4).saturating_add(191),
+                    (g / 4).saturating_add(191),
+                    (b / 4).saturating_add(191),
+                    255,
+                ])
+            };
+            image.put_pixel(x, y, shown);
+        }
+    }
+
+    let total = (width as u64 * height as u64 - masked_pixels).max(1);
+    let diff_image = match diff_image {
+        Some(image) => Some(
+            encode_blocking(Arc::new(DynamicImage::ImageRgba8(image)), ImageFormat::Png, 100)?.data_url,
+        ),
+        None => None,
+    };
+
+    Ok(DiffScreenshotsResponse {
+        first_capture: false,
+        width,
+        height,
+        changed_pixels,
+        masked_pixels,
+        score: output
+            .contains(&DiffOutput::Score)
+            .then(|| changed_pixels as f64 * 100.0 / total as f64),
+        regions: output
+            .contains(&DiffOutput::Regions)
+            .then(|| changed_regions(&cells, columns, rows, width, height)),
+        diff_image,
+    })
+}
+
+/// Boxes of the elements matching `selectors` in CSS pixels, with the viewport width to scale them by
+async fn selector_rects<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    selectors: &[String],
+) -> crate::Result<(f64, Vec<(f64, f64, f64, f64)>)> {
+    let code = format!(
+        r#"
+        const rects = [];
+        for (const selector of {}) {{
+            document.querySelectorAll(selector).forEach(element => {{
+                const rect = element.getBoundingClientRect();
+                if (rect.width > 0 && rect.height > 0) rects.push([rect.x, rect.y, rect.width, rect.height]);
+            }});
+        }}
+        return {{ viewport_width: window.innerWidth, rects: rects }};
+        "#,
+        serde_json::to_string(selectors).unwrap_or_else(|_| "[]".to_string())
+    );
+    let value = eval_with_result(app, window_label, &code, Duration::from_secs(5)).await?;
+    let viewport_width = value.get("viewport_width").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let rects = serde_json::from_value(value.get("rects").cloned().unwrap_or(Value::Null))
+        .map_err(|e| Error::Anyhow(format!("Failed to parse mask rectangles: {}", e)))?;
+    Ok((viewport_width, rects))
+}
+
+fn scale_masks(viewport_width: f64, rects: &[(f64, f64, f64, f64)], image_width: u32) -> Vec<MaskRect> {
+    if viewport_width <= 0.0 {
+        return Vec::new();
+    }
+    let scale = image_width as f64 / viewport_width;
+    rects
+        .iter()
+        .map(|&(x, y, width, height)| {
+            let left = (x * scale).floor().max(0.0);
+            let top = (y * scale).floor().max(0.0);
+            MaskRect {
+                x: left as u32,
+                y: top as u32,
+                width: ((x + width) * scale).ceil().max(left) as u32 - left as u32,
+                height: ((y + height) * scale).ceil().max(top) as u32 - top as u32,
+            }
+        })
+        .collect()
+}
+
+/// Compare two screenshots pixel by pixel, or a screenshot against a stored baseline
+pub async fn handle_diff_screenshots<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: DiffScreenshotsRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let threshold = request.threshold.unwrap_or(16);
+    let output = request
+        .output
+        .clone()
+        .unwrap_or_else(|| vec![DiffOutput::Score, DiffOutput::Regions]);
+
+    info!("[TAURI_MCP] Diffing screenshots (output {:?})", output);
+
+    let selector_rects = match request.mask_selectors.as_deref() {
+        Some(selectors) if !selectors.is_empty() => {
+            let window_label = request.window_label.as_deref().unwrap_or("main");
+            Some(selector_rects(app, window_label, selectors).await?)
+        }
+        _ => None,
+    };
+
+    let store = app.state::<ScreenshotBaselineStore>();
+    let baseline = request
+        .baseline
+        .as_ref()
+        .and_then(|name| store.baselines.lock().unwrap().get(name).cloned());
+    if request.before.is_none() && baseline.is_none() && request.baseline.is_none() {
+        return Err(Error::Anyhow("Either `before` or `baseline` is required".to_string()));
+    }
+
+    // Decoding and comparing full screenshots is CPU-bound; keep it off the async runtime
+    let request_masks = request.masks.clone().unwrap_or_default();
+    let before_source = request.before.clone();
+    let after_source = request.after.clone();
+    let stored = baseline.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let after = decode_image("after", &after_source)?;
+        let mut masks = request_masks;
+        if let Some((viewport_width, rects)) = &selector_rects {
+            masks.extend(scale_masks(*viewport_width, rects, after.width()));
+        }
+
+        let before = match (&before_source, &stored) {
+            (Some(before), _) => decode_image("before", before)?,
+            (None, Some(baseline)) => decode_image("baseline", &baseline.image)?,
+            (None, None) => {
+                return Ok((
+                    DiffScreenshotsResponse {
+                        first_capture: true,
+                        width: after.width(),
+                        height: after.height(),
+                        changed_pixels: 0,
+                        masked_pixels: 0,
+                        score: None,
+                        regions: None,
+                        diff_image: None,
+                    },
+                    masks,
+                ));
+            }
+        };
+        let mut all_masks = masks.clone();
+        if let Some(baseline) = &stored {
+            all_masks.extend(baseline.masks.iter().copied());
+        }
+        diff_images(&before, &after, &all_masks, threshold, &output).map(|response| (response, masks))
+    })
+    .await
+    .map_err(|e| Error::Anyhow(format!("Screenshot diff failed: {}", e)))?;
+
+    Ok(match result {
+        Ok((response, masks)) => {
+            if let Some(name) = &request.baseline {
+                if baseline.is_none() || request.update_baseline.unwrap_or(false) {
+                    store.baselines.lock().unwrap().insert(
+                        name.clone(),
+                        Arc::new(Baseline {
+                            image: request.after,
+                            masks,
+                        }),
+                    );
+                }
+            }
+            SocketResponse {
+                success: true,
+                data: Some(serde_json::to_value(response).map_err(|e| {
+                    Error::Anyhow(format!("Failed to serialize response: {}", e))
+                })?),
+                error: None,
+                ..Default::default()
+            }
+        }
+        Err(e) => SocketResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            ..Default::default()
+        },
+    })
+}
+
+/// Perceptual hash algorithm for `screenshot_hash`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    /// Brightness gradients between neighbouring cells; fast and good at spotting layout changes
+    #[default]
+    Dhash,
+    /// Low frequencies of a discrete cosine transform; more tolerant of small shifts and scaling
+    Phash,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScreenshotHashRequest {
+    pub window_label: Option<String>,
+    /// Hash only the first element matching this selector instead of the whole window
+    pub selector: Option<String>,
+    pub algorithm: Option<HashAlgorithm>,
+    /// Earlier hash (hex) to compare against; the response then carries the Hamming distance
+    pub compare_to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreenshotHashResponse {
+    /// 64-bit hash as 16 hex digits
+    pub hash: String,
+    pub algorithm: HashAlgorithm,
+    /// Size of the hashed capture in pixels
+    pub width: u32,
+    pub height: u32,
+    /// Bits that differ from `compare_to`; 0 means visually identical, under 10 usually means
+    /// only minor changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<u32>,
+}
+
+/// Bits set where a cell is brighter than its right-hand neighbour on a 9x8 grayscale thumbnail
+fn dhash(image: &DynamicImage) -> u64 {
+    let small = image
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y).0[0] > small.get_pixel(x + 1, y).0[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// Bits set where the 8x8 lowest DCT frequencies of a 32x32 grayscale thumbnail exceed their median
+fn phash(image: &DynamicImage) -> u64 {
+    const SIZE: usize = 32;
+    let small = image
+        .resize_exact(SIZE as u32, SIZE as u32, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let pixels: Vec<f64> = small.pixels().map(|p| p.0[0] as f64).collect();
+
+    let cosines: Vec<f64> = (0..8)
+        .flat_map(|u| {
+            (0..SIZE).map(move |x| {
+                (std::f64::consts::PI * u as f64 * (2 * x + 1) as f64 / (2 * SIZE) as f64).cos()
+            })
+        })
+        .collect();
+    let mut coefficients = Vec::with_capacity(64);
+    for v in 0..8 {
+        for u in 0..8 {
+            let mut sum = 0.0;
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    sum += pixels[y * SIZE + x] * cosines[u * SIZE + x] * cosines[v * SIZE + y];
+                }
+            }
+            coefficients.push(sum);
+        }
+    }
+
+    // The first coefficient is the average brightness and would swamp the median
+    let mut sorted = coefficients[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    coefficients
+        .iter()
+        .fold(0u64, |hash, &value| (hash << 1) | u64::from(value > median))
+}
+
+fn parse_hash(hash: &str) -> crate::Result<u64> {
+    u64::from_str_radix(hash.trim().trim_start_matches("0x"), 16)
+        .map_err(|e| Error::Anyhow(format!("Invalid `compare_to` hash {:?}: {}", hash, e)))
+}
+
+/// Capture a window as PNG, cropped to the first visible element matching `selector` and then
+/// to `region` (in pixels of the capture so far)
+pub(crate) async fn capture_area<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    selector: Option<&str>,
+    region: Option<MaskRect>,
+) -> crate::Result<DynamicImage> {
+    let element_rects = match selector {
+        Some(selector) => {
+            let (viewport_width, rects) = selector_rects(app, window_label, &[selector.to_string()]).await?;
+            if rects.is_empty() {
+                return Err(Error::Anyhow(format!("No visible element matches selector {}", selector)));
+            }
+            Some((viewport_width, rects))
+        }
+        None => None,
+    };
+
+    // PNG keeps encoder noise out of whatever reads the pixels
+    let screenshot = app
+        .tauri_mcp()
+        .take_screenshot_async(ScreenshotRequest {
+            window_label: window_label.to_string(),
+            format: Some(ImageFormat::Png),
+            quality: None,
+            max_width: None,
+        })
+        .await?;
+    let data = match screenshot.data {
+        Some(data) if screenshot.success => data,
+        _ => {
+            return Err(Error::WindowOperationFailed(
+                screenshot.error.unwrap_or_else(|| "Screenshot failed".to_string()),
+            ));
+        }
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut image = DynamicImage::ImageRgba8(decode_image("screenshot", &data)?);
+        let element = match &element_rects {
+            Some((viewport_width, rects)) => Some(
+                scale_masks(*viewport_width, &rects[..1], image.width())
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| Error::Anyhow("Could not locate the element in the screenshot".to_string()))?,
+            ),
+            None => None,
+        };
+        for area in element.into_iter().chain(region) {
+            let x = area.x.min(image.width());
+            let y = area.y.min(image.height());
+            let width = area.width.min(image.width() - x);
+            let height = area.height.min(image.height() - y);
+            if width == 0 || height == 0 {
+                return Err(Error::Anyhow("Requested area lies outside the capture".to_string()));
+            }
+            image = image.crop_imm(x, y, width, height);
+        }
+        Ok(image)
+    })
+    .await
+    .map_err(|e| Error::Anyhow(format!("Screenshot capture failed: {}", e)))?
+}
+
+/// Perceptual hash of a window, or one element in it, for cheap "did anything change" checks
+pub async fn handle_screenshot_hash<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> crate::Result<SocketResponse> {
+    let request: ScreenshotHashRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let algorithm = request.algorithm.unwrap_or_default();
+    let compare_to = request.compare_to.as_deref().map(parse_hash).transpose()?;
+
+    info!("[TAURI_MCP] Hashing screenshot of {} ({:?})", window_label, algorithm);
+
+    let image = match capture_area(app, &window_label, request.selector.as_deref(), None).await {
+        Ok(image) => image,
+        Err(e) => {
+            return Ok(SocketResponse {
+                success: false,
+                data: None,
+                error: Some(e.to_string()),
+                ..Default::default()
+            });
+        }
+    };
+
+    let response = tauri::async_runtime::spawn_blocking(move || {
+        let hash = match algorithm {
+            HashAlgorithm::Dhash => dhash(&image),
+            HashAlgorithm::Phash => phash(&image),
+        };
+        ScreenshotHashResponse {
+            hash: format!("{:016x}", hash),
+            algorithm,
+            width: image.width(),
+            height: image.height(),
+            distance: compare_to.map(|previous| (previous ^ hash).count_ones()),
+        }
+    })
+    .await
+    .map_err(|e| Error::Anyhow(format!("Screenshot hashing failed: {}", e)))?;
+
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}
@@ -4,6 +4,9 @@ use std::fmt;
 use std::sync::mpsc;
 use tauri::{AppHandle, Error as TauriError, Listener, Manager, Runtime, WebviewWindow};
 
+use crate::tools::js_bridge::eval_with_result;
+use crate::tools::sensitive_regions;
+
 // Custom error enum for the get_dom_text command
 #[derive(Debug)] // Add Serialize for the enum itself if it needs to be directly serialized
 // For now, we serialize its string representation
@@ -80,7 +83,18 @@ pub async fn handle_get_dom<R: Runtime>(
     let window = app.get_webview_window(&window_label).ok_or_else(|| {
         crate::error::Error::Anyhow(format!("Window not found: {}", window_label))
     })?;
-    let result = get_dom_text(app.clone(), window).await;
+    // With sensitive regions declared, the markup comes through the eval bridge so they can be
+    // emptied in the page before it leaves
+    let result = if sensitive_regions::regions(app).is_empty() {
+        get_dom_text(app.clone(), window).await
+    } else {
+        let script = sensitive_regions::html_script(app);
+        match eval_with_result(app, &window_label, &script, std::time::Duration::from_secs(5)).await {
+            Ok(Value::String(html)) if !html.is_empty() => Ok(html),
+            Ok(_) => Err(GetDomError::DomIsEmpty),
+            Err(e) => Err(GetDomError::JavaScriptError(e.to_string())),
+        }
+    };
     match result {
         Ok(dom_text) => {
             let data = serde_json::to_value(dom_text).map_err(|e| {
@@ -90,12 +104,14 @@ pub async fn handle_get_dom<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                ..Default::default()
             })
         }
         Err(e) => Ok(crate::socket_server::SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
@@ -205,6 +221,7 @@ pub async fn handle_get_element_position<R: Runtime>(
                     success: true,
                     data: Some(result_value.get("data").cloned().unwrap_or(Value::Null)),
                     error: None,
+                    ..Default::default()
                 })
             } else {
                 let error = result_value
@@ -216,6 +233,7 @@ pub async fn handle_get_element_position<R: Runtime>(
                     success: false,
                     data: None,
                     error: Some(error.to_string()),
+                    ..Default::default()
                 })
             }
         }
@@ -226,6 +244,7 @@ pub async fn handle_get_element_position<R: Runtime>(
                 "Timeout waiting for element position result: {}",
                 e
             )),
+            ..Default::default()
         }),
     }
 }
@@ -302,6 +321,7 @@ pub async fn handle_send_text_to_element<R: Runtime>(
                     success: true,
                     data: Some(result_value.get("data").cloned().unwrap_or(Value::Null)),
                     error: None,
+                    ..Default::default()
                 })
             } else {
                 let error = result_value
@@ -313,6 +333,7 @@ pub async fn handle_send_text_to_element<R: Runtime>(
                     success: false,
                     data: None,
                     error: Some(error.to_string()),
+                    ..Default::default()
                 })
             }
         }
@@ -320,6 +341,7 @@ pub async fn handle_send_text_to_element<R: Runtime>(
             success: false,
             data: None,
             error: Some(format!("Timeout waiting for text input completion: {}", e)),
+            ..Default::default()
         }),
     }
 }
@@ -0,0 +1,457 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime};
+use log::warn;
+
+use tokio::sync::oneshot;
+
+use crate::error::Error;
+use crate::tools::page_helpers;
+
+/// Event the fallback initialization script listens on for code to run
+const BRIDGE_REQUEST_EVENT: &str = "mcp-bridge-request";
+
+/// How long a webview eval may stay silent before the code is also offered to the event bridge.
+/// Both paths claim the call in the page first, so the code never runs twice.
+const FALLBACK_AFTER: Duration = Duration::from_millis(1000);
+
+/// Most scripts handed to the main thread in one task
+const MAX_BATCH: usize = 16;
+
+/// Pause between batches while more scripts are queued, so input and painting get the main
+/// thread in between
+const BATCH_GAP: Duration = Duration::from_millis(8);
+
+/// How a script reached the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStrategy {
+    /// Tauri's webview eval
+    Eval,
+    /// The event bridge initialization script, compiling the code with `Function`
+    EventBridge,
+    /// The event bridge loading the code as a `blob:` script, for pages whose CSP also forbids
+    /// `unsafe-eval`
+    ScriptElement,
+}
+
+/// Per-window secrets the event bridge requires on every request. Events can be emitted by any
+/// window, so without one a page could have another window's bridge run its code. The bridge asks
+/// for its token over IPC, which names the calling webview, so a page only ever learns its own.
+#[derive(Default)]
+pub(crate) struct BridgeTokens(Mutex<HashMap<String, String>>);
+
+impl BridgeTokens {
+    /// The window's token, created on first use
+    pub(crate) fn token(&self, window_label: &str) -> String {
+        self.0
+            .lock()
+            .unwrap()
+            .entry(window_label.to_string())
+            .or_insert_with(secret)
+            .clone()
+    }
+}
+
+/// Calls waiting for their reply, by an unguessable id, with the window that has to send it.
+/// Pages answer through the plugin's `eval_result` command, which names the calling webview, so
+/// no other window can complete or spoil a call.
+#[derive(Default)]
+pub(crate) struct PendingCalls(Mutex<HashMap<String, (String, oneshot::Sender<Value>)>>);
+
+impl PendingCalls {
+    fn register(&self, window_label: &str) -> (String, oneshot::Receiver<Value>) {
+        let (tx, rx) = oneshot::channel();
+        let id = format!("mcp-call-{}", secret());
+        self.0
+            .lock()
+            .unwrap()
+            .insert(id.clone(), (window_label.to_string(), tx));
+        (id, rx)
+    }
+
+    fn forget(&self, id: &str) {
+        self.0.lock().unwrap().remove(id);
+    }
+
+    /// Complete call `id` with a reply sent by `window_label`. Returns false, leaving the call
+    /// waiting, when there is no such call or it belongs to another window.
+    pub(crate) fn resolve(&self, id: &str, window_label: &str, reply: Value) -> bool {
+        let mut pending = self.0.lock().unwrap();
+        if pending.get(id).is_none_or(|(label, _)| label != window_label) {
+            return false;
+        }
+        let (_, tx) = pending.remove(id).unwrap();
+        let _ = tx.send(reply);
+        true
+    }
+}
+
+/// 128 unpredictable bits as hex, drawn from the OS-seeded keys std uses for `HashMap`
+pub(crate) fn secret() -> String {
+    let half = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", half(), half())
+}
+
+/// Boxed future returned by [`WebviewBackend`] so the trait stays object-safe
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = crate::Result<T>> + Send + 'a>>;
+
+/// Window lookup and script evaluation as used by the tool handlers. The plugin talks to
+/// webviews through Tauri by default; other backends (a fake in tests, a devtools protocol
+/// connection) can be supplied with [`crate::PluginConfig::webview_backend`].
+pub trait WebviewBackend: Send + Sync {
+    /// Whether a window with this label exists and can run scripts
+    fn has_window(&self, window_label: &str) -> bool;
+
+    /// Labels of every window that can run scripts
+    fn window_labels(&self) -> Vec<String>;
+
+    /// Evaluate a script without waiting for it to finish
+    fn eval(&self, window_label: &str, script: &str) -> crate::Result<()>;
+
+    /// Run `body` as the body of an async function and return the raw reply: `{ ok: true, value }`,
+    /// `{ ok: false, error, stack }`, or `{ ok: false, helpers_missing: true }` when the page does
+    /// not hold the current `window.__mcp` bundle. Replies may name the [`ExecutionStrategy`] that
+    /// ran the code in `strategy`.
+    fn call<'a>(
+        &'a self,
+        window_label: &'a str,
+        body: &'a str,
+        timeout: Duration,
+    ) -> BackendFuture<'a, Value>;
+}
+
+/// Backend registered by the plugin at setup
+pub(crate) struct SharedBackend(pub Arc<dyn WebviewBackend>);
+
+/// The backend tools should use for this app, created once when the plugin is set up
+pub fn backend<R: Runtime>(app: &AppHandle<R>) -> crate::Result<Arc<dyn WebviewBackend>> {
+    app.try_state::<SharedBackend>()
+        .map(|backend| backend.0.clone())
+        .ok_or_else(|| Error::PluginInit("The webview backend is not set up".to_string()))
+}
+
+/// Queue of webview evals. Dispatching each script on its own wakes the event loop once per
+/// script, and a burst of them starves input and painting. Instead queued scripts are run
+/// together from one main-thread task, where Tauri evaluates them directly, a batch at a time.
+#[derive(Default)]
+struct EvalScheduler {
+    queue: Mutex<VecDeque<(String, String)>>,
+    queued: tokio::sync::Notify,
+    started: AtomicBool,
+    /// Set when the owning backend is dropped, so the dispatch task ends
+    closed: AtomicBool,
+}
+
+impl EvalScheduler {
+    fn push<R: Runtime>(self: &Arc<Self>, app: &AppHandle<R>, window_label: &str, script: &str) {
+        self.queue
+            .lock()
+            .unwrap()
+            .push_back((window_label.to_string(), script.to_string()));
+        self.queued.notify_one();
+        if !self.started.swap(true, Ordering::AcqRel) {
+            tauri::async_runtime::spawn(Self::dispatch(self.clone(), app.clone()));
+        }
+    }
+
+    /// Stop the dispatch task. Scripts still queued are dropped.
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.queued.notify_one();
+    }
+
+    async fn dispatch<R: Runtime>(self: Arc<Self>, app: AppHandle<R>) {
+        loop {
+            self.queued.notified().await;
+            // The backend that owned the queue is gone
+            if self.closed.load(Ordering::Acquire) {
+                return;
+            }
+            loop {
+                let batch: Vec<(String, String)> = {
+                    let mut queue = self.queue.lock().unwrap();
+                    let take = queue.len().min(MAX_BATCH);
+                    queue.drain(..take).collect()
+                };
+                if batch.is_empty() {
+                    break;
+                }
+                let handle = app.clone();
+                let dispatched = app.run_on_main_thread(move || {
+                    for (window_label, script) in batch {
+                        let Some(window) = handle.get_webview_window(&window_label) else {
+                            warn!("[TAURI_MCP] Dropping a script for closed window '{}'", window_label);
+                            continue;
+                        };
+                        if let Err(e) = window.eval(&script) {
+                            warn!("[TAURI_MCP] Failed to evaluate JavaScript in '{}': {}", window_label, e);
+                        }
+                    }
+                });
+                if let Err(e) = dispatched {
+                    warn!("[TAURI_MCP] Failed to reach the main thread: {}", e);
+                }
+                if self.queue.lock().unwrap().is_empty() {
+                    break;
+                }
+                tokio::time::sleep(BATCH_GAP).await;
+            }
+        }
+    }
+}
+
+/// Evaluates through Tauri's webview API and receives results through the plugin's `eval_result`
+/// command
+pub struct TauriBackend<R: Runtime> {
+    app: AppHandle<R>,
+    scheduler: Arc<EvalScheduler>,
+    /// Whether pages carry [`event_bridge_script`], so silent evals can fall back to it
+    event_bridge: bool,
+    /// Windows where webview eval went unanswered; calls go straight to the event bridge
+    bridged: Mutex<HashSet<String>>,
+}
+
+impl<R: Runtime> Drop for TauriBackend<R> {
+    fn drop(&mut self) {
+        self.scheduler.close();
+    }
+}
+
+impl<R: Runtime> TauriBackend<R> {
+    pub fn new(app: AppHandle<R>) -> Self {
+        Self {
+            app,
+            scheduler: Arc::default(),
+            event_bridge: false,
+            bridged: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Fall back to the event bridge when a webview eval goes unanswered. Only enable this when
+    /// [`event_bridge_script`] is registered as an initialization script.
+    pub fn with_event_bridge(mut self, enabled: bool) -> Self {
+        self.event_bridge = enabled;
+        self
+    }
+
+    /// Offer `body` to the window's event bridge, which replies to [`PendingCalls`] call
+    /// `call_id`. The request only goes to that window and carries its [`BridgeTokens`] token.
+    fn send_to_bridge(&self, window_label: &str, call_id: &str, body: &str) -> crate::Result<()> {
+        let token = self
+            .app
+            .try_state::<BridgeTokens>()
+            .map(|tokens| tokens.token(window_label))
+            .ok_or_else(|| Error::Anyhow("The event bridge is not set up".to_string()))?;
+        self.app
+            .emit_to(
+                EventTarget::labeled(window_label),
+                BRIDGE_REQUEST_EVENT,
+                serde_json::json!({ "id": call_id, "token": token, "body": body }),
+            )
+            .map_err(|e| Error::Anyhow(format!("Failed to reach the event bridge: {}", e)))
+    }
+}
+
+/// Initialization script that runs code sent over the event system, for pages where webview eval
+/// silently does nothing (typically a Content-Security-Policy without `unsafe-eval` or inline
+/// scripts). It also installs the page helpers, since they can't be injected into such pages.
+///
+/// The code is compiled with `Function` where the CSP allows `unsafe-eval`, and otherwise loaded
+/// as a `blob:` script, which needs `blob:` in `script-src`. A policy that allows neither leaves
+/// nothing to run strings with; such calls fail with an error naming the CSP.
+///
+/// Requests are only run when they carry the window's [`BridgeTokens`] token, which the script
+/// fetches through the plugin's `bridge_token` command and keeps out of reach of the page.
+pub fn event_bridge_script() -> String {
+    format!(
+        r#"{helpers}
+(function() {{
+    const internals = window.__TAURI_INTERNALS__;
+    if (window.__mcpEventBridge || !internals || !internals.transformCallback) return;
+    window.__mcpEventBridge = true;
+    const token = internals.invoke('plugin:mcp|bridge_token').catch(() => null);
+    const claims = window.__mcpBridgeClaims || (window.__mcpBridgeClaims = {{}});
+    const runs = window.__mcpBridgeRuns || (window.__mcpBridgeRuns = {{}});
+    const reply = (id, payload) => internals.invoke('plugin:mcp|eval_result', {{ id, reply: payload }});
+    const compile = async (id, body) => {{
+        try {{
+            return {{ strategy: 'event_bridge', run: new Function('return (async function() {{\n' + body + '\n}})')() }};
+        }} catch (e) {{
+            if (!(e instanceof EvalError)) throw e;
+        }}
+        const url = URL.createObjectURL(new Blob(
+            ['window.__mcpBridgeRuns[' + JSON.stringify(id) + '] = async function() {{\n' + body + '\n}};'],
+            {{ type: 'text/javascript' }}
+        ));
+        try {{
+            await new Promise((resolve, reject) => {{
+                const script = document.createElement('script');
+                script.src = url;
+                script.onload = () => {{ script.remove(); resolve(); }};
+                script.onerror = () => {{
+                    script.remove();
+                    reject(new Error("The page's Content-Security-Policy blocks eval, inline and blob: scripts"));
+                }};
+                (document.head || document.documentElement).appendChild(script);
+            }});
+        }} finally {{
+            URL.revokeObjectURL(url);
+        }}
+        const run = runs[id];
+        delete runs[id];
+        if (!run) throw new SyntaxError('The script failed to compile; see the console');
+        return {{ strategy: 'script_element', run }};
+    }};
+    const handler = internals.transformCallback(async (event) => {{
+        const request = event && event.payload;
+        if (!request || !request.token || claims[request.id]) return;
+        if (request.token !== await token || claims[request.id]) return;
+        claims[request.id] = true;
+        let strategy = 'event_bridge';
+        try {{
+            const compiled = await compile(request.id, request.body);
+            strategy = compiled.strategy;
+            const value = await compiled.run();
+            await reply(request.id, {{ ok: true, value: value === undefined ? null : value, strategy }});
+        }} catch (e) {{
+            await reply(request.id, {{
+                ok: false,
+                error: (e && e.message) ? e.message : String(e),
+                stack: (e && e.stack) ? e.stack : null,
+                strategy
+            }});
+        }}
+    }});
+    internals.invoke('plugin:event|listen', {{ event: '{event}', target: {{ kind: 'Any' }}, handler }});
+}})();"#,
+        helpers = page_helpers::install_script(),
+        event = BRIDGE_REQUEST_EVENT
+    )
+}
+
+impl<R: Runtime> WebviewBackend for TauriBackend<R> {
+    fn has_window(&self, window_label: &str) -> bool {
+        self.app.get_webview_window(window_label).is_some()
+    }
+
+    fn window_labels(&self) -> Vec<String> {
+        self.app.webview_windows().into_keys().collect()
+    }
+
+    /// Queues the script for the main thread; see [`EvalScheduler`]
+    fn eval(&self, window_label: &str, script: &str) -> crate::Result<()> {
+        if !self.has_window(window_label) {
+            return Err(Error::WindowNotFound(window_label.to_string()));
+        }
+        self.scheduler.push(&self.app, window_label, script);
+        Ok(())
+    }
+
+    fn call<'a>(
+        &'a self,
+        window_label: &'a str,
+        body: &'a str,
+        timeout: Duration,
+    ) -> BackendFuture<'a, Value> {
+        Box::pin(async move {
+            let pending = self
+                .app
+                .try_state::<PendingCalls>()
+                .ok_or_else(|| Error::Anyhow("The result bridge is not set up".to_string()))?;
+
+            // Register the call before evaluating so a fast reply can't be missed
+            let (call_id, mut rx) = pending.register(window_label);
+
+            // Without the helpers the body can't run, so report their absence straight away.
+            // The claim keeps a late eval from running code the event bridge already ran.
+            let script = format!(
+                r#"
+                (async function() {{
+                    const claims = window.__mcpBridgeClaims || (window.__mcpBridgeClaims = {{}});
+                    if (claims['{call_id}']) return;
+                    claims['{call_id}'] = true;
+                    const reply = (payload) => window.__TAURI_INTERNALS__.invoke('plugin:mcp|eval_result', {{ id: '{call_id}', reply: payload }});
+                    if ({missing}) {{
+                        reply({{ ok: false, helpers_missing: true, strategy: 'eval' }});
+                        return;
+                    }}
+                    try {{
+                        const value = await (async function() {{ {body} }})();
+                        await reply({{ ok: true, value: value === undefined ? null : value, strategy: 'eval' }});
+                    }} catch (e) {{
+                        await reply({{
+                            ok: false,
+                            error: (e && e.message) ? e.message : String(e),
+                            stack: (e && e.stack) ? e.stack : null,
+                            strategy: 'eval'
+                        }});
+                    }}
+                }})();
+                "#,
+                missing = page_helpers::missing_check(),
+                body = body,
+                call_id = call_id
+            );
+
+            let started = Instant::now();
+            let bridged = self.event_bridge && self.bridged.lock().unwrap().contains(window_label);
+            let dispatched = if bridged {
+                self.send_to_bridge(window_label, &call_id, body)
+            } else {
+                self.eval(window_label, &script)
+            };
+            if let Err(e) = dispatched {
+                pending.forget(&call_id);
+                return Err(e);
+            }
+
+            let mut reply = tokio::time::timeout(timeout.min(FALLBACK_AFTER), &mut rx).await;
+            if reply.is_err() && timeout > FALLBACK_AFTER {
+                if self.event_bridge && !bridged {
+                    // Webview eval can be silently dropped by the page's CSP
+                    if let Err(e) = self.send_to_bridge(window_label, &call_id, body) {
+                        pending.forget(&call_id);
+                        return Err(e);
+                    }
+                }
+                reply = tokio::time::timeout(timeout.saturating_sub(started.elapsed()), &mut rx).await;
+            }
+
+            let response = match reply {
+                Ok(Ok(response)) => response,
+                Ok(Err(_)) => {
+                    return Err(Error::Anyhow(
+                        "JavaScript result channel closed unexpectedly".to_string(),
+                    ));
+                }
+                Err(_) => {
+                    pending.forget(&call_id);
+                    return Err(Error::Anyhow(format!(
+                        "Timed out after {}ms waiting for JavaScript result in window '{}'",
+                        timeout.as_millis(),
+                        window_label
+                    )));
+                }
+            };
+
+            if self.event_bridge {
+                let mut windows = self.bridged.lock().unwrap();
+                match response.get("strategy").and_then(|v| v.as_str()) {
+                    Some("eval") => windows.remove(window_label),
+                    Some(_) => windows.insert(window_label.to_string()),
+                    None => false,
+                };
+            }
+            Ok(response)
+        })
+    }
+}
@@ -1,5 +1,10 @@
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{AppHandle, Runtime};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use log::info;
 
 use crate::TauriMcpExt;
 use crate::error::Error;
@@ -23,12 +28,274 @@ pub async fn handle_manage_window<R: Runtime>(
                 success: true,
                 data: Some(data),
                 error: None,
+                ..Default::default()
             })
         }
         Err(e) => Ok(SocketResponse {
             success: false,
             data: None,
             error: Some(e.to_string()),
+            ..Default::default()
         }),
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct WindowStyleRequest {
+    pub window_label: Option<String>,
+    pub always_on_top: Option<bool>,
+    pub decorations: Option<bool>,
+    pub resizable: Option<bool>,
+    /// Keep the window out of the taskbar (Windows, Linux)
+    pub skip_taskbar: Option<bool>,
+    /// 0.0 (transparent) to 1.0 (opaque); macOS and Windows only
+    pub opacity: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowStyleResponse {
+    /// Flags this request changed
+    pub applied: Vec<&'static str>,
+    /// State read back from the window after the changes
+    pub always_on_top: bool,
+    pub decorated: bool,
+    pub resizable: bool,
+}
+
+/// Toggle window chrome flags. Unset fields are left alone, so an empty request just reports
+/// the current state.
+pub async fn handle_set_window_style<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: WindowStyleRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let window = app
+        .get_webview_window(&window_label)
+        .ok_or_else(|| Error::WindowNotFound(window_label.clone()))?;
+
+    info!("[TAURI_MCP] Setting window style for: {}", window_label);
+
+    if let Some(opacity) = request.opacity {
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(Error::Anyhow(format!("`opacity` must be between 0 and 1, got {}", opacity)));
+        }
+    }
+
+    let mut applied = Vec::new();
+    if let Some(always_on_top) = request.always_on_top {
+        window.set_always_on_top(always_on_top)?;
+        applied.push("always_on_top");
+    }
+    if let Some(decorations) = request.decorations {
+        window.set_decorations(decorations)?;
+        applied.push("decorations");
+    }
+    if let Some(resizable) = request.resizable {
+        window.set_resizable(resizable)?;
+        applied.push("resizable");
+    }
+    if let Some(skip_taskbar) = request.skip_taskbar {
+        window.set_skip_taskbar(skip_taskbar)?;
+        applied.push("skip_taskbar");
+    }
+    if let Some(opacity) = request.opacity {
+        if let Err(e) = crate::platform::current::set_window_opacity(&window, opacity) {
+            return Ok(SocketResponse {
+                success: false,
+                data: Some(serde_json::json!({ "applied": applied })),
+                error: Some(e.to_string()),
+                ..Default::default()
+            });
+        }
+        applied.push("opacity");
+    }
+
+    let response = WindowStyleResponse {
+        applied,
+        always_on_top: window.is_always_on_top()?,
+        decorated: window.is_decorated()?,
+        resizable: window.is_resizable()?,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response)
+            .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// How long to wait for the window to report a fullscreen change, which is animated on macOS
+const FULLSCREEN_SETTLE: Duration = Duration::from_secs(3);
+
+/// Window flags saved on entering kiosk mode, restored on leaving it
+#[derive(Debug, Clone, Copy)]
+struct SavedChrome {
+    fullscreen: bool,
+    always_on_top: bool,
+    decorated: bool,
+    resizable: bool,
+}
+
+/// Windows currently in kiosk mode, by label
+#[derive(Default)]
+pub struct KioskStore {
+    windows: Mutex<HashMap<String, SavedChrome>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetFullscreenRequest {
+    pub window_label: Option<String>,
+    /// Leave out to toggle
+    pub fullscreen: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetKioskModeRequest {
+    pub window_label: Option<String>,
+    /// Leave out to toggle
+    pub enabled: Option<bool>,
+}
+
+/// Window state after a fullscreen or kiosk change
+#[derive(Debug, Serialize)]
+pub struct WindowModeResponse {
+    pub fullscreen: bool,
+    pub kiosk: bool,
+    pub maximized: bool,
+    pub decorated: bool,
+    pub always_on_top: bool,
+    pub resizable: bool,
+    /// Inner size in logical pixels, i.e. the viewport the page lays out against
+    pub width: f64,
+    pub height: f64,
+    pub scale_factor: f64,
+    /// Name of the monitor the window is on, if the platform reports one
+    pub monitor: Option<String>,
+    /// False when the window didn't report the requested fullscreen state in time
+    pub settled: bool,
+}
+
+fn find_window<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<WebviewWindow<R>, Error> {
+    app.get_webview_window(label)
+        .ok_or_else(|| Error::WindowNotFound(label.to_string()))
+}
+
+/// Poll until the window reports `fullscreen`, returning whether it did in time
+async fn wait_for_fullscreen<R: Runtime>(window: &WebviewWindow<R>, fullscreen: bool) -> bool {
+    let deadline = Instant::now() + FULLSCREEN_SETTLE;
+    loop {
+        if window.is_fullscreen().unwrap_or(!fullscreen) == fullscreen {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+fn mode_response<R: Runtime>(
+    app: &AppHandle<R>,
+    window: &WebviewWindow<R>,
+    settled: bool,
+) -> Result<SocketResponse, Error> {
+    let scale_factor = window.scale_factor()?;
+    let size = window.inner_size()?.to_logical::<f64>(scale_factor);
+    let kiosk = app
+        .try_state::<KioskStore>()
+        .map(|store| store.windows.lock().unwrap().contains_key(window.label()))
+        .unwrap_or(false);
+    let response = WindowModeResponse {
+        fullscreen: window.is_fullscreen()?,
+        kiosk,
+        maximized: window.is_maximized()?,
+        decorated: window.is_decorated()?,
+        always_on_top: window.is_always_on_top()?,
+        resizable: window.is_resizable()?,
+        width: size.width,
+        height: size.height,
+        scale_factor,
+        monitor: window.current_monitor().ok().flatten().and_then(|monitor| monitor.name().cloned()),
+        settled,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response)
+            .map_err(|e| Error::Anyhow(format!("Failed to serialize response: {}", e)))?),
+        error: None,
+        ..Default::default()
+    })
+}
+
+/// Enter or leave fullscreen and report the resulting window state once the change has settled
+pub async fn handle_set_fullscreen<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: SetFullscreenRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let window = find_window(app, &window_label)?;
+    let fullscreen = match request.fullscreen {
+        Some(fullscreen) => fullscreen,
+        None => !window.is_fullscreen()?,
+    };
+
+    info!("[TAURI_MCP] Setting fullscreen={} for: {}", fullscreen, window_label);
+
+    window.set_fullscreen(fullscreen)?;
+    let settled = wait_for_fullscreen(&window, fullscreen).await;
+    mode_response(app, &window, settled)
+}
+
+/// Kiosk mode: fullscreen, always on top, undecorated, not resizable and out of the taskbar.
+/// Leaving it restores the flags the window had before. Kiosk state is tracked by the plugin,
+/// so windows put into kiosk mode some other way report `kiosk: false`.
+pub async fn handle_set_kiosk_mode<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: Value,
+) -> Result<SocketResponse, Error> {
+    let request: SetKioskModeRequest = serde_json::from_value(payload)
+        .map_err(|e| Error::Anyhow(format!("Invalid request format: {}", e)))?;
+    let window_label = request.window_label.unwrap_or_else(|| "main".to_string());
+    let window = find_window(app, &window_label)?;
+    let store = app.state::<KioskStore>();
+    let saved = store.windows.lock().unwrap().get(&window_label).copied();
+    let enabled = request.enabled.unwrap_or(saved.is_none());
+
+    info!("[TAURI_MCP] Setting kiosk mode={} for: {}", enabled, window_label);
+
+    let settled = match (enabled, saved) {
+        (true, None) => {
+            let saved = SavedChrome {
+                fullscreen: window.is_fullscreen()?,
+                always_on_top: window.is_always_on_top()?,
+                decorated: window.is_decorated()?,
+                resizable: window.is_resizable()?,
+            };
+            store.windows.lock().unwrap().insert(window_label.clone(), saved);
+            window.set_decorations(false)?;
+            window.set_resizable(false)?;
+            window.set_always_on_top(true)?;
+            window.set_skip_taskbar(true)?;
+            window.set_fullscreen(true)?;
+            wait_for_fullscreen(&window, true).await
+        }
+        (false, Some(saved)) => {
+            store.windows.lock().unwrap().remove(&window_label);
+            window.set_fullscreen(saved.fullscreen)?;
+            let settled = wait_for_fullscreen(&window, saved.fullscreen).await;
+            window.set_skip_taskbar(false)?;
+            window.set_always_on_top(saved.always_on_top)?;
+            window.set_resizable(saved.resizable)?;
+            window.set_decorations(saved.decorated)?;
+            settled
+        }
+        // Already in the requested mode
+        _ => true,
+    };
+    mode_response(app, &window, settled)
+}
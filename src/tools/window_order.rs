@@ -0,0 +1,275 @@
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info;
+
+use crate::error::Error;
+use crate::socket_server::SocketResponse;
+
+/// `(x, y, width, height)` in physical pixels
+type Rect = (f64, f64, f64, f64);
+
+/// Percentages at or beyond which a window counts as fully covered or fully on screen
+const FULL_PERCENT: f64 = 99.5;
+
+/// Where the stacking order came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSource {
+    /// The OS window list, which also covers other applications' windows
+    Os,
+    /// Only the focused window is known to be in front; the others keep label order
+    Focus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowVisibility {
+    Visible,
+    PartiallyOccluded,
+    /// Another window covers all of it; a screenshot may show that window instead
+    Occluded,
+    PartiallyOffScreen,
+    OffScreen,
+    Minimized,
+    Hidden,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowStackEntry {
+    pub label: String,
+    pub title: String,
+    /// Position among all the windows the OS reported, 0 being frontmost. `None` when the
+    /// window wasn't found in that list or the order came from focus.
+    pub os_z_index: Option<usize>,
+    pub focused: bool,
+    pub state: WindowVisibility,
+    /// Outer bounds in physical pixels
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Share of the window inside some monitor
+    pub on_screen_percent: f64,
+    /// Share of the window under windows stacked in front of it
+    pub covered_percent: f64,
+    /// Titles of the windows in front that overlap it
+    pub covered_by: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WindowOrderResponse {
+    pub order_source: OrderSource,
+    /// This app's windows, frontmost first
+    pub windows: Vec<WindowStackEntry>,
+}
+
+fn intersect(a: Rect, b: Rect) -> Option<Rect> {
+    let left = a.0.max(b.0);
+    let top = a.1.max(b.1);
+    let right = (a.0 + a.2).min(b.0 + b.2);
+    let bottom = (a.1 + a.3).min(b.1 + b.3);
+    (right > left && bottom > top).then(|| (left, top, right - left, bottom - top))
+}
+
+/// Area covered by the union of `rects`, by splitting the plane along every rectangle edge
+fn union_area(rects: &[Rect]) -> f64 {
+    let mut xs: Vec<f64> = rects.iter().flat_map(|r| [r.0, r.0 + r.2]).collect();
+    let mut ys: Vec<f64> = rects.iter().flat_map(|r| [r.1, r.1 + r.3]).collect();
+    xs.sort_by(|a, b| a.total_cmp(b));
+    ys.sort_by(|a, b| a.total_cmp(b));
+    xs.dedup();
+    ys.dedup();
+
+    let mut area = 0.0;
+    for column in xs.windows(2) {
+        for row in ys.windows(2) {
+            let (cx, cy) = ((column[0] + column[1]) / 2.0, (row[0] + row[1]) / 2.0);
+            if rects
+                .iter()
+                .any(|r| cx > r.0 && cx < r.0 + r.2 && cy > r.1 && cy < r.1 + r.3)
+            {
+                area += (column[1] - column[0]) * (row[1] - row[0]);
+            }
+        }
+    }
+    area
+}
+
+/// Percentage of `window` covered by `others`
+fn covered_percent(window: Rect, others: &[Rect]) -> f64 {
+    let area = window.2 * window.3;
+    if area <= 0.0 {
+        return 0.0;
+    }
+    let clipped: Vec<Rect> = others.iter().filter_map(|&other| intersect(window, other)).collect();
+    union_area(&clipped) * 100.0 / area
+}
+
+struct AppWindow {
+    label: String,
+    title: String,
+    bounds: Rect,
+    focused: bool,
+    visible: bool,
+    minimized: bool,
+}
+
+/// Report the stacking order of the app's windows and whether each is covered or off screen.
+/// The OS order is used where the platform exposes it (macOS, Windows). Windows of other
+/// applications only count towards `covered_percent` where the OS also reports their bounds
+/// (macOS).
+pub async fn handle_get_window_z_order<R: Runtime>(
+    app: &AppHandle<R>,
+    _payload: Value,
+) -> crate::Result<SocketResponse> {
+    info!("[TAURI_MCP] Reporting window stacking order");
+
+    let mut windows = Vec::new();
+    let mut scale_factor = 1.0;
+    for (label, window) in app.webview_windows() {
+        let position = window.outer_position()?;
+        let size = window.outer_size()?;
+        let focused = window.is_focused().unwrap_or(false);
+        if focused {
+            scale_factor = window.scale_factor().unwrap_or(1.0);
+        }
+        windows.push(AppWindow {
+            label,
+            title: window.title().unwrap_or_default(),
+            bounds: (position.x as f64, position.y as f64, size.width as f64, size.height as f64),
+            focused,
+            visible: window.is_visible().unwrap_or(true),
+            minimized: window.is_minimized().unwrap_or(false),
+        });
+    }
+    windows.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let monitors: Vec<Rect> = app
+        .available_monitors()
+        .map_err(|e| Error::WindowOperationFailed(format!("Failed to list monitors: {}", e)))?
+        .iter()
+        .map(|monitor| {
+            let (position, size) = (monitor.position(), monitor.size());
+            (position.x as f64, position.y as f64, size.width as f64, size.height as f64)
+        })
+        .collect();
+
+    // Reading the OS window list can be slow; keep it off the async runtime
+    let stack = tauri::async_runtime::spawn_blocking(move || {
+        crate::platform::current::window_stack(scale_factor)
+    })
+    .await
+    .map_err(|e| Error::Anyhow(format!("Failed to read window stack: {}", e)))?;
+
+    // Match each window to its entry in the OS list by title
+    let os_index = |window: &AppWindow| {
+        stack
+            .as_ref()
+            .and_then(|stack| stack.iter().position(|entry| entry.title == window.title))
+    };
+    let order_source = match &stack {
+        Some(_) if windows.iter().any(|window| os_index(window).is_some()) => OrderSource::Os,
+        _ => OrderSource::Focus,
+    };
+    let mut ordered: Vec<(Option<usize>, AppWindow)> = windows
+        .into_iter()
+        .map(|window| {
+            let index = match order_source {
+                OrderSource::Os => os_index(&window),
+                OrderSource::Focus => None,
+            };
+            (index, window)
+        })
+        .collect();
+    ordered.sort_by_key(|(index, window)| match order_source {
+        OrderSource::Os => (index.is_none(), index.unwrap_or(usize::MAX)),
+        OrderSource::Focus => (!window.focused, 0),
+    });
+
+    let own_titles: Vec<&str> = ordered.iter().map(|(_, window)| window.title.as_str()).collect();
+    let mut entries = Vec::new();
+    for (position, (os_z_index, window)) in ordered.iter().enumerate() {
+        // Windows in front: other applications' windows with known bounds ahead of this one in
+        // the OS list, plus this app's windows ahead of it (only the focused one when the order
+        // came from focus)
+        let mut in_front: Vec<(String, Rect)> = Vec::new();
+        if let (Some(stack), Some(index)) = (&stack, os_z_index) {
+            in_front.extend(stack[..*index].iter().filter_map(|entry| {
+                let bounds = entry.bounds?;
+                (!own_titles.contains(&entry.title.as_str())).then(|| (entry.title.clone(), bounds))
+            }));
+        }
+        in_front.extend(
+            ordered[..position]
+                .iter()
+                .filter(|(_, other)| other.visible && !other.minimized)
+                .filter(|(_, other)| order_source == OrderSource::Os || other.focused)
+                .map(|(_, other)| (other.title.clone(), other.bounds)),
+        );
+
+        let front_rects: Vec<Rect> = in_front.iter().map(|(_, rect)| *rect).collect();
+        let covered = covered_percent(window.bounds, &front_rects);
+        let area = window.bounds.2 * window.bounds.3;
+        let on_screen = if area > 0.0 {
+            let clipped: Vec<Rect> = monitors
+                .iter()
+                .filter_map(|&monitor| intersect(window.bounds, monitor))
+                .collect();
+            union_area(&clipped) * 100.0 / area
+        } else {
+            0.0
+        };
+
+        let state = if !window.visible {
+            WindowVisibility::Hidden
+        } else if window.minimized {
+            WindowVisibility::Minimized
+        } else if on_screen <= 100.0 - FULL_PERCENT {
+            WindowVisibility::OffScreen
+        } else if covered >= FULL_PERCENT {
+            WindowVisibility::Occluded
+        } else if covered > 100.0 - FULL_PERCENT {
+            WindowVisibility::PartiallyOccluded
+        } else if on_screen < FULL_PERCENT {
+            WindowVisibility::PartiallyOffScreen
+        } else {
+            WindowVisibility::Visible
+        };
+
+        let mut covered_by: Vec<String> = in_front
+            .iter()
+            .filter(|(_, rect)| intersect(window.bounds, *rect).is_some())
+            .map(|(title, _)| title.clone())
+            .collect();
+        covered_by.dedup();
+
+        entries.push(WindowStackEntry {
+            label: window.label.clone(),
+            title: window.title.clone(),
+            os_z_index: *os_z_index,
+            focused: window.focused,
+            state,
+            x: window.bounds.0 as i32,
+            y: window.bounds.1 as i32,
+            width: window.bounds.2 as u32,
+            height: window.bounds.3 as u32,
+            on_screen_percent: on_screen,
+            covered_percent: covered,
+            covered_by,
+        });
+    }
+
+    let response = WindowOrderResponse {
+        order_source,
+        windows: entries,
+    };
+    Ok(SocketResponse {
+        success: true,
+        data: Some(serde_json::to_value(response).map_err(|e| {
+            Error::Anyhow(format!("Failed to serialize response: {}", e))
+        })?),
+        error: None,
+        ..Default::default()
+    })
+}